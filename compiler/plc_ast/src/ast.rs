@@ -1,6 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 
 use std::{
+    collections::HashSet,
     fmt::{Debug, Display, Formatter},
     ops::Range,
 };
@@ -11,6 +12,7 @@ use crate::{
     control_statements::{
         AstControlStatement, CaseStatement, ConditionalBlock, ForLoopStatement, IfStatement, LoopStatement,
     },
+    instantiation,
     literals::{Array, AstLiteral, StringValue},
     pre_processor,
     provider::IdProvider,
@@ -23,7 +25,7 @@ pub struct GenericBinding {
     pub nature: TypeNature,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct Pou {
     pub name: String,
     pub variable_blocks: Vec<VariableBlock>,
@@ -37,9 +39,41 @@ pub struct Pou {
     pub generics: Vec<GenericBinding>,
     pub linkage: LinkageType,
     pub super_class: Option<String>,
+    /// true if the POU was marked with the `{test}` pragma
+    pub is_test: bool,
+    /// the property this method was lowered from if it is a property's `GET` or `SET` accessor
+    pub accessor: Option<PropertyAccessor>,
+    /// the attributes attached to the POU (e.g. `{attribute 'hmi'}`)
+    pub attributes: Vec<Attribute>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// a `GET` or `SET` accessor of a `PROPERTY`. Accessors are lowered into methods of the
+/// property's owner: the getter returns the property's value, the setter takes the new
+/// value as an input named after the property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyAccessor {
+    pub property: String,
+    pub kind: AccessorKind,
+    pub access: AccessModifier,
+}
+
+/// a user defined attribute (`{attribute 'name' := 'value'}`) attached to a POU, variable or type.
+/// Attributes carry no meaning for the compiler (except for `strict` on enum types), they are passed on
+/// to external tools.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attribute {
+    pub name: String,
+    pub value: Option<String>,
+    pub location: SourceRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessorKind {
+    Get,
+    Set,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PolymorphismMode {
     None,
     Abstract,
@@ -177,13 +211,20 @@ impl Debug for Pou {
         if !self.generics.is_empty() {
             str.field("generics", &self.generics);
         }
+        if !self.attributes.is_empty() {
+            str.field("attributes", &self.attributes);
+        }
         str.finish()
     }
 }
 
 impl Pou {
     pub fn get_return_name(&self) -> &str {
-        Pou::calc_return_name(&self.name)
+        match &self.accessor {
+            // inside of a getter, the property's name is used to set the returned value
+            Some(PropertyAccessor { property, kind: AccessorKind::Get, .. }) => property.as_str(),
+            _ => Pou::calc_return_name(&self.name),
+        }
     }
 
     pub fn calc_return_name(pou_name: &str) -> &str {
@@ -191,7 +232,7 @@ impl Pou {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Implementation {
     pub name: String,
     pub type_name: String,
@@ -212,7 +253,7 @@ pub enum LinkageType {
     BuiltIn,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum AccessModifier {
     Private,
     Public,
@@ -305,6 +346,7 @@ pub struct CompilationUnit {
     pub units: Vec<Pou>,
     pub implementations: Vec<Implementation>,
     pub user_types: Vec<UserTypeDeclaration>,
+    pub var_config: Vec<ConfigVariable>,
     pub file_name: String,
     pub new_lines: NewLines,
 }
@@ -316,6 +358,7 @@ impl CompilationUnit {
             units: Vec::new(),
             implementations: Vec::new(),
             user_types: Vec::new(),
+            var_config: Vec::new(),
             file_name: file_name.to_string(),
             new_lines,
         }
@@ -328,7 +371,7 @@ impl CompilationUnit {
 
     /// imports all elements of the other CompilationUnit into this CompilationUnit
     ///
-    /// this will import all global_vars, units, implementations and types. The imported
+    /// this will import all global_vars, units, implementations, types and config variables. The imported
     /// structs are moved from the other unit into this unit
     /// # Arguments
     /// `other` the other CompilationUnit to import the elements from.
@@ -337,6 +380,7 @@ impl CompilationUnit {
         self.units.extend(other.units);
         self.implementations.extend(other.implementations);
         self.user_types.extend(other.user_types);
+        self.var_config.extend(other.var_config);
     }
 }
 
@@ -369,7 +413,7 @@ pub enum ArgumentProperty {
     ByRef,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct VariableBlock {
     pub access: AccessModifier,
     pub constant: bool,
@@ -396,6 +440,8 @@ pub struct Variable {
     pub initializer: Option<AstStatement>,
     pub address: Option<AstStatement>,
     pub location: SourceRange,
+    /// the attributes attached to the variable (e.g. `{attribute 'hmi'}`)
+    pub attributes: Vec<Attribute>,
 }
 
 impl Debug for Variable {
@@ -408,6 +454,9 @@ impl Debug for Variable {
         if self.address.is_some() {
             var.field("address", &self.address);
         }
+        if !self.attributes.is_empty() {
+            var.field("attributes", &self.attributes);
+        }
         var.finish()
     }
 }
@@ -422,6 +471,50 @@ impl Variable {
     }
 }
 
+/// an address assigned to a template variable (`AT %I*`) of an instance in a `VAR_CONFIG` block,
+/// e.g. `prg.fbs[1].a AT %IX1.0 : BOOL;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigVariable {
+    /// the reference to the configured instance
+    pub reference: AstStatement,
+    pub data_type_declaration: DataTypeDeclaration,
+    /// the assigned hardware address
+    pub address: AstStatement,
+    pub location: SourceRange,
+}
+
+impl ConfigVariable {
+    /// returns the name of the configured instance as it is listed in the hardware configuration
+    /// (e.g. `prg.fbs[1,2].a`) or None if the reference is not a path of members and literal indexes
+    pub fn get_instance_path(&self) -> Option<String> {
+        get_instance_path(&self.reference)
+    }
+}
+
+fn get_instance_path(reference: &AstStatement) -> Option<String> {
+    match reference {
+        AstStatement::Identifier { name, .. } => Some(name.clone()),
+        AstStatement::ReferenceExpr { access: ReferenceAccess::Member(member), base, .. } => {
+            let member = get_instance_path(member)?;
+            match base {
+                Some(base) => Some(format!("{}.{member}", get_instance_path(base)?)),
+                None => Some(member),
+            }
+        }
+        AstStatement::ReferenceExpr { access: ReferenceAccess::Index(index), base: Some(base), .. } => {
+            let indexes = flatten_expression_list(index)
+                .into_iter()
+                .map(|it| match it {
+                    AstStatement::Literal { kind: AstLiteral::Integer(value), .. } => Some(value.to_string()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{}[{}]", get_instance_path(base)?, indexes.join(",")))
+        }
+        _ => None,
+    }
+}
+
 pub trait DiagnosticInfo {
     fn get_description(&self) -> String;
     fn get_location(&self) -> SourceRange;
@@ -568,27 +661,34 @@ impl DataTypeDeclaration {
     }
 
     pub fn get_referenced_type(&self) -> Option<String> {
-        let DataTypeDeclaration::DataTypeReference {referenced_type, ..} = self else { return None };
+        let DataTypeDeclaration::DataTypeReference { referenced_type, .. } = self else { return None };
         Some(referenced_type.to_owned())
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct UserTypeDeclaration {
     pub data_type: DataType,
     pub initializer: Option<AstStatement>,
     pub location: SourceRange,
     /// stores the original scope for compiler-generated types
     pub scope: Option<String>,
+    /// the generic parameters of a generic type (e.g. `TYPE Pair<T : ANY_NUM> : STRUCT ...`)
+    pub generics: Vec<GenericBinding>,
+    /// the attributes attached to the type (e.g. `{attribute 'hmi'}`)
+    pub attributes: Vec<Attribute>,
 }
 
 impl Debug for UserTypeDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("UserTypeDeclaration")
-            .field("data_type", &self.data_type)
+        let mut str = f.debug_struct("UserTypeDeclaration");
+        str.field("data_type", &self.data_type)
             .field("initializer", &self.initializer)
-            .field("scope", &self.scope)
-            .finish()
+            .field("scope", &self.scope);
+        if !self.attributes.is_empty() {
+            str.field("attributes", &self.attributes);
+        }
+        str.finish()
     }
 }
 
@@ -598,6 +698,11 @@ pub enum DataType {
         name: Option<String>, //maybe None for inline structs
         variables: Vec<Variable>,
     },
+    /// a struct whose members share the same memory
+    UnionType {
+        name: Option<String>, //maybe None for inline unions
+        variables: Vec<Variable>,
+    },
     EnumType {
         name: Option<String>, //maybe empty for inline enums
         numeric_type: String,
@@ -617,6 +722,8 @@ pub enum DataType {
     PointerType {
         name: Option<String>,
         referenced_type: Box<DataTypeDeclaration>,
+        /// true for `REFERENCE TO` declarations which are dereferenced automatically
+        auto_deref: bool,
     },
     StringType {
         name: Option<String>,
@@ -632,17 +739,27 @@ pub enum DataType {
         generic_symbol: String,
         nature: TypeNature,
     },
+    /// an instance of a generic FUNCTION_BLOCK, CLASS or TYPE (e.g. `FIFO<INT>`)
+    GenericInstance {
+        name: Option<String>,
+        generic_type: String,
+        type_arguments: Vec<String>,
+        /// the generic parameters declared by `generic_type`, resolved when the instance is created
+        generics: Vec<GenericBinding>,
+    },
 }
 
 impl DataType {
     pub fn set_name(&mut self, new_name: String) {
         match self {
             DataType::StructType { name, .. }
+            | DataType::UnionType { name, .. }
             | DataType::EnumType { name, .. }
             | DataType::SubRangeType { name, .. }
             | DataType::ArrayType { name, .. }
             | DataType::PointerType { name, .. }
-            | DataType::StringType { name, .. } => *name = Some(new_name),
+            | DataType::StringType { name, .. }
+            | DataType::GenericInstance { name, .. } => *name = Some(new_name),
             DataType::GenericType { name, .. } => *name = new_name,
             DataType::VarArgs { .. } => {} //No names on varargs
         }
@@ -651,11 +768,13 @@ impl DataType {
     pub fn get_name(&self) -> Option<&str> {
         match &self {
             DataType::StructType { name, .. }
+            | DataType::UnionType { name, .. }
             | DataType::EnumType { name, .. }
             | DataType::ArrayType { name, .. }
             | DataType::PointerType { name, .. }
             | DataType::StringType { name, .. }
-            | DataType::SubRangeType { name, .. } => name.as_ref().map(|x| x.as_str()),
+            | DataType::SubRangeType { name, .. }
+            | DataType::GenericInstance { name, .. } => name.as_ref().map(|x| x.as_str()),
             DataType::GenericType { name, .. } => Some(name.as_str()),
             DataType::VarArgs { referenced_type, .. } => {
                 referenced_type.as_ref().and_then(|it| DataTypeDeclaration::get_name(it.as_ref()))
@@ -807,6 +926,12 @@ pub enum AstStatement {
         right: Box<AstStatement>,
         id: AstId,
     },
+    // ReferenceAssignment (REF=)
+    ReferenceAssignment {
+        left: Box<AstStatement>,
+        right: Box<AstStatement>,
+        id: AstId,
+    },
     //Call Statement
     CallStatement {
         operator: Box<AstStatement>,
@@ -837,6 +962,18 @@ pub enum AstStatement {
         location: SourceRange,
         id: AstId,
     },
+    // label: (a jump target)
+    LabelStatement {
+        name: String,
+        location: SourceRange,
+        id: AstId,
+    },
+    // JMP label
+    JumpStatement {
+        label: String,
+        location: SourceRange,
+        id: AstId,
+    },
 }
 
 impl Debug for AstStatement {
@@ -870,6 +1007,9 @@ impl Debug for AstStatement {
             AstStatement::OutputAssignment { left, right, .. } => {
                 f.debug_struct("OutputAssignment").field("left", left).field("right", right).finish()
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                f.debug_struct("ReferenceAssignment").field("left", left).field("right", right).finish()
+            }
             AstStatement::CallStatement { operator, parameters, .. } => f
                 .debug_struct("CallStatement")
                 .field("operator", operator)
@@ -939,6 +1079,12 @@ impl Debug for AstStatement {
             AstStatement::ReturnStatement { .. } => f.debug_struct("ReturnStatement").finish(),
             AstStatement::ContinueStatement { .. } => f.debug_struct("ContinueStatement").finish(),
             AstStatement::ExitStatement { .. } => f.debug_struct("ExitStatement").finish(),
+            AstStatement::LabelStatement { name, .. } => {
+                f.debug_struct("LabelStatement").field("name", name).finish()
+            }
+            AstStatement::JumpStatement { label, .. } => {
+                f.debug_struct("JumpStatement").field("label", label).finish()
+            }
             AstStatement::CastStatement { target, type_name, .. } => {
                 f.debug_struct("CastStatement").field("type_name", type_name).field("target", target).finish()
             }
@@ -986,7 +1132,8 @@ impl AstStatement {
                 let right_loc = right.get_location();
                 left_loc.span(&right_loc)
             }
-            AstStatement::OutputAssignment { left, right, .. } => {
+            AstStatement::OutputAssignment { left, right, .. }
+            | AstStatement::ReferenceAssignment { left, right, .. } => {
                 let left_loc = left.get_location();
                 let right_loc = right.get_location();
                 left_loc.span(&right_loc)
@@ -1000,6 +1147,8 @@ impl AstStatement {
             AstStatement::ReturnStatement { location, .. } => location.clone(),
             AstStatement::ContinueStatement { location, .. } => location.clone(),
             AstStatement::ExitStatement { location, .. } => location.clone(),
+            AstStatement::LabelStatement { location, .. } => location.clone(),
+            AstStatement::JumpStatement { location, .. } => location.clone(),
             AstStatement::CastStatement { location, .. } => location.clone(),
             AstStatement::ReferenceExpr { location, .. } => location.clone(),
         }
@@ -1021,12 +1170,15 @@ impl AstStatement {
             AstStatement::VlaRangeStatement { id, .. } => *id,
             AstStatement::Assignment { id, .. } => *id,
             AstStatement::OutputAssignment { id, .. } => *id,
+            AstStatement::ReferenceAssignment { id, .. } => *id,
             AstStatement::CallStatement { id, .. } => *id,
             AstStatement::ControlStatement { id, .. } => *id,
             AstStatement::CaseCondition { id, .. } => *id,
             AstStatement::ReturnStatement { id, .. } => *id,
             AstStatement::ContinueStatement { id, .. } => *id,
             AstStatement::ExitStatement { id, .. } => *id,
+            AstStatement::LabelStatement { id, .. } => *id,
+            AstStatement::JumpStatement { id, .. } => *id,
             AstStatement::CastStatement { id, .. } => *id,
             AstStatement::ReferenceExpr { id, .. } => *id,
         }
@@ -1244,9 +1396,42 @@ pub fn flatten_expression_list(list: &AstStatement) -> Vec<&AstStatement> {
     }
 }
 
+/// flattens the elements of an array-literal of structs. Other than `flatten_expression_list` this keeps
+/// the `member := value` assignments of a struct-literal together. Since parentheses are not kept in the
+/// AST, a list of assignments to distinct members is considered to be a single struct-literal.
+pub fn flatten_struct_literal_list(list: &AstStatement) -> Vec<&AstStatement> {
+    match list {
+        AstStatement::ExpressionList { expressions, .. } if is_struct_literal(expressions) => vec![list],
+        AstStatement::ExpressionList { expressions, .. } => {
+            expressions.iter().flat_map(flatten_struct_literal_list).collect()
+        }
+        AstStatement::MultipliedStatement { multiplier, element, .. } => {
+            std::iter::repeat(flatten_struct_literal_list(element))
+                .take(*multiplier as usize)
+                .flatten()
+                .collect()
+        }
+        _ => vec![list],
+    }
+}
+
+fn is_struct_literal(expressions: &[AstStatement]) -> bool {
+    let mut members = HashSet::new();
+    expressions.iter().all(|it| match it {
+        AstStatement::Assignment { left, .. } => {
+            left.get_flat_reference_name().is_some_and(|name| members.insert(name.to_lowercase()))
+        }
+        _ => false,
+    })
+}
+
 pub fn pre_process(unit: &mut CompilationUnit, id_provider: IdProvider) {
     pre_processor::pre_process(unit, id_provider)
 }
+
+pub fn instantiate_generics(units: &mut [CompilationUnit], id_provider: IdProvider) {
+    instantiation::instantiate_generics(units, id_provider)
+}
 impl Operator {
     /// returns true, if this operator results in a bool value
     pub fn is_bool_type(&self) -> bool {
@@ -0,0 +1,487 @@
+// Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+
+//! Instantiates generic FUNCTION_BLOCKs, CLASSes and TYPEs.
+//!
+//! A generic declaration like `FUNCTION_BLOCK FIFO<T : ANY_NUM>` is a template: it is removed from its
+//! compilation unit and copied once per distinct type argument it is used with (e.g. `FIFO<INT>`). The copies
+//! are named after the template and their type arguments (`FIFO__INT`) and every use of a generic symbol
+//! inside a copy is replaced with the corresponding type argument. This runs before the pre-processor so
+//! the instances are indexed like any other POU or type.
+
+use std::collections::{HashMap, HashSet};
+
+use plc_util::convention::generic_instance_name;
+
+use crate::{
+    ast::{
+        AstStatement, CompilationUnit, DataType, DataTypeDeclaration, GenericBinding, Implementation, Pou,
+        PouType, ReferenceAccess, UserTypeDeclaration, Variable,
+    },
+    control_statements::AstControlStatement,
+    literals::AstLiteral,
+    provider::IdProvider,
+};
+
+/// a generic declaration together with everything that has to be copied for each of its instances
+struct Template {
+    /// the unit declaring the template, its instances are added to the same unit
+    unit: usize,
+    name: String,
+    generics: Vec<GenericBinding>,
+    /// the FUNCTION_BLOCK or CLASS followed by its methods, actions and property accessors
+    pous: Vec<Pou>,
+    implementations: Vec<Implementation>,
+    user_type: Option<UserTypeDeclaration>,
+}
+
+/// a requested instance, e.g. `("FIFO__INT", "FIFO", ["INT"])` for `FIFO<INT>`
+type PendingInstance = (String, String, Vec<String>);
+
+pub fn instantiate_generics(units: &mut [CompilationUnit], mut id_provider: IdProvider) {
+    let templates = collect_templates(units);
+
+    let mut pending = vec![];
+    for unit in units.iter_mut() {
+        for pou in unit.units.iter_mut() {
+            resolve_pou_instances(pou, &templates, &mut pending);
+        }
+        for variable in unit.global_vars.iter_mut().flat_map(|it| it.variables.iter_mut()) {
+            resolve_variable_instances(variable, &templates, &mut pending);
+        }
+        for user_type in unit.user_types.iter_mut() {
+            resolve_data_type_instances(&mut user_type.data_type, &templates, &mut pending);
+        }
+    }
+
+    let mut instantiated = HashSet::new();
+    while let Some((name, generic_type, type_arguments)) = pending.pop() {
+        if !instantiated.insert(name.to_lowercase()) {
+            continue;
+        }
+        let Some(template) = templates.get(&generic_type.to_lowercase()) else { continue };
+
+        let mut instantiator = Instantiator::new(template, &name, &type_arguments, &mut id_provider);
+        let mut pous = template.pous.clone();
+        let mut implementations = template.implementations.clone();
+        let mut user_type = template.user_type.clone();
+        pous.iter_mut().for_each(|it| instantiator.instantiate_pou(it));
+        implementations.iter_mut().for_each(|it| instantiator.instantiate_implementation(it));
+        if let Some(user_type) = user_type.as_mut() {
+            instantiator.instantiate_user_type(user_type);
+        }
+
+        // an instance may itself use generic instances (e.g. a `FIFO<T>` using a `RingBuffer<T>`)
+        for pou in pous.iter_mut() {
+            resolve_pou_instances(pou, &templates, &mut pending);
+        }
+        if let Some(user_type) = user_type.as_mut() {
+            resolve_data_type_instances(&mut user_type.data_type, &templates, &mut pending);
+        }
+
+        let unit = &mut units[template.unit];
+        unit.units.append(&mut pous);
+        unit.implementations.append(&mut implementations);
+        unit.user_types.extend(user_type);
+    }
+}
+
+/// removes all generic FUNCTION_BLOCKs, CLASSes and TYPEs from the given units and returns them
+/// as templates, stored by their lowercase name
+fn collect_templates(units: &mut [CompilationUnit]) -> HashMap<String, Template> {
+    let mut templates = HashMap::new();
+    for (index, unit) in units.iter_mut().enumerate() {
+        for pou in unit.units.iter().filter(|it| is_generic_container(it)) {
+            templates.insert(
+                pou.name.to_lowercase(),
+                Template {
+                    unit: index,
+                    name: pou.name.clone(),
+                    generics: pou.generics.clone(),
+                    pous: vec![],
+                    implementations: vec![],
+                    user_type: None,
+                },
+            );
+        }
+
+        // the container is followed by its methods and property accessors
+        let (generic_pous, pous): (Vec<_>, Vec<_>) = std::mem::take(&mut unit.units)
+            .into_iter()
+            .partition(|it| templates.contains_key(&owner_name(&it.name).to_lowercase()));
+        unit.units = pous;
+        for pou in generic_pous {
+            if let Some(template) = templates.get_mut(&owner_name(&pou.name).to_lowercase()) {
+                template.pous.push(pou);
+            }
+        }
+
+        let (generic_types, user_types): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut unit.user_types).into_iter().partition(|it| !it.generics.is_empty());
+        unit.user_types = user_types;
+        for user_type in generic_types {
+            let name = user_type.data_type.get_name().unwrap_or_default().to_string();
+            templates.insert(
+                name.to_lowercase(),
+                Template {
+                    unit: index,
+                    name,
+                    generics: user_type.generics.clone(),
+                    pous: vec![],
+                    implementations: vec![],
+                    user_type: Some(user_type),
+                },
+            );
+        }
+    }
+
+    // actions may be declared in another unit than their container
+    for unit in units.iter_mut() {
+        let (generic_implementations, implementations): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut unit.implementations)
+                .into_iter()
+                .partition(|it| templates.contains_key(&owner_name(&it.name).to_lowercase()));
+        unit.implementations = implementations;
+        for implementation in generic_implementations {
+            if let Some(template) = templates.get_mut(&owner_name(&implementation.name).to_lowercase()) {
+                template.implementations.push(implementation);
+            }
+        }
+    }
+    templates
+}
+
+fn is_generic_container(pou: &Pou) -> bool {
+    !pou.generics.is_empty() && matches!(pou.pou_type, PouType::FunctionBlock | PouType::Class)
+}
+
+/// returns the name of the POU owning the given (qualified) name, e.g. `FIFO` for `FIFO.push`
+fn owner_name(name: &str) -> &str {
+    name.split('.').next().unwrap_or(name)
+}
+
+fn resolve_pou_instances(
+    pou: &mut Pou,
+    templates: &HashMap<String, Template>,
+    pending: &mut Vec<PendingInstance>,
+) {
+    for variable in pou.variable_blocks.iter_mut().flat_map(|it| it.variables.iter_mut()) {
+        resolve_variable_instances(variable, templates, pending);
+    }
+    if let Some(return_type) = pou.return_type.as_mut() {
+        resolve_declaration_instances(return_type, templates, pending);
+    }
+}
+
+fn resolve_variable_instances(
+    variable: &mut Variable,
+    templates: &HashMap<String, Template>,
+    pending: &mut Vec<PendingInstance>,
+) {
+    resolve_declaration_instances(&mut variable.data_type_declaration, templates, pending)
+}
+
+fn resolve_declaration_instances(
+    declaration: &mut DataTypeDeclaration,
+    templates: &HashMap<String, Template>,
+    pending: &mut Vec<PendingInstance>,
+) {
+    if let DataTypeDeclaration::DataTypeDefinition { data_type, .. } = declaration {
+        resolve_data_type_instances(data_type, templates, pending)
+    }
+}
+
+/// binds every generic instance in the given data type to its template and requests its instantiation.
+/// Instances of unknown templates or with the wrong number of type arguments are left unbound and
+/// reported by the validation
+fn resolve_data_type_instances(
+    data_type: &mut DataType,
+    templates: &HashMap<String, Template>,
+    pending: &mut Vec<PendingInstance>,
+) {
+    match data_type {
+        DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
+            for variable in variables.iter_mut() {
+                resolve_variable_instances(variable, templates, pending)
+            }
+        }
+        DataType::ArrayType { referenced_type, .. }
+        | DataType::PointerType { referenced_type, .. }
+        | DataType::VarArgs { referenced_type: Some(referenced_type), .. } => {
+            resolve_declaration_instances(referenced_type, templates, pending)
+        }
+        DataType::GenericInstance { name, generic_type, type_arguments, generics } => {
+            if let Some(template) = templates.get(&generic_type.to_lowercase()) {
+                *generics = template.generics.clone();
+                if generics.len() == type_arguments.len() {
+                    // a named instance (`TYPE IntFifo : FIFO<INT>; END_TYPE`) is instantiated under its own name
+                    let instance_name =
+                        name.clone().unwrap_or_else(|| generic_instance_name(&template.name, type_arguments));
+                    pending.push((instance_name, template.name.clone(), type_arguments.clone()));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// copies a template's declarations for one set of type arguments
+struct Instantiator<'a> {
+    template_name: &'a str,
+    instance_name: &'a str,
+    /// the type names to replace, stored by their lowercase name: every generic symbol is replaced
+    /// with its type argument and the template's own name with the instance's name
+    replacements: HashMap<String, String>,
+    id_provider: &'a mut IdProvider,
+}
+
+impl<'a> Instantiator<'a> {
+    fn new(
+        template: &'a Template,
+        instance_name: &'a str,
+        type_arguments: &[String],
+        id_provider: &'a mut IdProvider,
+    ) -> Self {
+        let mut replacements = template
+            .generics
+            .iter()
+            .zip(type_arguments)
+            .map(|(binding, argument)| (binding.name.to_lowercase(), argument.clone()))
+            .collect::<HashMap<_, _>>();
+        replacements.insert(template.name.to_lowercase(), instance_name.to_string());
+        Instantiator { template_name: &template.name, instance_name, replacements, id_provider }
+    }
+
+    /// renames `FIFO` and `FIFO.push` to `FIFO__INT` and `FIFO__INT.push`
+    fn rename(&self, name: &mut String) {
+        let owner = owner_name(name);
+        if owner.eq_ignore_ascii_case(self.template_name) {
+            *name = format!("{}{}", self.instance_name, &name[owner.len()..]);
+        }
+    }
+
+    fn replace_type_name(&self, type_name: &mut String) {
+        if let Some(replacement) = self.replacements.get(&type_name.to_lowercase()) {
+            *type_name = replacement.clone();
+        }
+    }
+
+    fn instantiate_pou(&mut self, pou: &mut Pou) {
+        self.rename(&mut pou.name);
+        if let PouType::Method { owner_class } = &mut pou.pou_type {
+            self.rename(owner_class);
+        }
+        pou.generics.clear();
+        for variable in pou.variable_blocks.iter_mut().flat_map(|it| it.variables.iter_mut()) {
+            self.instantiate_variable(variable);
+        }
+        if let Some(return_type) = pou.return_type.as_mut() {
+            self.instantiate_declaration(return_type);
+        }
+    }
+
+    fn instantiate_implementation(&mut self, implementation: &mut Implementation) {
+        self.rename(&mut implementation.name);
+        self.rename(&mut implementation.type_name);
+        if let PouType::Method { owner_class } = &mut implementation.pou_type {
+            self.rename(owner_class);
+        }
+        implementation.generic = false;
+        implementation.statements.iter_mut().for_each(|it| self.instantiate_statement(it));
+    }
+
+    fn instantiate_user_type(&mut self, user_type: &mut UserTypeDeclaration) {
+        user_type.data_type.set_name(self.instance_name.to_string());
+        user_type.generics.clear();
+        self.instantiate_data_type(&mut user_type.data_type);
+        if let Some(initializer) = user_type.initializer.as_mut() {
+            self.instantiate_statement(initializer);
+        }
+    }
+
+    fn instantiate_variable(&mut self, variable: &mut Variable) {
+        self.instantiate_declaration(&mut variable.data_type_declaration);
+        if let Some(initializer) = variable.initializer.as_mut() {
+            self.instantiate_statement(initializer);
+        }
+        if let Some(address) = variable.address.as_mut() {
+            self.instantiate_statement(address);
+        }
+    }
+
+    fn instantiate_declaration(&mut self, declaration: &mut DataTypeDeclaration) {
+        match declaration {
+            DataTypeDeclaration::DataTypeReference { referenced_type, .. } => {
+                self.replace_type_name(referenced_type)
+            }
+            DataTypeDeclaration::DataTypeDefinition { data_type, .. } => {
+                self.instantiate_data_type(data_type)
+            }
+        }
+    }
+
+    fn instantiate_data_type(&mut self, data_type: &mut DataType) {
+        match data_type {
+            DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
+                variables.iter_mut().for_each(|it| self.instantiate_variable(it))
+            }
+            DataType::EnumType { numeric_type, elements, .. } => {
+                self.replace_type_name(numeric_type);
+                self.instantiate_statement(elements);
+            }
+            DataType::SubRangeType { referenced_type, bounds, .. } => {
+                self.replace_type_name(referenced_type);
+                if let Some(bounds) = bounds.as_mut() {
+                    self.instantiate_statement(bounds);
+                }
+            }
+            DataType::ArrayType { bounds, referenced_type, .. } => {
+                self.instantiate_statement(bounds);
+                self.instantiate_declaration(referenced_type);
+            }
+            DataType::PointerType { referenced_type, .. }
+            | DataType::VarArgs { referenced_type: Some(referenced_type), .. } => {
+                self.instantiate_declaration(referenced_type)
+            }
+            DataType::StringType { size: Some(size), .. } => self.instantiate_statement(size),
+            DataType::GenericInstance { type_arguments, .. } => {
+                type_arguments.iter_mut().for_each(|it| self.replace_type_name(it))
+            }
+            _ => {}
+        }
+    }
+
+    /// gives the copied statement fresh ids, so the annotations of different instances do not clash,
+    /// and replaces the generic symbols used as cast targets (e.g. `T#1`)
+    fn instantiate_statement(&mut self, statement: &mut AstStatement) {
+        match statement {
+            AstStatement::EmptyStatement { id, .. }
+            | AstStatement::DefaultValue { id, .. }
+            | AstStatement::Identifier { id, .. }
+            | AstStatement::VlaRangeStatement { id }
+            | AstStatement::ExitStatement { id, .. }
+            | AstStatement::ContinueStatement { id, .. }
+            | AstStatement::ReturnStatement { id, .. }
+            | AstStatement::LabelStatement { id, .. }
+            | AstStatement::JumpStatement { id, .. } => *id = self.id_provider.next_id(),
+            AstStatement::Literal { kind, id, .. } => {
+                *id = self.id_provider.next_id();
+                if let AstLiteral::Array(array) = kind {
+                    if let Some(elements) = array.elements.as_mut() {
+                        self.instantiate_statement(elements);
+                    }
+                }
+            }
+            AstStatement::CastStatement { target, type_name, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.replace_type_name(type_name);
+                self.instantiate_statement(target);
+            }
+            AstStatement::MultipliedStatement { element, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(element);
+            }
+            AstStatement::ReferenceExpr { access, base, id, .. } => {
+                *id = self.id_provider.next_id();
+                match access {
+                    ReferenceAccess::Member(it) | ReferenceAccess::Index(it) => {
+                        self.instantiate_statement(it)
+                    }
+                    ReferenceAccess::Cast(it) => {
+                        self.instantiate_statement(it);
+                        if let Some(AstStatement::ReferenceExpr {
+                            access: ReferenceAccess::Member(type_name),
+                            base: None,
+                            ..
+                        }) = base.as_deref_mut()
+                        {
+                            if let AstStatement::Identifier { name, .. } = type_name.as_mut() {
+                                self.replace_type_name(name);
+                            }
+                        }
+                    }
+                    ReferenceAccess::Deref | ReferenceAccess::Address => {}
+                }
+                if let Some(base) = base.as_mut() {
+                    self.instantiate_statement(base);
+                }
+            }
+            AstStatement::DirectAccess { index, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(index);
+            }
+            AstStatement::HardwareAccess { address, id, .. } => {
+                *id = self.id_provider.next_id();
+                address.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+            AstStatement::BinaryExpression { left, right, id, .. }
+            | AstStatement::Assignment { left, right, id }
+            | AstStatement::OutputAssignment { left, right, id }
+            | AstStatement::ReferenceAssignment { left, right, id } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(left);
+                self.instantiate_statement(right);
+            }
+            AstStatement::RangeStatement { start, end, id } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(start);
+                self.instantiate_statement(end);
+            }
+            AstStatement::UnaryExpression { value, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(value);
+            }
+            AstStatement::ExpressionList { expressions, id } => {
+                *id = self.id_provider.next_id();
+                expressions.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+            AstStatement::CallStatement { operator, parameters, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(operator);
+                if let Some(parameters) = parameters.as_mut() {
+                    self.instantiate_statement(parameters);
+                }
+            }
+            AstStatement::CaseCondition { condition, id } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_statement(condition);
+            }
+            AstStatement::ControlStatement { kind, id, .. } => {
+                *id = self.id_provider.next_id();
+                self.instantiate_control_statement(kind);
+            }
+        }
+    }
+
+    fn instantiate_control_statement(&mut self, kind: &mut AstControlStatement) {
+        match kind {
+            AstControlStatement::If(it) => {
+                for block in it.blocks.iter_mut() {
+                    self.instantiate_statement(&mut block.condition);
+                    block.body.iter_mut().for_each(|it| self.instantiate_statement(it));
+                }
+                it.else_block.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+            AstControlStatement::ForLoop(it) => {
+                self.instantiate_statement(&mut it.counter);
+                self.instantiate_statement(&mut it.start);
+                self.instantiate_statement(&mut it.end);
+                if let Some(by_step) = it.by_step.as_mut() {
+                    self.instantiate_statement(by_step);
+                }
+                it.body.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+            AstControlStatement::WhileLoop(it) | AstControlStatement::RepeatLoop(it) => {
+                self.instantiate_statement(&mut it.condition);
+                it.body.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+            AstControlStatement::Case(it) => {
+                self.instantiate_statement(&mut it.selector);
+                for block in it.case_blocks.iter_mut() {
+                    self.instantiate_statement(&mut block.condition);
+                    block.body.iter_mut().for_each(|it| self.instantiate_statement(it));
+                }
+                it.else_block.iter_mut().for_each(|it| self.instantiate_statement(it));
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use plc_util::convention::internal_type_name;
+use plc_util::convention::{generic_instance_name, internal_type_name};
 
 use crate::{
     ast::{
@@ -50,7 +50,7 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
     for dt in unit.user_types.iter_mut() {
         {
             match &mut dt.data_type {
-                DataType::StructType { name, variables, .. } => {
+                DataType::StructType { name, variables, .. } | DataType::UnionType { name, variables } => {
                     let name: &str = name.as_ref().map(|it| it.as_str()).unwrap_or("undefined");
                     variables
                         .iter_mut()
@@ -63,7 +63,7 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
                 {
                     let name: &str = name.as_ref().map(|it| it.as_str()).unwrap_or("undefined");
 
-                    let type_name = internal_type_name("", name);
+                    let type_name = implicit_type_name(referenced_type, internal_type_name("", name));
                     let type_ref = DataTypeDeclaration::DataTypeReference {
                         referenced_type: type_name.clone(),
                         location: SourceRange::undefined(), //return_type.get_location(),
@@ -74,7 +74,14 @@ pub fn pre_process(unit: &mut CompilationUnit, mut id_provider: IdProvider) {
                     {
                         data_type.set_name(type_name);
                         add_nested_datatypes(name, &mut data_type, &mut new_types, &location);
-                        let data_type = UserTypeDeclaration { data_type, initializer: None, location, scope };
+                        let data_type = UserTypeDeclaration {
+                            data_type,
+                            initializer: None,
+                            location,
+                            scope,
+                            generics: vec![],
+                            attributes: vec![],
+                        };
                         new_types.push(data_type);
                     }
                 }
@@ -187,6 +194,8 @@ fn preprocess_generic_structs(pou: &mut Pou) -> Vec<UserTypeDeclaration> {
             initializer: None,
             scope: Some(pou.name.clone()),
             location: pou.location.clone(),
+            generics: vec![],
+            attributes: vec![],
         };
         types.push(data_type);
         generic_types.insert(binding.name.clone(), new_name);
@@ -203,7 +212,7 @@ fn preprocess_generic_structs(pou: &mut Pou) -> Vec<UserTypeDeclaration> {
 fn preprocess_return_type(pou: &mut Pou, types: &mut Vec<UserTypeDeclaration>) {
     if let Some(return_type) = &pou.return_type {
         if should_generate_implicit(return_type) {
-            let type_name = format!("__{}_return", &pou.name); // TODO: Naming convention (see plc_util/src/convention.rs)
+            let type_name = implicit_type_name(return_type, format!("__{}_return", &pou.name)); // TODO: Naming convention (see plc_util/src/convention.rs)
             let type_ref = DataTypeDeclaration::DataTypeReference {
                 referenced_type: type_name.clone(),
                 location: return_type.get_location(),
@@ -213,7 +222,14 @@ fn preprocess_return_type(pou: &mut Pou, types: &mut Vec<UserTypeDeclaration>) {
             {
                 data_type.set_name(type_name);
                 add_nested_datatypes(pou.name.as_str(), &mut data_type, types, &location);
-                let data_type = UserTypeDeclaration { data_type, initializer: None, location, scope };
+                let data_type = UserTypeDeclaration {
+                    data_type,
+                    initializer: None,
+                    location,
+                    scope,
+                    generics: vec![],
+                    attributes: vec![],
+                };
                 types.push(data_type);
             }
         }
@@ -237,14 +253,24 @@ fn pre_process_variable_data_type(
     variable: &mut Variable,
     types: &mut Vec<UserTypeDeclaration>,
 ) {
-    let new_type_name = internal_type_name(&format!("{container_name}_"), &variable.name);
+    let new_type_name = implicit_type_name(
+        &variable.data_type_declaration,
+        internal_type_name(&format!("{container_name}_"), &variable.name),
+    );
     if let DataTypeDeclaration::DataTypeDefinition { mut data_type, location, scope } =
         variable.replace_data_type_with_reference_to(new_type_name.clone())
     {
         // create index entry
         add_nested_datatypes(new_type_name.as_str(), &mut data_type, types, &location);
         data_type.set_name(new_type_name);
-        types.push(UserTypeDeclaration { data_type, initializer: None, location, scope });
+        types.push(UserTypeDeclaration {
+            data_type,
+            initializer: None,
+            location,
+            scope,
+            generics: vec![],
+            attributes: vec![],
+        });
     }
     //make sure it gets generated
 }
@@ -255,13 +281,37 @@ fn add_nested_datatypes(
     types: &mut Vec<UserTypeDeclaration>,
     location: &SourceRange,
 ) {
-    let new_type_name = format!("{container_name}_"); // TODO: Naming convention (see plc_util/src/convention.rs)
+    let new_type_name = match datatype {
+        DataType::ArrayType { referenced_type, .. } | DataType::PointerType { referenced_type, .. } => {
+            implicit_type_name(referenced_type, format!("{container_name}_")) // TODO: Naming convention (see plc_util/src/convention.rs)
+        }
+        _ => format!("{container_name}_"), // TODO: Naming convention (see plc_util/src/convention.rs)
+    };
     if let Some(DataTypeDeclaration::DataTypeDefinition { mut data_type, location: inner_location, scope }) =
         datatype.replace_data_type_with_reference_to(new_type_name.clone(), location)
     {
         data_type.set_name(new_type_name.clone());
         add_nested_datatypes(new_type_name.as_str(), &mut data_type, types, &inner_location);
-        types.push(UserTypeDeclaration { data_type, initializer: None, location: location.clone(), scope });
+        types.push(UserTypeDeclaration {
+            data_type,
+            initializer: None,
+            location: location.clone(),
+            scope,
+            generics: vec![],
+            attributes: vec![],
+        });
+    }
+}
+
+/// generic instances are named after their template and type arguments (e.g. `FIFO__INT`), so every
+/// declaration of the same instance refers to the same type
+fn implicit_type_name(declaration: &DataTypeDeclaration, default_name: String) -> String {
+    match declaration {
+        DataTypeDeclaration::DataTypeDefinition {
+            data_type: DataType::GenericInstance { generic_type, type_arguments, .. },
+            ..
+        } => generic_instance_name(generic_type, type_arguments),
+        _ => default_name,
     }
 }
 
@@ -57,6 +57,47 @@ impl Diagnostic {
         }
     }
 
+    pub fn invalid_instance_path(range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: "Expected an instance path of members and literal array indexes (e.g. prg.fbs[1].a)"
+                .to_string(),
+            range: vec![range],
+            err_no: ErrNo::syntax__unexpected_token,
+        }
+    }
+
+    pub fn invalid_pragma_condition(reason: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Invalid pragma condition: {reason}"),
+            range: vec![range],
+            err_no: ErrNo::syntax__invalid_pragma,
+        }
+    }
+
+    pub fn invalid_attribute(range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: "Invalid attribute, expected {attribute 'name'} or {attribute 'name' := 'value'}".into(),
+            range: vec![range],
+            err_no: ErrNo::syntax__invalid_pragma,
+        }
+    }
+
+    pub fn unmatched_conditional_pragma(pragma: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("`{pragma}` has no matching `{{IF ...}}`"),
+            range: vec![range],
+            err_no: ErrNo::syntax__invalid_pragma,
+        }
+    }
+
+    pub fn unclosed_conditional_pragma(range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: "Missing `{END_IF}` for conditional pragma".into(),
+            range: vec![range],
+            err_no: ErrNo::syntax__invalid_pragma,
+        }
+    }
+
     pub fn unexpected_initializer_on_function_return(range: SourceRange) -> Diagnostic {
         Diagnostic::SyntaxError {
             message: "Return types cannot have a default value".into(),
@@ -363,6 +404,13 @@ impl Diagnostic {
         Diagnostic::GeneralError { message: reason.to_string(), err_no: ErrNo::general__param_err }
     }
 
+    pub fn tests_failed(failed: usize, total: usize) -> Diagnostic {
+        Diagnostic::GeneralError {
+            message: format!("{failed} of {total} tests failed"),
+            err_no: ErrNo::general__test_failure,
+        }
+    }
+
     pub fn llvm_error(file: &str, llvm_error: &str) -> Diagnostic {
         Diagnostic::GeneralError {
             message: format!("{file}: Internal llvm error: {:}", llvm_error),
@@ -400,6 +448,29 @@ impl Diagnostic {
         }
     }
 
+    pub fn not_a_generic_type(type_name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("`{type_name}` is not a generic type"),
+            range: vec![location],
+            err_no: ErrNo::type__invalid_generic_instance,
+        }
+    }
+
+    pub fn invalid_type_argument_count(
+        type_name: &str,
+        expected: usize,
+        received: usize,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid type argument count for `{type_name}`. Received {received} type arguments while {expected} were expected."
+            ),
+            range: vec![location],
+            err_no: ErrNo::type__invalid_generic_instance,
+        }
+    }
+
     pub fn unknown_type_nature(nature: &str, location: SourceRange) -> Diagnostic {
         Diagnostic::SyntaxError {
             message: format!("Unknown type nature {nature}."),
@@ -518,6 +589,46 @@ impl Diagnostic {
         }
     }
 
+    pub fn undefined_label(label: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Could not find label `{label}`"),
+            range: vec![range],
+            err_no: ErrNo::jump__undefined_label,
+        }
+    }
+
+    pub fn duplicate_label(label: &str, range: SourceRange, first_definition: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Duplicate label `{label}`"),
+            range: vec![range, first_definition],
+            err_no: ErrNo::jump__duplicate_label,
+        }
+    }
+
+    pub fn jump_into_loop(label: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Cannot jump to label `{label}`, jumping into a loop is not allowed"),
+            range: vec![range],
+            err_no: ErrNo::jump__into_loop,
+        }
+    }
+
+    pub fn missing_property_accessor(property: &str, accessor: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Property `{property}` has no {accessor} accessor"),
+            range: vec![range],
+            err_no: ErrNo::property__missing_accessor,
+        }
+    }
+
+    pub fn illegal_property_access(property: &str, access: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Illegal access to {access} property `{property}`"),
+            range: vec![range],
+            err_no: ErrNo::property__illegal_access,
+        }
+    }
+
     pub fn invalid_case_condition(range: SourceRange) -> Diagnostic {
         Diagnostic::SyntaxError {
             message: "Invalid case condition!".into(),
@@ -650,6 +761,54 @@ impl Diagnostic {
         }
     }
 
+    pub fn invalid_test_function(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid test function: {message}"),
+            range: vec![range],
+            err_no: ErrNo::pou__invalid_test,
+        }
+    }
+
+    pub fn invalid_cycle_budget(value: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid cycle budget '{value}', expected a positive TIME literal (e.g. T#5ms)"),
+            range: vec![range],
+            err_no: ErrNo::pou__invalid_cycle_budget,
+        }
+    }
+
+    pub fn invalid_union(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid union: {message}"),
+            range: vec![range],
+            err_no: ErrNo::type__invalid_union,
+        }
+    }
+
+    pub fn invalid_dynamic_memory(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid dynamic memory usage: {message}"),
+            range: vec![range],
+            err_no: ErrNo::call__invalid_dynamic_memory,
+        }
+    }
+
+    pub fn invalid_reference_binding(message: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid reference binding: {message}"),
+            range: vec![range],
+            err_no: ErrNo::reference__invalid_binding,
+        }
+    }
+
+    pub fn unbound_reference(name: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Reference `{name}` is used before it is bound"),
+            range: vec![range],
+            err_no: ErrNo::reference__unbound,
+        }
+    }
+
     pub fn invalid_vla_container(message: String, range: SourceRange) -> Diagnostic {
         Diagnostic::SemanticError { message, range: vec![range], err_no: ErrNo::vla__invalid_container }
     }
@@ -698,6 +857,76 @@ impl Diagnostic {
             err_no: ErrNo::var__invalid_enum_variant,
         }
     }
+
+    pub fn invalid_hardware_bit(bit: u64, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid bit index {bit}, a byte only has the bits 0 to 7"),
+            range: vec![range],
+            err_no: ErrNo::var__invalid_hardware_address,
+        }
+    }
+
+    pub fn hardware_size_mismatch(type_name: &str, access: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::ImprovementSuggestion {
+            message: format!("The size of {type_name} does not match the size of a {access} address"),
+            range: vec![range],
+        }
+    }
+
+    pub fn hardware_direction_conflict(name: &str, kind: &str, area: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::ImprovementSuggestion {
+            message: format!("{name} is an {kind} of its POU but is bound to an {area} address"),
+            range: vec![range],
+        }
+    }
+
+    pub fn overlapping_hardware_addresses(name: &str, other: &str, range: Vec<SourceRange>) -> Diagnostic {
+        Diagnostic::ImprovementSuggestion {
+            message: format!("The output addresses of {name} and {other} overlap"),
+            range,
+        }
+    }
+
+    pub fn unresolved_template_address(name: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("The template address of {name} is not assigned in a VAR_CONFIG block"),
+            range: vec![range],
+            err_no: ErrNo::var__unresolved_template_address,
+        }
+    }
+
+    pub fn invalid_config_variable(name: &str, reason: &str, range: Vec<SourceRange>) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("Invalid VAR_CONFIG entry for {name}: {reason}"),
+            range,
+            err_no: ErrNo::var__invalid_config_variable,
+        }
+    }
+
+    pub fn invalid_trace(name: &str, reason: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!("{name} cannot be traced: {reason}"),
+            range: vec![range],
+            err_no: ErrNo::var__invalid_trace,
+        }
+    }
+
+    pub fn strict_enum_mismatch(enum_type: &str, other_type: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SemanticError {
+            message: format!(
+                "Strict enum {enum_type} cannot be mixed with {other_type}, use an explicit conversion"
+            ),
+            range: vec![range],
+            err_no: ErrNo::type__strict_enum_mismatch,
+        }
+    }
+
+    pub fn unhandled_enum_elements(enum_type: &str, elements: &[&str], range: SourceRange) -> Diagnostic {
+        Diagnostic::ImprovementSuggestion {
+            message: format!("CASE over {enum_type} does not handle: {}", elements.join(", ")),
+            range: vec![range],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -6,12 +6,14 @@ pub enum ErrNo {
     //general
     general__io_err,
     general__param_err,
+    general__test_failure,
     duplicate_symbol,
 
     //syntax
     syntax__generic_error,
     syntax__missing_token,
     syntax__unexpected_token,
+    syntax__invalid_pragma,
 
     //semantic
     // pou related
@@ -21,10 +23,13 @@ pub enum ErrNo {
     pou__empty_variable_block,
     pou__missing_action_container,
     pou__recursive_data_structure,
+    pou__invalid_test,
+    pou__invalid_cycle_budget,
 
     // call
     call__invalid_parameter_type,
     call__invalid_parameter_count,
+    call__invalid_dynamic_memory,
 
     //variable related
     var__unresolved_constant,
@@ -36,6 +41,10 @@ pub enum ErrNo {
     var__assigning_to_var_input_ref,
     var__overflow,
     var__invalid_enum_variant,
+    var__invalid_hardware_address,
+    var__unresolved_template_address,
+    var__invalid_config_variable,
+    var__invalid_trace,
 
     //array related
     arr__invalid_array_assignment,
@@ -49,6 +58,8 @@ pub enum ErrNo {
     reference__unresolved,
     reference__illegal_access,
     reference__expected,
+    reference__invalid_binding,
+    reference__unbound,
 
     //type related
     type__cast_error,
@@ -66,9 +77,12 @@ pub enum ErrNo {
     type__invalid_nature,
     type__unknown_nature,
     type__unresolved_generic,
+    type__invalid_generic_instance,
     type__incompatible_size,
     type__invalid_operation,
     type__invalid_name,
+    type__invalid_union,
+    type__strict_enum_mismatch,
 
     //codegen related
     codegen__general,
@@ -84,4 +98,13 @@ pub enum ErrNo {
     case__duplicate_condition,
     case__case_condition_outside_case_statement,
     case__invalid_case_condition,
+
+    //jumps and labels
+    jump__undefined_label,
+    jump__duplicate_label,
+    jump__into_loop,
+
+    //properties
+    property__missing_accessor,
+    property__illegal_access,
 }
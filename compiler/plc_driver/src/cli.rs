@@ -3,7 +3,9 @@ use clap::{ArgGroup, CommandFactory, ErrorKind, Parser, Subcommand};
 use encoding_rs::Encoding;
 use std::{ffi::OsStr, num::ParseIntError, path::PathBuf};
 
-use plc::{output::FormatOption, ConfigFormat, DebugLevel, ErrorFormat, Target, Threads};
+use plc::{
+    coverage::CoverageFormat, output::FormatOption, ConfigFormat, DebugLevel, ErrorFormat, Target, Threads,
+};
 
 pub type ParameterError = clap::Error;
 
@@ -86,6 +88,16 @@ pub struct CompileParameters {
     #[clap(name = "include", long, short = 'i', help = "Include source files for external functions")]
     pub includes: Vec<String>,
 
+    #[clap(
+        name = "define",
+        long,
+        short = 'D',
+        help = "Define a symbol for the conditional compilation pragmas as NAME or NAME=VALUE",
+        global = true,
+        parse(try_from_str = parse_define)
+    )]
+    pub defines: Vec<(String, String)>,
+
     #[clap(
         name = "hardware-conf",
         long,
@@ -97,6 +109,30 @@ pub struct CompileParameters {
     ) ]
     pub hardware_config: Option<String>,
 
+    #[clap(
+        name = "pretty-printers",
+        long,
+        global = true,
+        help = "Generate gdb and lldb pretty-printers for the compiled types into the given directory"
+    )]
+    pub pretty_printers: Option<String>,
+
+    #[clap(
+        name = "symbols",
+        long,
+        global = true,
+        help = "Generate the symbol table of all accessible variables as JSON, PLCopen XML and OPC UA NodeSet2 into the given directory"
+    )]
+    pub symbols: Option<String>,
+
+    #[clap(
+        name = "export-xml",
+        long,
+        global = true,
+        help = "Export the types, POUs and global variables of the structured text sources to the given PLCopen XML file"
+    )]
+    pub export_xml: Option<String>,
+
     #[clap(
         name = "optimization",
         long,
@@ -161,6 +197,62 @@ pub struct CompileParameters {
     #[clap(name = "check", long, help = "Check only, do not generate any output", global = true)]
     pub check_only: bool,
 
+    #[clap(
+        name = "coverage",
+        long,
+        help = "Instrument statements and branches with coverage counters",
+        global = true
+    )]
+    pub coverage: bool,
+
+    #[clap(
+        name = "monitor-cycles",
+        long,
+        help = "Measure the execution time of every program and call the watchdog when its cycle budget is exceeded",
+        global = true
+    )]
+    pub monitor_cycles: bool,
+
+    #[clap(
+        name = "trace",
+        long,
+        help = "Record the variables marked with {attribute 'trace'} at the end of every program call",
+        global = true
+    )]
+    pub trace: bool,
+
+    #[clap(
+        name = "process-image",
+        long,
+        help = "Map the variables bound to %I, %Q and %M addresses into exported process image buffers",
+        global = true
+    )]
+    pub process_image: bool,
+
+    #[clap(
+        name = "embed-symbols",
+        long,
+        help = "Embed a symbol table into the output, used to access variables by name at runtime",
+        global = true
+    )]
+    pub embed_symbols: bool,
+
+    #[clap(
+        name = "no-dynamic-memory",
+        long,
+        help = "Report an error for every use of __NEW and __DELETE",
+        global = true
+    )]
+    pub no_dynamic_memory: bool,
+
+    #[clap(
+        name = "strict-enums",
+        long,
+        help = "Only allow enums to be compared, assigned and switched against their own type",
+        global = true
+    )]
+    pub strict_enums: bool,
+
     #[clap(subcommand)]
     pub commands: Option<SubCommands>,
 }
@@ -196,12 +288,58 @@ pub enum SubCommands {
         )]
         build_config: Option<String>,
     },
+
+    /// Creates a coverage report from the profiles collected by a program built with --coverage.
+    ///
+    /// The report is written to the file given with -o, or coverage.info (lcov) / coverage.html (html)
+    Coverage {
+        /// The coverage map written during the build (<output>.plccov)
+        coverage_map: String,
+
+        #[clap(
+            name = "profile",
+            long,
+            help = "The profiles written by the instrumented program",
+            default_value = plc::coverage::DEFAULT_PROFILE_FILE
+        )]
+        profiles: Vec<String>,
+
+        #[clap(name = "format", long, help = "The format of the report", arg_enum, default_value = "lcov")]
+        format: CoverageFormat,
+    },
+
+    /// Converts the samples recorded by a program built with --trace into a value change dump (VCD).
+    ///
+    /// The dump is written to the file given with -o, or trace.vcd
+    Trace {
+        /// The trace file written by the traced program
+        #[clap(default_value = plc::trace::DEFAULT_TRACE_FILE)]
+        trace_file: String,
+    },
+
+    /// Runs the functions marked with {test} and reports their results.
+    Test {
+        #[clap(
+            parse(try_from_str = validate_config)
+        )]
+        build_config: Option<String>,
+
+        #[clap(name = "junit", long, help = "Write the results as a JUnit XML report to the given file")]
+        junit: Option<String>,
+
+        #[clap(name = "filter", long, help = "Only run the tests whose name contains the given text")]
+        filter: Option<String>,
+    },
 }
 
 impl SubCommands {
     pub fn get_build_configuration(&self) -> Option<&str> {
-        let (SubCommands::Build { build_config, .. } | SubCommands::Check { build_config }) = self;
-        build_config.as_deref()
+        match self {
+            SubCommands::Build { build_config, .. }
+            | SubCommands::Check { build_config }
+            | SubCommands::Test { build_config, .. } => build_config.as_deref(),
+            SubCommands::Coverage { .. } | SubCommands::Trace { .. } => None,
+        }
     }
 }
 
@@ -209,6 +347,15 @@ fn parse_encoding(encoding: &str) -> Result<&'static Encoding, String> {
     Encoding::for_label(encoding.as_bytes()).ok_or(format!("Unknown encoding {encoding}"))
 }
 
+fn parse_define(define: &str) -> Result<(String, String), String> {
+    let (name, value) = define.split_once('=').unwrap_or((define, plc::lexer::DEFAULT_DEFINE_VALUE));
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid define {define}, expected NAME or NAME=VALUE"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
 fn validate_config(config_name: &str) -> Result<String, String> {
     if get_config_format(config_name).is_some() {
         Ok(config_name.to_string())
@@ -325,7 +472,7 @@ impl CompileParameters {
 mod cli_tests {
     use super::{CompileParameters, SubCommands};
     use clap::{CommandFactory, ErrorKind};
-    use plc::{output::FormatOption, ConfigFormat, ErrorFormat, OptimizationLevel};
+    use plc::{coverage::CoverageFormat, output::FormatOption, ConfigFormat, ErrorFormat, OptimizationLevel};
     use pretty_assertions::assert_eq;
     use std::ffi::OsStr;
     use std::fmt::Debug;
@@ -427,6 +574,19 @@ mod cli_tests {
         assert_eq!(parameters.target, vec!["x86_64-linux-gnu".into()]);
     }
 
+    #[test]
+    fn defines_are_parsed_as_name_value_pairs() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("alpha.st", "-D", "MACHINE=press_2", "-DDEBUG"))
+                .unwrap();
+
+        assert_eq!(
+            parameters.defines,
+            vec![("MACHINE".to_string(), "press_2".to_string()), ("DEBUG".to_string(), "TRUE".to_string())]
+        );
+        expect_argument_error(vec_of_strings!("alpha.st", "-D", "=1"), ErrorKind::ValueValidation);
+    }
+
     #[test]
     fn test_optimization_levels() {
         let parameters = CompileParameters::parse(vec_of_strings!("alpha.st")).unwrap();
@@ -647,6 +807,98 @@ mod cli_tests {
         }
     }
 
+    #[test]
+    fn coverage_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "coverage",
+            "out.plccov",
+            "--profile",
+            "run1.plcprof",
+            "--profile",
+            "run2.plcprof",
+            "--format",
+            "html",
+            "-o",
+            "report.html"
+        ))
+        .unwrap();
+        match parameters.commands {
+            Some(SubCommands::Coverage { coverage_map, profiles, format }) => {
+                assert_eq!(coverage_map, "out.plccov");
+                assert_eq!(profiles, vec!["run1.plcprof".to_string(), "run2.plcprof".to_string()]);
+                assert_eq!(format, CoverageFormat::Html);
+            }
+            _ => panic!("Unexpected command"),
+        };
+        assert_eq!(parameters.output, Some("report.html".to_string()));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("coverage", "out.plccov")).unwrap();
+        match parameters.commands {
+            Some(SubCommands::Coverage { profiles, format, .. }) => {
+                assert_eq!(profiles, vec!["default.plcprof".to_string()]);
+                assert_eq!(format, CoverageFormat::Lcov);
+            }
+            _ => panic!("Unexpected command"),
+        };
+    }
+
+    #[test]
+    fn trace_subcommand() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("trace", "run.plctrace", "-o", "run.vcd")).unwrap();
+        match parameters.commands {
+            Some(SubCommands::Trace { trace_file }) => assert_eq!(trace_file, "run.plctrace"),
+            _ => panic!("Unexpected command"),
+        };
+        assert_eq!(parameters.output, Some("run.vcd".to_string()));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("trace")).unwrap();
+        match parameters.commands {
+            Some(SubCommands::Trace { trace_file }) => assert_eq!(trace_file, "default.plctrace"),
+            _ => panic!("Unexpected command"),
+        };
+    }
+
+    #[test]
+    fn test_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "test",
+            "src/ProjectPlc.json",
+            "--junit",
+            "report.xml",
+            "--filter",
+            "math"
+        ))
+        .unwrap();
+        match parameters.commands {
+            Some(SubCommands::Test { build_config, junit, filter }) => {
+                assert_eq!(build_config, Some("src/ProjectPlc.json".to_string()));
+                assert_eq!(junit, Some("report.xml".to_string()));
+                assert_eq!(filter, Some("math".to_string()));
+            }
+            _ => panic!("Unexpected command"),
+        };
+    }
+
+    #[test]
+    fn flags_are_set() {
+        let flags: [(&str, fn(&CompileParameters) -> bool); 7] = [
+            ("--coverage", |it| it.coverage),
+            ("--monitor-cycles", |it| it.monitor_cycles),
+            ("--trace", |it| it.trace),
+            ("--process-image", |it| it.process_image),
+            ("--embed-symbols", |it| it.embed_symbols),
+            ("--no-dynamic-memory", |it| it.no_dynamic_memory),
+            ("--strict-enums", |it| it.strict_enums),
+        ];
+        let defaults = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
+        for (flag, is_set) in flags {
+            assert!(!is_set(&defaults), "{flag} is set by default");
+            let parameters = CompileParameters::parse(vec_of_strings!("input.st", flag)).unwrap();
+            assert!(is_set(&parameters), "{flag} is not set");
+        }
+    }
+
     #[test]
     fn sysroot_added() {
         let parameters = CompileParameters::parse(vec_of_strings!(
@@ -691,6 +943,22 @@ mod cli_tests {
         expect_argument_error(vec_of_strings!("foo", "--hardware-conf=conf.xml"), ErrorKind::ValueValidation);
     }
 
+    #[test]
+    fn value_options_are_set() {
+        let options: [(&[&str], fn(&CompileParameters) -> Option<&str>); 3] = [
+            (&["--pretty-printers", "debug"], |it| it.pretty_printers.as_deref()),
+            (&["--symbols", "symbols"], |it| it.symbols.as_deref()),
+            (&["--export-xml", "project.xml"], |it| it.export_xml.as_deref()),
+        ];
+        let defaults = CompileParameters::parse(vec_of_strings!("foo", "-g")).unwrap();
+        for (option, value) in options {
+            assert_eq!(value(&defaults), None, "{} is set by default", option[0]);
+            let args = vec_of_strings!("foo", "-g").iter().chain(option).copied().collect::<Vec<_>>();
+            let parameters = CompileParameters::parse(&args).unwrap();
+            assert_eq!(value(&parameters), option.last().copied());
+        }
+    }
+
     #[test]
     fn error_format_default_set() {
         // make sure the default error format is set
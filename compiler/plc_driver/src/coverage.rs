@@ -0,0 +1,33 @@
+//! The `coverage` subcommand, turns the profiles of an instrumented program into a report
+use std::{collections::HashMap, fs, path::Path};
+
+use plc::coverage::{self, CoverageFormat, CoverageMap, FileCoverage};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+pub fn generate_report(
+    coverage_map: &Path,
+    profiles: &[String],
+    format: CoverageFormat,
+    output: &Path,
+) -> Result<(), Diagnostic> {
+    let maps = fs::read_to_string(coverage_map)
+        .map_err(|err| Diagnostic::io_read_error(&coverage_map.to_string_lossy(), &err.to_string()))?;
+    let maps: Vec<CoverageMap> = serde_json::from_str(&maps)
+        .map_err(|err| Diagnostic::io_read_error(&coverage_map.to_string_lossy(), &err.to_string()))?;
+
+    let mut counters: HashMap<String, Vec<u64>> = HashMap::new();
+    for profile in profiles {
+        let content = fs::read_to_string(profile)
+            .map_err(|err| Diagnostic::io_read_error(profile, &err.to_string()))?;
+        coverage::read_profile(&content, &mut counters)?;
+    }
+
+    let files =
+        maps.iter().map(|map| FileCoverage::new(map, counters.get(&map.file_name))).collect::<Vec<_>>();
+    let report = match format {
+        CoverageFormat::Lcov => coverage::generate_lcov(&files),
+        CoverageFormat::Html => coverage::generate_html(&files, |file| fs::read_to_string(file).ok()),
+    };
+    fs::write(output, report)
+        .map_err(|err| Diagnostic::io_write_error(&output.to_string_lossy(), &err.to_string()))
+}
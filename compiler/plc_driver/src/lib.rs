@@ -16,15 +16,23 @@ use std::{
 };
 
 use ast::provider::IdProvider;
-use cli::{CompileParameters, ParameterError};
-use plc::{output::FormatOption, DebugLevel, ErrorFormat, OptimizationLevel, Threads};
+use cli::{CompileParameters, ParameterError, SubCommands};
+use plc::{
+    lexer::{Defines, DEFAULT_DEFINE_VALUE},
+    output::FormatOption,
+    validation::ValidationOptions,
+    DebugLevel, ErrorFormat, OptimizationLevel, Target, Threads,
+};
 use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
 use project::project::{LibraryInformation, Project};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use source_code::SourceContainer;
 
 pub mod cli;
+mod coverage;
 pub mod pipelines;
+mod test_runner;
+mod trace;
 
 #[cfg(test)]
 mod tests;
@@ -47,6 +55,12 @@ pub struct CompileOptions {
     pub optimization: OptimizationLevel,
     pub error_format: ErrorFormat,
     pub debug_level: DebugLevel,
+    /// Instrument the generated code with coverage counters
+    pub coverage: bool,
+    /// Measure the execution time of every program
+    pub monitor_cycles: bool,
+    /// Record the traced variables of every program
+    pub trace: bool,
 }
 
 impl Default for CompileOptions {
@@ -59,6 +73,9 @@ impl Default for CompileOptions {
             optimization: OptimizationLevel::None,
             error_format: ErrorFormat::None,
             debug_level: DebugLevel::None,
+            coverage: false,
+            monitor_cycles: false,
+            trace: false,
         }
     }
 }
@@ -110,6 +127,15 @@ impl CompileError {
 pub fn compile<T: AsRef<str> + AsRef<OsStr> + Debug>(args: &[T]) -> Result<(), CompileError> {
     //Parse the arguments
     let compile_parameters = CompileParameters::parse(args)?;
+    if let Some(SubCommands::Coverage { coverage_map, profiles, format }) = &compile_parameters.commands {
+        let output = compile_parameters.output.as_deref().unwrap_or_else(|| format.get_default_output());
+        return coverage::generate_report(Path::new(coverage_map), profiles, *format, Path::new(output))
+            .map_err(Into::into);
+    }
+    if let Some(SubCommands::Trace { trace_file }) = &compile_parameters.commands {
+        let output = compile_parameters.output.as_deref().unwrap_or(trace::DEFAULT_VCD_FILE);
+        return trace::generate_vcd(Path::new(trace_file), Path::new(output)).map_err(Into::into);
+    }
     let project = get_project(&compile_parameters)?;
     let output_format = compile_parameters.output_format().unwrap_or_else(|| project.get_output_format());
     let location = project.get_location().map(|it| it.to_path_buf());
@@ -149,18 +175,46 @@ pub fn compile<T: AsRef<str> + AsRef<OsStr> + Debug>(args: &[T]) -> Result<(), C
     }
 
     // 1 : Parse
-    let annotated_project = pipelines::ParsedProject::parse(
+    let parsed_project = pipelines::ParsedProject::parse(
         &project,
         compile_parameters.encoding,
         id_provider.clone(),
         &mut diagnostician,
-    )?
+    )?;
+    if let Some(location) = &compile_parameters.export_xml {
+        parsed_project.export_xml(&project, compile_parameters.encoding, Path::new(location))?;
+    }
     // 2 : Index
-    .index(id_provider.clone())?
-    // 3 : Resolve
-    .annotate(id_provider, &diagnostician)?;
+    let annotated_project = parsed_project
+        .index(id_provider.clone())?
+        .with_process_image(compile_parameters.process_image, id_provider.clone())
+        .with_symbol_table(compile_parameters.embed_symbols, id_provider.clone())
+        // 3 : Resolve
+        .annotate(id_provider, &diagnostician)?;
     // 4 : Validate
-    annotated_project.validate(&mut diagnostician)?;
+    let validation_options = ValidationOptions {
+        allow_dynamic_memory: !compile_parameters.no_dynamic_memory,
+        strict_enums: project.has_strict_enums(),
+    };
+    annotated_project.validate(&mut diagnostician, validation_options)?;
+    if let Some(SubCommands::Test { junit, filter, .. }) = &compile_parameters.commands {
+        let compile_options = CompileOptions {
+            root: location,
+            output: project.get_output_name(),
+            optimization: compile_parameters.optimization,
+            error_format: compile_parameters.error_format,
+            debug_level: compile_parameters.debug_level(),
+            ..Default::default()
+        };
+        return test_runner::run_tests(
+            &project,
+            &annotated_project,
+            &compile_options,
+            filter.as_deref(),
+            junit.as_deref().map(Path::new),
+        )
+        .map_err(Into::into);
+    }
     // 5 : Codegen
     if !compile_parameters.is_check() {
         generate(
@@ -194,6 +248,9 @@ fn generate(
         optimization: compile_parameters.optimization,
         error_format: compile_parameters.error_format,
         debug_level: compile_parameters.debug_level(),
+        coverage: compile_parameters.coverage,
+        monitor_cycles: compile_parameters.monitor_cycles,
+        trace: compile_parameters.trace,
     };
     let res = if compile_parameters.single_module {
         log::info!("Using single module mode");
@@ -232,6 +289,17 @@ fn generate(
     {
         annotated_project.generate_hardware_information(format, location)?;
     }
+    if compile_parameters.coverage {
+        let location = build_location.as_deref().unwrap_or_else(|| Path::new(""));
+        annotated_project.generate_coverage_map(&location.join(format!("{output_name}.plccov")))?;
+    }
+    let binary = Path::new(&output_name).file_name().and_then(OsStr::to_str).unwrap_or(&output_name);
+    if let Some(location) = &compile_parameters.pretty_printers {
+        annotated_project.generate_pretty_printers(Path::new(location), binary)?;
+    }
+    if let Some(location) = &compile_parameters.symbols {
+        annotated_project.generate_symbols(Path::new(location), binary)?;
+    }
     if let Some(lib_location) = lib_location {
         for library in
             project.get_libraries().iter().filter(|it| it.should_copy()).map(|it| it.get_compiled_lib())
@@ -285,6 +353,41 @@ fn get_project(compile_parameters: &CompileParameters) -> Result<Project<PathBuf
             }
         })
         .map(|proj| proj.with_output_name(compile_parameters.output.clone()))
+        .map(|proj| {
+            proj.with_defines(get_predefined_symbols(compile_parameters))
+                .with_defines(compile_parameters.defines.iter().cloned())
+        })
+        .map(|proj| proj.with_strict_enums(compile_parameters.strict_enums))
+}
+
+/// Returns the symbols describing the target and compile options, they can be tested by the
+/// conditional compilation pragmas like any other define
+fn get_predefined_symbols(compile_parameters: &CompileParameters) -> Defines {
+    let mut defines = Defines::default();
+    //All targets are generated from the same sources, so the target is only known if there is one
+    let triple = match compile_parameters.target.as_slice() {
+        [] => Some(Target::System.get_target_triple().as_str().to_string_lossy().into_owned()),
+        [target] => target.try_get_name().map(str::to_string),
+        _ => None,
+    };
+    if let Some(triple) = triple {
+        defines.define("__TARGET", &triple);
+        defines.define("__ARCH", triple.split('-').next().unwrap_or_default());
+    }
+    let optimization = match compile_parameters.optimization {
+        OptimizationLevel::None => "none",
+        OptimizationLevel::Less => "less",
+        OptimizationLevel::Default => "default",
+        OptimizationLevel::Aggressive => "aggressive",
+    };
+    defines.define("__OPTIMIZATION", optimization);
+    if compile_parameters.debug_level() != DebugLevel::None {
+        defines.define("__DEBUG", DEFAULT_DEFINE_VALUE);
+    }
+    if compile_parameters.coverage {
+        defines.define("__COVERAGE", DEFAULT_DEFINE_VALUE);
+    }
+    defines
 }
 
 fn get_config(root: &Path) -> Option<PathBuf> {
@@ -3,22 +3,29 @@ use std::{
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use crate::{CompileOptions, LinkOptions};
 use ast::{
-    ast::{pre_process, CompilationUnit, LinkageType, SourceRange},
+    ast::{instantiate_generics, pre_process, CompilationUnit, LinkageType, SourceRange},
     provider::IdProvider,
 };
+use chrono::NaiveDateTime;
 use encoding_rs::Encoding;
 use indexmap::IndexSet;
 use plc::{
     codegen::{CodegenContext, GeneratedModule},
+    coverage::CoverageMap,
     index::Index,
     output::FormatOption,
     parser::parse_file,
+    pretty_printers::Debugger,
+    process_image::PROCESS_IMAGE_FILE,
     resolver::{AnnotationMapImpl, AstAnnotations, Dependency, StringLiterals, TypeAnnotator},
-    validation::Validator,
+    symbols::SymbolFormat,
+    testing::{self, TestCase},
+    validation::{ValidationOptions, Validator},
     ConfigFormat, Target,
 };
 use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic, errno::ErrNo};
@@ -58,18 +65,25 @@ impl ParsedProject {
                     )
                 })?;
 
-                let parse_func = match loaded_source.get_type() {
-                    source_code::SourceType::Text => parse_file,
-                    source_code::SourceType::Xml => cfc::xml_parser::parse_file,
+                let unit = match loaded_source.get_type() {
+                    source_code::SourceType::Text => parse_file(
+                        &loaded_source.source,
+                        loaded_source.get_location_str(),
+                        LinkageType::Internal,
+                        id_provider.clone(),
+                        project.get_defines(),
+                        diagnostician,
+                    ),
+                    source_code::SourceType::Xml => cfc::xml_parser::parse_file(
+                        &loaded_source.source,
+                        loaded_source.get_location_str(),
+                        LinkageType::Internal,
+                        id_provider.clone(),
+                        diagnostician,
+                    ),
                     source_code::SourceType::Unknown => unreachable!(),
                 };
-                Ok(parse_func(
-                    &loaded_source.source,
-                    loaded_source.get_location_str(),
-                    LinkageType::Internal,
-                    id_provider.clone(),
-                    diagnostician,
-                ))
+                Ok(unit)
             })
             .collect::<Result<Vec<_>, Diagnostic>>()?;
         units.extend(sources);
@@ -89,6 +103,7 @@ impl ParsedProject {
                     loaded_source.get_location_str(),
                     LinkageType::External,
                     id_provider.clone(),
+                    project.get_defines(),
                     diagnostician,
                 ))
             })
@@ -111,6 +126,7 @@ impl ParsedProject {
                     loaded_source.get_location_str(),
                     LinkageType::External,
                     id_provider.clone(),
+                    project.get_defines(),
                     diagnostician,
                 ))
             })
@@ -120,8 +136,41 @@ impl ParsedProject {
         Ok(ParsedProject(units))
     }
 
+    /// Exports the structured text sources of the project to a PLCopen XML file
+    /// (see [`cfc::xml_exporter::export`])
+    pub fn export_xml<T: SourceContainer>(
+        &self,
+        project: &Project<T>,
+        encoding: Option<&'static Encoding>,
+        location: &Path,
+    ) -> Result<(), Diagnostic> {
+        // the units of the project's sources come first, in the order of the sources
+        let sources = self
+            .0
+            .iter()
+            .zip(project.get_sources())
+            .filter(|(_, it)| matches!(it.get_type(), source_code::SourceType::Text))
+            .map(|(unit, it)| {
+                let loaded_source = it.load_source(encoding).map_err(|err| {
+                    Diagnostic::io_read_error(
+                        &it.get_location().expect("Location should not be empty").to_string_lossy(),
+                        &err,
+                    )
+                })?;
+                Ok((unit, loaded_source.source))
+            })
+            .collect::<Result<Vec<_>, Diagnostic>>()?;
+        let sources = sources.iter().map(|(unit, source)| (*unit, source.as_str())).collect::<Vec<_>>();
+
+        let content = cfc::xml_exporter::export(project.get_name(), &get_creation_date_time(), &sources);
+        fs::write(location, content)
+            .map_err(|it| Diagnostic::io_write_error(&location.to_string_lossy(), &it.to_string()))
+    }
+
     /// Creates an index out of a pased project. The index could then be used to query datatypes
-    pub fn index(self, id_provider: IdProvider) -> Result<IndexedProject, Diagnostic> {
+    pub fn index(mut self, id_provider: IdProvider) -> Result<IndexedProject, Diagnostic> {
+        //Generic instances may use templates declared in other units
+        instantiate_generics(&mut self.0, id_provider.clone());
         let indexed_units = self
             .0
             .into_par_iter()
@@ -162,6 +211,36 @@ pub struct IndexedProject {
 }
 
 impl IndexedProject {
+    /// Adds the process image of the variables bound to fixed hardware addresses (see [`plc::process_image`])
+    /// as an additional unit
+    pub fn with_process_image(self, process_image: bool, id_provider: IdProvider) -> Self {
+        if !process_image {
+            return self;
+        }
+        // the array bounds must be known to name all instances of the bound variables
+        let (mut index, _) = plc::resolver::const_evaluator::evaluate_constants(self.index);
+        let mut units = self.units;
+        if let Some(unit) = plc::process_image::generate_process_image(&index, id_provider) {
+            index.import(plc::index::visitor::visit(&unit));
+            units.push(unit);
+        }
+        IndexedProject { units, index }
+    }
+
+    /// Adds the embedded symbol table (see [`plc::symbols::generate_symbol_table`]) as an additional unit
+    pub fn with_symbol_table(self, embed_symbols: bool, id_provider: IdProvider) -> Self {
+        if !embed_symbols {
+            return self;
+        }
+        // the array bounds and type sizes depend on constants
+        let (mut index, _) = plc::resolver::const_evaluator::evaluate_constants(self.index);
+        let mut units = self.units;
+        let unit = plc::symbols::generate_symbol_table(&index, id_provider);
+        index.import(plc::index::visitor::visit(&unit));
+        units.push(unit);
+        IndexedProject { units, index }
+    }
+
     /// Creates annotations on the project in order to facilitate codegen and validation
     pub fn annotate(
         self,
@@ -207,9 +286,13 @@ pub struct AnnotatedProject {
 
 impl AnnotatedProject {
     /// Validates the project, reports any new diagnostics on the fly
-    pub fn validate(&self, diagnostician: &mut Diagnostician) -> Result<(), Diagnostic> {
+    pub fn validate(
+        &self,
+        diagnostician: &mut Diagnostician,
+        options: ValidationOptions,
+    ) -> Result<(), Diagnostic> {
         // perform global validation
-        let mut validator = Validator::new();
+        let mut validator = Validator::with_options(options);
         validator.perform_global_validation(&self.index);
         diagnostician.handle(validator.diagnostics());
 
@@ -217,8 +300,13 @@ impl AnnotatedProject {
         self.units.iter().for_each(|(unit, _, _)| {
             // validate unit
             validator.visit_unit(&self.annotations, &self.index, unit);
+            let mut diagnostics = validator.diagnostics();
+            //The generated process image accesses private variables of programs and function blocks
+            if unit.file_name == PROCESS_IMAGE_FILE {
+                diagnostics.retain(|it| it.get_type() != &ErrNo::reference__illegal_access);
+            }
             // log errors
-            diagnostician.handle(validator.diagnostics());
+            diagnostician.handle(diagnostics);
         });
         Ok(())
     }
@@ -265,7 +353,10 @@ impl AnnotatedProject {
             &unit.file_name,
             compile_options.optimization,
             compile_options.debug_level,
-        );
+        )
+        .with_coverage(compile_options.coverage)
+        .with_cycle_monitor(compile_options.monitor_cycles)
+        .with_trace(compile_options.trace);
         //Create a types codegen, this contains all the type declarations
         //Associate the index type with LLVM types
         let llvm_index = code_generator.generate_llvm_index(
@@ -330,7 +421,8 @@ impl AnnotatedProject {
                         let current_dir = env::current_dir()?;
                         let current_dir = compile_options.root.as_deref().unwrap_or(&current_dir);
                         let unit_location = PathBuf::from(&unit.file_name);
-                        let unit_location = std::fs::canonicalize(unit_location)?;
+                        //Generated units like the process image have no file
+                        let unit_location = std::fs::canonicalize(&unit_location).unwrap_or(unit_location);
                         let output_name = if unit_location.starts_with(current_dir) {
                             unit_location.strip_prefix(current_dir)?
                         } else if unit_location.has_root() {
@@ -382,6 +474,58 @@ impl AnnotatedProject {
         })?;
         Ok(())
     }
+
+    /// Returns the functions marked with `{test}` of all units
+    pub fn get_test_cases(&self) -> Vec<TestCase> {
+        self.units.iter().flat_map(|(unit, ..)| testing::collect_tests(unit, &self.index)).collect()
+    }
+
+    /// Writes the coverage regions of all units, used to map the counters of a coverage profile
+    /// back to the source files
+    pub fn generate_coverage_map(&self, location: &Path) -> Result<(), Diagnostic> {
+        let maps = self.units.iter().map(|(unit, ..)| CoverageMap::new(unit)).collect::<Vec<_>>();
+        let content = serde_json::to_string_pretty(&maps)
+            .map_err(|err| Diagnostic::io_write_error(&location.to_string_lossy(), &err.to_string()))?;
+        fs::write(location, content)
+            .map_err(|err| Diagnostic::io_write_error(&location.to_string_lossy(), &err.to_string()))
+    }
+
+    /// Writes gdb and lldb pretty-printer scripts for the given binary into the location
+    pub fn generate_pretty_printers(&self, location: &Path, binary: &str) -> Result<(), Diagnostic> {
+        let printers = plc::pretty_printers::collect_pretty_printers(&self.index);
+        fs::create_dir_all(location)?;
+        for debugger in [Debugger::Gdb, Debugger::Lldb] {
+            let script = plc::pretty_printers::generate_pretty_printers(&printers, debugger)?;
+            let script_location = location.join(debugger.get_script_name(binary));
+            File::create(&script_location).and_then(|mut it| it.write_all(script.as_bytes())).map_err(
+                |it| Diagnostic::io_write_error(&script_location.to_string_lossy(), &it.to_string()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the symbol table of all accessible variables as JSON, PLCopen XML and OPC UA NodeSet2
+    /// for the given binary into the location
+    pub fn generate_symbols(&self, location: &Path, binary: &str) -> Result<(), Diagnostic> {
+        let symbols = plc::symbols::collect_symbols(&self.index);
+        fs::create_dir_all(location)?;
+        for format in [SymbolFormat::Json, SymbolFormat::PlcOpenXml, SymbolFormat::NodeSet] {
+            let content = plc::symbols::generate_symbols(&symbols, format, binary)?;
+            let file_location = location.join(format.get_file_name(binary));
+            fs::write(&file_location, content).map_err(|it| {
+                Diagnostic::io_write_error(&file_location.to_string_lossy(), &it.to_string())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// the current time in UTC as a `xsd:dateTime`
+fn get_creation_date_time() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or_default();
+    NaiveDateTime::from_timestamp_opt(seconds as i64, 0)
+        .map(|it| format!("{}T{}Z", it.date(), it.time()))
+        .unwrap_or_default()
 }
 
 /// Ensures the directores for the various targets have been created
@@ -0,0 +1,128 @@
+//! The `test` subcommand, runs the functions marked with `{test}` in the JIT and reports their results
+use std::{
+    cell::RefCell,
+    ffi::CStr,
+    fs,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use plc::{
+    codegen::{CodegenContext, GeneratedModule},
+    testing::{self, AssertionFailure, TestResult, TestReturnType},
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use project::project::Project;
+
+use crate::{pipelines::AnnotatedProject, CompileOptions};
+
+thread_local! {
+    /// the assertions that failed during the current test
+    static FAILURES: RefCell<Vec<AssertionFailure>> = RefCell::new(Vec::new());
+}
+
+/// called by the generated code when an assertion fails
+extern "C" fn assert_failed(message: *const c_char, file: *const c_char, line: i32) {
+    // SAFETY: the generated code passes null-terminated global string constants
+    let (message, file) = unsafe {
+        (
+            CStr::from_ptr(message).to_string_lossy().to_string(),
+            CStr::from_ptr(file).to_string_lossy().to_string(),
+        )
+    };
+    FAILURES.with(|it| it.borrow_mut().push(AssertionFailure { message, file, line: line as u32 }));
+}
+
+/// calls the test with the type it was declared with, the returned value is ignored
+fn run_test(module: &GeneratedModule, name: &str, return_type: TestReturnType) {
+    match return_type {
+        TestReturnType::Integer(8) => {
+            module.run_no_param::<i8>(name);
+        }
+        TestReturnType::Integer(16) => {
+            module.run_no_param::<i16>(name);
+        }
+        TestReturnType::Integer(32) => {
+            module.run_no_param::<i32>(name);
+        }
+        TestReturnType::Integer(_) => {
+            module.run_no_param::<i64>(name);
+        }
+        TestReturnType::Float(32) => {
+            module.run_no_param::<f32>(name);
+        }
+        TestReturnType::Float(_) => {
+            module.run_no_param::<f64>(name);
+        }
+        TestReturnType::Void => module.run_no_param::<()>(name),
+    }
+}
+
+pub fn run_tests(
+    project: &Project<PathBuf>,
+    annotated_project: &AnnotatedProject,
+    compile_options: &CompileOptions,
+    filter: Option<&str>,
+    junit: Option<&Path>,
+) -> Result<(), Diagnostic> {
+    let tests = annotated_project
+        .get_test_cases()
+        .into_iter()
+        .filter(|it| match filter {
+            Some(filter) => it.name.contains(filter),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    let context = CodegenContext::create();
+    let Some(module) = annotated_project.generate_single_module(&context, compile_options)? else {
+        return Ok(());
+    };
+    // compiled libraries can only be used in the JIT if they are shared objects
+    for library in project.get_libraries() {
+        for object in library.get_compiled_lib().get_objects() {
+            let path = object.get_path();
+            if path.extension().and_then(|it| it.to_str()) == Some("so") {
+                module.load_library(path)?;
+            } else {
+                log::warn!("Library {} is not a shared object and cannot be loaded", path.to_string_lossy());
+            }
+        }
+    }
+    module.add_global_function_mapping(testing::ASSERT_FAILED_FUNCTION, assert_failed as usize);
+
+    println!("running {} tests", tests.len());
+    let mut results = vec![];
+    for test in tests {
+        FAILURES.with(|it| it.borrow_mut().clear());
+        let start = Instant::now();
+        run_test(&module, &test.name, test.return_type);
+        let duration = start.elapsed();
+        let failures = FAILURES.with(|it| it.take());
+
+        if failures.is_empty() {
+            println!("test {} ... ok", test.name);
+        } else {
+            println!("test {} ... FAILED", test.name);
+            for failure in &failures {
+                println!("    {}:{}: {}", failure.file, failure.line, failure.message);
+            }
+        }
+        results.push(TestResult { test, failures, duration });
+    }
+
+    let failed = results.iter().filter(|it| !it.is_success()).count();
+    println!("test result: {} passed; {failed} failed", results.len() - failed);
+
+    if let Some(junit) = junit {
+        fs::write(junit, testing::generate_junit(project.get_name(), &results))
+            .map_err(|err| Diagnostic::io_write_error(&junit.to_string_lossy(), &err.to_string()))?;
+    }
+
+    if failed > 0 {
+        Err(Diagnostic::tests_failed(failed, results.len()))
+    } else {
+        Ok(())
+    }
+}
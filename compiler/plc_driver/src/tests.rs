@@ -10,6 +10,8 @@ use crate::{pipelines, CompileOptions};
 
 mod external_files;
 mod multi_files;
+mod process_image;
+mod test_runner;
 
 pub fn compile_with_root<S, T>(
     sources: T,
@@ -0,0 +1,41 @@
+use ast::provider::IdProvider;
+use plc::validation::ValidationOptions;
+use plc_diagnostics::{diagnostician::Diagnostician, reporter::DiagnosticReporter};
+use project::project::Project;
+use source_code::SourceCode;
+
+use crate::pipelines;
+
+#[test]
+fn process_image_may_access_private_variables() {
+    //Given a program with a private variable bound to an input
+    let source = SourceCode::new(
+        "
+    PROGRAM prg
+    VAR
+        x AT %IX0.0 : BOOL;
+        y AT %IW1 : INT;
+    END_VAR
+    END_PROGRAM
+    ",
+        "prg.st",
+    );
+    let project = Project::new("TestProject".into()).with_sources(vec![source]);
+    let mut diagnostician = Diagnostician::buffered();
+    let id_provider = IdProvider::default();
+
+    //When the project is validated with a process image
+    pipelines::ParsedProject::parse(&project, None, id_provider.clone(), &mut diagnostician)
+        .unwrap()
+        .index(id_provider.clone())
+        .unwrap()
+        .with_process_image(true, id_provider.clone())
+        .annotate(id_provider, &diagnostician)
+        .unwrap()
+        .validate(&mut diagnostician, ValidationOptions::default())
+        .unwrap();
+
+    //Then the generated copy functions are validated, but their access to the variables is not reported
+    let report = diagnostician.buffer().unwrap_or_default();
+    assert!(!report.contains("Illegal access"), "{report}");
+}
@@ -0,0 +1,53 @@
+use std::fs;
+
+use ast::provider::IdProvider;
+use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
+use project::project::Project;
+
+use crate::{pipelines, test_runner, CompileOptions};
+
+#[test]
+fn passing_and_failing_tests_are_reported() {
+    //Given a passing and a failing test with different return types
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("math.st");
+    fs::write(
+        &source,
+        "
+    {test}
+    FUNCTION test_passes : BOOL
+        ASSERT_EQUAL(3, 1 + 2);
+        test_passes := TRUE;
+    END_FUNCTION
+
+    {test}
+    FUNCTION test_fails : LREAL
+        ASSERT_TRUE(1 > 2);
+        test_fails := 1.5;
+    END_FUNCTION
+    ",
+    )
+    .unwrap();
+    let project = Project::new("TestProject".into()).with_sources(vec![source]);
+    let mut diagnostician = Diagnostician::null_diagnostician();
+    let id_provider = IdProvider::default();
+    let annotated_project =
+        pipelines::ParsedProject::parse(&project, None, id_provider.clone(), &mut diagnostician)
+            .unwrap()
+            .index(id_provider.clone())
+            .unwrap()
+            .annotate(id_provider, &diagnostician)
+            .unwrap();
+
+    //When they are run
+    let junit = dir.path().join("report.xml");
+    let result =
+        test_runner::run_tests(&project, &annotated_project, &CompileOptions::default(), None, Some(&junit));
+
+    //Then only the failing test is reported
+    assert_eq!(result, Err(Diagnostic::tests_failed(1, 2)));
+    let report = fs::read_to_string(junit).unwrap();
+    assert!(report.contains(r#"<testsuite name="TestProject" tests="2" failures="1""#), "{report}");
+    assert!(report.contains(r#"<testcase name="test_passes""#), "{report}");
+    assert!(report.contains(r#"<failure message="ASSERT_TRUE failed""#), "{report}");
+}
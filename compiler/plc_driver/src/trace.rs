@@ -0,0 +1,15 @@
+//! The `trace` subcommand, turns the samples recorded by a traced program into a value change dump
+use std::{fs, path::Path};
+
+use plc::trace;
+use plc_diagnostics::diagnostics::Diagnostic;
+
+pub const DEFAULT_VCD_FILE: &str = "trace.vcd";
+
+pub fn generate_vcd(trace_file: &Path, output: &Path) -> Result<(), Diagnostic> {
+    let content = fs::read_to_string(trace_file)
+        .map_err(|err| Diagnostic::io_read_error(&trace_file.to_string_lossy(), &err.to_string()))?;
+    let trace = trace::read_trace(&content)?;
+    fs::write(output, trace::generate_vcd(&trace))
+        .map_err(|err| Diagnostic::io_write_error(&output.to_string_lossy(), &err.to_string()))
+}
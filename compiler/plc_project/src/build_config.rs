@@ -3,6 +3,7 @@ use plc_diagnostics::diagnostics::Diagnostic;
 use regex::Captures;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -45,6 +46,12 @@ pub struct ProjectConfig {
     pub libraries: Vec<LibraryConfig>,
     #[serde(default)]
     pub package_commands: Vec<String>,
+    /// Symbols for the conditional compilation pragmas (`{IF defined(NAME)}`)
+    #[serde(default)]
+    pub defines: HashMap<String, String>,
+    /// Whether all enums of the project are strict
+    #[serde(default)]
+    pub strict_enums: bool,
 }
 
 impl ProjectConfig {
@@ -82,6 +89,7 @@ fn resolve_environment_variables(to_replace: &str) -> Result<String, Diagnostic>
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::path::PathBuf;
     use std::{env, vec};
 
@@ -129,6 +137,8 @@ mod tests {
                 },
             ],
             package_commands: vec![],
+            defines: HashMap::from([("MACHINE".to_string(), "press_2".to_string())]),
+            strict_enums: true,
         };
         let proj = ProjectConfig::try_parse(
             r#"
@@ -139,6 +149,10 @@ mod tests {
                 ],
                 "compile_type" : "Shared",
                 "output": "proj.so",
+                "defines" : {
+                    "MACHINE" : "press_2"
+                },
+                "strict_enums" : true,
                 "libraries" : [
                     {
                         "name" : "copy",
@@ -183,6 +197,8 @@ mod tests {
         assert_eq!(test_project.files, proj.files);
         assert_eq!(test_project.compile_type, proj.compile_type);
         assert_eq!(test_project.output, proj.output);
+        assert_eq!(test_project.defines, proj.defines);
+        assert_eq!(test_project.strict_enums, proj.strict_enums);
         let proj_lib = proj.libraries;
         let testproj_lib = test_project.libraries;
         assert_eq!(testproj_lib[0].name, proj_lib[0].name);
@@ -11,7 +11,7 @@ use crate::{
     object::Object,
 };
 
-use plc::output::FormatOption;
+use plc::{lexer::Defines, output::FormatOption};
 use source_code::{SourceContainer, SourceType};
 
 #[derive(Debug)]
@@ -81,6 +81,10 @@ pub struct Project<T: SourceContainer> {
     format: FormatOption,
     /// Output Name
     output: Option<String>,
+    /// Symbols for the conditional compilation pragmas
+    defines: Defines,
+    /// Whether all enums are strict
+    strict_enums: bool,
 }
 
 impl<T: SourceContainer> LibraryInformation<T> {
@@ -169,6 +173,8 @@ impl Project<PathBuf> {
             output: project_config.output,
             includes: vec![],
             objects: vec![],
+            defines: project_config.defines.into_iter().collect(),
+            strict_enums: project_config.strict_enums,
         })
     }
 
@@ -216,6 +222,8 @@ impl<S: SourceContainer> Project<S> {
             libraries: vec![],
             format: FormatOption::default(),
             output: None,
+            defines: Defines::default(),
+            strict_enums: false,
         }
     }
 
@@ -242,6 +250,21 @@ impl<S: SourceContainer> Project<S> {
         proj
     }
 
+    /// Adds the given defines, replacing existing defines with the same name
+    pub fn with_defines<K: AsRef<str>, V: AsRef<str>>(
+        mut self,
+        defines: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.defines.extend(defines);
+        self
+    }
+
+    /// Makes all enums strict, a project configured with strict enums stays strict
+    pub fn with_strict_enums(mut self, strict_enums: bool) -> Self {
+        self.strict_enums |= strict_enums;
+        self
+    }
+
     pub fn get_location(&self) -> Option<&Path> {
         self.location.as_deref()
     }
@@ -281,6 +304,14 @@ impl<S: SourceContainer> Project<S> {
     pub fn get_output_format(&self) -> FormatOption {
         self.format
     }
+
+    pub fn get_defines(&self) -> &Defines {
+        &self.defines
+    }
+
+    pub fn has_strict_enums(&self) -> bool {
+        self.strict_enums
+    }
 }
 
 fn resolve_file_paths(location: Option<&Path>, inputs: Vec<PathBuf>) -> Result<Vec<PathBuf>, Diagnostic> {
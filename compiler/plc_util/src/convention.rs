@@ -12,6 +12,12 @@ pub fn internal_type_name<T: AsRef<str> + Display>(prefix: T, original_type_name
     format!("__{prefix}{original_type_name}")
 }
 
+/// Returns the name of a generic type's instance in the form of `<generic_name>__<argument>__<argument>...`,
+/// e.g. `FIFO__INT` for `FIFO<INT>`.
+pub fn generic_instance_name<T: AsRef<str>>(generic_name: &str, type_arguments: &[T]) -> String {
+    type_arguments.iter().fold(generic_name.to_string(), |name, it| format!("{name}__{}", it.as_ref()))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -23,4 +29,9 @@ mod tests {
     fn internal_type_name() {
         assert_eq!(super::internal_type_name("POINTER_TO_", "foo"), "__POINTER_TO_foo");
     }
+
+    #[test]
+    fn generic_instance_name() {
+        assert_eq!(super::generic_instance_name("FIFO", &["INT", "REAL"]), "FIFO__INT__REAL");
+    }
 }
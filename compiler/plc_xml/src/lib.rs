@@ -3,6 +3,7 @@
 
 pub mod error;
 mod extensions;
+pub mod xml_exporter;
 pub mod xml_parser;
 pub(crate) mod model {
     pub mod action;
@@ -10,7 +11,9 @@ pub(crate) mod model {
     pub mod body;
     pub mod connector;
     pub mod control;
+    pub mod data_type;
     pub mod fbd;
+    pub mod global_variables;
     pub mod interface;
     pub mod pou;
     pub mod project;
@@ -1,20 +1,38 @@
 use quick_xml::events::Event;
 
 use super::fbd::FunctionBlockDiagram;
-use crate::{error::Error, reader::PeekableReader, xml_parser::Parseable};
+use crate::{error::Error, extensions::TryToString, reader::PeekableReader, xml_parser::Parseable};
 
 #[derive(Debug, Default)]
 pub(crate) struct Body {
     pub function_block_diagram: Option<FunctionBlockDiagram>,
+    /// the statements of a `<ST>` body
+    pub structured_text: Option<String>,
 }
 
 impl Body {
     fn new(fbd: Option<FunctionBlockDiagram>) -> Result<Self, Error> {
-        Ok(Self { function_block_diagram: fbd })
+        Ok(Self { function_block_diagram: fbd, structured_text: None })
     }
 
     fn empty() -> Result<Self, Error> {
-        Ok(Self { function_block_diagram: None })
+        Ok(Self { function_block_diagram: None, structured_text: None })
+    }
+
+    fn structured_text(text: String) -> Result<Self, Error> {
+        Ok(Self { function_block_diagram: None, structured_text: Some(text) })
+    }
+
+    /// reads the text of a `<ST>` body, which is usually wrapped in a `<xhtml:p>` element
+    fn visit_structured_text(reader: &mut PeekableReader) -> Result<String, Error> {
+        // consume the ST tag
+        reader.consume()?;
+        let text = match reader.next()? {
+            Event::Start(tag) => reader.read_text(tag.name())?,
+            Event::Text(text) => text.into_inner().try_to_string()?,
+            _ => String::new(),
+        };
+        Ok(html_escape::decode_html_entities(&text).into_owned())
     }
 }
 
@@ -31,9 +49,15 @@ impl Parseable for Body {
 
                         return Body::new(Some(fbd));
                     }
+                    b"ST" => {
+                        let text = Body::visit_structured_text(reader)?;
+                        reader.consume_until(vec![b"body"])?;
+
+                        return Body::structured_text(text);
+                    }
                     _ => reader.consume()?,
                 },
-                Event::Empty(tag) if tag.name().as_ref() == b"FBD" => return Body::empty(),
+                Event::Empty(tag) if matches!(tag.name().as_ref(), b"FBD" | b"ST") => return Body::empty(),
                 Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"body"])),
                 _ => reader.consume()?,
             }
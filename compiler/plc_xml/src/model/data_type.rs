@@ -0,0 +1,439 @@
+use std::ops::Range;
+
+use quick_xml::events::Event;
+
+use crate::{
+    error::Error,
+    extensions::{GetOrErr, TryToString},
+    reader::PeekableReader,
+    xml_parser::Parseable,
+};
+
+/// A user defined type of the `dataTypes` element
+#[derive(Debug, PartialEq)]
+pub(crate) struct DataType {
+    pub name: String,
+    /// the range of the `dataType` start tag
+    pub location: Range<usize>,
+    pub base_type: TypeDefinition,
+    pub initial_value: Option<String>,
+}
+
+/// The types of the `dataType` group as specified in the official XSD
+#[derive(Debug, PartialEq)]
+pub(crate) enum TypeDefinition {
+    /// an elementary type (e.g. `<INT/>`) or a derived type referenced by its name
+    Reference(String),
+    String {
+        is_wide: bool,
+        length: Option<String>,
+    },
+    Array {
+        dimensions: Vec<(String, String)>,
+        base_type: Box<TypeDefinition>,
+    },
+    Struct(Vec<VariableDeclaration>),
+    Enum {
+        values: Vec<(String, Option<String>)>,
+        base_type: Option<Box<TypeDefinition>>,
+    },
+    Subrange {
+        lower: String,
+        upper: String,
+        base_type: Box<TypeDefinition>,
+    },
+    Pointer(Box<TypeDefinition>),
+}
+
+/// A variable of a struct or of a `globalVars` element
+#[derive(Debug, PartialEq)]
+pub(crate) struct VariableDeclaration {
+    pub name: String,
+    /// the range of the `variable` start tag
+    pub location: Range<usize>,
+    pub address: Option<String>,
+    pub data_type: TypeDefinition,
+    /// the initial value as structured text
+    pub initial_value: Option<String>,
+}
+
+impl Parseable for DataType {
+    type Item = Self;
+
+    fn visit(reader: &mut PeekableReader) -> Result<Self::Item, Error> {
+        let location = reader.peek_range()?;
+        let name = reader.attributes()?.get_or_err("name")?;
+        let mut base_type = None;
+        let mut initial_value = None;
+        loop {
+            match reader.peek()? {
+                Event::Start(tag) => match tag.name().as_ref() {
+                    b"baseType" => base_type = Some(TypeDefinition::visit_wrapped(reader, b"baseType")?),
+                    b"initialValue" => initial_value = Some(visit_initial_value(reader)?),
+                    _ => reader.consume_element()?,
+                },
+                Event::End(tag) if tag.name().as_ref() == b"dataType" => {
+                    reader.consume()?;
+                    break;
+                }
+                Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"dataType"])),
+                _ => reader.consume()?,
+            }
+        }
+
+        let base_type = base_type.ok_or_else(|| Error::MissingElement("baseType".to_string()))?;
+        Ok(DataType { name, location, base_type, initial_value })
+    }
+}
+
+impl TypeDefinition {
+    /// reads the type of a `type` or `baseType` element
+    pub fn visit_wrapped(reader: &mut PeekableReader, wrapper: &'static [u8]) -> Result<Self, Error> {
+        // consume the wrapper's start
+        reader.consume()?;
+        loop {
+            match reader.peek()? {
+                Event::Start(_) | Event::Empty(_) => {
+                    let data_type = TypeDefinition::visit(reader)?;
+                    reader.consume_until(vec![wrapper])?;
+                    return Ok(data_type);
+                }
+                Event::End(_) | Event::Eof => return Err(Error::MissingElement(wrapper.try_to_string()?)),
+                _ => reader.consume()?,
+            }
+        }
+    }
+}
+
+impl Parseable for TypeDefinition {
+    type Item = Self;
+
+    fn visit(reader: &mut PeekableReader) -> Result<Self::Item, Error> {
+        let (name, is_empty) = match reader.peek()? {
+            Event::Start(tag) => (tag.name().try_to_string()?, false),
+            Event::Empty(tag) => (tag.name().try_to_string()?, true),
+            _ => return Err(Error::MissingElement("type".to_string())),
+        };
+        let attributes = reader.attributes()?;
+
+        let mut dimensions = vec![];
+        let mut range = None;
+        let mut values = vec![];
+        let mut variables = vec![];
+        let mut base_type = None;
+        while !is_empty {
+            match reader.peek()? {
+                Event::Start(tag) | Event::Empty(tag) => match tag.name().as_ref() {
+                    b"dimension" | b"range" => {
+                        let attributes = reader.element_attributes()?;
+                        let bounds = (attributes.get_or_err("lower")?, attributes.get_or_err("upper")?);
+                        match name.as_str() {
+                            "array" => dimensions.push(bounds),
+                            _ => range = Some(bounds),
+                        }
+                    }
+                    b"value" => {
+                        let attributes = reader.element_attributes()?;
+                        values.push((attributes.get_or_err("name")?, attributes.get("value").cloned()))
+                    }
+                    b"variable" => variables.push(VariableDeclaration::visit(reader)?),
+                    b"baseType" => base_type = Some(TypeDefinition::visit_wrapped(reader, b"baseType")?),
+                    // the values of an enum are wrapped in a `values` element
+                    b"values" => reader.consume()?,
+                    _ => reader.consume_element()?,
+                },
+                Event::End(tag) if tag.name().as_ref() == b"values" => reader.consume()?,
+                Event::End(_) => {
+                    reader.consume()?;
+                    break;
+                }
+                Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"type"])),
+                _ => reader.consume()?,
+            }
+        }
+
+        let get_base_type = |base_type: Option<TypeDefinition>| {
+            base_type.ok_or_else(|| Error::MissingElement("baseType".to_string()))
+        };
+        Ok(match name.as_str() {
+            "derived" => TypeDefinition::Reference(attributes.get_or_err("name")?),
+            "string" | "wstring" => TypeDefinition::String {
+                is_wide: name == "wstring",
+                length: attributes.get("length").cloned(),
+            },
+            "array" => TypeDefinition::Array { dimensions, base_type: Box::new(get_base_type(base_type)?) },
+            "struct" => TypeDefinition::Struct(variables),
+            "enum" => TypeDefinition::Enum { values, base_type: base_type.map(Box::new) },
+            "subrangeSigned" | "subrangeUnsigned" => {
+                let (lower, upper) = range.ok_or_else(|| Error::MissingElement("range".to_string()))?;
+                TypeDefinition::Subrange { lower, upper, base_type: Box::new(get_base_type(base_type)?) }
+            }
+            "pointer" => TypeDefinition::Pointer(Box::new(get_base_type(base_type)?)),
+            // all other elements name an elementary type
+            _ => TypeDefinition::Reference(name),
+        })
+    }
+}
+
+impl Parseable for VariableDeclaration {
+    type Item = Self;
+
+    fn visit(reader: &mut PeekableReader) -> Result<Self::Item, Error> {
+        let location = reader.peek_range()?;
+        let attributes = reader.attributes()?;
+        let mut data_type = None;
+        let mut initial_value = None;
+        loop {
+            match reader.peek()? {
+                Event::Start(tag) => match tag.name().as_ref() {
+                    b"type" => data_type = Some(TypeDefinition::visit_wrapped(reader, b"type")?),
+                    b"initialValue" => initial_value = Some(visit_initial_value(reader)?),
+                    _ => reader.consume_element()?,
+                },
+                Event::End(tag) if tag.name().as_ref() == b"variable" => {
+                    reader.consume()?;
+                    break;
+                }
+                Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"variable"])),
+                _ => reader.consume()?,
+            }
+        }
+
+        Ok(VariableDeclaration {
+            name: attributes.get_or_err("name")?,
+            location,
+            address: attributes.get("address").cloned(),
+            data_type: data_type.ok_or_else(|| Error::MissingElement("type".to_string()))?,
+            initial_value,
+        })
+    }
+}
+
+/// reads the value of an `initialValue` element as structured text
+fn visit_initial_value(reader: &mut PeekableReader) -> Result<String, Error> {
+    // consume the initialValue's start
+    reader.consume()?;
+    let value = visit_child_value(reader)?;
+    reader.consume_until(vec![b"initialValue"])?;
+    Ok(value)
+}
+
+/// reads the first `simpleValue`, `arrayValue` or `structValue` child of the current element
+fn visit_child_value(reader: &mut PeekableReader) -> Result<String, Error> {
+    loop {
+        match reader.peek()? {
+            Event::Start(_) | Event::Empty(_) => return visit_value(reader),
+            Event::End(_) | Event::Eof => return Err(Error::MissingElement("simpleValue".to_string())),
+            _ => reader.consume()?,
+        }
+    }
+}
+
+/// reads a value, arrays are written as `[1, 2(0)]`, structs as `(x := 1, y := 2)`
+fn visit_value(reader: &mut PeekableReader) -> Result<String, Error> {
+    let (name, is_empty) = match reader.peek()? {
+        Event::Start(tag) => (tag.name().try_to_string()?, false),
+        Event::Empty(tag) => (tag.name().try_to_string()?, true),
+        _ => return Err(Error::MissingElement("simpleValue".to_string())),
+    };
+    if name != "arrayValue" && name != "structValue" {
+        return reader.element_attributes()?.get_or_err("value");
+    }
+
+    // consume the arrayValue's or structValue's start
+    reader.consume()?;
+    let mut elements = vec![];
+    while !is_empty {
+        match reader.peek()? {
+            Event::Start(tag) if tag.name().as_ref() == b"value" => {
+                let attributes = reader.attributes()?;
+                let value = visit_child_value(reader)?;
+                reader.consume_until(vec![b"value"])?;
+                elements.push(match (attributes.get("repetitionValue"), attributes.get("member")) {
+                    (Some(repetition), _) => format!("{repetition}({value})"),
+                    (None, Some(member)) => format!("{member} := {value}"),
+                    (None, None) => value,
+                });
+            }
+            Event::End(_) => {
+                reader.consume()?;
+                break;
+            }
+            Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"value"])),
+            _ => reader.consume()?,
+        }
+    }
+
+    Ok(match name.as_str() {
+        "arrayValue" => format!("[{}]", elements.join(", ")),
+        _ => format!("({})", elements.join(", ")),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::ops::Range;
+
+    use crate::{
+        model::data_type::{DataType, TypeDefinition, VariableDeclaration},
+        reader::PeekableReader,
+        serializer::{
+            XArray, XDataType, XDerived, XDimension, XEnum, XInitialValue, XRange, XSimpleValue, XStruct,
+            XSubrangeSigned, XType, XValue, XValues, XVariable,
+        },
+        xml_parser::Parseable,
+    };
+
+    /// the range of the first start tag in the content which begins with `tag`
+    pub(crate) fn get_tag_range(content: &str, tag: &str) -> Range<usize> {
+        let start = content.find(tag).unwrap();
+        start..start + content[start..].find('>').unwrap() + 1
+    }
+
+    #[test]
+    fn struct_with_initialized_member() {
+        let content = XDataType::new()
+            .with_attribute("name", "Point")
+            .with_base_type(
+                XType::new()
+                    .with_struct(
+                        XStruct::new()
+                            .with_variable(
+                                XVariable::new()
+                                    .with_attribute("name", "x")
+                                    .with_type(XType::new().with_elementary("INT"))
+                                    .with_initial_value(XInitialValue::new().with_simple_value(
+                                        XSimpleValue::new().with_attribute("value", "1").close(),
+                                    )),
+                            )
+                            .with_variable(
+                                XVariable::new().with_attribute("name", "buffer").with_type(
+                                    XType::new().with_array(
+                                        XArray::new()
+                                            .with_dimension(
+                                                XDimension::new()
+                                                    .with_attribute("lower", "0")
+                                                    .with_attribute("upper", "9")
+                                                    .close(),
+                                            )
+                                            .with_base_type(
+                                                XType::new()
+                                                    .with_derived(
+                                                        XDerived::new()
+                                                            .with_attribute("name", "Color")
+                                                            .close(),
+                                                    )
+                                                    .into_base_type(),
+                                            ),
+                                    ),
+                                ),
+                            ),
+                    )
+                    .into_base_type(),
+            )
+            .serialize();
+
+        let mut reader = PeekableReader::new(&content);
+        assert_eq!(
+            DataType::visit(&mut reader).unwrap(),
+            DataType {
+                name: "Point".to_string(),
+                location: get_tag_range(&content, "<dataType"),
+                base_type: TypeDefinition::Struct(vec![
+                    VariableDeclaration {
+                        name: "x".to_string(),
+                        location: get_tag_range(&content, r#"<variable name="x""#),
+                        address: None,
+                        data_type: TypeDefinition::Reference("INT".to_string()),
+                        initial_value: Some("1".to_string()),
+                    },
+                    VariableDeclaration {
+                        name: "buffer".to_string(),
+                        location: get_tag_range(&content, r#"<variable name="buffer""#),
+                        address: None,
+                        data_type: TypeDefinition::Array {
+                            dimensions: vec![("0".to_string(), "9".to_string())],
+                            base_type: Box::new(TypeDefinition::Reference("Color".to_string())),
+                        },
+                        initial_value: None,
+                    },
+                ]),
+                initial_value: None,
+            }
+        );
+    }
+
+    #[test]
+    fn enum_and_subrange() {
+        let content = XDataType::new()
+            .with_attribute("name", "Color")
+            .with_base_type(
+                XType::new()
+                    .with_enum(
+                        XEnum::new().with_values(
+                            XValues::new()
+                                .with_value(XValue::new().with_attribute("name", "red").close())
+                                .with_value(
+                                    XValue::new()
+                                        .with_attribute("name", "green")
+                                        .with_attribute("value", "5")
+                                        .close(),
+                                ),
+                        ),
+                    )
+                    .into_base_type(),
+            )
+            .with_initial_value(
+                XInitialValue::new()
+                    .with_simple_value(XSimpleValue::new().with_attribute("value", "green").close()),
+            )
+            .serialize();
+
+        let mut reader = PeekableReader::new(&content);
+        assert_eq!(
+            DataType::visit(&mut reader).unwrap(),
+            DataType {
+                name: "Color".to_string(),
+                location: get_tag_range(&content, "<dataType"),
+                base_type: TypeDefinition::Enum {
+                    values: vec![("red".to_string(), None), ("green".to_string(), Some("5".to_string()))],
+                    base_type: None,
+                },
+                initial_value: Some("green".to_string()),
+            }
+        );
+
+        let content = XDataType::new()
+            .with_attribute("name", "Percent")
+            .with_base_type(
+                XType::new()
+                    .with_subrange_signed(
+                        XSubrangeSigned::new()
+                            .with_range(
+                                XRange::new()
+                                    .with_attribute("lower", "0")
+                                    .with_attribute("upper", "100")
+                                    .close(),
+                            )
+                            .with_base_type(XType::new().with_elementary("INT").into_base_type()),
+                    )
+                    .into_base_type(),
+            )
+            .serialize();
+
+        let mut reader = PeekableReader::new(&content);
+        assert_eq!(
+            DataType::visit(&mut reader).unwrap(),
+            DataType {
+                name: "Percent".to_string(),
+                location: get_tag_range(&content, "<dataType"),
+                base_type: TypeDefinition::Subrange {
+                    lower: "0".to_string(),
+                    upper: "100".to_string(),
+                    base_type: Box::new(TypeDefinition::Reference("INT".to_string())),
+                },
+                initial_value: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use quick_xml::events::Event;
+
+use crate::{error::Error, reader::PeekableReader, xml_parser::Parseable};
+
+use super::data_type::VariableDeclaration;
+
+/// The `globalVars` of a configuration or a resource
+#[derive(Debug, PartialEq)]
+pub(crate) struct GlobalVariables {
+    /// the range of the `globalVars` start tag
+    pub location: Range<usize>,
+    pub constant: bool,
+    pub retain: bool,
+    pub variables: Vec<VariableDeclaration>,
+}
+
+impl Parseable for GlobalVariables {
+    type Item = Self;
+
+    fn visit(reader: &mut PeekableReader) -> Result<Self::Item, Error> {
+        let location = reader.peek_range()?;
+        let is_empty = matches!(reader.peek()?, Event::Empty(_));
+        let attributes = reader.attributes()?;
+        let mut variables = vec![];
+        while !is_empty {
+            match reader.peek()? {
+                Event::Start(tag) if tag.name().as_ref() == b"variable" => {
+                    variables.push(VariableDeclaration::visit(reader)?)
+                }
+                Event::Start(_) => reader.consume_element()?,
+                Event::End(tag) if tag.name().as_ref() == b"globalVars" => {
+                    reader.consume()?;
+                    break;
+                }
+                Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![b"globalVars"])),
+                _ => reader.consume()?,
+            }
+        }
+
+        Ok(GlobalVariables {
+            location,
+            constant: attributes.get("constant").map_or(false, |it| it == "true"),
+            retain: attributes.get("retain").map_or(false, |it| it == "true"),
+            variables,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        model::{
+            data_type::{tests::get_tag_range, TypeDefinition, VariableDeclaration},
+            global_variables::GlobalVariables,
+        },
+        reader::PeekableReader,
+        serializer::{XGlobalVars, XString, XType, XVariable},
+        xml_parser::Parseable,
+    };
+
+    #[test]
+    fn constant_variables_with_address() {
+        let content =
+            XGlobalVars::new()
+                .with_attribute("constant", "true")
+                .with_variable(
+                    XVariable::new()
+                        .with_attribute("name", "input")
+                        .with_attribute("address", "%IX1.0")
+                        .with_type(XType::new().with_elementary("BOOL")),
+                )
+                .with_variable(XVariable::new().with_attribute("name", "message").with_type(
+                    XType::new().with_string(XString::new().with_attribute("length", "20").close()),
+                ))
+                .serialize();
+
+        let mut reader = PeekableReader::new(&content);
+        assert_eq!(
+            GlobalVariables::visit(&mut reader).unwrap(),
+            GlobalVariables {
+                location: get_tag_range(&content, "<globalVars"),
+                constant: true,
+                retain: false,
+                variables: vec![
+                    VariableDeclaration {
+                        name: "input".to_string(),
+                        location: get_tag_range(&content, r#"<variable name="input""#),
+                        address: Some("%IX1.0".to_string()),
+                        data_type: TypeDefinition::Reference("BOOL".to_string()),
+                        initial_value: None,
+                    },
+                    VariableDeclaration {
+                        name: "message".to_string(),
+                        location: get_tag_range(&content, r#"<variable name="message""#),
+                        address: None,
+                        data_type: TypeDefinition::String { is_wide: false, length: Some("20".to_string()) },
+                        initial_value: None,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let content = XGlobalVars::new().close().serialize();
+
+        let mut reader = PeekableReader::new(&content);
+        assert_eq!(
+            GlobalVariables::visit(&mut reader).unwrap(),
+            GlobalVariables {
+                location: get_tag_range(&content, "<globalVars"),
+                constant: false,
+                retain: false,
+                variables: vec![]
+            }
+        );
+    }
+}
@@ -24,6 +24,16 @@ impl Interface {
         Some(&data.content)
     }
 
+    // The statements of a structured text body follow the declaration, before the END_... keyword
+    pub fn append_body(self, statements: &str) -> Self {
+        match self.add_data {
+            Some(old_data) => Interface {
+                add_data: Some(Data::new_implementation(&format!("{}\n{}", old_data.content, statements))),
+            },
+            None => self,
+        }
+    }
+
     // We have to append a END_... to the declaration, as it is missing in our text declaration
     pub fn append_end_keyword(self, pou_type: &PouType) -> Self {
         let Some(old_data) = self.add_data else {
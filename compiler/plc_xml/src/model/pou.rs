@@ -71,7 +71,9 @@ impl Parseable for Pou {
                         reader.consume_until_start(b"content")?;
                         match reader.next()? {
                             Event::Start(tag) => {
-                                pou.interface = Some(Interface::new(&reader.read_text(tag.name())?))
+                                let content = reader.read_text(tag.name())?;
+                                pou.interface =
+                                    Some(Interface::new(&html_escape::decode_html_entities(&content)))
                             }
                             _ => reader.consume()?,
                         }
@@ -79,6 +81,10 @@ impl Parseable for Pou {
                     b"body" => {
                         pou.body = Body::visit(reader)?;
                         if let Some(interface) = pou.interface {
+                            let interface = match &pou.body.structured_text {
+                                Some(statements) => interface.append_body(statements),
+                                None => interface,
+                            };
                             pou.interface = Some(interface.append_end_keyword(&pou.pou_type));
                         }
 
@@ -1,17 +1,21 @@
+use quick_xml::events::Event;
+
 use crate::xml_parser::Parseable;
 
-use super::pou::Pou;
+use super::{data_type::DataType, global_variables::GlobalVariables, pou::Pou};
 
 /// The Project root as specified in the official XSD
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub(crate) struct Project {
     pub pous: Vec<Pou>,
+    /// the types of the `dataTypes` element
+    pub data_types: Vec<DataType>,
+    /// the `globalVars` of all configurations and resources
+    pub global_variables: Vec<GlobalVariables>,
     /*
     attributes,
-    dataTypes,
     fileHeader,
     contentHeader,
-    instances,
     addData,
     documentation
     */
@@ -20,13 +24,27 @@ pub(crate) struct Project {
 impl Parseable for Project {
     type Item = Self;
 
-    fn visit(_reader: &mut crate::reader::PeekableReader) -> Result<Self::Item, crate::error::Error> {
-        unimplemented!()
+    fn visit(reader: &mut crate::reader::PeekableReader) -> Result<Self::Item, crate::error::Error> {
+        let mut project = Project::default();
+        loop {
+            match reader.peek()? {
+                Event::Start(tag) if tag.name().as_ref() == b"pou" => project.pous.push(Pou::visit(reader)?),
+                Event::Start(tag) if tag.name().as_ref() == b"dataType" => {
+                    project.data_types.push(DataType::visit(reader)?)
+                }
+                Event::Start(tag) | Event::Empty(tag) if tag.name().as_ref() == b"globalVars" => {
+                    project.global_variables.push(GlobalVariables::visit(reader)?)
+                }
+                Event::End(tag) if tag.name().as_ref() == b"project" => return Ok(project),
+                Event::Eof => return Err(crate::error::Error::UnexpectedEndOfFile(vec![b"project"])),
+                _ => reader.consume()?,
+            }
+        }
     }
 }
 
 impl Project {
     pub fn pou_entry(reader: &mut crate::reader::PeekableReader) -> Result<Self, crate::error::Error> {
-        Ok(Project { pous: vec![Pou::visit(reader)?] })
+        Ok(Project { pous: vec![Pou::visit(reader)?], ..Default::default() })
     }
 }
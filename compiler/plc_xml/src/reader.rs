@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
 
-use quick_xml::{events::Event, name::QName, Reader};
+use quick_xml::{
+    events::{BytesStart, Event},
+    name::QName,
+    Reader,
+};
 
 use crate::{error::Error, extensions::TryToString};
 
@@ -79,12 +83,46 @@ impl<'xml> PeekableReader<'xml> {
             _ => todo!(),
         };
 
-        let mut hm = HashMap::new();
-        for it in tag.attributes().flatten() {
-            hm.insert(it.key.try_to_string()?, it.value.try_to_string()?);
-        }
+        get_attributes(&tag)
+    }
+
+    /// Advances the reader past the element it is positioned at including its children, returning
+    /// the element's attributes.
+    pub fn element_attributes(&mut self) -> Result<HashMap<String, String>, Error> {
+        let attributes = match self.peek()? {
+            Event::Start(tag) | Event::Empty(tag) => get_attributes(tag)?,
+            event => return Err(Error::UnexpectedElement(format!("{event:?}"))),
+        };
+
+        self.consume_element()?;
+        Ok(attributes)
+    }
+
+    /// Returns the byte range of the start tag of the element the reader is positioned at.
+    pub fn peek_range(&mut self) -> Result<Range<usize>, Error> {
+        let tag_length = match self.peek()? {
+            Event::Start(tag) => tag.len() + "<>".len(),
+            Event::Empty(tag) => tag.len() + "</>".len(),
+            event => return Err(Error::UnexpectedElement(format!("{event:?}"))),
+        };
 
-        Ok(hm)
+        let end = self.reader.buffer_position();
+        Ok(end.saturating_sub(tag_length)..end)
+    }
+
+    /// Advances the reader past the element it is positioned at including its children.
+    pub fn consume_element(&mut self) -> Result<(), Error> {
+        let mut depth = 0;
+        loop {
+            match self.next()? {
+                Event::Start(_) => depth += 1,
+                Event::End(_) if depth <= 1 => return Ok(()),
+                Event::End(_) => depth -= 1,
+                Event::Empty(_) if depth == 0 => return Ok(()),
+                Event::Eof => return Err(Error::UnexpectedEndOfFile(vec![])),
+                _ => continue,
+            }
+        }
     }
 
     pub fn read_text(&mut self, name: QName) -> Result<String, Error> {
@@ -92,6 +130,15 @@ impl<'xml> PeekableReader<'xml> {
     }
 }
 
+fn get_attributes(tag: &BytesStart) -> Result<HashMap<String, String>, Error> {
+    let mut hm = HashMap::new();
+    for it in tag.attributes().flatten() {
+        hm.insert(it.key.try_to_string()?, it.value.try_to_string()?);
+    }
+
+    Ok(hm)
+}
+
 #[test]
 fn peek() {
     const CONTENT: &str = r#"
@@ -128,3 +175,23 @@ fn peek() {
     assert_eq!(temp.peek().unwrap(), &Event::Start(quick_xml::events::BytesStart::new("body")));
     assert_eq!(temp.next().unwrap(), Event::Start(quick_xml::events::BytesStart::new("body")));
 }
+
+#[test]
+fn element_attributes_of_end_tag_is_an_error() {
+    let mut reader = PeekableReader::new("<body></body>");
+    reader.consume().unwrap();
+    assert!(matches!(reader.element_attributes(), Err(Error::UnexpectedElement(_))));
+}
+
+#[test]
+fn peek_range_is_the_start_tag() {
+    const CONTENT: &str = r#"<types> <dataType name="foo"><baseType><INT /></baseType></dataType></types>"#;
+
+    let mut reader = PeekableReader::new(CONTENT);
+    reader.consume().unwrap();
+    assert_eq!(&CONTENT[reader.peek_range().unwrap()], r#"<dataType name="foo">"#);
+
+    reader.consume().unwrap();
+    reader.consume().unwrap();
+    assert_eq!(&CONTENT[reader.peek_range().unwrap()], "<INT />");
+}
@@ -1,4 +1,4 @@
-type Attributes = Vec<(&'static str, &'static str)>;
+type Attributes = Vec<(&'static str, String)>;
 
 /// Number of spaces to use when indenting XML
 const INDENT_SPACES: usize = 4;
@@ -6,7 +6,7 @@ const INDENT_SPACES: usize = 4;
 #[derive(Debug, Default)]
 enum Content {
     Node(Vec<Node>),
-    Data(&'static str),
+    Data(String),
 
     #[default]
     Empty,
@@ -54,7 +54,7 @@ impl Node {
     fn attributes(&self) -> String {
         let mut fmt = String::new();
         for attr in &self.attributes {
-            fmt = format!(r#"{fmt}{key}="{value}" "#, key = attr.0, value = attr.1)
+            fmt = format!(r#"{fmt}{key}="{value}" "#, key = attr.0, value = escape_attribute(&attr.1))
         }
 
         fmt
@@ -65,7 +65,9 @@ impl Node {
         let (indent, name, attributes) = (" ".repeat(level * INDENT_SPACES), self.name, self.attributes());
         let mut fmt = String::new();
         match self.content {
-            Content::Data(data) => fmt = format!("{indent}<{name} {attributes}>{data}</{name}>\n",),
+            Content::Data(ref data) => {
+                fmt = format!("{indent}<{name} {attributes}>{data}</{name}>\n", data = escape_text(data))
+            }
             _ => {
                 if self.closed {
                     fmt = format!(
@@ -106,6 +108,16 @@ impl Node {
     }
 }
 
+/// escapes the markup characters of an element's text
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// escapes the markup characters and double quotes of an attribute value
+fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
 pub(crate) fn with_header(data: &str) -> String {
     let header = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
     format!("{header}\n{data}")
@@ -140,13 +152,13 @@ macro_rules! declare_type_and_extend_if_needed {
                     Self(Node { name: $name_xml, attributes: vec![], closed: false, content: Content::Empty })
                 }
 
-                pub fn with_attribute(mut self, key: &'static str, value: &'static str) -> Self {
-                    self.0.attributes.push((key, value));
+                pub fn with_attribute(mut self, key: &'static str, value: impl Into<String>) -> Self {
+                    self.0.attributes.push((key, value.into()));
                     self
                 }
 
-                pub fn with_data(mut self, data: &'static str) -> Self {
-                    self.0.content = Content::Data(data);
+                pub fn with_data(mut self, data: impl Into<String>) -> Self {
+                    self.0.content = Content::Data(data.into());
                     self
                 }
 
@@ -178,6 +190,67 @@ macro_rules! declare_type_and_extend_if_needed {
 }
 
 declare_type_and_extend_if_needed! {
+    (
+        XProject, "project",
+        (XFileHeader, with_file_header),
+        (XContentHeader, with_content_header),
+        (XTypes, with_types),
+        (XInstances, with_instances)
+    ),
+    (XFileHeader, "fileHeader",),
+    (
+        XContentHeader, "contentHeader",
+        (XCoordinateInfo, with_coordinate_info)
+    ),
+    (
+        XCoordinateInfo, "coordinateInfo",
+        (XFbdInfo, with_fbd),
+        (XLdInfo, with_ld),
+        (XSfcInfo, with_sfc)
+    ),
+    (
+        XFbdInfo, "fbd",
+        (XScaling, with_scaling)
+    ),
+    (
+        XLdInfo, "ld",
+        (XScaling, with_scaling)
+    ),
+    (
+        XSfcInfo, "sfc",
+        (XScaling, with_scaling)
+    ),
+    (XScaling, "scaling",),
+    (
+        XTypes, "types",
+        (XDataTypes, with_data_types),
+        (XPous, with_pous)
+    ),
+    (
+        XDataTypes, "dataTypes",
+        (XDataType, with_data_type)
+    ),
+    (
+        XDataType, "dataType",
+        (XBaseType, with_base_type),
+        (XInitialValue, with_initial_value)
+    ),
+    (
+        XPous, "pous",
+        (XPou, with_pou)
+    ),
+    (
+        XInstances, "instances",
+        (XConfigurations, with_configurations)
+    ),
+    (
+        XConfigurations, "configurations",
+        (XConfiguration, with_configuration)
+    ),
+    (
+        XConfiguration, "configuration",
+        (XGlobalVars, with_global_vars)
+    ),
     (
         XPou, "pou",
         (XBody, with_body),
@@ -192,8 +265,14 @@ declare_type_and_extend_if_needed! {
     (
 
         XBody, "body",
-        (XFbd, with_fbd)
+        (XFbd, with_fbd),
+        (XSt, with_st)
+    ),
+    (
+        XSt, "ST",
+        (XXhtml, with_xhtml)
     ),
+    (XXhtml, "xhtml:p",),
     (
         XConnectionPointIn, "connectionPointIn",
         (XConnection, with_connection),
@@ -215,7 +294,9 @@ declare_type_and_extend_if_needed! {
     (
         XVariable, "variable",
         (XConnectionPointIn, with_connection_in),
-        (XConnectionPointOut, with_connection_out)
+        (XConnectionPointOut, with_connection_out),
+        (XType, with_type),
+        (XInitialValue, with_initial_value)
     ),
     (
         XInVariable, "inVariable",
@@ -243,10 +324,95 @@ declare_type_and_extend_if_needed! {
     ),
     (
         XInterface, "interface",
+        (XReturnType, with_return_type),
+        (XInputVars, with_input_vars),
+        (XOutputVars, with_output_vars),
+        (XInOutVars, with_in_out_vars),
         (XLocalVars, with_local_vars),
+        (XTempVars, with_temp_vars),
         (XAddData, with_add_data)
     ),
-    (XLocalVars, "localVars",),
+    (
+        XLocalVars, "localVars",
+        (XVariable, with_variable)
+    ),
+    (
+        XInputVars, "inputVars",
+        (XVariable, with_variable)
+    ),
+    (
+        XOutputVars, "outputVars",
+        (XVariable, with_variable)
+    ),
+    (
+        XInOutVars, "inOutVars",
+        (XVariable, with_variable)
+    ),
+    (
+        XTempVars, "tempVars",
+        (XVariable, with_variable)
+    ),
+    (
+        XGlobalVars, "globalVars",
+        (XVariable, with_variable)
+    ),
+    (XReturnType, "returnType",),
+    (
+        XInitialValue, "initialValue",
+        (XSimpleValue, with_simple_value)
+    ),
+    (XSimpleValue, "simpleValue",),
+    (
+        XType, "type",
+        (XDerived, with_derived),
+        (XString, with_string),
+        (XWstring, with_wstring),
+        (XArray, with_array),
+        (XStruct, with_struct),
+        (XEnum, with_enum),
+        (XSubrangeSigned, with_subrange_signed),
+        (XSubrangeUnsigned, with_subrange_unsigned),
+        (XPointer, with_pointer)
+    ),
+    (XBaseType, "baseType",),
+    (XDerived, "derived",),
+    (XString, "string",),
+    (XWstring, "wstring",),
+    (
+        XArray, "array",
+        (XDimension, with_dimension),
+        (XBaseType, with_base_type)
+    ),
+    (XDimension, "dimension",),
+    (
+        XStruct, "struct",
+        (XVariable, with_variable)
+    ),
+    (
+        XEnum, "enum",
+        (XValues, with_values),
+        (XBaseType, with_base_type)
+    ),
+    (
+        XValues, "values",
+        (XValue, with_value)
+    ),
+    (XValue, "value",),
+    (
+        XSubrangeSigned, "subrangeSigned",
+        (XRange, with_range),
+        (XBaseType, with_base_type)
+    ),
+    (
+        XSubrangeUnsigned, "subrangeUnsigned",
+        (XRange, with_range),
+        (XBaseType, with_base_type)
+    ),
+    (XRange, "range",),
+    (
+        XPointer, "pointer",
+        (XBaseType, with_base_type)
+    ),
     (
         XAddData, "addData",
         (XData, with_data_data)
@@ -279,6 +445,29 @@ declare_type_and_extend_if_needed! {
     ),
 }
 
+impl XType {
+    /// adds the element of an elementary type (e.g. `<INT/>`)
+    pub(crate) fn with_elementary(mut self, name: &'static str) -> Self {
+        self.get_inner_ref_mut().content.push(Node {
+            name,
+            attributes: vec![],
+            closed: true,
+            content: Content::Empty,
+        });
+        self
+    }
+
+    /// the same type as the base type of a data type, an array, an enum, a subrange or a pointer
+    pub(crate) fn into_base_type(self) -> XBaseType {
+        XBaseType(Node { name: "baseType", ..self.get_inner() })
+    }
+
+    /// the same type as the return type of a function
+    pub(crate) fn into_return_type(self) -> XReturnType {
+        XReturnType(Node { name: "returnType", ..self.get_inner() })
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,471 @@
+//! Exports structured text sources as a PLCopen TC6 XML project.
+//!
+//! Types and global variables are written as structured XML, expressions (e.g. initial values or array
+//! bounds) keep the text they were declared with. Every POU carries a structured interface and its
+//! text declaration as additional data, which is what the importer reads back. Its statements are
+//! written as a `<ST>` body.
+//!
+//! The importer reads the types and global variables back as well, so an exported project compiles
+//! again without its structured text sources.
+use std::ops::Range;
+
+use ast::ast::{
+    flatten_expression_list, AstStatement, CompilationUnit, DataType, DataTypeDeclaration, Implementation,
+    LinkageType, Pou, PouType, UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
+};
+use plc::symbols::PLCOPEN_ELEMENTARY_TYPES;
+
+use crate::serializer::{
+    with_header, XAddData, XArray, XBody, XConfiguration, XConfigurations, XContent, XContentHeader,
+    XCoordinateInfo, XData, XDataType, XDataTypes, XDerived, XDimension, XEnum, XFbdInfo, XFileHeader,
+    XGlobalVars, XInOutVars, XInitialValue, XInputVars, XInstances, XInterface, XLdInfo, XLocalVars,
+    XOutputVars, XPointer, XPou, XPous, XProject, XRange, XScaling, XSfcInfo, XSimpleValue, XSt, XString,
+    XStruct, XSubrangeSigned, XSubrangeUnsigned, XTempVars, XTextDeclaration, XType, XTypes, XValue, XValues,
+    XVariable, XWstring, XXhtml,
+};
+
+const TC6_NAMESPACE: &str = "http://www.plcopen.org/xml/tc6_0201";
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+/// the additional data holding the text declaration of a POU
+const TEXT_DECLARATION_DATA: &str = "www.bachmann.at/plc/plcopenxml";
+
+/// the elementary types whose subranges are unsigned
+const UNSIGNED_TYPES: &[&str] = &["BYTE", "WORD", "DWORD", "LWORD", "USINT", "UINT", "UDINT", "ULINT"];
+
+/// the variables of a block as the given variable list, marked `constant` or `retain` like the block
+macro_rules! variable_list {
+    ($list:ident, $block:expr, $source:expr) => {{
+        let block: &VariableBlock = $block;
+        let mut list = $list::new();
+        if block.constant {
+            list = list.with_attribute("constant", "true");
+        }
+        if block.retain {
+            list = list.with_attribute("retain", "true");
+        }
+        block.variables.iter().fold(list, |list, it| list.with_variable(export_variable(it, $source)))
+    }};
+}
+
+/// Exports the types, POUs and global variables of the given units as a PLCopen TC6 project.
+///
+/// Every unit is passed with the source it was parsed from. Only programs, functions and function
+/// blocks are exported as POUs, types that cannot be expressed in PLCopen XML (e.g. unions or generic
+/// types) are skipped.
+pub fn export(project_name: &str, creation_date_time: &str, sources: &[(&CompilationUnit, &str)]) -> String {
+    let mut data_types = XDataTypes::new();
+    let mut pous = XPous::new();
+    let mut configuration = XConfiguration::new().with_attribute("name", project_name);
+    for (unit, source) in sources {
+        for user_type in &unit.user_types {
+            if let Some(data_type) = export_data_type(user_type, source) {
+                data_types = data_types.with_data_type(data_type);
+            }
+        }
+
+        for pou in unit.units.iter().filter(|it| is_exported(it)) {
+            let implementation = unit
+                .implementations
+                .iter()
+                .find(|it| it.pou_type == pou.pou_type && it.name.eq_ignore_ascii_case(&pou.name));
+            if let Some(implementation) = implementation {
+                pous = pous.with_pou(export_pou(pou, implementation, source));
+            }
+        }
+
+        for block in unit.global_vars.iter().filter(|it| it.linkage == LinkageType::Internal) {
+            configuration = configuration.with_global_vars(variable_list!(XGlobalVars, block, source));
+        }
+    }
+
+    let project = XProject::new()
+        .with_attribute("xmlns", TC6_NAMESPACE)
+        .with_file_header(
+            XFileHeader::new()
+                .with_attribute("companyName", "")
+                .with_attribute("productName", project_name)
+                .with_attribute("productVersion", env!("CARGO_PKG_VERSION"))
+                .with_attribute("creationDateTime", creation_date_time)
+                .close(),
+        )
+        .with_content_header(
+            XContentHeader::new().with_attribute("name", project_name).with_coordinate_info(
+                XCoordinateInfo::new()
+                    .with_fbd(XFbdInfo::new().with_scaling(scaling()))
+                    .with_ld(XLdInfo::new().with_scaling(scaling()))
+                    .with_sfc(XSfcInfo::new().with_scaling(scaling())),
+            ),
+        )
+        .with_types(XTypes::new().with_data_types(data_types).with_pous(pous))
+        .with_instances(
+            XInstances::new().with_configurations(XConfigurations::new().with_configuration(configuration)),
+        );
+
+    with_header(&project.serialize())
+}
+
+fn is_exported(pou: &Pou) -> bool {
+    pou.linkage == LinkageType::Internal
+        && matches!(pou.pou_type, PouType::Program | PouType::Function | PouType::FunctionBlock)
+        && !pou.location.is_undefined()
+}
+
+fn scaling() -> XScaling {
+    XScaling::new().with_attribute("x", "1").with_attribute("y", "1").close()
+}
+
+fn export_pou(pou: &Pou, implementation: &Implementation, source: &str) -> XPou {
+    let pou_type = match pou.pou_type {
+        PouType::Function => "function",
+        PouType::FunctionBlock => "functionBlock",
+        _ => "program",
+    };
+
+    // the declaration reaches from the POU's attributes to its first statement
+    let start =
+        pou.attributes.iter().map(|it| it.location.get_start()).fold(pou.location.get_start(), usize::min);
+    let declaration = get_text(source, start..implementation.location.get_start());
+    let statements = strip_end_keyword(get_text(source, implementation.location.to_range()));
+
+    XPou::new()
+        .with_attribute("name", &pou.name)
+        .with_attribute("pouType", pou_type)
+        .with_interface(export_interface(pou, declaration, source))
+        .with_body(XBody::new().with_st(
+            XSt::new().with_xhtml(
+                XXhtml::new().with_attribute("xmlns:xhtml", XHTML_NAMESPACE).with_data(statements),
+            ),
+        ))
+}
+
+fn export_interface(pou: &Pou, declaration: &str, source: &str) -> XInterface {
+    let mut interface = XInterface::new();
+    if let Some(return_type) = &pou.return_type {
+        interface = interface.with_return_type(export_type(return_type, source).into_return_type());
+    }
+
+    for block in &pou.variable_blocks {
+        interface = match block.variable_block_type {
+            VariableBlockType::Input(_) => {
+                interface.with_input_vars(variable_list!(XInputVars, block, source))
+            }
+            VariableBlockType::Output => {
+                interface.with_output_vars(variable_list!(XOutputVars, block, source))
+            }
+            VariableBlockType::InOut => interface.with_in_out_vars(variable_list!(XInOutVars, block, source)),
+            VariableBlockType::Local => interface.with_local_vars(variable_list!(XLocalVars, block, source)),
+            VariableBlockType::Temp => interface.with_temp_vars(variable_list!(XTempVars, block, source)),
+            // global variables are declared by the configuration
+            VariableBlockType::Global => interface,
+        };
+    }
+
+    interface.with_add_data(
+        XAddData::new().with_data_data(
+            XData::new()
+                .with_attribute("name", TEXT_DECLARATION_DATA)
+                .with_attribute("handleUnknown", "implementation")
+                .with_text_declaration(
+                    XTextDeclaration::new().with_content(XContent::new().with_data(declaration)),
+                ),
+        ),
+    )
+}
+
+fn export_variable(variable: &Variable, source: &str) -> XVariable {
+    let mut xml = XVariable::new().with_attribute("name", &variable.name);
+    if let Some(address) = &variable.address {
+        // the address' location includes the AT keyword
+        let address = get_text(source, address.get_location().to_range());
+        xml = xml.with_attribute("address", address.find('%').map_or(address, |it| &address[it..]));
+    }
+
+    xml = xml.with_type(export_type(&variable.data_type_declaration, source));
+    match &variable.initializer {
+        Some(initializer) => xml.with_initial_value(export_initial_value(initializer, source)),
+        None => xml,
+    }
+}
+
+fn export_data_type(user_type: &UserTypeDeclaration, source: &str) -> Option<XDataType> {
+    if !user_type.generics.is_empty() || user_type.scope.is_some() {
+        return None;
+    }
+
+    let name = user_type.data_type.get_name()?;
+    let data_type = XDataType::new()
+        .with_attribute("name", name)
+        .with_base_type(define_type(&user_type.data_type, source)?.into_base_type());
+    Some(match &user_type.initializer {
+        Some(initializer) => data_type.with_initial_value(export_initial_value(initializer, source)),
+        None => data_type,
+    })
+}
+
+fn export_initial_value(initializer: &AstStatement, source: &str) -> XInitialValue {
+    XInitialValue::new().with_simple_value(
+        XSimpleValue::new()
+            .with_attribute("value", get_text(source, initializer.get_location().to_range()))
+            .close(),
+    )
+}
+
+/// the type of a declaration, definitions without a PLCopen equivalent are referenced by their text
+fn export_type(declaration: &DataTypeDeclaration, source: &str) -> XType {
+    match declaration {
+        DataTypeDeclaration::DataTypeReference { referenced_type, .. } => reference_type(referenced_type),
+        DataTypeDeclaration::DataTypeDefinition { data_type, location, .. } => define_type(data_type, source)
+            .unwrap_or_else(|| derived_type(get_text(source, location.to_range()))),
+    }
+}
+
+fn reference_type(name: &str) -> XType {
+    let type_name = name.to_uppercase();
+    match PLCOPEN_ELEMENTARY_TYPES.iter().copied().find(|it| *it == type_name) {
+        Some(elementary) => XType::new().with_elementary(elementary),
+        None if type_name == "STRING" => XType::new().with_string(XString::new().close()),
+        None if type_name == "WSTRING" => XType::new().with_wstring(XWstring::new().close()),
+        None => derived_type(name),
+    }
+}
+
+fn derived_type(name: &str) -> XType {
+    XType::new().with_derived(XDerived::new().with_attribute("name", name).close())
+}
+
+/// the structure of a type definition, `None` if it cannot be expressed in PLCopen XML
+fn define_type(data_type: &DataType, source: &str) -> Option<XType> {
+    let xml = match data_type {
+        DataType::StructType { variables, .. } => XType::new().with_struct(
+            variables.iter().fold(XStruct::new(), |xml, it| xml.with_variable(export_variable(it, source))),
+        ),
+        DataType::EnumType { elements, numeric_type, .. } => {
+            let values =
+                flatten_expression_list(elements).into_iter().try_fold(XValues::new(), |values, it| {
+                    Some(values.with_value(export_enum_value(it, source)?))
+                })?;
+            XType::new().with_enum(
+                XEnum::new()
+                    .with_values(values)
+                    .with_base_type(reference_type(numeric_type).into_base_type()),
+            )
+        }
+        DataType::SubRangeType { referenced_type, bounds: None, .. } => reference_type(referenced_type),
+        DataType::SubRangeType { referenced_type, bounds: Some(bounds), .. } => {
+            let (lower, upper) = get_bounds(bounds, source)?;
+            let range = XRange::new().with_attribute("lower", lower).with_attribute("upper", upper).close();
+            let base_type = reference_type(referenced_type).into_base_type();
+            if UNSIGNED_TYPES.contains(&referenced_type.to_uppercase().as_str()) {
+                XType::new().with_subrange_unsigned(
+                    XSubrangeUnsigned::new().with_range(range).with_base_type(base_type),
+                )
+            } else {
+                XType::new()
+                    .with_subrange_signed(XSubrangeSigned::new().with_range(range).with_base_type(base_type))
+            }
+        }
+        DataType::ArrayType { bounds, referenced_type, is_variable_length: false, .. } => {
+            let array =
+                flatten_expression_list(bounds).into_iter().try_fold(XArray::new(), |array, it| {
+                    let (lower, upper) = get_bounds(it, source)?;
+                    let dimension =
+                        XDimension::new().with_attribute("lower", lower).with_attribute("upper", upper);
+                    Some(array.with_dimension(dimension.close()))
+                })?;
+            XType::new()
+                .with_array(array.with_base_type(export_type(referenced_type, source).into_base_type()))
+        }
+        DataType::PointerType { referenced_type, auto_deref: false, .. } => XType::new().with_pointer(
+            XPointer::new().with_base_type(export_type(referenced_type, source).into_base_type()),
+        ),
+        DataType::StringType { is_wide, size, .. } => {
+            let length = size.as_ref().map(|it| get_text(source, it.get_location().to_range()));
+            match (*is_wide, length) {
+                (false, Some(length)) => {
+                    XType::new().with_string(XString::new().with_attribute("length", length).close())
+                }
+                (false, None) => XType::new().with_string(XString::new().close()),
+                (true, Some(length)) => {
+                    XType::new().with_wstring(XWstring::new().with_attribute("length", length).close())
+                }
+                (true, None) => XType::new().with_wstring(XWstring::new().close()),
+            }
+        }
+        _ => return None,
+    };
+    Some(xml)
+}
+
+/// an element of an enum with its explicit value
+fn export_enum_value(element: &AstStatement, source: &str) -> Option<XValue> {
+    match element {
+        AstStatement::Assignment { left, right, .. } => Some(
+            XValue::new()
+                .with_attribute("name", left.get_flat_reference_name()?)
+                .with_attribute("value", get_text(source, right.get_location().to_range()))
+                .close(),
+        ),
+        _ => Some(XValue::new().with_attribute("name", element.get_flat_reference_name()?).close()),
+    }
+}
+
+/// the text of the lower and the upper bound of a range statement
+fn get_bounds<'s>(range: &AstStatement, source: &'s str) -> Option<(&'s str, &'s str)> {
+    match range {
+        AstStatement::RangeStatement { start, end, .. } => Some((
+            get_text(source, start.get_location().to_range()),
+            get_text(source, end.get_location().to_range()),
+        )),
+        _ => None,
+    }
+}
+
+fn get_text(source: &str, range: Range<usize>) -> &str {
+    source.get(range).unwrap_or_default().trim()
+}
+
+/// removes the trailing `END_...` keyword of an implementation
+fn strip_end_keyword(statements: &str) -> &str {
+    let start = statements.rfind(|it: char| !(it.is_alphanumeric() || it == '_')).map_or(0, |it| it + 1);
+    if statements[start..].to_uppercase().starts_with("END_") {
+        statements[..start].trim_end()
+    } else {
+        statements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{
+        ast::{CompilationUnit, LinkageType, SourceRangeFactory},
+        provider::IdProvider,
+    };
+    use plc::lexer;
+
+    use crate::xml_parser;
+
+    const SOURCE: &str = "
+TYPE Point : STRUCT x : INT; y : INT := 2; END_STRUCT END_TYPE
+TYPE Color : (red, green := 5, blue); END_TYPE
+TYPE Percent : INT(0..100); END_TYPE
+TYPE Buffer : ARRAY[0..9] OF BYTE; END_TYPE
+
+VAR_GLOBAL CONSTANT limit : INT := 10; END_VAR
+VAR_GLOBAL input AT %IX1.0 : BOOL; END_VAR
+
+FUNCTION add : INT
+VAR_INPUT a, b : INT; END_VAR
+add := a + b;
+END_FUNCTION
+
+PROGRAM main
+VAR count : INT; END_VAR
+IF count < limit THEN count := add(count, 1); END_IF
+END_PROGRAM
+";
+
+    fn parse(source: &str) -> CompilationUnit {
+        let (unit, diagnostics) = plc::parser::parse(
+            lexer::lex_with_ids(source, IdProvider::default(), SourceRangeFactory::for_file("test.st")),
+            LinkageType::Internal,
+            "test.st",
+        );
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        unit
+    }
+
+    fn export(source: &str) -> String {
+        super::export("test", "2023-05-01T12:00:00", &[(&parse(source), source)])
+    }
+
+    #[test]
+    fn data_types_are_exported_as_structured_types() {
+        let xml = export(SOURCE);
+
+        assert!(xml.contains(r#"<dataType name="Point" >"#));
+        assert!(xml.contains(r#"<simpleValue value="2" />"#));
+        assert!(xml.contains(r#"<value name="red" />"#));
+        assert!(xml.contains(r#"<value name="green" value="5" />"#));
+        assert!(xml.contains(r#"<range lower="0" upper="100" />"#));
+        assert!(xml.contains(r#"<dimension lower="0" upper="9" />"#));
+    }
+
+    #[test]
+    fn global_variables_are_exported_into_the_configuration() {
+        let xml = export(SOURCE);
+
+        assert!(xml.contains(r#"<configuration name="test" >"#));
+        assert!(xml.contains(r#"<globalVars constant="true" >"#));
+        assert!(xml.contains(r#"<variable name="input" address="%IX1.0" >"#));
+    }
+
+    #[test]
+    fn pous_keep_their_text_declaration_and_body() {
+        let xml = export(SOURCE);
+
+        assert!(xml.contains(r#"<pou name="add" pouType="function" >"#));
+        assert!(xml.contains("<content >FUNCTION add : INT\nVAR_INPUT a, b : INT; END_VAR</content>"));
+        assert!(xml.contains(
+            r#"<xhtml:p xmlns:xhtml="http://www.w3.org/1999/xhtml" >IF count &lt; limit THEN count := add(count, 1); END_IF</xhtml:p>"#
+        ));
+    }
+
+    #[test]
+    fn exported_data_types_and_global_variables_are_imported() {
+        let original = parse(SOURCE);
+        let xml = export(SOURCE);
+
+        let (imported, diagnostics) =
+            xml_parser::parse(&xml, "test.xml", LinkageType::Internal, IdProvider::default());
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(format!("{:#?}", imported.user_types), format!("{:#?}", original.user_types));
+        let globals = |unit: &CompilationUnit| {
+            unit.global_vars
+                .iter()
+                .flat_map(|block| {
+                    block.variables.iter().map(move |it| {
+                        let declaration = format!("{:?}: {:?}", it.data_type_declaration, it.initializer);
+                        (block.constant, it.name.clone(), declaration, it.address.is_some())
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(globals(&imported), globals(&original));
+    }
+
+    #[test]
+    fn exported_pous_are_imported_with_their_statements() {
+        let source = "
+FUNCTION add : INT
+VAR_INPUT a, b : INT; END_VAR
+add := a + b;
+END_FUNCTION
+
+FUNCTION_BLOCK counter
+VAR_OUTPUT count : INT; END_VAR
+count := add(count, 1);
+END_FUNCTION_BLOCK
+
+PROGRAM main
+VAR instance : counter; END_VAR
+IF instance.count < 10 THEN instance(); END_IF
+END_PROGRAM
+";
+        let original = parse(source);
+        let xml = super::export("test", "2023-05-01T12:00:00", &[(&original, source)]);
+
+        let (imported, diagnostics) =
+            xml_parser::parse(&xml, "test.xml", LinkageType::Internal, IdProvider::default());
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(format!("{:#?}", imported.units), format!("{:#?}", original.units));
+        let statements = |unit: &CompilationUnit| {
+            unit.implementations
+                .iter()
+                .map(|it| format!("{}: {:#?}", it.name, it.statements))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(statements(&imported), statements(&original));
+    }
+}
@@ -1,11 +1,14 @@
 use ast::{
     ast::{
-        AstId, AstStatement, CompilationUnit, Implementation, LinkageType, PouType as AstPouType,
+        AstId, AstStatement, CompilationUnit, Implementation, LinkageType, NewLines, PouType as AstPouType,
         SourceRange, SourceRangeFactory,
     },
     provider::IdProvider,
 };
-use plc::{lexer, parser::expressions_parser::parse_expression};
+use plc::{
+    lexer::{self, Token},
+    parser::expressions_parser::parse_expression,
+};
 use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
 
 use quick_xml::events::Event;
@@ -18,7 +21,9 @@ use crate::{
 
 mod action;
 mod block;
+mod data_type;
 mod fbd;
+mod global_variables;
 mod pou;
 mod tests;
 mod variables;
@@ -54,7 +59,7 @@ pub fn parse_file(
     unit
 }
 
-fn parse(
+pub(crate) fn parse(
     source: &str,
     location: &'static str,
     linkage: LinkageType,
@@ -69,13 +74,19 @@ fn parse(
     // create a new parse session
     let parser = ParseSession::new(&project, location, id_provider, linkage);
 
-    // try to parse a declaration data field
-    let Some((unit, diagnostics)) = parser.try_parse_declaration() else {
-        unimplemented!("XML schemas without text declarations are not yet supported")
+    // try to parse a declaration data field, a project without POUs may still declare types and globals
+    let (mut unit, mut diagnostics) = match parser.try_parse_declaration() {
+        Some(declaration) => declaration,
+        None if project.pous.is_empty() => (CompilationUnit::new(location, NewLines::build(source)), vec![]),
+        None => unimplemented!("XML schemas without text declarations are not yet supported"),
     };
+    parser.parse_project_declarations(&mut unit, &mut diagnostics);
 
-    // transform the data model into rusty AST statements and add them to the compilation unit
-    (unit.with_implementations(parser.parse_model()), diagnostics)
+    // POUs with a structured text body are implemented by their declaration, all others are transformed
+    // from the data model into rusty AST statements and added to the compilation unit
+    unit.implementations.retain(|it| !parser.is_modeled(&it.name));
+    unit.implementations.extend(parser.parse_model());
+    (unit, diagnostics)
 }
 
 pub(crate) struct ParseSession<'parse> {
@@ -102,23 +113,45 @@ impl<'parse> ParseSession<'parse> {
         }
     }
 
-    /// parse the compilation unit from the addData field
+    /// parse the compilation unit from the addData fields of all POUs
     fn try_parse_declaration(&self) -> Option<(CompilationUnit, Vec<Diagnostic>)> {
-        let Some(content) = self.project.pous
-            .first()
-            .and_then(|it|
-                it.interface
-                    .as_ref()
-                    .and_then(|it|
-                        it.get_data_content()
-                    )
-        ) else {
-            return None
-        };
+        let mut declarations = self
+            .project
+            .pous
+            .iter()
+            .filter_map(|it| it.interface.as_ref().and_then(|it| it.get_data_content()))
+            .map(|content| self.parse_declaration(content));
+
+        let (mut unit, mut diagnostics) = declarations.next()?;
+        for (other, other_diagnostics) in declarations {
+            unit.import(other);
+            diagnostics.extend(other_diagnostics);
+        }
+        Some((unit, diagnostics))
+    }
+
+    /// transforms the data types and the global variables of the project, malformed declarations are skipped
+    /// and reported
+    fn parse_project_declarations(&self, unit: &mut CompilationUnit, diagnostics: &mut Vec<Diagnostic>) {
+        for data_type in &self.project.data_types {
+            match data_type.transform(self) {
+                Ok(user_type) => unit.user_types.push(user_type),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        }
 
+        for global_variables in &self.project.global_variables {
+            match global_variables.transform(self) {
+                Ok(block) => unit.global_vars.push(block),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        }
+    }
+
+    fn parse_declaration(&self, content: &str) -> (CompilationUnit, Vec<Diagnostic>) {
         //TODO: if our ST parser returns a diagnostic here, we might not have a text declaration and need to rely on the XML to provide us with
         // the necessary data. for now, we will assume to always have a text declaration
-        Some(plc::parser::parse(
+        plc::parser::parse(
             lexer::lex_with_ids(
                 content,
                 self.id_provider.clone(),
@@ -126,7 +159,15 @@ impl<'parse> ParseSession<'parse> {
             ),
             self.linkage,
             self.file_name,
-        ))
+        )
+    }
+
+    /// whether the POU is implemented by its data model rather than by a structured text body
+    fn is_modeled(&self, pou_name: &str) -> bool {
+        self.project
+            .pous
+            .iter()
+            .any(|it| it.body.structured_text.is_none() && it.name.eq_ignore_ascii_case(pou_name))
     }
 
     fn parse_expression(&self, expr: &str) -> AstStatement {
@@ -138,11 +179,46 @@ impl<'parse> ParseSession<'parse> {
         ))
     }
 
+    /// parses a value of the data model (e.g. an initial value or an array bound), text which is not a single
+    /// expression is reported at the location of the element it was read from
+    fn parse_value(&self, value: &str, location: &SourceRange) -> Result<AstStatement, Diagnostic> {
+        let mut lexer = lexer::lex_with_ids(
+            html_escape::decode_html_entities_to_string(value, &mut String::new()),
+            self.id_provider.clone(),
+            SourceRangeFactory::internal(),
+        );
+        let statement = parse_expression(&mut lexer);
+        if lexer.diagnostics.is_empty()
+            && lexer.token == Token::End
+            && !matches!(statement, AstStatement::EmptyStatement { .. })
+        {
+            Ok(statement)
+        } else {
+            Err(Diagnostic::syntax_error(&format!("Invalid value '{value}'"), location.clone()))
+        }
+    }
+
+    /// parses the bounds of an array dimension or a subrange
+    fn parse_range(
+        &self,
+        lower: &str,
+        upper: &str,
+        location: &SourceRange,
+    ) -> Result<AstStatement, Diagnostic> {
+        Ok(AstStatement::RangeStatement {
+            start: Box::new(self.parse_value(lower, location)?),
+            end: Box::new(self.parse_value(upper, location)?),
+            id: self.next_id(),
+        })
+    }
+
     fn parse_model(&self) -> Vec<Implementation> {
         let mut implementations = vec![];
         for pou in &self.project.pous {
-            // transform body
-            implementations.push(pou.build_implementation(self));
+            // transform body, structured text bodies are part of the declaration
+            if pou.body.structured_text.is_none() {
+                implementations.push(pou.build_implementation(self));
+            }
             // transform actions
             pou.actions.iter().for_each(|action| implementations.push(action.build_implementation(self)));
         }
@@ -0,0 +1,368 @@
+use ast::ast::{
+    AstFactory, AstStatement, DataType as AstDataType, DataTypeDeclaration, SourceRange, UserTypeDeclaration,
+    Variable,
+};
+use plc::typesystem::DINT_TYPE;
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::model::data_type::{DataType, TypeDefinition, VariableDeclaration};
+
+use super::ParseSession;
+
+impl DataType {
+    pub(crate) fn transform(&self, session: &ParseSession) -> Result<UserTypeDeclaration, Diagnostic> {
+        let location = session.create_range(self.location.clone());
+        let data_type = match self.base_type.transform(session, Some(self.name.clone()), &location)? {
+            DataTypeDeclaration::DataTypeDefinition { data_type, .. } => data_type,
+            // an alias of another type (e.g. `TYPE MyInt : INT; END_TYPE`)
+            DataTypeDeclaration::DataTypeReference { referenced_type, .. } => {
+                AstDataType::SubRangeType { name: Some(self.name.clone()), referenced_type, bounds: None }
+            }
+        };
+
+        let initializer = match (&self.base_type, &self.initial_value) {
+            // structs are initialized by their members
+            (TypeDefinition::Struct(_), _) | (_, None) => None,
+            (_, Some(initial_value)) => Some(session.parse_value(initial_value, &location)?),
+        };
+
+        Ok(UserTypeDeclaration {
+            data_type,
+            initializer,
+            location,
+            scope: None,
+            generics: vec![],
+            attributes: vec![],
+        })
+    }
+}
+
+impl TypeDefinition {
+    /// the declaration of the type, types without a name are defined inline (e.g. the type of a variable)
+    fn transform(
+        &self,
+        session: &ParseSession,
+        name: Option<String>,
+        location: &SourceRange,
+    ) -> Result<DataTypeDeclaration, Diagnostic> {
+        let data_type = match self {
+            TypeDefinition::Reference(referenced_type) => {
+                return Ok(DataTypeDeclaration::DataTypeReference {
+                    referenced_type: referenced_type.to_string(),
+                    location: location.clone(),
+                })
+            }
+            TypeDefinition::String { is_wide, length: None } => {
+                return Ok(DataTypeDeclaration::DataTypeReference {
+                    referenced_type: if *is_wide { "WSTRING" } else { "STRING" }.to_string(),
+                    location: location.clone(),
+                })
+            }
+            TypeDefinition::String { is_wide, length: Some(length) } => AstDataType::StringType {
+                name,
+                is_wide: *is_wide,
+                size: Some(session.parse_value(length, location)?),
+            },
+            TypeDefinition::Array { dimensions, base_type } => {
+                let mut bounds = dimensions
+                    .iter()
+                    .map(|(lower, upper)| session.parse_range(lower, upper, location))
+                    .collect::<Result<Vec<_>, _>>()?;
+                AstDataType::ArrayType {
+                    name,
+                    bounds: match bounds.len() {
+                        1 => bounds.remove(0),
+                        _ => AstStatement::ExpressionList { expressions: bounds, id: session.next_id() },
+                    },
+                    referenced_type: Box::new(base_type.transform(session, None, location)?),
+                    is_variable_length: false,
+                }
+            }
+            TypeDefinition::Struct(variables) => AstDataType::StructType {
+                name,
+                variables: variables.iter().map(|it| it.transform(session)).collect::<Result<Vec<_>, _>>()?,
+            },
+            TypeDefinition::Enum { values, base_type } => {
+                let mut elements = values
+                    .iter()
+                    .map(|(name, value)| {
+                        let element = AstFactory::create_member_reference(
+                            AstFactory::create_identifier(name, location, session.next_id()),
+                            None,
+                            session.next_id(),
+                        );
+                        Ok(match value {
+                            Some(value) => AstStatement::Assignment {
+                                left: Box::new(element),
+                                right: Box::new(session.parse_value(value, location)?),
+                                id: session.next_id(),
+                            },
+                            None => element,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Diagnostic>>()?;
+                AstDataType::EnumType {
+                    name,
+                    numeric_type: match base_type.as_deref() {
+                        Some(base_type) => base_type.get_referenced_type(location)?,
+                        None => DINT_TYPE.to_string(),
+                    },
+                    elements: match elements.len() {
+                        1 => elements.remove(0),
+                        _ => AstStatement::ExpressionList { expressions: elements, id: session.next_id() },
+                    },
+                }
+            }
+            TypeDefinition::Subrange { lower, upper, base_type } => AstDataType::SubRangeType {
+                name,
+                referenced_type: base_type.get_referenced_type(location)?,
+                bounds: Some(session.parse_range(lower, upper, location)?),
+            },
+            TypeDefinition::Pointer(base_type) => AstDataType::PointerType {
+                name,
+                referenced_type: Box::new(base_type.transform(session, None, location)?),
+                auto_deref: false,
+            },
+        };
+
+        Ok(DataTypeDeclaration::DataTypeDefinition { data_type, location: location.clone(), scope: None })
+    }
+
+    /// the name of the base type of an enum or a subrange, which has to be an elementary type
+    fn get_referenced_type(&self, location: &SourceRange) -> Result<String, Diagnostic> {
+        match self {
+            TypeDefinition::Reference(name) => Ok(name.to_string()),
+            _ => Err(Diagnostic::syntax_error("Expected an elementary base type", location.clone())),
+        }
+    }
+}
+
+impl VariableDeclaration {
+    pub(crate) fn transform(&self, session: &ParseSession) -> Result<Variable, Diagnostic> {
+        let location = session.create_range(self.location.clone());
+        let initializer = match &self.initial_value {
+            Some(initial_value) => Some(session.parse_value(initial_value, &location)?),
+            None => None,
+        };
+        let address = match &self.address {
+            Some(address) => match session.parse_value(address, &location)? {
+                hardware_access @ AstStatement::HardwareAccess { .. } => Some(hardware_access),
+                _ => {
+                    return Err(Diagnostic::syntax_error(
+                        &format!("Invalid hardware address '{address}'"),
+                        location,
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        Ok(Variable {
+            name: self.name.to_string(),
+            data_type_declaration: self.data_type.transform(session, None, &location)?,
+            initializer,
+            address,
+            location,
+            attributes: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{
+        ast::{CompilationUnit, LinkageType, SourceRangeFactory, UserTypeDeclaration},
+        provider::IdProvider,
+    };
+    use plc::lexer;
+    use plc_diagnostics::diagnostics::Diagnostic;
+
+    use crate::{
+        model::{
+            data_type::{DataType, TypeDefinition, VariableDeclaration},
+            project::Project,
+        },
+        xml_parser::ParseSession,
+    };
+
+    fn transform(data_type: DataType) -> Result<UserTypeDeclaration, Diagnostic> {
+        let project = Project::default();
+        let session = ParseSession::new(&project, "test.xml", IdProvider::default(), LinkageType::Internal);
+        data_type.transform(&session)
+    }
+
+    /// the type as declared in structured text
+    fn parse(source: &str) -> UserTypeDeclaration {
+        let (mut unit, diagnostics): (CompilationUnit, _) = plc::parser::parse(
+            lexer::lex_with_ids(source, IdProvider::default(), SourceRangeFactory::for_file("test.st")),
+            LinkageType::Internal,
+            "test.st",
+        );
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        unit.user_types.remove(0)
+    }
+
+    fn data_type(name: &str, base_type: TypeDefinition, initial_value: Option<&str>) -> DataType {
+        DataType {
+            name: name.to_string(),
+            location: 10..20,
+            base_type,
+            initial_value: initial_value.map(str::to_string),
+        }
+    }
+
+    fn variable(name: &str, data_type: TypeDefinition, initial_value: Option<&str>) -> VariableDeclaration {
+        VariableDeclaration {
+            name: name.to_string(),
+            location: 30..40,
+            address: None,
+            data_type,
+            initial_value: initial_value.map(str::to_string),
+        }
+    }
+
+    fn reference(name: &str) -> Box<TypeDefinition> {
+        Box::new(TypeDefinition::Reference(name.to_string()))
+    }
+
+    #[test]
+    fn struct_with_array_and_pointer_members() {
+        let point = data_type(
+            "Point",
+            TypeDefinition::Struct(vec![
+                variable("x", *reference("INT"), Some("1")),
+                variable(
+                    "values",
+                    TypeDefinition::Array {
+                        dimensions: vec![
+                            ("0".to_string(), "9".to_string()),
+                            ("1".to_string(), "2".to_string()),
+                        ],
+                        base_type: reference("BYTE"),
+                    },
+                    Some("[20(0)]"),
+                ),
+                variable("next", TypeDefinition::Pointer(reference("Point")), None),
+                variable(
+                    "name",
+                    TypeDefinition::String { is_wide: true, length: Some("20".to_string()) },
+                    None,
+                ),
+            ]),
+            None,
+        );
+
+        assert_eq!(
+            format!("{:#?}", transform(point).unwrap()),
+            format!(
+                "{:#?}",
+                parse(
+                    "TYPE Point : STRUCT
+                        x : INT := 1;
+                        values : ARRAY[0..9, 1..2] OF BYTE := [20(0)];
+                        next : REF_TO Point;
+                        name : WSTRING[20];
+                    END_STRUCT END_TYPE"
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn enums_with_and_without_base_type() {
+        let color = data_type(
+            "Color",
+            TypeDefinition::Enum {
+                values: vec![("red".to_string(), None), ("green".to_string(), Some("5".to_string()))],
+                base_type: None,
+            },
+            Some("green"),
+        );
+        assert_eq!(
+            format!("{:#?}", transform(color).unwrap()),
+            format!("{:#?}", parse("TYPE Color : (red, green := 5) := green; END_TYPE"))
+        );
+
+        let state = data_type(
+            "State",
+            TypeDefinition::Enum {
+                values: vec![("idle".to_string(), None)],
+                base_type: Some(reference("INT")),
+            },
+            None,
+        );
+        assert_eq!(
+            format!("{:#?}", transform(state).unwrap()),
+            format!("{:#?}", parse("TYPE State : INT (idle); END_TYPE"))
+        );
+    }
+
+    #[test]
+    fn subranges_and_aliases() {
+        let percent = data_type(
+            "Percent",
+            TypeDefinition::Subrange {
+                lower: "0".to_string(),
+                upper: "100".to_string(),
+                base_type: reference("INT"),
+            },
+            Some("50"),
+        );
+        assert_eq!(
+            format!("{:#?}", transform(percent).unwrap()),
+            format!("{:#?}", parse("TYPE Percent : INT(0..100) := 50; END_TYPE"))
+        );
+
+        let counter = data_type("Counter", *reference("DINT"), Some("1"));
+        assert_eq!(
+            format!("{:#?}", transform(counter).unwrap()),
+            format!("{:#?}", parse("TYPE Counter : DINT := 1; END_TYPE"))
+        );
+
+        let text = data_type("Text", TypeDefinition::String { is_wide: false, length: None }, None);
+        assert_eq!(
+            format!("{:#?}", transform(text).unwrap()),
+            format!("{:#?}", parse("TYPE Text : STRING; END_TYPE"))
+        );
+    }
+
+    #[test]
+    fn malformed_values_are_reported_at_the_element() {
+        let counter = data_type("Counter", *reference("DINT"), Some("1 +"));
+        let diagnostic = transform(counter).unwrap_err();
+        assert_eq!(diagnostic.get_message(), "Invalid value '1 +'");
+        assert_eq!(diagnostic.get_location(), SourceRangeFactory::for_file("test.xml").create_range(10..20));
+
+        let buffer = data_type(
+            "Buffer",
+            TypeDefinition::Array {
+                dimensions: vec![("0".to_string(), "".to_string())],
+                base_type: reference("BYTE"),
+            },
+            None,
+        );
+        assert_eq!(transform(buffer).unwrap_err().get_message(), "Invalid value ''");
+
+        let point = data_type(
+            "Point",
+            TypeDefinition::Struct(vec![variable("x", *reference("INT"), Some("a b"))]),
+            None,
+        );
+        let diagnostic = transform(point).unwrap_err();
+        assert_eq!(diagnostic.get_message(), "Invalid value 'a b'");
+        assert_eq!(diagnostic.get_location(), SourceRangeFactory::for_file("test.xml").create_range(30..40));
+    }
+
+    #[test]
+    fn enum_base_type_must_be_elementary() {
+        let color = data_type(
+            "Color",
+            TypeDefinition::Enum {
+                values: vec![("red".to_string(), None)],
+                base_type: Some(Box::new(TypeDefinition::Pointer(reference("INT")))),
+            },
+            None,
+        );
+        assert_eq!(transform(color).unwrap_err().get_message(), "Expected an elementary base type");
+    }
+}
@@ -0,0 +1,122 @@
+use ast::ast::{AccessModifier, AstStatement, VariableBlock, VariableBlockType};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::model::global_variables::GlobalVariables;
+
+use super::ParseSession;
+
+impl GlobalVariables {
+    pub(crate) fn transform(&self, session: &ParseSession) -> Result<VariableBlock, Diagnostic> {
+        let mut variables =
+            self.variables.iter().map(|it| it.transform(session)).collect::<Result<Vec<_>, _>>()?;
+        if self.constant {
+            // like in a structured text declaration, constants without an initial value keep their default value
+            variables.iter_mut().filter(|it| it.initializer.is_none()).for_each(|it| {
+                it.initializer =
+                    Some(AstStatement::DefaultValue { location: it.location.clone(), id: session.next_id() });
+            });
+        }
+
+        Ok(VariableBlock {
+            access: AccessModifier::Protected,
+            constant: self.constant,
+            retain: self.retain,
+            variables,
+            variable_block_type: VariableBlockType::Global,
+            linkage: session.linkage,
+            location: session.create_range(self.location.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{
+        ast::{AstStatement, LinkageType, SourceRangeFactory, VariableBlock},
+        provider::IdProvider,
+    };
+    use plc::lexer;
+    use plc_diagnostics::diagnostics::Diagnostic;
+
+    use crate::{
+        model::{
+            data_type::{TypeDefinition, VariableDeclaration},
+            global_variables::GlobalVariables,
+            project::Project,
+        },
+        xml_parser::ParseSession,
+    };
+
+    fn transform(global_variables: GlobalVariables) -> Result<VariableBlock, Diagnostic> {
+        let project = Project::default();
+        let session = ParseSession::new(&project, "test.xml", IdProvider::default(), LinkageType::Internal);
+        global_variables.transform(&session)
+    }
+
+    /// the variable block as declared in structured text
+    fn parse(source: &str) -> VariableBlock {
+        let (mut unit, diagnostics) = plc::parser::parse(
+            lexer::lex_with_ids(source, IdProvider::default(), SourceRangeFactory::for_file("test.st")),
+            LinkageType::Internal,
+            "test.st",
+        );
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        unit.global_vars.remove(0)
+    }
+
+    fn variable(name: &str, address: Option<&str>, initial_value: Option<&str>) -> VariableDeclaration {
+        VariableDeclaration {
+            name: name.to_string(),
+            location: 30..40,
+            address: address.map(str::to_string),
+            data_type: TypeDefinition::Reference("INT".to_string()),
+            initial_value: initial_value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn constants_without_initial_value_keep_their_default_value() {
+        let block = transform(GlobalVariables {
+            location: 10..20,
+            constant: true,
+            retain: false,
+            variables: vec![variable("limit", None, Some("10")), variable("lower", None, None)],
+        })
+        .unwrap();
+
+        let expected = parse("VAR_GLOBAL CONSTANT limit : INT := 10; lower : INT; END_VAR");
+        assert_eq!(format!("{block:#?}"), format!("{expected:#?}"));
+        assert!(block.constant);
+        assert!(!block.retain);
+        assert!(matches!(block.variables[1].initializer, Some(AstStatement::DefaultValue { .. })));
+    }
+
+    #[test]
+    fn variables_keep_their_address() {
+        let block = transform(GlobalVariables {
+            location: 10..20,
+            constant: false,
+            retain: true,
+            variables: vec![variable("input", Some("%IW2"), None)],
+        })
+        .unwrap();
+
+        assert!(block.retain);
+        assert_eq!(block.location, SourceRangeFactory::for_file("test.xml").create_range(10..20));
+        assert!(matches!(block.variables[0].address, Some(AstStatement::HardwareAccess { .. })));
+    }
+
+    #[test]
+    fn malformed_addresses_are_reported_at_the_variable() {
+        let diagnostic = transform(GlobalVariables {
+            location: 10..20,
+            constant: false,
+            retain: false,
+            variables: vec![variable("input", Some("input_1"), None)],
+        })
+        .unwrap_err();
+
+        assert_eq!(diagnostic.get_message(), "Invalid hardware address 'input_1'");
+        assert_eq!(diagnostic.get_location(), SourceRangeFactory::for_file("test.xml").create_range(30..40));
+    }
+}
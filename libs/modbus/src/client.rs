@@ -0,0 +1,166 @@
+//! A minimal Modbus TCP client supporting the functions needed to exchange the process image
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::Error;
+
+pub const READ_COILS: u8 = 0x01;
+pub const READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const READ_INPUT_REGISTERS: u8 = 0x04;
+pub const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+pub const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// the size of the MBAP header preceding every request and response
+pub const HEADER_SIZE: usize = 7;
+
+pub struct Client {
+    stream: TcpStream,
+    unit: u8,
+    transaction: u16,
+}
+
+impl Client {
+    /// connects to the Modbus TCP server at the given address, requests and responses time out
+    /// after the given duration
+    pub fn connect(address: &str, unit: u8, timeout: Duration) -> Result<Client, Error> {
+        let socket = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| Error::Config(format!("Cannot resolve the address {address}")))?;
+        let stream = TcpStream::connect_timeout(&socket, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.set_nodelay(true)?;
+        Ok(Client { stream, unit, transaction: 0 })
+    }
+
+    pub fn read_coils(&mut self, start: u16, count: u16) -> Result<Vec<bool>, Error> {
+        self.read_bits(READ_COILS, start, count)
+    }
+
+    pub fn read_discrete_inputs(&mut self, start: u16, count: u16) -> Result<Vec<bool>, Error> {
+        self.read_bits(READ_DISCRETE_INPUTS, start, count)
+    }
+
+    pub fn read_holding_registers(&mut self, start: u16, count: u16) -> Result<Vec<u16>, Error> {
+        self.read_registers(READ_HOLDING_REGISTERS, start, count)
+    }
+
+    pub fn read_input_registers(&mut self, start: u16, count: u16) -> Result<Vec<u16>, Error> {
+        self.read_registers(READ_INPUT_REGISTERS, start, count)
+    }
+
+    pub fn write_coils(&mut self, start: u16, values: &[bool]) -> Result<(), Error> {
+        let mut pdu = request(WRITE_MULTIPLE_COILS, start, values.len() as u16);
+        let bytes = pack_bits(values);
+        pdu.push(bytes.len() as u8);
+        pdu.extend_from_slice(&bytes);
+        self.write(&pdu)
+    }
+
+    pub fn write_registers(&mut self, start: u16, values: &[u16]) -> Result<(), Error> {
+        let mut pdu = request(WRITE_MULTIPLE_REGISTERS, start, values.len() as u16);
+        pdu.push((values.len() * 2) as u8);
+        values.iter().for_each(|it| pdu.extend_from_slice(&it.to_be_bytes()));
+        self.write(&pdu)
+    }
+
+    fn read_bits(&mut self, function: u8, start: u16, count: u16) -> Result<Vec<bool>, Error> {
+        let data = self.read(function, start, count, (usize::from(count) + 7) / 8)?;
+        Ok(unpack_bits(&data, count.into()))
+    }
+
+    fn read_registers(&mut self, function: u8, start: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let data = self.read(function, start, count, usize::from(count) * 2)?;
+        Ok(data.chunks(2).map(|it| u16::from_be_bytes([it[0], it[1]])).collect())
+    }
+
+    /// sends a read request and returns the data of the response
+    fn read(
+        &mut self,
+        function: u8,
+        start: u16,
+        count: u16,
+        expected_bytes: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let mut response = self.request(&request(function, start, count))?;
+        // function code, byte count, data
+        match response.get(1) {
+            Some(&byte_count)
+                if usize::from(byte_count) == expected_bytes && response.len() == expected_bytes + 2 =>
+            {
+                Ok(response.split_off(2))
+            }
+            _ => Err(Error::Protocol(format!(
+                "Unexpected response length {} to function {function}",
+                response.len()
+            ))),
+        }
+    }
+
+    /// sends a write request, the response repeats the start and the number of written values
+    fn write(&mut self, pdu: &[u8]) -> Result<(), Error> {
+        let response = self.request(pdu)?;
+        if response[..] != pdu[..5] {
+            return Err(Error::Protocol(format!("Unexpected response to function {}", pdu[0])));
+        }
+        Ok(())
+    }
+
+    /// sends the given protocol data unit and returns the protocol data unit of the response
+    fn request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        self.transaction = self.transaction.wrapping_add(1);
+        let mut frame = Vec::with_capacity(HEADER_SIZE + pdu.len());
+        frame.extend_from_slice(&self.transaction.to_be_bytes());
+        // the protocol identifier of Modbus
+        frame.extend_from_slice(&0u16.to_be_bytes());
+        frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+        frame.push(self.unit);
+        frame.extend_from_slice(pdu);
+        self.stream.write_all(&frame)?;
+
+        let mut header = [0; HEADER_SIZE];
+        self.stream.read_exact(&mut header)?;
+        let transaction = u16::from_be_bytes([header[0], header[1]]);
+        let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        if transaction != self.transaction || length < 2 {
+            return Err(Error::Protocol(format!("Unexpected response header {header:?}")));
+        }
+        let mut response = vec![0; length - 1];
+        self.stream.read_exact(&mut response)?;
+
+        let function = pdu[0];
+        match response[0] {
+            it if it == function => Ok(response),
+            it if it == function | 0x80 => {
+                Err(Error::Exception { function, code: response.get(1).copied().unwrap_or_default() })
+            }
+            it => Err(Error::Protocol(format!(
+                "Unexpected function {it} in the response to function {function}"
+            ))),
+        }
+    }
+}
+
+/// creates a request with the function code, the first register and the number of registers
+fn request(function: u8, start: u16, count: u16) -> Vec<u8> {
+    let mut pdu = vec![function];
+    pdu.extend_from_slice(&start.to_be_bytes());
+    pdu.extend_from_slice(&count.to_be_bytes());
+    pdu
+}
+
+/// packs the given bits into bytes, the first bit is the least significant bit of the first byte
+pub fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|byte| byte.iter().enumerate().fold(0, |value, (bit, set)| value | (u8::from(*set) << bit)))
+        .collect()
+}
+
+pub fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+    (0..count).map(|bit| bytes[bit / 8] & (1 << (bit % 8)) != 0).collect()
+}
@@ -0,0 +1,332 @@
+//! The hardware configuration written by `plc --hardware-conf` and the mapping of its variables
+//! to the registers of Modbus devices.
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::Error;
+
+/// The format of a configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// returns the format of the given file, `.toml` files are read as TOML, all others as JSON
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|it| it.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(content: &str, format: Format) -> Result<T, Error> {
+    match format {
+        Format::Json => serde_json::from_str(content).map_err(|err| Error::Config(err.to_string())),
+        Format::Toml => toml::from_str(content).map_err(|err| Error::Config(err.to_string())),
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    let content = fs::read_to_string(path)?;
+    parse(&content, Format::of(path))
+}
+
+/// The area a variable is bound to (`%I`, `%Q`, `%M` or `%G`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Area {
+    Input,
+    Output,
+    Memory,
+    Global,
+}
+
+/// The size of a hardware address (`%IX`, `%IB`, `%IW`, `%ID`, `%IL` or `%I*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Access {
+    Bit,
+    Byte,
+    Word,
+    DWord,
+    LWord,
+    Template,
+}
+
+/// A variable of the hardware configuration
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Binding {
+    /// the name of the instance, e.g. `prg.fbs[1].x`
+    pub name: String,
+    pub direction: Area,
+    #[serde(rename = "type")]
+    pub access: Access,
+    pub address: Vec<String>,
+}
+
+/// The location of a variable in the buffer of its area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageAddress {
+    /// the first byte of the address
+    pub byte: usize,
+    /// the bit of a bit address (`%IX1.2`)
+    pub bit: Option<u8>,
+    /// the number of bytes covered by the address
+    pub size: usize,
+}
+
+impl Binding {
+    /// Returns the location of the variable in the process image generated with `--process-image`.
+    /// Addresses are counted in units of their size: `%IX1.2` is bit 2 of byte 1,
+    /// `%IW1` covers the bytes 2 and 3
+    pub fn get_image_address(&self) -> Result<ImageAddress, Error> {
+        let address = self
+            .address
+            .iter()
+            .map(|it| it.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::Config(format!("Invalid address {:?} of {}", self.address, self.name)))?;
+        let size = match self.access {
+            Access::Bit => {
+                let bit = address.get(1).copied().unwrap_or_default();
+                if bit > 7 {
+                    return Err(Error::Config(format!("Invalid bit index {bit} of {}", self.name)));
+                }
+                let byte = address.first().copied().unwrap_or_default();
+                return Ok(ImageAddress { byte, bit: Some(bit as u8), size: 1 });
+            }
+            Access::Byte => 1,
+            Access::Word => 2,
+            Access::DWord => 4,
+            Access::LWord => 8,
+            Access::Template => {
+                return Err(Error::Config(format!("The template address of {} is not configured", self.name)))
+            }
+        };
+        let first = address.first().copied().unwrap_or_default();
+        Ok(ImageAddress { byte: first * size, bit: None, size })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    // the JSON configuration lists the instances of every declared variable together
+    Many(Vec<Binding>),
+    One(Binding),
+}
+
+#[derive(Deserialize)]
+struct ConfigurationFile {
+    #[serde(rename = "HardwareConfiguration", default)]
+    entries: Vec<Entry>,
+}
+
+/// The hardware configuration written by `plc --hardware-conf`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HardwareConfiguration {
+    bindings: Vec<Binding>,
+}
+
+impl HardwareConfiguration {
+    pub fn from_file(path: &Path) -> Result<HardwareConfiguration, Error> {
+        read::<ConfigurationFile>(path).map(HardwareConfiguration::from)
+    }
+
+    pub fn parse(content: &str, format: Format) -> Result<HardwareConfiguration, Error> {
+        parse::<ConfigurationFile>(content, format).map(HardwareConfiguration::from)
+    }
+
+    pub fn get_bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// returns the binding of the given instance, names are compared case insensitive
+    pub fn find_binding(&self, name: &str) -> Option<&Binding> {
+        self.bindings.iter().find(|it| it.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl From<ConfigurationFile> for HardwareConfiguration {
+    fn from(file: ConfigurationFile) -> Self {
+        let bindings = file
+            .entries
+            .into_iter()
+            .flat_map(|it| match it {
+                Entry::Many(bindings) => bindings,
+                Entry::One(binding) => vec![binding],
+            })
+            .collect();
+        HardwareConfiguration { bindings }
+    }
+}
+
+/// The kind of register a variable is mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterKind {
+    Coil,
+    DiscreteInput,
+    HoldingRegister,
+    InputRegister,
+}
+
+impl RegisterKind {
+    /// coils and holding registers can be written, discrete inputs and input registers are read only
+    pub fn is_writable(self) -> bool {
+        matches!(self, RegisterKind::Coil | RegisterKind::HoldingRegister)
+    }
+
+    pub fn is_bit(self) -> bool {
+        matches!(self, RegisterKind::Coil | RegisterKind::DiscreteInput)
+    }
+}
+
+/// A Modbus TCP server
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Device {
+    /// the address of the server, e.g. `192.168.0.10:502`
+    pub address: String,
+    /// the unit identifier sent with every request
+    #[serde(default = "default_unit")]
+    pub unit: u8,
+}
+
+fn default_unit() -> u8 {
+    1
+}
+
+/// The first register a variable of the hardware configuration is mapped to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VariableMapping {
+    /// the name of the variable in the hardware configuration
+    pub name: String,
+    /// the name of the device in the mapping file
+    pub device: String,
+    pub kind: RegisterKind,
+    pub register: u16,
+}
+
+/// The Modbus devices and the registers the variables of the hardware configuration are mapped to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Mapping {
+    pub devices: HashMap<String, Device>,
+    #[serde(default)]
+    pub variables: Vec<VariableMapping>,
+}
+
+impl Mapping {
+    pub fn from_file(path: &Path) -> Result<Mapping, Error> {
+        read(path)
+    }
+
+    pub fn parse(content: &str, format: Format) -> Result<Mapping, Error> {
+        parse(content, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Access, Area, Binding, Format, HardwareConfiguration, ImageAddress, Mapping, RegisterKind};
+
+    fn binding(access: Access, address: &[&str]) -> Binding {
+        Binding {
+            name: "x".to_string(),
+            direction: Area::Input,
+            access,
+            address: address.iter().map(|it| it.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn json_hardware_configuration_is_read() {
+        let config = HardwareConfiguration::parse(
+            r#"{
+                "HardwareConfiguration": [
+                    [{ "name": "a", "direction": "Input", "type": "Bit", "address": ["1", "2"] }],
+                    [
+                        { "name": "fbs[0].b", "direction": "Output", "type": "Word", "address": ["3"] },
+                        { "name": "fbs[1].b", "direction": "Output", "type": "Template", "address": [] }
+                    ]
+                ]
+            }"#,
+            Format::Json,
+        )
+        .unwrap();
+
+        let names = config.get_bindings().iter().map(|it| it.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "fbs[0].b", "fbs[1].b"]);
+        assert_eq!(config.find_binding("FBS[0].B").map(|it| it.access), Some(Access::Word));
+    }
+
+    #[test]
+    fn toml_hardware_configuration_is_read() {
+        let config = HardwareConfiguration::parse(
+            r#"
+            [[HardwareConfiguration]]
+            name = 'a'
+            direction = 'Memory'
+            type = 'DWord'
+            address = ['2']
+            "#,
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.get_bindings(),
+            &[Binding {
+                name: "a".to_string(),
+                direction: Area::Memory,
+                access: Access::DWord,
+                address: vec!["2".to_string()]
+            }]
+        );
+    }
+
+    #[test]
+    fn addresses_are_counted_in_units_of_their_size() {
+        let address = |byte, bit, size| ImageAddress { byte, bit, size };
+        assert_eq!(binding(Access::Bit, &["1", "2"]).get_image_address().unwrap(), address(1, Some(2), 1));
+        assert_eq!(binding(Access::Byte, &["3"]).get_image_address().unwrap(), address(3, None, 1));
+        assert_eq!(binding(Access::Word, &["2"]).get_image_address().unwrap(), address(4, None, 2));
+        assert_eq!(binding(Access::DWord, &["1"]).get_image_address().unwrap(), address(4, None, 4));
+        assert_eq!(binding(Access::LWord, &["1"]).get_image_address().unwrap(), address(8, None, 8));
+    }
+
+    #[test]
+    fn unconfigured_and_invalid_addresses_are_reported() {
+        assert_eq!(
+            binding(Access::Template, &[]).get_image_address().unwrap_err().to_string(),
+            "Invalid configuration: The template address of x is not configured"
+        );
+        assert_eq!(
+            binding(Access::Bit, &["0", "8"]).get_image_address().unwrap_err().to_string(),
+            "Invalid configuration: Invalid bit index 8 of x"
+        );
+    }
+
+    #[test]
+    fn mapping_is_read() {
+        let mapping = Mapping::parse(
+            r#"
+            [devices.io]
+            address = '127.0.0.1:502'
+
+            [[variables]]
+            name = 'prg.start'
+            device = 'io'
+            kind = 'discrete_input'
+            register = 4
+            "#,
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(mapping.devices["io"].unit, 1);
+        assert_eq!(mapping.variables[0].kind, RegisterKind::DiscreteInput);
+        assert_eq!(mapping.variables[0].register, 4);
+    }
+}
@@ -0,0 +1,251 @@
+//! Exchanges the process image of a PLC program with Modbus TCP devices.
+//!
+//! A program compiled with `--process-image` exports one buffer per area (`__PI_INPUT`, `__PI_OUTPUT`).
+//! [`ModbusIo`] reads the hardware configuration written by `--hardware-conf` and a mapping file that
+//! assigns the bound variables to the coils and registers of Modbus devices:
+//!
+//! ```toml
+//! [devices.io]
+//! address = '192.168.0.10:502'
+//! unit = 1
+//!
+//! [[variables]]
+//! name = 'PLC_PRG.start'
+//! device = 'io'
+//! kind = 'discrete_input'
+//! register = 0
+//! ```
+//!
+//! Once per cycle a runtime calls [`ModbusIo::read_inputs`] with `__PI_INPUT` before `__PI_READ_INPUTS()`
+//! and [`ModbusIo::write_outputs`] with `__PI_OUTPUT` after `__PI_WRITE_OUTPUTS()`.
+//!
+//! Bit variables (`%IX`) are mapped to a single coil, discrete input or register (`0` or `1`).
+//! Larger variables are mapped to one coil or discrete input per bit, starting with the least
+//! significant bit, or to one register per word, starting with the most significant word.
+//! A byte is mapped to the low byte of a register.
+use std::{path::Path, time::Duration};
+
+use client::Client;
+use config::{Area, HardwareConfiguration, ImageAddress, Mapping, RegisterKind};
+
+pub mod client;
+pub mod config;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    #[error("Modbus exception {code} in response to function {function}")]
+    Exception { function: u8, code: u8 },
+    #[error("Invalid Modbus response: {0}")]
+    Protocol(String),
+}
+
+/// the time to wait for a connection or a response if no other timeout is set
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A device with a connection that is established on first use and after errors
+struct Connection {
+    address: String,
+    unit: u8,
+    client: Option<Client>,
+}
+
+/// A variable copied between the process image and a device
+struct Transfer {
+    name: String,
+    device: usize,
+    kind: RegisterKind,
+    register: u16,
+    address: ImageAddress,
+}
+
+impl Transfer {
+    /// the number of coils, discrete inputs or registers the variable is mapped to
+    fn get_count(&self) -> u16 {
+        match (self.address.bit, self.kind.is_bit()) {
+            (Some(_), _) => 1,
+            (None, true) => self.address.size as u16 * 8,
+            (None, false) => (self.address.size as u16 + 1) / 2,
+        }
+    }
+
+    fn check_bounds(&self, image: &[u8]) -> Result<(), Error> {
+        if self.address.byte + self.address.size > image.len() {
+            return Err(Error::Config(format!("{} is outside of the process image", self.name)));
+        }
+        Ok(())
+    }
+}
+
+/// Copies the inputs of Modbus devices into the process image and the outputs back
+pub struct ModbusIo {
+    connections: Vec<Connection>,
+    inputs: Vec<Transfer>,
+    outputs: Vec<Transfer>,
+    timeout: Duration,
+}
+
+impl ModbusIo {
+    pub fn from_files(hardware_configuration: &Path, mapping: &Path) -> Result<ModbusIo, Error> {
+        ModbusIo::new(
+            &HardwareConfiguration::from_file(hardware_configuration)?,
+            &Mapping::from_file(mapping)?,
+        )
+    }
+
+    /// Creates the transfers of all mapped variables. Devices are connected on first use.
+    pub fn new(configuration: &HardwareConfiguration, mapping: &Mapping) -> Result<ModbusIo, Error> {
+        let mut device_names = mapping.devices.keys().collect::<Vec<_>>();
+        device_names.sort();
+        let connections = device_names
+            .iter()
+            .map(|it| Connection {
+                address: mapping.devices[*it].address.clone(),
+                unit: mapping.devices[*it].unit,
+                client: None,
+            })
+            .collect();
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for variable in &mapping.variables {
+            let binding = configuration.find_binding(&variable.name).ok_or_else(|| {
+                Error::Config(format!("{} is not listed in the hardware configuration", variable.name))
+            })?;
+            let device = device_names.iter().position(|it| **it == variable.device).ok_or_else(|| {
+                Error::Config(format!("Unknown device {} of {}", variable.device, variable.name))
+            })?;
+            let transfer = Transfer {
+                name: binding.name.clone(),
+                device,
+                kind: variable.kind,
+                register: variable.register,
+                address: binding.get_image_address()?,
+            };
+            match binding.direction {
+                Area::Input => inputs.push(transfer),
+                Area::Output if variable.kind.is_writable() => outputs.push(transfer),
+                Area::Output => {
+                    return Err(Error::Config(format!(
+                        "The output {} cannot be mapped to a read only {:?}",
+                        variable.name, variable.kind
+                    )))
+                }
+                Area::Memory | Area::Global => {
+                    return Err(Error::Config(format!(
+                        "{} is neither an input nor an output and cannot be mapped",
+                        variable.name
+                    )))
+                }
+            }
+        }
+
+        Ok(ModbusIo { connections, inputs, outputs, timeout: DEFAULT_TIMEOUT })
+    }
+
+    /// sets the time to wait for a connection or a response
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// reads all mapped inputs into the given input area (`__PI_INPUT`)
+    pub fn read_inputs(&mut self, image: &mut [u8]) -> Result<(), Error> {
+        for transfer in &self.inputs {
+            transfer.check_bounds(image)?;
+            let count = transfer.get_count();
+            let start = transfer.register;
+            let value = with_client(&mut self.connections[transfer.device], self.timeout, |client| {
+                Ok(match transfer.kind {
+                    RegisterKind::Coil => bits_to_value(&client.read_coils(start, count)?),
+                    RegisterKind::DiscreteInput => bits_to_value(&client.read_discrete_inputs(start, count)?),
+                    RegisterKind::HoldingRegister => {
+                        registers_to_value(&client.read_holding_registers(start, count)?)
+                    }
+                    RegisterKind::InputRegister => {
+                        registers_to_value(&client.read_input_registers(start, count)?)
+                    }
+                })
+            })?;
+            write_value(image, &transfer.address, value);
+        }
+        Ok(())
+    }
+
+    /// writes all mapped outputs of the given output area (`__PI_OUTPUT`) to their devices
+    pub fn write_outputs(&mut self, image: &[u8]) -> Result<(), Error> {
+        for transfer in &self.outputs {
+            transfer.check_bounds(image)?;
+            let count = transfer.get_count();
+            let value = read_value(image, &transfer.address);
+            with_client(&mut self.connections[transfer.device], self.timeout, |client| {
+                match transfer.kind {
+                    RegisterKind::Coil => {
+                        let bits = (0..count).map(|bit| value & (1 << bit) != 0).collect::<Vec<_>>();
+                        client.write_coils(transfer.register, &bits)
+                    }
+                    _ => {
+                        let registers =
+                            (0..count).rev().map(|word| (value >> (16 * word)) as u16).collect::<Vec<_>>();
+                        client.write_registers(transfer.register, &registers)
+                    }
+                }
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// runs the given requests on the connection of the device, a failed connection is dropped
+/// and re-established with the next request
+fn with_client<T>(
+    connection: &mut Connection,
+    timeout: Duration,
+    requests: impl FnOnce(&mut Client) -> Result<T, Error>,
+) -> Result<T, Error> {
+    if connection.client.is_none() {
+        connection.client = Some(Client::connect(&connection.address, connection.unit, timeout)?);
+    }
+    let result = requests(connection.client.as_mut().expect("the device is connected"));
+    if matches!(result, Err(Error::Io(_)) | Err(Error::Protocol(_))) {
+        connection.client = None;
+    }
+    result
+}
+
+/// combines bits to a value, the first bit is the least significant bit
+fn bits_to_value(bits: &[bool]) -> u64 {
+    bits.iter().enumerate().fold(0, |value, (bit, set)| value | (u64::from(*set) << bit))
+}
+
+/// combines registers to a value, the first register is the most significant word
+fn registers_to_value(registers: &[u16]) -> u64 {
+    registers.iter().fold(0, |value, it| (value << 16) | u64::from(*it))
+}
+
+/// writes the value into the image in the byte order of the target
+fn write_value(image: &mut [u8], address: &ImageAddress, value: u64) {
+    let bytes = &mut image[address.byte..address.byte + address.size];
+    match (address.bit, address.size) {
+        (Some(bit), _) if value != 0 => bytes[0] |= 1 << bit,
+        (Some(bit), _) => bytes[0] &= !(1 << bit),
+        (None, 1) => bytes[0] = value as u8,
+        (None, 2) => bytes.copy_from_slice(&(value as u16).to_ne_bytes()),
+        (None, 4) => bytes.copy_from_slice(&(value as u32).to_ne_bytes()),
+        (None, _) => bytes.copy_from_slice(&value.to_ne_bytes()),
+    }
+}
+
+/// reads the value from the image in the byte order of the target
+fn read_value(image: &[u8], address: &ImageAddress) -> u64 {
+    let bytes = &image[address.byte..address.byte + address.size];
+    match (address.bit, address.size) {
+        (Some(bit), _) => u64::from((bytes[0] >> bit) & 1),
+        (None, 1) => u64::from(bytes[0]),
+        (None, 2) => u64::from(u16::from_ne_bytes([bytes[0], bytes[1]])),
+        (None, 4) => u64::from(u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        (None, _) => u64::from_ne_bytes(bytes.try_into().expect("an lword covers 8 bytes")),
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use plc_modbus::client::{
+    pack_bits, unpack_bits, HEADER_SIZE, READ_COILS, READ_DISCRETE_INPUTS, READ_HOLDING_REGISTERS,
+    READ_INPUT_REGISTERS, WRITE_MULTIPLE_COILS, WRITE_MULTIPLE_REGISTERS,
+};
+
+/// the exception returned for requests outside of the server's registers
+pub const ILLEGAL_DATA_ADDRESS: u8 = 0x02;
+
+/// The registers of the in-process server
+#[derive(Debug, Default)]
+pub struct Registers {
+    pub coils: Vec<bool>,
+    pub discrete_inputs: Vec<bool>,
+    pub holding_registers: Vec<u16>,
+    pub input_registers: Vec<u16>,
+}
+
+impl Registers {
+    pub fn new(size: usize) -> Registers {
+        Registers {
+            coils: vec![false; size],
+            discrete_inputs: vec![false; size],
+            holding_registers: vec![0; size],
+            input_registers: vec![0; size],
+        }
+    }
+}
+
+/// Starts a Modbus TCP server on a free local port standing in for a device. Returns the address of the
+/// server and its registers, which can be changed and inspected while the server is running.
+pub fn start_server(size: usize) -> (String, Arc<Mutex<Registers>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let registers = Arc::new(Mutex::new(Registers::new(size)));
+    let shared = registers.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let registers = shared.clone();
+            thread::spawn(move || serve(stream, &registers));
+        }
+    });
+    (address, registers)
+}
+
+fn serve(mut stream: TcpStream, registers: &Mutex<Registers>) {
+    let mut header = [0; HEADER_SIZE];
+    while stream.read_exact(&mut header).is_ok() {
+        let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        let mut request = vec![0; length - 1];
+        if stream.read_exact(&mut request).is_err() {
+            return;
+        }
+
+        let response = handle(&request, &mut registers.lock().unwrap());
+        let mut frame = header[..4].to_vec();
+        frame.extend_from_slice(&(response.len() as u16 + 1).to_be_bytes());
+        frame.push(header[6]);
+        frame.extend_from_slice(&response);
+        if stream.write_all(&frame).is_err() {
+            return;
+        }
+    }
+}
+
+fn handle(request: &[u8], registers: &mut Registers) -> Vec<u8> {
+    let function = request[0];
+    let start = usize::from(u16::from_be_bytes([request[1], request[2]]));
+    let count = usize::from(u16::from_be_bytes([request[3], request[4]]));
+    let range = start..start + count;
+    let mut response = vec![function];
+    let in_range = |size: usize| range.end <= size;
+    match function {
+        READ_COILS | READ_DISCRETE_INPUTS => {
+            let bits = if function == READ_COILS { &registers.coils } else { &registers.discrete_inputs };
+            if !in_range(bits.len()) {
+                return vec![function | 0x80, ILLEGAL_DATA_ADDRESS];
+            }
+            let bytes = pack_bits(&bits[range]);
+            response.push(bytes.len() as u8);
+            response.extend_from_slice(&bytes);
+        }
+        READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+            let values = if function == READ_HOLDING_REGISTERS {
+                &registers.holding_registers
+            } else {
+                &registers.input_registers
+            };
+            if !in_range(values.len()) {
+                return vec![function | 0x80, ILLEGAL_DATA_ADDRESS];
+            }
+            response.push((count * 2) as u8);
+            values[range].iter().for_each(|it| response.extend_from_slice(&it.to_be_bytes()));
+        }
+        WRITE_MULTIPLE_COILS => {
+            if !in_range(registers.coils.len()) {
+                return vec![function | 0x80, ILLEGAL_DATA_ADDRESS];
+            }
+            registers.coils[range].copy_from_slice(&unpack_bits(&request[6..], count));
+            response.extend_from_slice(&request[1..5]);
+        }
+        WRITE_MULTIPLE_REGISTERS => {
+            if !in_range(registers.holding_registers.len()) {
+                return vec![function | 0x80, ILLEGAL_DATA_ADDRESS];
+            }
+            for (register, value) in registers.holding_registers[range].iter_mut().zip(request[6..].chunks(2))
+            {
+                *register = u16::from_be_bytes([value[0], value[1]]);
+            }
+            response.extend_from_slice(&request[1..5]);
+        }
+        // illegal function
+        _ => return vec![function | 0x80, 0x01],
+    }
+    response
+}
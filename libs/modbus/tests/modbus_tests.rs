@@ -0,0 +1,222 @@
+use plc_modbus::{
+    config::{Format, HardwareConfiguration, Mapping},
+    Error, ModbusIo,
+};
+
+mod common;
+
+use common::{start_server, ILLEGAL_DATA_ADDRESS};
+
+const HARDWARE_CONFIGURATION: &str = r#"{
+    "HardwareConfiguration": [
+        [{ "name": "prg.start", "direction": "Input", "type": "Bit", "address": ["0", "3"] }],
+        [{ "name": "prg.level", "direction": "Input", "type": "Word", "address": ["1"] }],
+        [{ "name": "prg.switches", "direction": "Input", "type": "Byte", "address": ["4"] }],
+        [{ "name": "prg.counter", "direction": "Input", "type": "DWord", "address": ["2"] }],
+        [{ "name": "prg.lamp", "direction": "Output", "type": "Bit", "address": ["0", "1"] }],
+        [{ "name": "prg.setpoint", "direction": "Output", "type": "Word", "address": ["1"] }],
+        [{ "name": "prg.leds", "direction": "Output", "type": "Byte", "address": ["0"] }],
+        [{ "name": "prg.marker", "direction": "Memory", "type": "Byte", "address": ["0"] }]
+    ]
+}"#;
+
+fn create_io(address: &str, variables: &str) -> Result<ModbusIo, Error> {
+    let configuration = HardwareConfiguration::parse(HARDWARE_CONFIGURATION, Format::Json).unwrap();
+    let mapping = Mapping::parse(
+        &format!(
+            r#"
+            [devices.io]
+            address = '{address}'
+
+            {variables}
+            "#
+        ),
+        Format::Toml,
+    )
+    .unwrap();
+    ModbusIo::new(&configuration, &mapping)
+}
+
+#[test]
+fn inputs_are_read_into_the_process_image() {
+    let (address, registers) = start_server(16);
+    let mut io = create_io(
+        &address,
+        r#"
+        [[variables]]
+        name = 'prg.start'
+        device = 'io'
+        kind = 'discrete_input'
+        register = 2
+
+        [[variables]]
+        name = 'prg.level'
+        device = 'io'
+        kind = 'input_register'
+        register = 0
+
+        [[variables]]
+        name = 'prg.switches'
+        device = 'io'
+        kind = 'coil'
+        register = 8
+
+        [[variables]]
+        name = 'prg.counter'
+        device = 'io'
+        kind = 'holding_register'
+        register = 4
+        "#,
+    )
+    .unwrap();
+    {
+        let mut registers = registers.lock().unwrap();
+        registers.discrete_inputs[2] = true;
+        registers.input_registers[0] = 0x1234;
+        registers.coils[8] = true;
+        registers.coils[10] = true;
+        registers.holding_registers[4] = 0xCAFE;
+        registers.holding_registers[5] = 0xBABE;
+    }
+
+    let mut image = [0u8; 12];
+    // other bits of the byte are not touched
+    image[0] = 0b0000_0001;
+    io.read_inputs(&mut image).unwrap();
+
+    assert_eq!(image[0], 0b0000_1001);
+    assert_eq!(u16::from_ne_bytes([image[2], image[3]]), 0x1234);
+    assert_eq!(image[4], 0b0000_0101);
+    assert_eq!(u32::from_ne_bytes([image[8], image[9], image[10], image[11]]), 0xCAFE_BABE);
+
+    // a reset input clears its bit
+    registers.lock().unwrap().discrete_inputs[2] = false;
+    io.read_inputs(&mut image).unwrap();
+    assert_eq!(image[0], 0b0000_0001);
+}
+
+#[test]
+fn outputs_are_written_to_the_device() {
+    let (address, registers) = start_server(16);
+    let mut io = create_io(
+        &address,
+        r#"
+        [[variables]]
+        name = 'prg.lamp'
+        device = 'io'
+        kind = 'coil'
+        register = 1
+
+        [[variables]]
+        name = 'prg.setpoint'
+        device = 'io'
+        kind = 'holding_register'
+        register = 3
+
+        [[variables]]
+        name = 'prg.leds'
+        device = 'io'
+        kind = 'coil'
+        register = 8
+        "#,
+    )
+    .unwrap();
+
+    let mut image = [0u8; 4];
+    image[0] = 0b1000_0010;
+    image[2..4].copy_from_slice(&0xBEEFu16.to_ne_bytes());
+    io.write_outputs(&image).unwrap();
+
+    let registers = registers.lock().unwrap();
+    assert!(registers.coils[1]);
+    assert_eq!(registers.holding_registers[3], 0xBEEF);
+    assert_eq!(&registers.coils[8..16], &[false, true, false, false, false, false, false, true]);
+}
+
+#[test]
+fn exceptions_of_the_device_are_reported() {
+    let (address, _) = start_server(4);
+    let mut io = create_io(
+        &address,
+        r#"
+        [[variables]]
+        name = 'prg.level'
+        device = 'io'
+        kind = 'input_register'
+        register = 10
+        "#,
+    )
+    .unwrap();
+
+    let mut image = [0u8; 4];
+    let err = io.read_inputs(&mut image).unwrap_err();
+    assert!(matches!(err, Error::Exception { function: 4, code: ILLEGAL_DATA_ADDRESS }));
+    assert_eq!(err.to_string(), "Modbus exception 2 in response to function 4");
+}
+
+#[test]
+fn variables_outside_of_the_process_image_are_reported() {
+    let (address, _) = start_server(4);
+    let mut io = create_io(
+        &address,
+        r#"
+        [[variables]]
+        name = 'prg.level'
+        device = 'io'
+        kind = 'input_register'
+        register = 0
+        "#,
+    )
+    .unwrap();
+
+    let mut image = [0u8; 2];
+    assert_eq!(
+        io.read_inputs(&mut image).unwrap_err().to_string(),
+        "Invalid configuration: prg.level is outside of the process image"
+    );
+}
+
+#[test]
+fn unreachable_devices_are_reported() {
+    // bind and drop a listener to get a port nobody listens on
+    let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    let mut io = create_io(
+        &address,
+        r#"
+        [[variables]]
+        name = 'prg.start'
+        device = 'io'
+        kind = 'coil'
+        register = 0
+        "#,
+    )
+    .unwrap();
+
+    let mut image = [0u8; 1];
+    assert!(matches!(io.read_inputs(&mut image), Err(Error::Io(_))));
+}
+
+#[test]
+fn invalid_mappings_are_reported() {
+    let variable = |name: &str, device: &str, kind: &str| {
+        format!("[[variables]]\nname = '{name}'\ndevice = '{device}'\nkind = '{kind}'\nregister = 0")
+    };
+    let error = |variables: String| create_io("127.0.0.1:502", &variables).err().unwrap().to_string();
+
+    assert_eq!(
+        error(variable("prg.unknown", "io", "coil")),
+        "Invalid configuration: prg.unknown is not listed in the hardware configuration"
+    );
+    assert_eq!(
+        error(variable("prg.start", "other", "coil")),
+        "Invalid configuration: Unknown device other of prg.start"
+    );
+    assert_eq!(
+        error(variable("prg.lamp", "io", "discrete_input")),
+        "Invalid configuration: The output prg.lamp cannot be mapped to a read only DiscreteInput"
+    );
+    assert_eq!(
+        error(variable("prg.marker", "io", "coil")),
+        "Invalid configuration: prg.marker is neither an input nor an output and cannot be mapped"
+    );
+}
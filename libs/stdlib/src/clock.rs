@@ -0,0 +1,117 @@
+//! The time source of the standard library timers.
+//!
+//! By default the clock follows the system's monotonic clock. A manual clock can be installed at
+//! runtime, which only moves when it is set or advanced explicitly. This allows testing timers
+//! deterministically against the production library.
+//!
+//! The manual clock is installed for the calling thread, so tests running in parallel cannot
+//! influence each other.
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use crate::timers::Time;
+
+thread_local! {
+    /// the time of the manual clock, `None` if the system clock is used
+    static MANUAL_TIME: Cell<Option<Duration>> = const { Cell::new(None) };
+    /// the reference point of the system clock
+    static EPOCH: Instant = Instant::now();
+}
+
+/// Returns the current time of the clock.
+/// The time is only meaningful in relation to other values returned by this function.
+pub fn now() -> Duration {
+    MANUAL_TIME.with(Cell::get).unwrap_or_else(|| EPOCH.with(Instant::elapsed))
+}
+
+/// Installs a manual clock starting at the given time
+pub fn use_manual(start: Duration) {
+    MANUAL_TIME.with(|it| it.set(Some(start)));
+}
+
+/// Switches back to the system clock
+pub fn use_system() {
+    MANUAL_TIME.with(|it| it.set(None));
+}
+
+/// Advances the manual clock by the given duration, installs a manual clock at the current time if
+/// the system clock is in use
+pub fn advance(duration: Duration) {
+    let time = now() + duration;
+    use_manual(time);
+}
+
+fn to_time(duration: Duration) -> Time {
+    duration.as_nanos() as Time
+}
+
+fn from_time(time: Time) -> Duration {
+    Duration::from_nanos(time.max(0) as u64)
+}
+
+/// Returns the current time of the clock
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn CLOCK_NOW() -> Time {
+    to_time(now())
+}
+
+/// Installs a manual clock starting at the given time and returns the clock's time
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn CLOCK_USE_MANUAL(start: Time) -> Time {
+    use_manual(from_time(start));
+    CLOCK_NOW()
+}
+
+/// Switches back to the system clock and returns the clock's time
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn CLOCK_USE_SYSTEM() -> Time {
+    use_system();
+    CLOCK_NOW()
+}
+
+/// Advances the manual clock by the given duration and returns the clock's time
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn CLOCK_ADVANCE(duration: Time) -> Time {
+    advance(from_time(duration));
+    CLOCK_NOW()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn manual_clock_only_moves_when_advanced() {
+        assert_eq!(CLOCK_USE_MANUAL(1_000), 1_000);
+        assert_eq!(CLOCK_NOW(), 1_000);
+        assert_eq!(CLOCK_ADVANCE(500), 1_500);
+        assert_eq!(now(), Duration::from_nanos(1_500));
+    }
+
+    #[test]
+    fn system_clock_is_monotonic() {
+        CLOCK_USE_SYSTEM();
+        let first = CLOCK_NOW();
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(CLOCK_NOW() > first);
+    }
+
+    #[test]
+    fn advancing_the_system_clock_installs_a_manual_clock() {
+        use_system();
+        let start = now();
+        advance(Duration::from_secs(10));
+        let advanced = now();
+        assert!(advanced >= start + Duration::from_secs(10));
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(now(), advanced);
+    }
+}
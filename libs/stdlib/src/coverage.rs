@@ -0,0 +1,108 @@
+//! Runtime support for programs compiled with `--coverage`.
+//!
+//! Every instrumented module registers its counters on startup. When the program exits,
+//! the counters are appended to the profile file named by `PLC_COVERAGE_FILE`
+//! (`default.plcprof` if unset), one line per module: `<module>\t<counter> <counter> ...`
+use std::{
+    ffi::CStr,
+    fs::OpenOptions,
+    io::Write,
+    os::raw::c_char,
+    sync::{Mutex, Once},
+};
+
+// the standard library cannot depend on the compiler, these have to match `plc::coverage`
+const COVERAGE_FILE_VARIABLE: &str = "PLC_COVERAGE_FILE";
+const DEFAULT_PROFILE_FILE: &str = "default.plcprof";
+
+struct RegisteredModule {
+    name: String,
+    counters: usize,
+    len: usize,
+}
+
+static MODULES: Mutex<Vec<RegisteredModule>> = Mutex::new(Vec::new());
+static REGISTER_DUMP: Once = Once::new();
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> i32;
+}
+
+/// Registers the counters of an instrumented module, called by the module's constructor
+///
+/// # Safety
+///
+/// Works on raw pointers, `module` must be a null-terminated string and `counters` must point
+/// to `len` counters that stay valid until the program exits.
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn __plc_coverage_register(module: *const c_char, counters: *const u64, len: u64) {
+    if module.is_null() || counters.is_null() {
+        return;
+    }
+    let name = CStr::from_ptr(module).to_string_lossy().into_owned();
+    if let Ok(mut modules) = MODULES.lock() {
+        modules.push(RegisteredModule { name, counters: counters as usize, len: len as usize });
+    }
+    REGISTER_DUMP.call_once(|| {
+        atexit(dump_at_exit);
+    });
+}
+
+/// Appends the current counters of all registered modules to the profile file
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn __plc_coverage_dump() {
+    let file = std::env::var(COVERAGE_FILE_VARIABLE).unwrap_or_else(|_| DEFAULT_PROFILE_FILE.to_string());
+    let profile = get_profile();
+    if profile.is_empty() {
+        return;
+    }
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file)
+        .and_then(|mut f| f.write_all(profile.as_bytes()));
+    if let Err(err) = result {
+        log::error!("Could not write coverage profile {file}: {err}");
+    }
+}
+
+extern "C" fn dump_at_exit() {
+    __plc_coverage_dump()
+}
+
+fn get_profile() -> String {
+    let Ok(modules) = MODULES.lock() else {
+        return String::new();
+    };
+    modules
+        .iter()
+        .map(|module| {
+            // SAFETY: the pointer was registered by the module's constructor and outlives the program
+            let counters = unsafe { std::slice::from_raw_parts(module.counters as *const u64, module.len) };
+            let counters = counters.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(" ");
+            format!("{}\t{counters}\n", module.name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_counters_are_written_to_the_profile() {
+        static COUNTERS: [u64; 3] = [1, 0, 4];
+        unsafe { __plc_coverage_register(b"test.st\0".as_ptr() as *const c_char, COUNTERS.as_ptr(), 3) };
+
+        assert!(get_profile().contains("test.st\t1 0 4\n"));
+    }
+
+    #[test]
+    fn runtime_matches_the_compiler() {
+        assert_eq!(COVERAGE_FILE_VARIABLE, plc::coverage::COVERAGE_FILE_VARIABLE);
+        assert_eq!(DEFAULT_PROFILE_FILE, plc::coverage::DEFAULT_PROFILE_FILE);
+        assert_eq!(stringify!(__plc_coverage_register), plc::coverage::COVERAGE_REGISTER_FUNCTION);
+    }
+}
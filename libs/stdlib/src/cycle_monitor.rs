@@ -0,0 +1,155 @@
+//! Runtime support for programs compiled with `--monitor-cycles`.
+//!
+//! Every monitored program takes a timestamp from the [`clock`](crate::clock) when it is entered and
+//! reports it before it returns. The execution times are collected in the program's exported
+//! statistics (`__CYCLE_<program>`). If a program exceeds the budget configured with
+//! `{attribute 'cycle_budget' := 'T#5ms'}`, the watchdog handler is called. The default handler logs
+//! a warning, a runtime can install its own handler with [`__plc_cycle_set_watchdog`].
+use std::{ffi::CStr, os::raw::c_char, sync::Mutex};
+
+use crate::{clock::CLOCK_NOW, timers::Time};
+
+/// The execution times of a program in nanoseconds
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CycleStatistics {
+    /// the number of completed calls
+    pub cycles: u64,
+    pub last: Time,
+    pub min: Time,
+    pub max: Time,
+    pub average: Time,
+    pub total: Time,
+    /// the budget of a single call, 0 if the program has no budget
+    pub budget: Time,
+    /// the number of calls exceeding the budget
+    pub overruns: u64,
+}
+
+impl CycleStatistics {
+    /// records the execution time of a call, returns true if the call exceeded the budget
+    pub fn record(&mut self, time: Time) -> bool {
+        self.min = if self.cycles == 0 { time } else { self.min.min(time) };
+        self.max = self.max.max(time);
+        self.last = time;
+        self.cycles += 1;
+        self.total = self.total.saturating_add(time);
+        self.average = self.total / self.cycles as Time;
+        let overrun = self.budget > 0 && time > self.budget;
+        if overrun {
+            self.overruns += 1;
+        }
+        overrun
+    }
+}
+
+/// Called with the name and the statistics of a program that exceeded its budget
+pub type WatchdogHandler = extern "C" fn(program: *const c_char, statistics: *const CycleStatistics);
+
+static WATCHDOG: Mutex<Option<WatchdogHandler>> = Mutex::new(None);
+
+extern "C" fn log_overrun(program: *const c_char, statistics: *const CycleStatistics) {
+    // SAFETY: the handler is only called by `__plc_cycle_end` with valid pointers
+    let (program, statistics) = unsafe { (CStr::from_ptr(program).to_string_lossy(), &*statistics) };
+    log::warn!(
+        "{program} exceeded its cycle budget of {}ns with {}ns ({} of {} cycles)",
+        statistics.budget,
+        statistics.last,
+        statistics.overruns,
+        statistics.cycles
+    );
+}
+
+/// Returns the start time of a program, called when a monitored program is entered
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn __plc_cycle_begin() -> Time {
+    CLOCK_NOW()
+}
+
+/// Records the execution time of a program since the given start time, called before a monitored
+/// program returns. Calls the watchdog handler if the program exceeded its budget.
+///
+/// # Safety
+///
+/// Works on raw pointers, `program` must be a null-terminated string and `statistics` must point
+/// to the statistics of the program.
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn __plc_cycle_end(
+    program: *const c_char,
+    statistics: *mut CycleStatistics,
+    start: Time,
+) {
+    if program.is_null() || statistics.is_null() {
+        return;
+    }
+    if (*statistics).record(CLOCK_NOW() - start) {
+        // the handler may install another handler, so the lock is released before calling it
+        let handler = WATCHDOG.lock().ok().and_then(|it| *it).unwrap_or(log_overrun);
+        handler(program, statistics);
+    }
+}
+
+/// Installs the handler called when a program exceeds its budget, `None` restores the default
+/// handler logging a warning
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn __plc_cycle_set_watchdog(handler: Option<WatchdogHandler>) {
+    if let Ok(mut watchdog) = WATCHDOG.lock() {
+        *watchdog = handler;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{CLOCK_ADVANCE, CLOCK_USE_MANUAL};
+
+    #[test]
+    fn execution_times_are_recorded() {
+        let mut statistics = CycleStatistics::default();
+        CLOCK_USE_MANUAL(1_000);
+        for time in [300, 100, 200] {
+            let start = __plc_cycle_begin();
+            CLOCK_ADVANCE(time);
+            unsafe { __plc_cycle_end(b"prg\0".as_ptr() as *const c_char, &mut statistics, start) };
+        }
+
+        assert_eq!(
+            statistics,
+            CycleStatistics {
+                cycles: 3,
+                last: 200,
+                min: 100,
+                max: 300,
+                average: 200,
+                total: 600,
+                budget: 0,
+                overruns: 0
+            }
+        );
+    }
+
+    #[test]
+    fn the_watchdog_is_called_when_the_budget_is_exceeded() {
+        static OVERRUNS: Mutex<Vec<(String, Time)>> = Mutex::new(Vec::new());
+        extern "C" fn record(program: *const c_char, statistics: *const CycleStatistics) {
+            let program = unsafe { CStr::from_ptr(program) }.to_string_lossy().into_owned();
+            OVERRUNS.lock().unwrap().push((program, unsafe { (*statistics).last }));
+        }
+        __plc_cycle_set_watchdog(Some(record));
+
+        let mut statistics = CycleStatistics { budget: 150, ..Default::default() };
+        CLOCK_USE_MANUAL(0);
+        for time in [100, 150, 200] {
+            let start = __plc_cycle_begin();
+            CLOCK_ADVANCE(time);
+            unsafe { __plc_cycle_end(b"prg\0".as_ptr() as *const c_char, &mut statistics, start) };
+        }
+        __plc_cycle_set_watchdog(None);
+
+        assert_eq!(statistics.overruns, 1);
+        assert_eq!(*OVERRUNS.lock().unwrap(), vec![("prg".to_string(), 200)]);
+    }
+}
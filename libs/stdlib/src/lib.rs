@@ -4,7 +4,10 @@ pub mod arithmetic_functions;
 pub mod bistable_functionblocks;
 pub mod bit_num_conversion;
 pub mod bit_shift_functions;
+pub mod clock;
 pub mod counters;
+pub mod coverage;
+pub mod cycle_monitor;
 pub mod date_time_conversion;
 pub mod date_time_extra_functions;
 pub mod date_time_numeric_functions;
@@ -12,9 +15,11 @@ pub mod endianness_conversion_functions;
 pub mod extra_functions;
 pub mod flanks;
 pub mod numerical_functions;
+pub mod process_control;
 pub mod string_conversion;
 pub mod string_functions;
 pub mod timers;
+pub mod trace;
 pub mod types;
 pub mod utils;
 pub mod validation_functions;
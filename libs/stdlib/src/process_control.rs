@@ -0,0 +1,350 @@
+//! Function blocks for process control.
+//!
+//! All blocks work on `LREAL` values. Blocks that depend on time measure the time between two
+//! calls using the [`clock`], the first call after a reset does not integrate or differentiate.
+use crate::{clock, timers::Time};
+
+/// The time of the previous call of a function block
+#[repr(C)]
+#[derive(Debug, Default)]
+struct Cycle {
+    last_time: Time,
+    started: bool,
+}
+
+impl Cycle {
+    /// Returns the seconds since the previous call, `None` on the first call after a reset
+    fn elapsed(&mut self) -> Option<f64> {
+        let now = clock::now().as_nanos() as Time;
+        let elapsed = self.started.then(|| to_seconds(now - self.last_time));
+        self.last_time = now;
+        self.started = true;
+        elapsed
+    }
+
+    fn reset(&mut self) {
+        self.started = false;
+    }
+}
+
+fn to_seconds(time: Time) -> f64 {
+    time as f64 / 1e9
+}
+
+/// Returns true if the state of a hysteresis should be on, `xin1` has to exceed `xin2 + eps` to
+/// switch on and to fall below `xin2 - eps` to switch off again
+fn hysteresis(q: bool, xin1: f64, xin2: f64, eps: f64) -> bool {
+    if q {
+        xin1 >= xin2 - eps
+    } else {
+        xin1 > xin2 + eps
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct PidParams {
+    setpoint: f64,
+    actual: f64,
+    kp: f64,
+    tn: Time,
+    tv: Time,
+    y_manual: f64,
+    y_min: f64,
+    y_max: f64,
+    manual: bool,
+    reset: bool,
+    y: f64,
+    limits_active: bool,
+    /// the integral part of the output
+    integral: f64,
+    last_error: f64,
+    cycle: Cycle,
+}
+
+impl PidParams {
+    /// Limits the output to the configured range, returns the output and whether it was limited
+    fn limit(&self, value: f64) -> (f64, bool) {
+        if self.y_max > self.y_min {
+            let limited = value.clamp(self.y_min, self.y_max);
+            (limited, limited != value)
+        } else {
+            (value, false)
+        }
+    }
+}
+
+///.
+/// PID controller with anti-windup and manual mode
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn PID(params: &mut PidParams) {
+    if params.reset {
+        params.integral = 0.0;
+        params.last_error = 0.0;
+        params.y = 0.0;
+        params.limits_active = false;
+        params.cycle.reset();
+        return;
+    }
+
+    let error = params.setpoint - params.actual;
+    let elapsed = params.cycle.elapsed().unwrap_or_default();
+    let proportional = params.kp * error;
+    if params.manual {
+        let (y, limited) = params.limit(params.y_manual);
+        params.y = y;
+        params.limits_active = limited;
+        // bumpless transfer, the automatic mode continues from the manual output
+        params.integral = y - proportional;
+    } else {
+        let integral = if params.tn > 0 {
+            params.integral + params.kp * error * elapsed / to_seconds(params.tn)
+        } else {
+            0.0
+        };
+        let derivative = if params.tv > 0 && elapsed > 0.0 {
+            params.kp * to_seconds(params.tv) * (error - params.last_error) / elapsed
+        } else {
+            0.0
+        };
+        let unlimited = proportional + integral + derivative;
+        let (y, limited) = params.limit(unlimited);
+        // anti-windup, the integral part is frozen while it drives the output further into a limit
+        let increment = integral - params.integral;
+        let winding_up = (unlimited > y && increment > 0.0) || (unlimited < y && increment < 0.0);
+        if !winding_up {
+            params.integral = integral;
+        }
+        params.y = y;
+        params.limits_active = limited;
+    }
+    params.last_error = error;
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct RampParams {
+    input: f64,
+    rise: f64,
+    fall: f64,
+    reset: bool,
+    output: f64,
+    busy: bool,
+    cycle: Cycle,
+}
+
+///.
+/// Ramp generator, the output follows the input with a limited rate
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn RAMP(params: &mut RampParams) {
+    if params.reset {
+        params.cycle.reset();
+    }
+    params.output = match params.cycle.elapsed() {
+        Some(elapsed) => {
+            let difference = params.input - params.output;
+            let step = if difference > 0.0 {
+                limit_step(difference, params.rise * elapsed)
+            } else {
+                -limit_step(-difference, params.fall * elapsed)
+            };
+            params.output + step
+        }
+        None => params.input,
+    };
+    params.busy = params.output != params.input;
+}
+
+/// Limits a positive step to the given maximum, a maximum of 0 or less does not limit the step
+fn limit_step(step: f64, max: f64) -> f64 {
+    if max > 0.0 {
+        step.min(max)
+    } else {
+        step
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct HysteresisParams {
+    xin1: f64,
+    xin2: f64,
+    eps: f64,
+    q: bool,
+}
+
+///.
+/// Boolean hysteresis on the difference of two values
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn HYSTERESIS(params: &mut HysteresisParams) {
+    params.q = hysteresis(params.q, params.xin1, params.xin2, params.eps);
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct LimitsAlarmParams {
+    high: f64,
+    input: f64,
+    low: f64,
+    eps: f64,
+    high_alarm: bool,
+    alarm: bool,
+    low_alarm: bool,
+}
+
+///.
+/// High and low limit alarms with hysteresis
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn LIMITS_ALARM(params: &mut LimitsAlarmParams) {
+    let eps = params.eps / 2.0;
+    params.high_alarm = hysteresis(params.high_alarm, params.input, params.high - eps, eps);
+    params.low_alarm = hysteresis(params.low_alarm, params.low + eps, params.input, eps);
+    params.alarm = params.high_alarm || params.low_alarm;
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct IntegratorParams {
+    input: f64,
+    k: f64,
+    x0: f64,
+    reset: bool,
+    output: f64,
+    cycle: Cycle,
+}
+
+///.
+/// Integrates the input over time
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn INTEGRATOR(params: &mut IntegratorParams) {
+    if params.reset {
+        params.output = params.x0;
+        params.cycle.reset();
+        return;
+    }
+    if let Some(elapsed) = params.cycle.elapsed() {
+        params.output += params.k * params.input * elapsed;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct DerivativeParams {
+    input: f64,
+    k: f64,
+    output: f64,
+    last_input: f64,
+    cycle: Cycle,
+}
+
+///.
+/// Differentiates the input over time
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn DERIVATIVE(params: &mut DerivativeParams) {
+    match params.cycle.elapsed() {
+        Some(elapsed) if elapsed > 0.0 => {
+            params.output = params.k * (params.input - params.last_input) / elapsed;
+        }
+        // no time passed, the previous output is kept
+        Some(_) => {}
+        None => params.output = 0.0,
+    }
+    params.last_input = params.input;
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct LeadLagParams {
+    input: f64,
+    k: f64,
+    t_lead: Time,
+    t_lag: Time,
+    reset: bool,
+    output: f64,
+    last_input: f64,
+    cycle: Cycle,
+}
+
+///.
+/// Lead/lag element with the transfer function `K * (1 + T_LEAD * s) / (1 + T_LAG * s)`
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn LEAD_LAG(params: &mut LeadLagParams) {
+    if params.reset {
+        params.cycle.reset();
+    }
+    let t_lead = to_seconds(params.t_lead);
+    let t_lag = to_seconds(params.t_lag);
+    params.output = match params.cycle.elapsed() {
+        // backward euler discretization of the transfer function
+        Some(elapsed) if t_lag + elapsed > 0.0 => {
+            (t_lag * params.output
+                + params.k * (t_lead * (params.input - params.last_input) + elapsed * params.input))
+                / (t_lag + elapsed)
+        }
+        // the element starts in its steady state
+        _ => params.k * params.input,
+    };
+    params.last_input = params.input;
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct DeadBandParams {
+    input: f64,
+    width: f64,
+    output: f64,
+}
+
+///.
+/// Dead band around zero, the output is 0 within the band and reduced by the band's width outside
+///
+/// # Safety
+/// Working with raw pointers
+///
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn DEAD_BAND(params: &mut DeadBandParams) {
+    let width = params.width.abs();
+    params.output = if params.input > width {
+        params.input - width
+    } else if params.input < -width {
+        params.input + width
+    } else {
+        0.0
+    };
+}
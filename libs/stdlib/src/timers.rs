@@ -1,14 +1,6 @@
 use std::time::Duration;
-#[cfg(not(feature = "mock_time"))]
-use std::time::Instant;
 
-#[cfg(feature = "mock_time")]
-use test_time_helpers::Instant;
-
-use crate::utils::Signal;
-
-#[cfg(feature = "mock_time")]
-pub mod test_time_helpers;
+use crate::{clock, utils::Signal};
 
 pub type Time = i64;
 
@@ -20,7 +12,8 @@ pub struct TimerParams {
     output: bool,
     elapsed_time: Time,
     input_edge: Signal,
-    start_time: Option<Instant>,
+    /// the time of the [`clock`] when the timer was started
+    start_time: Option<Duration>,
 }
 
 impl TimerParams {
@@ -32,7 +25,7 @@ impl TimerParams {
     }
 
     fn start(&mut self) {
-        self.start_time = Some(Instant::now());
+        self.start_time = Some(clock::now());
         self.set_elapsed_time(0);
     }
 
@@ -61,7 +54,7 @@ impl TimerParams {
     }
 
     fn get_run_time(&self) -> Option<Duration> {
-        self.start_time.map(|it| it.elapsed())
+        self.start_time.map(|it| clock::now().saturating_sub(it))
     }
 
     fn set_output(&mut self, value: bool) {
@@ -0,0 +1,166 @@
+//! Runtime support for programs compiled with `--trace`.
+//!
+//! Every traced program passes the values of its traced variables at the end of each call. The
+//! latest samples are kept in a ring buffer holding `PLC_TRACE_SAMPLES` samples (1024 if unset). When
+//! the program exits, the buffer is written to the file named by `PLC_TRACE_FILE` (`default.plctrace`
+//! if unset): one line per program describing its signals, `program\t<description>`, followed by one
+//! line per sample, `sample\t<time>\t<program>\t<value> <value> ...`.
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::CStr,
+    fmt::Write,
+    os::raw::c_char,
+    sync::{Mutex, Once},
+};
+
+use crate::{clock::CLOCK_NOW, timers::Time};
+
+const TRACE_FILE_VARIABLE: &str = "PLC_TRACE_FILE";
+const DEFAULT_TRACE_FILE: &str = "default.plctrace";
+const TRACE_SAMPLES_VARIABLE: &str = "PLC_TRACE_SAMPLES";
+const DEFAULT_TRACE_SAMPLES: usize = 1024;
+
+struct Sample {
+    time: Time,
+    program: usize,
+    values: Vec<i64>,
+}
+
+struct TraceBuffer {
+    /// the descriptions of the traced programs, the program name is the first field
+    programs: Vec<String>,
+    /// the index of a program by the address of its description
+    descriptions: HashMap<usize, usize>,
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl TraceBuffer {
+    fn new(capacity: usize) -> TraceBuffer {
+        TraceBuffer {
+            programs: Vec::new(),
+            descriptions: HashMap::new(),
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// adds a sample of the described program, dropping the oldest sample if the buffer is full
+    fn push(&mut self, description: *const c_char, time: Time, values: Vec<i64>) {
+        let program = *self.descriptions.entry(description as usize).or_insert_with(|| {
+            // SAFETY: the description is a null-terminated constant of the traced program
+            let description = unsafe { CStr::from_ptr(description) }.to_string_lossy().into_owned();
+            match self.programs.iter().position(|it| *it == description) {
+                Some(program) => program,
+                None => {
+                    self.programs.push(description);
+                    self.programs.len() - 1
+                }
+            }
+        });
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { time, program, values });
+    }
+
+    fn get_trace(&self) -> String {
+        let mut trace = String::new();
+        for description in &self.programs {
+            let _ = writeln!(trace, "program\t{description}");
+        }
+        for sample in &self.samples {
+            let program = self.programs[sample.program].split('\t').next().unwrap_or_default();
+            let values = sample.values.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(" ");
+            let _ = writeln!(trace, "sample\t{}\t{program}\t{values}", sample.time);
+        }
+        trace
+    }
+}
+
+static BUFFER: Mutex<Option<TraceBuffer>> = Mutex::new(None);
+static REGISTER_DUMP: Once = Once::new();
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> i32;
+}
+
+/// Records the values of a program's traced variables, called before a traced program returns
+///
+/// # Safety
+///
+/// Works on raw pointers, `description` must be a null-terminated string that stays valid until the
+/// program exits and `values` must point to `count` values.
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern "C" fn __plc_trace_record(description: *const c_char, values: *const i64, count: u64) {
+    if description.is_null() || values.is_null() {
+        return;
+    }
+    let time = CLOCK_NOW();
+    let values = std::slice::from_raw_parts(values, count as usize).to_vec();
+    if let Ok(mut buffer) = BUFFER.lock() {
+        buffer
+            .get_or_insert_with(|| {
+                let capacity = std::env::var(TRACE_SAMPLES_VARIABLE)
+                    .ok()
+                    .and_then(|it| it.parse().ok())
+                    .unwrap_or(DEFAULT_TRACE_SAMPLES);
+                TraceBuffer::new(capacity)
+            })
+            .push(description, time, values);
+    }
+    REGISTER_DUMP.call_once(|| {
+        atexit(dump_at_exit);
+    });
+}
+
+/// Writes the recorded samples to the trace file, replacing its previous content
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn __plc_trace_dump() {
+    let trace = match BUFFER.lock() {
+        Ok(buffer) => buffer.as_ref().map(TraceBuffer::get_trace).unwrap_or_default(),
+        Err(_) => return,
+    };
+    if trace.is_empty() {
+        return;
+    }
+    let file = std::env::var(TRACE_FILE_VARIABLE).unwrap_or_else(|_| DEFAULT_TRACE_FILE.to_string());
+    if let Err(err) = std::fs::write(&file, trace) {
+        log::error!("Could not write trace {file}: {err}");
+    }
+}
+
+extern "C" fn dump_at_exit() {
+    __plc_trace_dump()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_oldest_samples_are_dropped() {
+        static PRG: &[u8] = b"PLC_PRG\ton:b:1\tspeed:i:16\0";
+        static MAIN: &[u8] = b"main\tcount:u:8\0";
+        let mut buffer = TraceBuffer::new(3);
+        buffer.push(PRG.as_ptr() as *const c_char, 100, vec![1, -2]);
+        buffer.push(MAIN.as_ptr() as *const c_char, 100, vec![7]);
+        buffer.push(PRG.as_ptr() as *const c_char, 200, vec![0, 3]);
+        buffer.push(PRG.as_ptr() as *const c_char, 300, vec![1, 4]);
+
+        assert_eq!(
+            buffer.get_trace(),
+            "program\tPLC_PRG\ton:b:1\tspeed:i:16
+program\tmain\tcount:u:8
+sample\t100\tmain\t7
+sample\t200\tPLC_PRG\t0 3
+sample\t300\tPLC_PRG\t1 4
+"
+        );
+    }
+}
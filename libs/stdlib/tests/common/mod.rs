@@ -226,6 +226,18 @@ pub fn compile_with_native<T: Compilable>(context: &CodegenContext, source: T) -
         ("TOF", iec61131std::timers::TOF as usize),
         ("TOF_TIME", iec61131std::timers::TOF_TIME as usize),
         ("TOF_LTIME", iec61131std::timers::TOF_LTIME as usize),
+        ("CLOCK_NOW", iec61131std::clock::CLOCK_NOW as usize),
+        ("CLOCK_USE_MANUAL", iec61131std::clock::CLOCK_USE_MANUAL as usize),
+        ("CLOCK_USE_SYSTEM", iec61131std::clock::CLOCK_USE_SYSTEM as usize),
+        ("CLOCK_ADVANCE", iec61131std::clock::CLOCK_ADVANCE as usize),
+        ("PID", iec61131std::process_control::PID as usize),
+        ("RAMP", iec61131std::process_control::RAMP as usize),
+        ("HYSTERESIS", iec61131std::process_control::HYSTERESIS as usize),
+        ("LIMITS_ALARM", iec61131std::process_control::LIMITS_ALARM as usize),
+        ("INTEGRATOR", iec61131std::process_control::INTEGRATOR as usize),
+        ("DERIVATIVE", iec61131std::process_control::DERIVATIVE as usize),
+        ("LEAD_LAG", iec61131std::process_control::LEAD_LAG as usize),
+        ("DEAD_BAND", iec61131std::process_control::DEAD_BAND as usize),
         ("SR", iec61131std::bistable_functionblocks::SR as usize),
         ("RS", iec61131std::bistable_functionblocks::RS as usize),
         ("R_TRIG", iec61131std::flanks::R_TRIG as usize),
@@ -0,0 +1,386 @@
+use std::time::Duration;
+
+use common::compile_with_native;
+use iec61131std::process_control::{
+    DeadBandParams, DerivativeParams, HysteresisParams, IntegratorParams, LeadLagParams, LimitsAlarmParams,
+    PidParams, RampParams,
+};
+
+// Import common functionality into the integration tests
+mod common;
+
+use common::add_std;
+use plc::codegen::CodegenContext;
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct PidType {
+    sp: f64,
+    pv: f64,
+    manual: bool,
+    pid_inst: PidParams,
+    y: f64,
+    limited: bool,
+}
+
+#[test]
+fn pid_integrates_the_error_and_does_not_wind_up() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            sp : LREAL;
+            pv : LREAL;
+            manual : BOOL;
+        END_VAR
+        VAR
+            pid_inst : PID;
+            y : LREAL;
+            limited : BOOL;
+        END_VAR
+            pid_inst(SP := sp, PV := pv, KP := 2.0, TN := T#1s, Y_MIN := 0.0, Y_MAX := 10.0,
+                MANUAL := manual, Y_MANUAL := 4.0, Y => y, LIMITS_ACTIVE => limited);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = PidType { sp: 1.0, ..PidType::default() };
+    //On the first call only the proportional part is active
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 2.0);
+    //The integral part grows by KP * error every second
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 4.0);
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 6.0);
+    //A large error drives the output into the limit, the integral part is frozen
+    main_inst.sp = 10.0;
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 10.0);
+    assert!(main_inst.limited);
+    //Once the error is small again the controller continues from the frozen integral part
+    main_inst.sp = 1.0;
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 8.0);
+    assert!(!main_inst.limited);
+}
+
+#[test]
+fn pid_switches_bumpless_from_manual_to_automatic() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            sp : LREAL;
+            pv : LREAL;
+            manual : BOOL;
+        END_VAR
+        VAR
+            pid_inst : PID;
+            y : LREAL;
+            limited : BOOL;
+        END_VAR
+            pid_inst(SP := sp, PV := pv, KP := 2.0, TN := T#1s, Y_MIN := 0.0, Y_MAX := 10.0,
+                MANUAL := manual, Y_MANUAL := 4.0, Y => y, LIMITS_ACTIVE => limited);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = PidType { sp: 1.0, manual: true, ..PidType::default() };
+    //In manual mode the manual output is used
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 4.0);
+    //Switching to automatic mode keeps the output
+    main_inst.manual = false;
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 4.0);
+    //Afterwards the integral part continues to grow
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.y, 6.0);
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct RampType {
+    target: f64,
+    ramp_inst: RampParams,
+    out: f64,
+    busy: bool,
+}
+
+#[test]
+fn ramp_limits_the_rate_of_change() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            target : LREAL;
+        END_VAR
+        VAR
+            ramp_inst : RAMP;
+            out : LREAL;
+            busy : BOOL;
+        END_VAR
+            ramp_inst(IN := target, RISE := 2.0, FALL := 4.0, OUT => out, BUSY => busy);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = RampType::default();
+    //The first call follows the input
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.out, 0.0);
+    assert!(!main_inst.busy);
+    //The output rises by 2 per second
+    main_inst.target = 10.0;
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.out, 2.0);
+    assert!(main_inst.busy);
+    iec61131std::clock::advance(Duration::from_secs(2));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.out, 6.0);
+    //The output stops at the target
+    iec61131std::clock::advance(Duration::from_secs(10));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.out, 10.0);
+    assert!(!main_inst.busy);
+    //The output falls by 4 per second
+    main_inst.target = 0.0;
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.out, 6.0);
+    assert!(main_inst.busy);
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct HysteresisType {
+    value: f64,
+    hysteresis_inst: HysteresisParams,
+    q: bool,
+}
+
+#[test]
+fn hysteresis_switches_outside_the_band() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        VAR
+            hysteresis_inst : HYSTERESIS;
+            q : BOOL;
+        END_VAR
+            hysteresis_inst(XIN1 := value, XIN2 := 10.0, EPS := 1.0, Q => q);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    let mut main_inst = HysteresisType::default();
+    for (value, expected) in [(10.5, false), (11.5, true), (9.5, true), (8.5, false), (10.5, false)] {
+        main_inst.value = value;
+        module.run::<_, ()>("main", &mut main_inst);
+        assert_eq!(main_inst.q, expected, "for value {value}");
+    }
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct LimitsAlarmType {
+    value: f64,
+    alarm_inst: LimitsAlarmParams,
+    qh: bool,
+    q: bool,
+    ql: bool,
+}
+
+#[test]
+fn limits_alarm_reports_high_and_low_limits() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        VAR
+            alarm_inst : LIMITS_ALARM;
+            qh : BOOL;
+            q : BOOL;
+            ql : BOOL;
+        END_VAR
+            alarm_inst(H := 100.0, X := value, L := 10.0, EPS := 2.0, QH => qh, Q => q, QL => ql);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    let mut main_inst = LimitsAlarmType::default();
+    for (value, qh, ql) in [
+        (50.0, false, false),
+        (101.0, true, false),
+        (99.0, true, false),
+        (97.0, false, false),
+        (9.0, false, true),
+        (11.0, false, true),
+        (13.0, false, false),
+    ] {
+        main_inst.value = value;
+        module.run::<_, ()>("main", &mut main_inst);
+        assert_eq!((main_inst.qh, main_inst.ql), (qh, ql), "for value {value}");
+        assert_eq!(main_inst.q, qh || ql, "for value {value}");
+    }
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct IntegratorType {
+    value: f64,
+    reset: bool,
+    integrator_inst: IntegratorParams,
+    derivative_inst: DerivativeParams,
+    integral: f64,
+    derivative: f64,
+}
+
+#[test]
+fn integrator_and_derivative_use_the_time_between_calls() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            value : LREAL;
+            reset : BOOL;
+        END_VAR
+        VAR
+            integrator_inst : INTEGRATOR;
+            derivative_inst : DERIVATIVE;
+            integral : LREAL;
+            derivative : LREAL;
+        END_VAR
+            integrator_inst(IN := value, K := 2.0, X0 := 10.0, RESET := reset, OUT => integral);
+            derivative_inst(IN := value, K := 2.0, OUT => derivative);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = IntegratorType { value: 1.0, ..IntegratorType::default() };
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.integral, 0.0);
+    assert_eq!(main_inst.derivative, 0.0);
+    //After half a second the integral is K * 1 * 0.5
+    iec61131std::clock::advance(Duration::from_millis(500));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.integral, 1.0);
+    assert_eq!(main_inst.derivative, 0.0);
+    //The input rises by 3 within half a second
+    main_inst.value = 4.0;
+    iec61131std::clock::advance(Duration::from_millis(500));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.integral, 5.0);
+    assert_eq!(main_inst.derivative, 12.0);
+    //A reset sets the integral to X0
+    main_inst.reset = true;
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.integral, 10.0);
+    //The first call after a reset does not integrate
+    main_inst.reset = false;
+    iec61131std::clock::advance(Duration::from_millis(500));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!(main_inst.integral, 10.0);
+    assert_eq!(main_inst.derivative, 0.0);
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct LeadLagType {
+    value: f64,
+    lag_inst: LeadLagParams,
+    lead_inst: LeadLagParams,
+    lag: f64,
+    lead: f64,
+}
+
+#[test]
+fn lead_lag_follows_a_step_of_the_input() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        VAR
+            lag_inst : LEAD_LAG;
+            lead_inst : LEAD_LAG;
+            lag : LREAL;
+            lead : LREAL;
+        END_VAR
+            lag_inst(IN := value, K := 1.0, T_LAG := T#1s, OUT => lag);
+            lead_inst(IN := value, K := 1.0, T_LEAD := T#1s, OUT => lead);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = LeadLagType::default();
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!((main_inst.lag, main_inst.lead), (0.0, 0.0));
+    //The lag approaches the step, the lead overshoots it
+    main_inst.value = 1.0;
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!((main_inst.lag, main_inst.lead), (0.5, 2.0));
+    iec61131std::clock::advance(Duration::from_secs(1));
+    module.run::<_, ()>("main", &mut main_inst);
+    assert_eq!((main_inst.lag, main_inst.lead), (0.75, 1.0));
+}
+
+#[repr(C)]
+#[derive(Default, Debug)]
+struct DeadBandType {
+    value: f64,
+    dead_band_inst: DeadBandParams,
+    out: f64,
+}
+
+#[test]
+fn dead_band_suppresses_small_values() {
+    let prog = r#"
+        PROGRAM main
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        VAR
+            dead_band_inst : DEAD_BAND;
+            out : LREAL;
+        END_VAR
+            dead_band_inst(IN := value, WIDTH := 2.0, OUT => out);
+        END_PROGRAM
+    "#;
+
+    let source = add_std!(prog, "process_control.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    let mut main_inst = DeadBandType::default();
+    for (value, expected) in [(1.5, 0.0), (-2.0, 0.0), (5.0, 3.0), (-5.0, -3.0)] {
+        main_inst.value = value;
+        module.run::<_, ()>("main", &mut main_inst);
+        assert_eq!(main_inst.out, expected, "for value {value}");
+    }
+}
@@ -72,28 +72,29 @@ fn tp_true_for_time() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     //On first call, out is true, et is 0
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 5ms, out is true, et is 5ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
     //At 10ms, out is true, et is 10ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     //After 15ms, out is false, et is 10/
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     //After 20ms, input is off, out remains off, et set to 0
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
@@ -120,6 +121,7 @@ fn tp_does_not_retrigger_on_consecutive_input() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
 
     let mut main_inst = MainType { value: true, ..MainType::default() };
     //On first call, out is true, et is 0
@@ -127,17 +129,17 @@ fn tp_does_not_retrigger_on_consecutive_input() {
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //At 10ms, out is true, et is 10ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     //After 15ms, out is false, et is 10/
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     //After 20ms, out is false, et is 10/
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
@@ -162,6 +164,7 @@ fn tp_not_interrupted_by_signal_change() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
 
     //On first call with true, out is true, et is 0
@@ -170,7 +173,7 @@ fn tp_not_interrupted_by_signal_change() {
     assert_eq!(main_inst.tp_et, 0);
 
     //advance 1 ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(1));
+    iec61131std::clock::advance(Duration::from_millis(1));
     //call timer with false
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
@@ -178,7 +181,7 @@ fn tp_not_interrupted_by_signal_change() {
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 1_000_000);
     // advance by 1 ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(1));
+    iec61131std::clock::advance(Duration::from_millis(1));
     //call timer with true
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
@@ -206,31 +209,32 @@ fn ton_returns_true_after_time_preset() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true First call -> false
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     // Value true After 5ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
     // Value true After 10ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     // Value true After 15ms -> true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 10_000_000);
     // Value false after 20ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
@@ -256,19 +260,20 @@ fn ton_counts_elapsed_time_while_waiting() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true, counter starts at 0
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     // Value true after 5ms counter at 5ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
     // Value false after 6ms counter at 0ms (stopped)
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(1));
+    iec61131std::clock::advance(Duration::from_millis(1));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
@@ -294,37 +299,38 @@ fn ton_waits_again_after_turining_off() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true First call -> false
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     // Value true After 5ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
     // Value true After 10ms -> true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 9_000_000);
     // Value false After 15ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     // Value true after 20ms -> false
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     // Value true after 30ms -> true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
@@ -350,19 +356,20 @@ fn toff_starts_timer_after_input_is_off() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true First call -> true
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     //Turn off after 10ms -> Timer kicks in, output remains true
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 15 ms, output still true, time elapsed is 5ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
@@ -387,19 +394,20 @@ fn toff_runs_for_preset_time() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true First call -> true
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     //Turn off after 10ms -> Timer kicks in, output remains true
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 20ms, output is turned off, time elapsed is equal to tp (9ms)
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(!main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 9_000_000);
@@ -407,7 +415,7 @@ fn toff_runs_for_preset_time() {
     //On the next true signal, the timer's elapsed time is set to 0 again
     // Value true First call -> true
     main_inst.value = true;
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
@@ -432,37 +440,76 @@ fn toff_keeps_returning_true_if_input_returns_to_true() {
     let source = add_std!(prog, "timers.st");
     let context = CodegenContext::create();
     let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
     let mut main_inst = MainType { value: true, ..MainType::default() };
     // Value true First call -> false
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //Turn off after 10ms -> Timer kicks in, output remains true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(10));
+    iec61131std::clock::advance(Duration::from_millis(10));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 15 ms, output still true, time elapsed is 5ms
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
     //After 16ms, the input becomes true again, the timer stops, et is set to 0 but the signal remains true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(1));
+    iec61131std::clock::advance(Duration::from_millis(1));
     main_inst.value = true;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 20ms, the input turns off, the timer starts again
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(4));
+    iec61131std::clock::advance(Duration::from_millis(4));
     main_inst.value = false;
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 0);
     //After 25ms, the input is still off, the timer's elapsed time is 5ms, the output is true
-    iec61131std::timers::test_time_helpers::MockClock::advance(Duration::from_millis(5));
+    iec61131std::clock::advance(Duration::from_millis(5));
     module.run::<_, ()>("main", &mut main_inst);
     assert!(main_inst.tp_out);
     assert_eq!(main_inst.tp_et, 5_000_000);
 }
+
+#[test]
+fn timers_can_be_driven_by_the_manual_clock_from_st() {
+    let prog = r#"
+    PROGRAM main
+        VAR_INPUT
+            value : BOOL;
+        END_VAR
+        VAR
+            tp_out  : BOOL;
+            tp_et   : TIME;
+            tp_inst : TON;
+        END_VAR
+        tp_inst(IN := value, PT := T#10ms, Q => tp_out, ET => tp_et);
+        CLOCK_ADVANCE(LTIME#4ms);
+    END_PROGRAM
+"#;
+
+    let source = add_std!(prog, "timers.st", "clock.st");
+    let context = CodegenContext::create();
+    let module = compile_with_native(&context, source);
+    iec61131std::clock::use_manual(Duration::ZERO);
+    let mut main_inst = MainType { value: true, ..MainType::default() };
+    //Every cycle advances the clock by 4ms after the timer was called
+    module.run::<_, ()>("main", &mut main_inst);
+    assert!(!main_inst.tp_out);
+    assert_eq!(main_inst.tp_et, 0);
+    module.run::<_, ()>("main", &mut main_inst);
+    assert!(!main_inst.tp_out);
+    assert_eq!(main_inst.tp_et, 4_000_000);
+    module.run::<_, ()>("main", &mut main_inst);
+    assert!(!main_inst.tp_out);
+    assert_eq!(main_inst.tp_et, 8_000_000);
+    module.run::<_, ()>("main", &mut main_inst);
+    assert!(main_inst.tp_out);
+    assert_eq!(main_inst.tp_et, 10_000_000);
+    assert_eq!(iec61131std::clock::now(), Duration::from_millis(16));
+}
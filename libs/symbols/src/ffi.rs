@@ -0,0 +1,105 @@
+//! The C interface of the library:
+//!
+//! ```c
+//! typedef struct {
+//!     void* pointer;
+//!     uint32_t kind; // 0: other, 1: bool, 2: signed, 3: unsigned, 4: float, 5: string, 6: wide string
+//!     uint32_t size;
+//!     bool readable;
+//!     bool writable;
+//! } PlcSymbol;
+//!
+//! void* plc_symbols_open(const char* path);
+//! bool plc_symbols_resolve(const void* library, const char* path, PlcSymbol* symbol);
+//! void plc_symbols_close(void* library);
+//! ```
+use std::{ffi::CStr, os::raw::c_char, path::Path, ptr};
+
+use crate::{Library, TypeKind};
+
+/// A variable resolved by [`plc_symbols_resolve`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct PlcSymbol {
+    pub pointer: *mut u8,
+    pub kind: u32,
+    /// the size of the variable in bytes
+    pub size: u32,
+    pub readable: bool,
+    pub writable: bool,
+}
+
+fn get_kind(kind: &TypeKind) -> u32 {
+    match kind {
+        TypeKind::Bool => 1,
+        TypeKind::Signed => 2,
+        TypeKind::Unsigned => 3,
+        TypeKind::Float => 4,
+        TypeKind::String { wide: false } => 5,
+        TypeKind::String { wide: true } => 6,
+        TypeKind::Struct { .. } | TypeKind::Array { .. } | TypeKind::Other => 0,
+    }
+}
+
+/// Opens the shared library at the given path, returns null if the library cannot be loaded or has
+/// no valid symbol table
+///
+/// # Safety
+///
+/// The path must be a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn plc_symbols_open(path: *const c_char) -> *mut Library {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let path = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(_) => return ptr::null_mut(),
+    };
+    match Library::open(Path::new(path)) {
+        Ok(library) => Box::into_raw(Box::new(library)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Resolves the path to a variable of the library, returns false if the path cannot be resolved
+///
+/// # Safety
+///
+/// The library must be returned by [`plc_symbols_open`] and not yet closed, the path must be a null
+/// terminated string and the symbol must point to writable memory
+#[no_mangle]
+pub unsafe extern "C" fn plc_symbols_resolve(
+    library: *const Library,
+    path: *const c_char,
+    symbol: *mut PlcSymbol,
+) -> bool {
+    if library.is_null() || path.is_null() || symbol.is_null() {
+        return false;
+    }
+    let variable = match CStr::from_ptr(path).to_str().ok().and_then(|it| (*library).resolve(it).ok()) {
+        Some(variable) => variable,
+        None => return false,
+    };
+    let data_type = variable.get_data_type();
+    *symbol = PlcSymbol {
+        pointer: variable.get_pointer(),
+        kind: get_kind(&data_type.kind),
+        size: data_type.size,
+        readable: variable.get_access().is_readable(),
+        writable: variable.get_access().is_writable(),
+    };
+    true
+}
+
+/// Closes a library opened with [`plc_symbols_open`], pointers to its variables must no longer be used
+///
+/// # Safety
+///
+/// The library must be returned by [`plc_symbols_open`] and not yet closed
+#[no_mangle]
+pub unsafe extern "C" fn plc_symbols_close(library: *mut Library) {
+    if !library.is_null() {
+        drop(Box::from_raw(library));
+    }
+}
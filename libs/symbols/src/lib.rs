@@ -0,0 +1,466 @@
+//! Accesses the variables of a PLC program by name at runtime.
+//!
+//! A program compiled with `--embed-symbols` exports the string constant `__SYMBOL_TABLE`, describing its
+//! global variables, program instances and the layout of their types. [`SymbolTable`] resolves paths like
+//! `PLC_PRG.motors[2].speed` against this table. Names are compared case insensitive, multi dimensional
+//! arrays are indexed with `[1,2]` and arrays of arrays with `[1][2]`, like the names listed by `--symbols`.
+//!
+//! [`Library`] loads the symbol table of a shared library and resolves paths to its variables:
+//!
+//! ```no_run
+//! # use plc_symbols::{Library, Value};
+//! let library = Library::open("./libplc.so".as_ref()).unwrap();
+//! let speed = library.resolve("PLC_PRG.motors[2].speed").unwrap();
+//! speed.write(Value::Signed(1500)).unwrap();
+//! assert_eq!(speed.read().unwrap(), Value::Signed(1500));
+//! ```
+//!
+//! The same is offered to C through the functions of [`ffi`].
+use std::ptr;
+
+use serde::Deserialize;
+
+#[cfg(unix)]
+pub mod ffi;
+#[cfg(unix)]
+mod library;
+
+#[cfg(unix)]
+pub use library::Library;
+
+/// The exported constant holding the symbol table
+pub const SYMBOL_TABLE_VARIABLE: &str = "__SYMBOL_TABLE";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid path {0}")]
+    InvalidPath(String),
+    #[error("Unknown variable {0}")]
+    UnknownVariable(String),
+    #[error("{0} has no member {1}")]
+    UnknownMember(String, String),
+    #[error("{0} is not an array")]
+    NotAnArray(String),
+    #[error("{path} expects {expected} indexes")]
+    IndexCount { path: String, expected: usize },
+    #[error("Index {index:?} is out of the bounds of {path}")]
+    IndexOutOfBounds { path: String, index: Vec<i64> },
+    #[error("Unknown symbol {0}")]
+    UnknownSymbol(String),
+    #[error("Cannot load {0}: {1}")]
+    Load(String, String),
+    #[error("{0} has no symbol table, it needs to be compiled with --embed-symbols")]
+    MissingSymbolTable(String),
+    #[error("Invalid symbol table: {0}")]
+    Format(String),
+    #[error("The variable cannot be read")]
+    NotReadable,
+    #[error("The variable cannot be written")]
+    NotWritable,
+    #[error("Variables of type {0} cannot be read or written")]
+    NotElementary(String),
+    #[error("{value:?} cannot be assigned to a variable of type {type_name}")]
+    TypeMismatch { value: Value, type_name: String },
+}
+
+/// The access granted to clients with the `symbol` attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Access {
+    None,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl Access {
+    pub fn is_readable(self) -> bool {
+        matches!(self, Access::Read | Access::ReadWrite)
+    }
+
+    pub fn is_writable(self) -> bool {
+        matches!(self, Access::Write | Access::ReadWrite)
+    }
+}
+
+/// A member of a struct, function block or program
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub name: String,
+    /// the byte offset of the member in its container
+    pub offset: u32,
+    #[serde(rename = "type")]
+    pub data_type: usize,
+    /// members without an access inherit the access of their container
+    pub access: Option<Access>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind")]
+pub enum TypeKind {
+    Bool,
+    Signed,
+    Unsigned,
+    Float,
+    String {
+        wide: bool,
+    },
+    Struct {
+        members: Vec<Member>,
+    },
+    /// the elements are stored row major, `stride` bytes apart
+    Array {
+        dimensions: Vec<[i64; 2]>,
+        element: usize,
+        stride: u32,
+    },
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DataType {
+    pub name: String,
+    pub size: u32,
+    #[serde(flatten)]
+    pub kind: TypeKind,
+}
+
+/// A global variable or program instance
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Root {
+    pub name: String,
+    /// the name of the exported symbol
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub data_type: usize,
+    pub access: Option<Access>,
+}
+
+/// The symbol table embedded with `--embed-symbols`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SymbolTable {
+    variables: Vec<Root>,
+    /// types are referenced by their position
+    types: Vec<DataType>,
+}
+
+/// The position of a variable relative to an exported symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location<'a> {
+    pub symbol: &'a str,
+    pub offset: usize,
+    pub data_type: &'a DataType,
+    pub access: Access,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Name(String),
+    Index(Vec<i64>),
+}
+
+/// splits `a.b[1,2][3].c` into the names and indexes it consists of
+fn parse_path(path: &str) -> Result<Vec<Segment>, Error> {
+    let invalid = || Error::InvalidPath(path.to_string());
+    let mut segments = vec![];
+    let mut rest = path.trim();
+    while !rest.is_empty() {
+        if let Some(indexes) = rest.strip_prefix('[') {
+            let end = indexes.find(']').ok_or_else(invalid)?;
+            let index = indexes[..end]
+                .split(',')
+                .map(|it| it.trim().parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+            segments.push(Segment::Index(index));
+            rest = &indexes[end + 1..];
+        } else {
+            // every name but the first one follows a dot
+            let name = if segments.is_empty() { rest } else { rest.strip_prefix('.').ok_or_else(invalid)? };
+            let end = name.find(['.', '[']).unwrap_or(name.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(Segment::Name(name[..end].to_string()));
+            rest = &name[end..];
+        }
+    }
+    match segments.first() {
+        Some(Segment::Name(_)) => Ok(segments),
+        _ => Err(invalid()),
+    }
+}
+
+/// the row major position of the element with the given indexes
+fn get_position(dimensions: &[[i64; 2]], index: &[i64]) -> Option<usize> {
+    dimensions.iter().zip(index).try_fold(0, |position, ([start, end], index)| {
+        (start..=end)
+            .contains(&index)
+            .then(|| position * (end - start + 1) as usize + (index - start) as usize)
+    })
+}
+
+impl SymbolTable {
+    pub fn parse(table: &str) -> Result<SymbolTable, Error> {
+        serde_json::from_str(table).map_err(|err| Error::Format(err.to_string()))
+    }
+
+    pub fn get_variables(&self) -> &[Root] {
+        &self.variables
+    }
+
+    pub fn get_type(&self, id: usize) -> Result<&DataType, Error> {
+        self.types.get(id).ok_or_else(|| Error::Format(format!("Unknown type {id}")))
+    }
+
+    /// Returns the exported symbol containing the variable with the given path and the variable's offset
+    pub fn resolve(&self, path: &str) -> Result<Location<'_>, Error> {
+        let mut segments = parse_path(path)?.into_iter();
+        let name = match segments.next() {
+            Some(Segment::Name(name)) => name,
+            _ => unreachable!("paths start with a name"),
+        };
+        let root = self
+            .variables
+            .iter()
+            .find(|it| it.name.eq_ignore_ascii_case(&name))
+            .ok_or(Error::UnknownVariable(name))?;
+        let mut location = Location {
+            symbol: &root.symbol,
+            offset: 0,
+            data_type: self.get_type(root.data_type)?,
+            access: root.access.unwrap_or(Access::ReadWrite),
+        };
+        // the path resolved so far, used in errors
+        let mut resolved = root.name.clone();
+        for segment in segments {
+            let data_type = location.data_type;
+            match (segment, &data_type.kind) {
+                (Segment::Name(name), TypeKind::Struct { members }) => {
+                    let member = members
+                        .iter()
+                        .find(|it| it.name.eq_ignore_ascii_case(&name))
+                        .ok_or_else(|| Error::UnknownMember(resolved.clone(), name))?;
+                    location.offset += member.offset as usize;
+                    location.data_type = self.get_type(member.data_type)?;
+                    location.access = member.access.unwrap_or(location.access);
+                    resolved = format!("{resolved}.{}", member.name);
+                }
+                (Segment::Name(name), _) => return Err(Error::UnknownMember(resolved, name)),
+                (Segment::Index(index), TypeKind::Array { dimensions, element, stride }) => {
+                    if index.len() != dimensions.len() {
+                        return Err(Error::IndexCount { path: resolved, expected: dimensions.len() });
+                    }
+                    let position = get_position(dimensions, &index).ok_or_else(|| {
+                        Error::IndexOutOfBounds { path: resolved.clone(), index: index.clone() }
+                    })?;
+                    location.offset += position * *stride as usize;
+                    location.data_type = self.get_type(*element)?;
+                    let index = index.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+                    resolved = format!("{resolved}[{}]", index.join(","));
+                }
+                (Segment::Index(_), _) => return Err(Error::NotAnArray(resolved)),
+            }
+        }
+        Ok(location)
+    }
+
+    /// Resolves the path to a variable in memory, `lookup` returns the address of an exported symbol
+    ///
+    /// # Safety
+    ///
+    /// The addresses returned by `lookup` must point to the variables described by this table and stay
+    /// valid as long as the returned variable is used
+    pub unsafe fn resolve_variable(
+        &self,
+        path: &str,
+        lookup: impl FnOnce(&str) -> Option<*mut u8>,
+    ) -> Result<Variable<'_>, Error> {
+        let location = self.resolve(path)?;
+        let symbol =
+            lookup(location.symbol).ok_or_else(|| Error::UnknownSymbol(location.symbol.to_string()))?;
+        Ok(Variable::new(symbol.add(location.offset), location.data_type, location.access))
+    }
+}
+
+/// The value of an elementary variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+}
+
+/// A variable in the memory of a running program
+#[derive(Debug, Clone, Copy)]
+pub struct Variable<'a> {
+    pointer: *mut u8,
+    data_type: &'a DataType,
+    access: Access,
+}
+
+impl<'a> Variable<'a> {
+    /// # Safety
+    ///
+    /// The pointer must point to a variable of the given type, valid as long as the variable is used
+    pub unsafe fn new(pointer: *mut u8, data_type: &'a DataType, access: Access) -> Variable<'a> {
+        Variable { pointer, data_type, access }
+    }
+
+    pub fn get_pointer(&self) -> *mut u8 {
+        self.pointer
+    }
+
+    pub fn get_data_type(&self) -> &'a DataType {
+        self.data_type
+    }
+
+    pub fn get_access(&self) -> Access {
+        self.access
+    }
+
+    pub fn read(&self) -> Result<Value, Error> {
+        if !self.access.is_readable() {
+            return Err(Error::NotReadable);
+        }
+        let pointer = self.pointer;
+        // variables of packed structs are not necessarily aligned
+        let value = unsafe {
+            match (&self.data_type.kind, self.data_type.size) {
+                (TypeKind::Bool, _) => Value::Bool(ptr::read(pointer) != 0),
+                (TypeKind::Signed, 1) => Value::Signed(ptr::read(pointer as *const i8).into()),
+                (TypeKind::Signed, 2) => Value::Signed(ptr::read_unaligned(pointer as *const i16).into()),
+                (TypeKind::Signed, 4) => Value::Signed(ptr::read_unaligned(pointer as *const i32).into()),
+                (TypeKind::Signed, 8) => Value::Signed(ptr::read_unaligned(pointer as *const i64)),
+                (TypeKind::Unsigned, 1) => Value::Unsigned(ptr::read(pointer).into()),
+                (TypeKind::Unsigned, 2) => Value::Unsigned(ptr::read_unaligned(pointer as *const u16).into()),
+                (TypeKind::Unsigned, 4) => Value::Unsigned(ptr::read_unaligned(pointer as *const u32).into()),
+                (TypeKind::Unsigned, 8) => Value::Unsigned(ptr::read_unaligned(pointer as *const u64)),
+                (TypeKind::Float, 4) => Value::Float(ptr::read_unaligned(pointer as *const f32).into()),
+                (TypeKind::Float, 8) => Value::Float(ptr::read_unaligned(pointer as *const f64)),
+                (TypeKind::String { wide: false }, size) => {
+                    let bytes = std::slice::from_raw_parts(pointer, size as usize);
+                    let length = bytes.iter().position(|it| *it == 0).unwrap_or(bytes.len());
+                    Value::String(String::from_utf8_lossy(&bytes[..length]).into_owned())
+                }
+                (TypeKind::String { wide: true }, size) => {
+                    let characters = (0..size as usize / 2)
+                        .map(|it| ptr::read_unaligned((pointer as *const u16).add(it)))
+                        .take_while(|it| *it != 0)
+                        .collect::<Vec<_>>();
+                    Value::String(String::from_utf16_lossy(&characters))
+                }
+                _ => return Err(Error::NotElementary(self.data_type.name.clone())),
+            }
+        };
+        Ok(value)
+    }
+
+    /// Writes the value, integers are accepted if they fit into the variable's type
+    pub fn write(&self, value: Value) -> Result<(), Error> {
+        if !self.access.is_writable() {
+            return Err(Error::NotWritable);
+        }
+        let mismatch =
+            || Error::TypeMismatch { value: value.clone(), type_name: self.data_type.name.clone() };
+        let pointer = self.pointer;
+        let integer = match value {
+            Value::Signed(it) => Some(i128::from(it)),
+            Value::Unsigned(it) => Some(i128::from(it)),
+            _ => None,
+        };
+        unsafe {
+            match (&self.data_type.kind, self.data_type.size, &value, integer) {
+                (TypeKind::Bool, _, Value::Bool(it), _) => ptr::write(pointer, u8::from(*it)),
+                (TypeKind::Signed, 1, _, Some(it)) => {
+                    ptr::write(pointer as *mut i8, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Signed, 2, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut i16, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Signed, 4, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut i32, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Signed, 8, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut i64, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Unsigned, 1, _, Some(it)) => {
+                    ptr::write(pointer, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Unsigned, 2, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut u16, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Unsigned, 4, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut u32, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Unsigned, 8, _, Some(it)) => {
+                    ptr::write_unaligned(pointer as *mut u64, it.try_into().map_err(|_| mismatch())?)
+                }
+                (TypeKind::Float, 4, Value::Float(it), _) => {
+                    ptr::write_unaligned(pointer as *mut f32, *it as f32)
+                }
+                (TypeKind::Float, 8, Value::Float(it), _) => ptr::write_unaligned(pointer as *mut f64, *it),
+                (TypeKind::String { wide: false }, size, Value::String(it), _) => {
+                    // the last byte is reserved for the terminator
+                    if it.len() >= size as usize {
+                        return Err(mismatch());
+                    }
+                    ptr::copy_nonoverlapping(it.as_ptr(), pointer, it.len());
+                    ptr::write(pointer.add(it.len()), 0);
+                }
+                (TypeKind::String { wide: true }, size, Value::String(it), _) => {
+                    let characters = it.encode_utf16().chain(std::iter::once(0)).collect::<Vec<_>>();
+                    if characters.len() > size as usize / 2 {
+                        return Err(mismatch());
+                    }
+                    for (offset, character) in characters.into_iter().enumerate() {
+                        ptr::write_unaligned((pointer as *mut u16).add(offset), character);
+                    }
+                }
+                (TypeKind::Bool | TypeKind::Signed | TypeKind::Unsigned | TypeKind::Float, ..)
+                | (TypeKind::String { .. }, ..) => return Err(mismatch()),
+                _ => return Err(Error::NotElementary(self.data_type.name.clone())),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_position, parse_path, Segment};
+
+    #[test]
+    fn paths_are_split_into_names_and_indexes() {
+        let name = |it: &str| Segment::Name(it.to_string());
+        assert_eq!(
+            parse_path("PLC_PRG.values[1, -2][3].x").unwrap(),
+            vec![
+                name("PLC_PRG"),
+                name("values"),
+                Segment::Index(vec![1, -2]),
+                Segment::Index(vec![3]),
+                name("x")
+            ]
+        );
+        assert_eq!(parse_path("counter").unwrap(), vec![name("counter")]);
+    }
+
+    #[test]
+    fn invalid_paths_are_reported() {
+        for path in ["", "[1]", "a.", "a..b", "a[1", "a[x]", "a[1]b", "a.[1]"] {
+            assert_eq!(parse_path(path).unwrap_err().to_string(), format!("Invalid path {path}"));
+        }
+    }
+
+    #[test]
+    fn elements_are_stored_row_major() {
+        let dimensions = [[1, 2], [-1, 1]];
+        assert_eq!(get_position(&dimensions, &[1, -1]), Some(0));
+        assert_eq!(get_position(&dimensions, &[1, 1]), Some(2));
+        assert_eq!(get_position(&dimensions, &[2, 0]), Some(4));
+        assert_eq!(get_position(&dimensions, &[3, 0]), None);
+        assert_eq!(get_position(&dimensions, &[1, 2]), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Loads the symbol table of a shared library compiled with `--embed-symbols`
+use std::{
+    ffi::{CStr, CString},
+    os::{raw::c_char, unix::ffi::OsStrExt},
+    path::Path,
+};
+
+use crate::{Error, SymbolTable, Variable, SYMBOL_TABLE_VARIABLE};
+
+/// A shared library opened with `dlopen`, the library is closed when dropped
+#[derive(Debug)]
+pub struct Library {
+    handle: *mut libc::c_void,
+    table: SymbolTable,
+}
+
+impl Library {
+    pub fn open(path: &Path) -> Result<Library, Error> {
+        let name = path.display().to_string();
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| Error::Load(name.clone(), "the path contains a null byte".to_string()))?;
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW) };
+        if handle.is_null() {
+            return Err(Error::Load(name, get_dl_error()));
+        }
+        // created before the table is read to close the library on errors
+        let mut library = Library { handle, table: SymbolTable::default() };
+        let table = library.get_symbol(SYMBOL_TABLE_VARIABLE).ok_or(Error::MissingSymbolTable(name))?;
+        let table = unsafe { CStr::from_ptr(table as *const c_char) }
+            .to_str()
+            .map_err(|err| Error::Format(err.to_string()))?;
+        library.table = SymbolTable::parse(table)?;
+        Ok(library)
+    }
+
+    pub fn get_symbol_table(&self) -> &SymbolTable {
+        &self.table
+    }
+
+    /// returns the address of the exported symbol with the given name
+    pub fn get_symbol(&self, name: &str) -> Option<*mut u8> {
+        let name = CString::new(name).ok()?;
+        let symbol = unsafe { libc::dlsym(self.handle, name.as_ptr()) };
+        (!symbol.is_null()).then_some(symbol as *mut u8)
+    }
+
+    /// Resolves the path to a variable of the library, e.g. `PLC_PRG.motors[2].speed`
+    pub fn resolve(&self, path: &str) -> Result<Variable<'_>, Error> {
+        // the symbols of the library are described by its own table
+        unsafe { self.table.resolve_variable(path, |it| self.get_symbol(it)) }
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { libc::dlclose(self.handle) };
+    }
+}
+
+fn get_dl_error() -> String {
+    let error = unsafe { libc::dlerror() };
+    if error.is_null() {
+        "unknown error".to_string()
+    } else {
+        unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned()
+    }
+}
@@ -0,0 +1,167 @@
+use plc_symbols::{Access, Error, SymbolTable, Value};
+
+/// The table of the program:
+///
+/// ```st
+/// TYPE Motor : STRUCT
+///     speed : INT;
+///     {attribute 'symbol' := 'read'}
+///     setpoint : LREAL;
+/// END_STRUCT END_TYPE
+///
+/// VAR_GLOBAL CONSTANT
+///     limit : DINT := 5;
+/// END_VAR
+///
+/// PROGRAM PLC_PRG
+/// VAR
+///     on : BOOL;
+///     motors : ARRAY[1..2, 0..2] OF Motor;
+///     name : STRING[10];
+///     label : WSTRING[3];
+/// END_VAR
+/// END_PROGRAM
+/// ```
+const SYMBOL_TABLE: &str = r#"{
+    "variables": [
+        { "name": "limit", "symbol": "limit", "type": 0, "access": "Read" },
+        { "name": "PLC_PRG", "symbol": "PLC_PRG_instance", "type": 7 }
+    ],
+    "types": [
+        { "name": "DINT", "size": 4, "kind": "Signed" },
+        { "name": "BOOL", "size": 1, "kind": "Bool" },
+        { "name": "INT", "size": 2, "kind": "Signed" },
+        { "name": "LREAL", "size": 8, "kind": "Float" },
+        { "name": "Motor", "size": 16, "kind": "Struct", "members": [
+            { "name": "speed", "offset": 0, "type": 2 },
+            { "name": "setpoint", "offset": 8, "type": 3, "access": "Read" }
+        ]},
+        { "name": "ARRAY[1..2, 0..2] OF Motor", "size": 96, "kind": "Array",
+          "dimensions": [[1, 2], [0, 2]], "element": 4, "stride": 16 },
+        { "name": "STRING[10]", "size": 11, "kind": "String", "wide": false },
+        { "name": "PLC_PRG", "size": 128, "kind": "Struct", "members": [
+            { "name": "on", "offset": 0, "type": 1 },
+            { "name": "motors", "offset": 8, "type": 5 },
+            { "name": "name", "offset": 104, "type": 6 },
+            { "name": "label", "offset": 116, "type": 8 }
+        ]},
+        { "name": "WSTRING[3]", "size": 8, "kind": "String", "wide": true }
+    ]
+}"#;
+
+#[repr(C)]
+#[derive(Default)]
+struct Motor {
+    speed: i16,
+    setpoint: f64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Program {
+    on: bool,
+    motors: [[Motor; 3]; 2],
+    name: [u8; 11],
+    label: [u16; 4],
+}
+
+/// the offset of `member` in `program`
+fn offset_of<T>(program: &Program, member: &T) -> usize {
+    member as *const T as usize - program as *const Program as usize
+}
+
+#[test]
+fn paths_are_resolved_to_their_offsets() {
+    let program = Program::default();
+    let table = SymbolTable::parse(SYMBOL_TABLE).unwrap();
+
+    let location = table.resolve("plc_prg.MOTORS[2,1].setpoint").unwrap();
+    assert_eq!(location.symbol, "PLC_PRG_instance");
+    assert_eq!(location.offset, offset_of(&program, &program.motors) + (3 + 1) * 16 + 8);
+    assert_eq!(location.data_type.name, "LREAL");
+    assert_eq!(location.access, Access::Read);
+
+    let location = table.resolve("PLC_PRG.name").unwrap();
+    assert_eq!(location.offset, offset_of(&program, &program.name));
+    assert_eq!(location.access, Access::ReadWrite);
+
+    let location = table.resolve("limit").unwrap();
+    assert_eq!((location.symbol, location.offset, location.access), ("limit", 0, Access::Read));
+}
+
+#[test]
+fn variables_are_read_and_written() {
+    let table = SymbolTable::parse(SYMBOL_TABLE).unwrap();
+    let mut program = Program::default();
+    program.motors[1][2].setpoint = 2.5;
+    let base = &mut program as *mut Program as *mut u8;
+    let lookup = |symbol: &str| (symbol == "PLC_PRG_instance").then_some(base);
+
+    let speed = unsafe { table.resolve_variable("PLC_PRG.motors[1,2].speed", lookup) }.unwrap();
+    speed.write(Value::Signed(-1500)).unwrap();
+    assert_eq!(speed.read().unwrap(), Value::Signed(-1500));
+    let setpoint = unsafe { table.resolve_variable("PLC_PRG.motors[2,2].setpoint", lookup) }.unwrap();
+    assert_eq!(setpoint.read().unwrap(), Value::Float(2.5));
+    let on = unsafe { table.resolve_variable("PLC_PRG.on", lookup) }.unwrap();
+    on.write(Value::Bool(true)).unwrap();
+    let name = unsafe { table.resolve_variable("PLC_PRG.name", lookup) }.unwrap();
+    name.write(Value::String("conveyor".to_string())).unwrap();
+    let label = unsafe { table.resolve_variable("PLC_PRG.label", lookup) }.unwrap();
+    label.write(Value::String("äb".to_string())).unwrap();
+    assert_eq!(label.read().unwrap(), Value::String("äb".to_string()));
+
+    assert_eq!(program.motors[0][2].speed, -1500);
+    assert!(program.on);
+    assert_eq!(&program.name[..9], b"conveyor\0");
+    assert_eq!(program.label[..3], [0xE4, 0x62, 0]);
+}
+
+#[test]
+fn invalid_accesses_are_reported() {
+    let table = SymbolTable::parse(SYMBOL_TABLE).unwrap();
+    let mut program = Program::default();
+    let base = &mut program as *mut Program as *mut u8;
+    let lookup = |symbol: &str| (symbol == "PLC_PRG_instance").then_some(base);
+    let variable = |path: &str| unsafe { table.resolve_variable(path, lookup) }.unwrap();
+
+    let error = |result: Result<(), Error>| result.unwrap_err().to_string();
+    assert_eq!(
+        error(variable("PLC_PRG.motors[1,0].setpoint").write(Value::Float(1.0))),
+        "The variable cannot be written"
+    );
+    assert_eq!(
+        error(variable("PLC_PRG.motors[1,0].speed").write(Value::Signed(40_000))),
+        "Signed(40000) cannot be assigned to a variable of type INT"
+    );
+    assert_eq!(
+        error(variable("PLC_PRG.on").write(Value::Unsigned(1))),
+        "Unsigned(1) cannot be assigned to a variable of type BOOL"
+    );
+    assert_eq!(
+        error(variable("PLC_PRG.name").write(Value::String("a long conveyor".to_string()))),
+        r#"String("a long conveyor") cannot be assigned to a variable of type STRING[10]"#
+    );
+    assert_eq!(
+        variable("PLC_PRG.motors[1,0]").read().unwrap_err().to_string(),
+        "Variables of type Motor cannot be read or written"
+    );
+    assert_eq!(
+        unsafe { table.resolve_variable("limit", lookup) }.unwrap_err().to_string(),
+        "Unknown symbol limit"
+    );
+}
+
+#[test]
+fn unresolvable_paths_are_reported() {
+    let table = SymbolTable::parse(SYMBOL_TABLE).unwrap();
+    let error = |path: &str| table.resolve(path).unwrap_err().to_string();
+
+    assert_eq!(error("main.on"), "Unknown variable main");
+    assert_eq!(error("PLC_PRG.off"), "PLC_PRG has no member off");
+    assert_eq!(error("PLC_PRG.on.x"), "PLC_PRG.on has no member x");
+    assert_eq!(error("PLC_PRG.on[1]"), "PLC_PRG.on is not an array");
+    assert_eq!(error("PLC_PRG.motors[1]"), "PLC_PRG.motors expects 2 indexes");
+    assert_eq!(error("PLC_PRG.motors[3,0]"), "Index [3, 0] is out of the bounds of PLC_PRG.motors");
+    assert_eq!(error("PLC_PRG.motors[1,2].x"), "PLC_PRG.motors[1,2] has no member x");
+    assert_eq!(error("PLC_PRG..on"), "Invalid path PLC_PRG..on");
+}
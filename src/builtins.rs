@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use inkwell::{
     basic_block::BasicBlock,
     types::BasicType,
-    values::{BasicValue, IntValue},
+    values::{BasicValue, BasicValueEnum, IntValue},
+    AddressSpace, FloatPredicate, IntPredicate,
 };
 use lazy_static::lazy_static;
 use plc_ast::{
@@ -17,16 +18,21 @@ use plc_ast::{
 use plc_diagnostics::diagnostics::Diagnostic;
 
 use crate::{
-    codegen::generators::expression_generator::{self, ExpressionCodeGenerator, ExpressionValue},
-    index::Index,
+    codegen::generators::{
+        expression_generator::{self, ExpressionCodeGenerator, ExpressionValue},
+        ADDRESS_SPACE_GENERIC,
+    },
+    dynamic_memory,
+    index::{self, Index},
     lexer, parser,
     resolver::{
         self,
         generics::{no_generic_name_resolver, GenericType},
         AnnotationMap, StatementAnnotation, TypeAnnotator, VisitorContext,
     },
+    testing,
     typesystem::{self, get_literal_actual_signed_type_name},
-    validation::{Validator, Validators},
+    validation::{self, Validator, Validators},
 };
 
 // Defines a set of functions that are always included in a compiled application
@@ -322,9 +328,484 @@ lazy_static! {
                 }
             }
         ),
+        (
+            "__NEW",
+            BuiltIn {
+                decl: "FUNCTION __NEW<U: ANY> : LWORD
+                VAR_INPUT
+                    data_type : U;
+                    count : LINT;
+                END_VAR
+                END_FUNCTION",
+                annotation: Some(|annotator, operator, parameters, _| {
+                    annotate_new(annotator, operator, parameters)
+                }),
+                validation: Some(|validator, operator, parameters, annotations, index| {
+                    validate_new(validator, operator, parameters, annotations, index)
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    generate_new(generator, params, location)
+                }
+            }
+        ),
+        (
+            "__DELETE",
+            BuiltIn {
+                decl: "FUNCTION __DELETE<U: ANY> : BOOL
+                VAR_IN_OUT
+                    ptr : U;
+                END_VAR
+                END_FUNCTION",
+                annotation: Some(|annotator, operator, _, _| {
+                    annotator.annotate(
+                        operator, StatementAnnotation::Function {
+                            return_type: typesystem::BOOL_TYPE.to_string(), qualified_name: "__DELETE".to_string(), call_name: None
+                        }
+                    );
+                }),
+                validation: Some(|validator, operator, parameters, annotations, index| {
+                    validate_delete(validator, operator, parameters, annotations, index)
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    generate_delete(generator, params, location)
+                }
+            }
+        ),
+        (
+            "__ISVALIDREF",
+            BuiltIn {
+                decl: "FUNCTION __ISVALIDREF<U: ANY> : BOOL
+                VAR_INPUT
+                    in : U;
+                END_VAR
+                END_FUNCTION",
+                annotation: Some(|annotator, operator, _, _| {
+                    annotator.annotate(
+                        operator, StatementAnnotation::Function {
+                            return_type: typesystem::BOOL_TYPE.to_string(), qualified_name: "__ISVALIDREF".to_string(), call_name: None
+                        }
+                    );
+                }),
+                validation: Some(|validator, operator, parameters, annotations, index| {
+                    validate_is_valid_ref(validator, operator, parameters, annotations, index)
+                }),
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    generate_is_valid_ref(generator, params, location)
+                }
+            }
+        ),
+        (
+            "ASSERT_TRUE",
+            BuiltIn {
+                decl: "FUNCTION ASSERT_TRUE : BOOL
+                VAR_INPUT
+                    in : BOOL;
+                END_VAR
+                END_FUNCTION",
+                annotation: None,
+                validation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    if let [condition] = params {
+                        let value = generator.generate_expression(condition)?.into_int_value();
+                        let value = expression_generator::to_i1(value, &generator.llvm.builder);
+                        generate_assertion(generator, condition, value, "ASSERT_TRUE failed", location)
+                    } else {
+                        Err(Diagnostic::codegen_error("Expected exactly one parameter for ASSERT_TRUE", location))
+                    }
+                }
+            }
+        ),
+        (
+            "ASSERT_FALSE",
+            BuiltIn {
+                decl: "FUNCTION ASSERT_FALSE : BOOL
+                VAR_INPUT
+                    in : BOOL;
+                END_VAR
+                END_FUNCTION",
+                annotation: None,
+                validation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    if let [condition] = params {
+                        let value = generator.generate_expression(condition)?.into_int_value();
+                        let value = expression_generator::to_i1(value, &generator.llvm.builder);
+                        let value = generator.llvm.builder.build_not(value, "");
+                        generate_assertion(generator, condition, value, "ASSERT_FALSE failed", location)
+                    } else {
+                        Err(Diagnostic::codegen_error("Expected exactly one parameter for ASSERT_FALSE", location))
+                    }
+                }
+            }
+        ),
+        (
+            "ASSERT_EQUAL",
+            BuiltIn {
+                decl: "FUNCTION ASSERT_EQUAL<U: ANY_ELEMENTARY> : BOOL
+                VAR_INPUT
+                    expected : U;
+                    actual : U;
+                END_VAR
+                END_FUNCTION",
+                annotation: None,
+                validation: None,
+                generic_name_resolver: no_generic_name_resolver,
+                code : |generator, params, location| {
+                    if let &[expected, actual] = params {
+                        let builder = &generator.llvm.builder;
+                        // both values are cast to the resolved generic type
+                        let value = match (generator.generate_expression(expected)?, generator.generate_expression(actual)?) {
+                            (BasicValueEnum::IntValue(expected), BasicValueEnum::IntValue(actual)) => {
+                                builder.build_int_compare(IntPredicate::EQ, expected, actual, "")
+                            }
+                            (BasicValueEnum::FloatValue(expected), BasicValueEnum::FloatValue(actual)) => {
+                                builder.build_float_compare(FloatPredicate::OEQ, expected, actual, "")
+                            }
+                            _ => {
+                                let type_name = generator.get_type_hint_for(expected)?.get_name();
+                                return Err(Diagnostic::codegen_error(
+                                    &format!("ASSERT_EQUAL cannot compare values of type {type_name}"),
+                                    location,
+                                ));
+                            }
+                        };
+                        generate_assertion(
+                            generator,
+                            expected,
+                            value,
+                            "ASSERT_EQUAL failed, the actual value does not match the expected value",
+                            location,
+                        )
+                    } else {
+                        Err(Diagnostic::codegen_error("Expected exactly two parameters for ASSERT_EQUAL", location))
+                    }
+                }
+            }
+        ),
     ]);
 }
 
+/// Generates a call to the assertion runtime (see [`testing::ASSERT_FAILED_FUNCTION`]) that is executed
+/// if the given condition is false. The condition is returned as the BOOL result of the assertion.
+fn generate_assertion<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    statement: &AstStatement,
+    condition: IntValue<'ink>,
+    message: &str,
+    location: SourceRange,
+) -> Result<ExpressionValue<'ink>, Diagnostic> {
+    let context = generator.llvm.context;
+    let builder = &generator.llvm.builder;
+    let function_context = generator.get_function_context(statement)?;
+    let assert_failed = generator
+        .llvm_index
+        .find_associated_implementation(testing::ASSERT_FAILED_FUNCTION)
+        .ok_or_else(|| Diagnostic::codegen_error("Could not find the assertion runtime", location.clone()))?;
+
+    let failed_block = context.append_basic_block(function_context.function, "assertion_failed");
+    let continue_block = context.append_basic_block(function_context.function, "continue");
+    builder.build_conditional_branch(condition, continue_block, failed_block);
+
+    builder.position_at_end(failed_block);
+    let message = builder.build_global_string_ptr(message, "assertion_message");
+    let file =
+        builder.build_global_string_ptr(location.get_file_name().unwrap_or("<internal>"), "assertion_file");
+    let line = function_context.new_lines.get_line_nr(location.get_start()) + 1;
+    builder.build_call(
+        assert_failed,
+        &[
+            message.as_pointer_value().into(),
+            file.as_pointer_value().into(),
+            context.i32_type().const_int(line as u64, false).into(),
+        ],
+        "",
+    );
+    builder.build_unconditional_branch(continue_block);
+
+    builder.position_at_end(continue_block);
+    let bool_type = generator.llvm_index.get_associated_type(typesystem::BOOL_TYPE)?.into_int_type();
+    Ok(ExpressionValue::RValue(builder.build_int_z_extend(condition, bool_type, "").as_basic_value_enum()))
+}
+
+/// Annotates `__NEW(type)` with a pointer to the requested type
+fn annotate_new(annotator: &mut TypeAnnotator, operator: &AstStatement, parameters: Option<&AstStatement>) {
+    // invalid parameters are reported during validation
+    let Some(parameters) = parameters else { return };
+    let params = flatten_expression_list(parameters);
+    let Some(StatementAnnotation::Type { type_name }) =
+        params.get(0).and_then(|it| annotator.annotation_map.get(it)).cloned()
+    else {
+        return;
+    };
+    if let Some(count) = params.get(1) {
+        annotator.annotation_map.annotate_type_hint(count, StatementAnnotation::value(typesystem::LINT_TYPE));
+    }
+
+    let ptr_type = resolver::add_pointer_type(&mut annotator.annotation_map.new_index, type_name);
+    annotator.annotate(
+        operator,
+        StatementAnnotation::Function {
+            return_type: ptr_type,
+            qualified_name: "__NEW".to_string(),
+            call_name: None,
+        },
+    );
+}
+
+fn validate_dynamic_memory_allowed(validator: &mut Validator, operator: &AstStatement) {
+    if !validator.get_options().allow_dynamic_memory {
+        validator.push_diagnostic(Diagnostic::invalid_dynamic_memory(
+            "dynamic memory is disabled for this build",
+            operator.get_location(),
+        ));
+    }
+}
+
+fn validate_new(
+    validator: &mut Validator,
+    operator: &AstStatement,
+    parameters: &Option<AstStatement>,
+    annotations: &dyn AnnotationMap,
+    index: &Index,
+) {
+    validate_dynamic_memory_allowed(validator, operator);
+    let Some(parameters) = parameters else {
+        validator.push_diagnostic(Diagnostic::invalid_parameter_count(1, 0, operator.get_location()));
+        return;
+    };
+
+    let params = flatten_expression_list(parameters);
+    if params.len() > 2 {
+        validator.push_diagnostic(Diagnostic::invalid_parameter_count(
+            2,
+            params.len(),
+            operator.get_location(),
+        ));
+    }
+
+    if let Some(type_reference) = params.get(0) {
+        if !matches!(annotations.get(type_reference), Some(StatementAnnotation::Type { .. })) {
+            validator.push_diagnostic(Diagnostic::invalid_dynamic_memory(
+                "__NEW expects a data type as its first parameter",
+                type_reference.get_location(),
+            ));
+        }
+    }
+
+    if let Some(count) = params.get(1) {
+        let count_type = annotations.get_type_or_void(count, index);
+        if !count_type.has_nature(TypeNature::Int, index) {
+            validator.push_diagnostic(Diagnostic::invalid_type_nature(
+                count_type.get_name(),
+                &format!("{:?}", TypeNature::Int),
+                count.get_location(),
+            ));
+        }
+    }
+}
+
+fn validate_delete(
+    validator: &mut Validator,
+    operator: &AstStatement,
+    parameters: &Option<AstStatement>,
+    annotations: &dyn AnnotationMap,
+    index: &Index,
+) {
+    validate_dynamic_memory_allowed(validator, operator);
+    let Some(parameters) = parameters else {
+        validator.push_diagnostic(Diagnostic::invalid_parameter_count(1, 0, operator.get_location()));
+        return;
+    };
+
+    let params = flatten_expression_list(parameters);
+    if params.len() > 1 {
+        validator.push_diagnostic(Diagnostic::invalid_parameter_count(
+            1,
+            params.len(),
+            operator.get_location(),
+        ));
+    }
+
+    if let Some(pointer) = params.get(0) {
+        let pointer_type = annotations.get_type_or_void(pointer, index);
+        if !pointer_type.get_type_information().is_pointer() {
+            validator.push_diagnostic(Diagnostic::invalid_dynamic_memory(
+                &format!("__DELETE expects a pointer, found {}", pointer_type.get_name()),
+                pointer.get_location(),
+            ));
+        }
+    }
+}
+
+/// Generates the code for `__NEW(type)` and `__NEW(type, count)`. The memory is requested from the
+/// allocator runtime (see [`dynamic_memory::ALLOC_FUNCTION`]) and every element is initialized like a
+/// variable of the requested type. The result is a pointer to the first element or null if the
+/// allocation failed.
+fn generate_new<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    params: &[&AstStatement],
+    location: SourceRange,
+) -> Result<ExpressionValue<'ink>, Diagnostic> {
+    let (type_reference, count) = match params {
+        [type_reference] => (*type_reference, None),
+        [type_reference, count] => (*type_reference, Some(*count)),
+        _ => return Err(Diagnostic::codegen_error("Expected one or two parameters for __NEW", location)),
+    };
+    let Some(StatementAnnotation::Type { type_name }) = generator.annotations.get(type_reference) else {
+        return Err(Diagnostic::codegen_error(
+            "Expected a data type as the first parameter of __NEW",
+            location,
+        ));
+    };
+
+    let llvm = generator.llvm;
+    let builder = &llvm.builder;
+    let size_type = llvm.context.i64_type();
+    let element_type = generator.llvm_index.get_associated_type(type_name)?;
+    let element_size = element_type
+        .size_of()
+        .ok_or_else(|| Diagnostic::codegen_error("Parameter type is not sized.", location.clone()))?;
+    let count = match count {
+        Some(count) => {
+            builder.build_int_cast(generator.generate_expression(count)?.into_int_value(), size_type, "")
+        }
+        None => size_type.const_int(1, false),
+    };
+
+    let alloc = generator
+        .llvm_index
+        .find_associated_implementation(dynamic_memory::ALLOC_FUNCTION)
+        .ok_or_else(|| Diagnostic::codegen_error("Could not find the allocator runtime", location.clone()))?;
+    let size = builder.build_int_mul(element_size, count, "");
+    let memory =
+        builder.build_call(alloc, &[size.into()], "").try_as_basic_value().left().ok_or_else(|| {
+            Diagnostic::codegen_error("The allocator runtime does not return memory", location.clone())
+        })?;
+    let pointer = builder.build_pointer_cast(
+        memory.into_pointer_value(),
+        element_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)),
+        "",
+    );
+
+    // the allocated memory is zeroed, only types with an initial value need to be initialized
+    let initializer = generator.llvm_index.find_global_value(&index::get_initializer_name(type_name));
+    let initial_value = generator.llvm_index.find_associated_initial_value(type_name);
+    if initializer.is_none() && initial_value.is_none() {
+        return Ok(ExpressionValue::RValue(pointer.as_basic_value_enum()));
+    }
+
+    let context = llvm.context;
+    let function_context = generator.get_function_context(type_reference)?;
+    let insert_block = builder.get_insert_block().expect("Builder should have a block at this point");
+    let init_block = context.append_basic_block(function_context.function, "init");
+    let continue_block = context.append_basic_block(function_context.function, "continue");
+    let has_elements = builder.build_and(
+        builder.build_is_not_null(pointer, ""),
+        builder.build_int_compare(IntPredicate::SGT, count, size_type.const_zero(), ""),
+        "",
+    );
+    builder.build_conditional_branch(has_elements, init_block, continue_block);
+
+    // initialize the elements one after the other
+    builder.position_at_end(init_block);
+    let element_index = builder.build_phi(size_type, "");
+    let current_index = element_index.as_basic_value().into_int_value();
+    let element = unsafe { builder.build_in_bounds_gep(pointer, &[current_index], "") };
+    if let Some(initializer) = initializer {
+        // function blocks and structs are copied from their global initializer
+        let alignment = std::cmp::max(1, initializer.get_alignment());
+        builder
+            .build_memcpy(element, alignment, initializer.as_pointer_value(), alignment, element_size)
+            .map_err(|err| Diagnostic::codegen_error(err, location.clone()))?;
+    } else if let Some(initial_value) = initial_value {
+        builder.build_store(element, initial_value);
+    }
+    let next_index = builder.build_int_add(current_index, size_type.const_int(1, false), "");
+    let has_next = builder.build_int_compare(IntPredicate::SLT, next_index, count, "");
+    builder.build_conditional_branch(has_next, init_block, continue_block);
+    element_index.add_incoming(&[(&size_type.const_zero(), insert_block), (&next_index, init_block)]);
+
+    builder.position_at_end(continue_block);
+    Ok(ExpressionValue::RValue(pointer.as_basic_value_enum()))
+}
+
+/// Generates the code for `__DELETE(ptr)`, the memory is returned to the allocator runtime (see
+/// [`dynamic_memory::FREE_FUNCTION`]) and the pointer is set to null. The result is TRUE if the pointer
+/// was not null.
+fn generate_delete<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    params: &[&AstStatement],
+    location: SourceRange,
+) -> Result<ExpressionValue<'ink>, Diagnostic> {
+    let [pointer] = params else {
+        return Err(Diagnostic::codegen_error("Expected exactly one parameter for __DELETE", location));
+    };
+
+    let llvm = generator.llvm;
+    let builder = &llvm.builder;
+    let free = generator
+        .llvm_index
+        .find_associated_implementation(dynamic_memory::FREE_FUNCTION)
+        .ok_or_else(|| Diagnostic::codegen_error("Could not find the allocator runtime", location.clone()))?;
+    let pointer_location = generator.generate_lvalue(pointer)?;
+    let memory = builder.build_load(pointer_location, "").into_pointer_value();
+    let i8_ptr_type = llvm.context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+    builder.build_call(free, &[builder.build_pointer_cast(memory, i8_ptr_type, "").into()], "");
+
+    builder.build_store(pointer_location, memory.get_type().const_null());
+
+    let bool_type = generator.llvm_index.get_associated_type(typesystem::BOOL_TYPE)?.into_int_type();
+    let released = builder.build_is_not_null(memory, "");
+    Ok(ExpressionValue::RValue(builder.build_int_z_extend(released, bool_type, "").as_basic_value_enum()))
+}
+
+fn validate_is_valid_ref(
+    validator: &mut Validator,
+    operator: &AstStatement,
+    parameters: &Option<AstStatement>,
+    annotations: &dyn AnnotationMap,
+    index: &Index,
+) {
+    let params = parameters.as_ref().map(flatten_expression_list).unwrap_or_default();
+    let [reference] = params.as_slice() else {
+        validator.push_diagnostic(Diagnostic::invalid_parameter_count(
+            1,
+            params.len(),
+            operator.get_location(),
+        ));
+        return;
+    };
+
+    if validation::find_reference_inner_type(reference, annotations, index).is_none() {
+        validator.push_diagnostic(Diagnostic::invalid_reference_binding(
+            "__ISVALIDREF expects a REFERENCE TO variable",
+            reference.get_location(),
+        ));
+    }
+}
+
+/// Generates the code for `__ISVALIDREF(ref)`, the result is TRUE if the reference is bound
+fn generate_is_valid_ref<'ink>(
+    generator: &ExpressionCodeGenerator<'ink, '_>,
+    params: &[&AstStatement],
+    location: SourceRange,
+) -> Result<ExpressionValue<'ink>, Diagnostic> {
+    let [reference] = params else {
+        return Err(Diagnostic::codegen_error("Expected exactly one parameter for __ISVALIDREF", location));
+    };
+
+    let builder = &generator.llvm.builder;
+    let pointer_location = generator.generate_lvalue_without_auto_deref(reference)?;
+    let pointer = builder.build_load(pointer_location, "").into_pointer_value();
+    let bool_type = generator.llvm_index.get_associated_type(typesystem::BOOL_TYPE)?.into_int_type();
+    let is_bound = builder.build_is_not_null(pointer, "");
+    Ok(ExpressionValue::RValue(builder.build_int_z_extend(is_bound, bool_type, "").as_basic_value_enum()))
+}
+
 fn annotate_variable_length_array_bound_function(
     annotator: &mut TypeAnnotator,
     parameters: Option<&AstStatement>,
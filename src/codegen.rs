@@ -7,6 +7,8 @@ use std::{
 
 /// module to generate llvm intermediate representation for a CompilationUnit
 use self::{
+    coverage::CoverageInstrumentation,
+    cycle_monitor::CycleMonitor,
     debug::{Debug, DebugBuilderEnum},
     generators::{
         data_type_generator,
@@ -15,6 +17,7 @@ use self::{
         variable_generator::VariableGenerator,
     },
     llvm_index::LlvmTypedIndex,
+    trace::TraceInstrumentation,
 };
 use crate::{
     output::FormatOption,
@@ -38,12 +41,15 @@ use inkwell::{
 use plc_ast::ast::{CompilationUnit, LinkageType, SourceRange};
 use plc_diagnostics::diagnostics::Diagnostic;
 
+mod coverage;
+mod cycle_monitor;
 mod debug;
 pub(crate) mod generators;
 mod llvm_index;
 mod llvm_typesystem;
 #[cfg(test)]
 mod tests;
+mod trace;
 
 /// A wrapper around the LLVM context to allow passing it without exposing the inkwell dependencies
 pub struct CodegenContext(Context);
@@ -71,6 +77,12 @@ pub struct CodeGen<'ink> {
     pub debug: DebugBuilderEnum<'ink>,
 
     pub module_location: String,
+    /// instrument statements and branches with coverage counters
+    pub instrument_coverage: bool,
+    /// measure the execution time of every program
+    pub monitor_cycles: bool,
+    /// record the traced variables of every program
+    pub trace: bool,
 }
 
 pub struct GeneratedModule<'ink> {
@@ -93,7 +105,32 @@ impl<'ink> CodeGen<'ink> {
         let module = context.create_module(module_location);
         module.set_source_file_name(module_location);
         let debug = debug::DebugBuilderEnum::new(context, &module, root, optimization_level, debug_level);
-        CodeGen { module, debug, module_location: module_location.to_string() }
+        CodeGen {
+            module,
+            debug,
+            module_location: module_location.to_string(),
+            instrument_coverage: false,
+            monitor_cycles: false,
+            trace: false,
+        }
+    }
+
+    /// enables or disables the coverage instrumentation of the generated code
+    pub fn with_coverage(mut self, instrument_coverage: bool) -> Self {
+        self.instrument_coverage = instrument_coverage;
+        self
+    }
+
+    /// enables or disables the cycle time monitoring of the generated programs
+    pub fn with_cycle_monitor(mut self, monitor_cycles: bool) -> Self {
+        self.monitor_cycles = monitor_cycles;
+        self
+    }
+
+    /// enables or disables the tracing of the variables marked with `{attribute 'trace'}`
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
     }
 
     pub fn generate_llvm_index(
@@ -196,6 +233,14 @@ impl<'ink> CodeGen<'ink> {
     ) -> Result<GeneratedModule<'ink>, Diagnostic> {
         //generate all pous
         let llvm = Llvm::new(context, context.create_builder());
+        let coverage = self
+            .instrument_coverage
+            .then(|| CoverageInstrumentation::new(&llvm, &self.module, unit))
+            .flatten();
+        let cycle_monitor =
+            self.monitor_cycles.then(|| CycleMonitor::new(&llvm, &self.module, unit, global_index)).flatten();
+        let trace =
+            self.trace.then(|| TraceInstrumentation::new(&llvm, &self.module, unit, global_index)).flatten();
         let pou_generator = PouGenerator::new(llvm, global_index, annotations, llvm_index);
 
         //Generate the POU stubs in the first go to make sure they can be referenced.
@@ -203,11 +248,22 @@ impl<'ink> CodeGen<'ink> {
             //Don't generate external or generic functions
             if let Some(entry) = global_index.find_pou(implementation.name.as_str()) {
                 if !entry.is_generic() && entry.get_linkage() != &LinkageType::External {
-                    pou_generator.generate_implementation(implementation, &self.debug, &unit.new_lines)?;
+                    pou_generator.generate_implementation(
+                        implementation,
+                        &self.debug,
+                        coverage.as_ref(),
+                        cycle_monitor.as_ref(),
+                        trace.as_ref(),
+                        &unit.new_lines,
+                    )?;
                 }
             }
         }
 
+        if let Some(coverage) = &coverage {
+            coverage.finalize(&Llvm::new(context, context.create_builder()), &self.module);
+        }
+
         self.debug.finalize();
         log::debug!("{}", self.module.to_string());
 
@@ -469,6 +525,19 @@ impl<'ink> GeneratedModule<'ink> {
         }
     }
 
+    ///
+    /// Makes the symbols of the given shared library available to the functions run inside the
+    /// compiled module
+    ///
+    pub fn load_library(&self, path: &Path) -> Result<(), Diagnostic> {
+        // returns true on failure
+        if inkwell::support::load_library_permanently(&path.to_string_lossy()) {
+            Err(Diagnostic::io_read_error(&path.to_string_lossy(), "Could not load library"))
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn add_global_function_mapping(&self, function_name: &str, local_function: usize) {
         let engine = self.get_execution_engine();
         if let Some(function) = self.module.get_function(function_name) {
@@ -0,0 +1,129 @@
+use inkwell::{
+    module::{Linkage, Module},
+    values::{FunctionValue, GlobalValue},
+    AddressSpace,
+};
+use plc_ast::ast::{AstStatement, CompilationUnit};
+
+use super::generators::{llvm::Llvm, ADDRESS_SPACE_GENERIC};
+use crate::coverage::{CoverageMap, COVERAGE_REGISTER_FUNCTION};
+
+/// Inserts the counters described by the unit's [`CoverageMap`] into the generated code.
+/// The counters are stored in a private global array that is registered with the coverage runtime
+/// by a module constructor.
+pub struct CoverageInstrumentation<'ink> {
+    map: CoverageMap,
+    counters: GlobalValue<'ink>,
+}
+
+impl<'ink> CoverageInstrumentation<'ink> {
+    /// creates the counters for the given unit, returns `None` if the unit contains no statements
+    pub fn new(llvm: &Llvm<'ink>, module: &Module<'ink>, unit: &CompilationUnit) -> Option<Self> {
+        let map = CoverageMap::new(unit);
+        if map.is_empty() {
+            return None;
+        }
+        let counters_type = llvm.context.i64_type().array_type(map.len() as u32);
+        let counters = module.add_global(counters_type, None, "__plc_coverage_counters");
+        counters.set_initializer(&counters_type.const_zero());
+        counters.set_linkage(Linkage::Private);
+        Some(CoverageInstrumentation { map, counters })
+    }
+
+    /// increments the statement's counter at the builder's current position
+    pub fn count_statement(&self, llvm: &Llvm<'ink>, statement: &AstStatement) {
+        if let Some(counter) = self.map.get_statement_counter(statement) {
+            self.increment(llvm, counter);
+        }
+    }
+
+    /// increments the counter of the branch guarded by the given condition
+    pub fn count_branch(&self, llvm: &Llvm<'ink>, condition: &AstStatement) {
+        if let Some(counter) = self.map.get_branch_counter(condition) {
+            self.increment(llvm, counter);
+        }
+    }
+
+    /// increments the counter of the given ELSE block
+    pub fn count_else(&self, llvm: &Llvm<'ink>, else_block: &[AstStatement]) {
+        if let Some(counter) = self.map.get_else_counter(else_block) {
+            self.increment(llvm, counter);
+        }
+    }
+
+    fn increment(&self, llvm: &Llvm<'ink>, counter: usize) {
+        let i32_type = llvm.context.i32_type();
+        let i64_type = llvm.context.i64_type();
+        let counter_ptr = unsafe {
+            llvm.builder.build_in_bounds_gep(
+                self.counters.as_pointer_value(),
+                &[i32_type.const_zero(), i32_type.const_int(counter as u64, false)],
+                "coverage_counter",
+            )
+        };
+        let count = llvm.builder.build_load(counter_ptr, "coverage_count").into_int_value();
+        let count = llvm.builder.build_int_add(count, i64_type.const_int(1, false), "coverage_count");
+        llvm.builder.build_store(counter_ptr, count);
+    }
+
+    /// generates the module constructor registering the counters with the coverage runtime
+    /// `__plc_coverage_register(module: *const i8, counters: *mut i64, len: i64)`
+    pub fn finalize(&self, llvm: &Llvm<'ink>, module: &Module<'ink>) {
+        let context = llvm.context;
+        let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+        let i64_type = context.i64_type();
+        let void_type = context.void_type();
+
+        let register = module.get_function(COVERAGE_REGISTER_FUNCTION).unwrap_or_else(|| {
+            let register_type = void_type.fn_type(
+                &[
+                    i8_ptr_type.into(),
+                    i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)).into(),
+                    i64_type.into(),
+                ],
+                false,
+            );
+            module.add_function(COVERAGE_REGISTER_FUNCTION, register_type, None)
+        });
+
+        let constructor =
+            module.add_function("__plc_coverage_init", void_type.fn_type(&[], false), Some(Linkage::Private));
+        let block = context.append_basic_block(constructor, "entry");
+        llvm.builder.position_at_end(block);
+        let module_name = llvm.builder.build_global_string_ptr(&self.map.file_name, "coverage_module_name");
+        let counters = llvm.builder.build_pointer_cast(
+            self.counters.as_pointer_value(),
+            i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)),
+            "coverage_counters",
+        );
+        llvm.builder.build_call(
+            register,
+            &[
+                module_name.as_pointer_value().into(),
+                counters.into(),
+                i64_type.const_int(self.map.len() as u64, false).into(),
+            ],
+            "",
+        );
+        llvm.builder.build_return(None);
+
+        register_constructor(llvm, module, constructor);
+    }
+}
+
+/// appends the given function to the module's `llvm.global_ctors`
+fn register_constructor<'ink>(llvm: &Llvm<'ink>, module: &Module<'ink>, constructor: FunctionValue<'ink>) {
+    let context = llvm.context;
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+    let constructor_ptr_type = constructor.get_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+    let entry_type = context
+        .struct_type(&[context.i32_type().into(), constructor_ptr_type.into(), i8_ptr_type.into()], false);
+    let entry = entry_type.const_named_struct(&[
+        context.i32_type().const_int(65535, false).into(),
+        constructor.as_global_value().as_pointer_value().into(),
+        i8_ptr_type.const_null().into(),
+    ]);
+    let constructors = module.add_global(entry_type.array_type(1), None, "llvm.global_ctors");
+    constructors.set_linkage(Linkage::Appending);
+    constructors.set_initializer(&entry_type.const_array(&[entry]));
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use inkwell::{
+    module::Module,
+    values::{FunctionValue, GlobalValue, IntValue, PointerValue},
+    AddressSpace,
+};
+use plc_ast::ast::{CompilationUnit, LinkageType, PouType};
+
+use super::generators::{llvm::Llvm, ADDRESS_SPACE_GENERIC};
+use crate::{
+    cycle_monitor::{
+        get_budget, get_statistics_name, CYCLE_BEGIN_FUNCTION, CYCLE_BUDGET_FIELD, CYCLE_END_FUNCTION,
+        CYCLE_STATISTICS_FIELDS,
+    },
+    index::Index,
+};
+
+/// Wraps the bodies of the unit's programs with calls to the cycle time runtime (see [`crate::cycle_monitor`]).
+/// The statistics of every program are stored in an exported global of 64 bit fields.
+pub struct CycleMonitor<'ink> {
+    statistics: HashMap<String, GlobalValue<'ink>>,
+    begin: FunctionValue<'ink>,
+    end: FunctionValue<'ink>,
+}
+
+/// The time a program was entered, reported before every return of the program
+#[derive(Clone, Copy)]
+pub struct CycleStart<'ink> {
+    program: PointerValue<'ink>,
+    statistics: PointerValue<'ink>,
+    start: IntValue<'ink>,
+    end: FunctionValue<'ink>,
+}
+
+impl<'ink> CycleMonitor<'ink> {
+    /// creates the statistics of the unit's programs, returns `None` if the unit contains no programs
+    pub fn new(
+        llvm: &Llvm<'ink>,
+        module: &Module<'ink>,
+        unit: &CompilationUnit,
+        index: &Index,
+    ) -> Option<Self> {
+        let programs = unit
+            .implementations
+            .iter()
+            .filter(|it| it.pou_type == PouType::Program && it.linkage != LinkageType::External)
+            .collect::<Vec<_>>();
+        if programs.is_empty() {
+            return None;
+        }
+        let context = llvm.context;
+        let i64_type = context.i64_type();
+        let statistics_type = i64_type.array_type(CYCLE_STATISTICS_FIELDS);
+        let statistics = programs
+            .into_iter()
+            .map(|program| {
+                // invalid budgets are reported by the validation
+                let budget = index
+                    .find_pou(&program.name)
+                    .and_then(|it| get_budget(it.get_attributes()))
+                    .and_then(Result::ok)
+                    .unwrap_or_default();
+                let mut fields = vec![i64_type.const_zero(); CYCLE_STATISTICS_FIELDS as usize];
+                fields[CYCLE_BUDGET_FIELD as usize] = i64_type.const_int(budget as u64, true);
+                let global = module.add_global(statistics_type, None, &get_statistics_name(&program.name));
+                global.set_initializer(&i64_type.const_array(&fields));
+                (program.name.to_lowercase(), global)
+            })
+            .collect();
+
+        let begin = module
+            .get_function(CYCLE_BEGIN_FUNCTION)
+            .unwrap_or_else(|| module.add_function(CYCLE_BEGIN_FUNCTION, i64_type.fn_type(&[], false), None));
+        let end = module.get_function(CYCLE_END_FUNCTION).unwrap_or_else(|| {
+            let end_type = context.void_type().fn_type(
+                &[
+                    context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)).into(),
+                    i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)).into(),
+                    i64_type.into(),
+                ],
+                false,
+            );
+            module.add_function(CYCLE_END_FUNCTION, end_type, None)
+        });
+        Some(CycleMonitor { statistics, begin, end })
+    }
+
+    /// takes the start time of the given program at the builder's current position,
+    /// returns `None` if the POU is not a monitored program
+    pub fn begin(&self, llvm: &Llvm<'ink>, program: &str) -> Option<CycleStart<'ink>> {
+        let statistics = self.statistics.get(&program.to_lowercase())?;
+        let i64_type = llvm.context.i64_type();
+        let name = llvm.builder.build_global_string_ptr(program, "cycle_program");
+        let statistics = llvm.builder.build_pointer_cast(
+            statistics.as_pointer_value(),
+            i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)),
+            "cycle_statistics",
+        );
+        let start = llvm.builder.build_call(self.begin, &[], "cycle_start").try_as_basic_value().left()?;
+        Some(CycleStart {
+            program: name.as_pointer_value(),
+            statistics,
+            start: start.into_int_value(),
+            end: self.end,
+        })
+    }
+}
+
+impl<'ink> CycleStart<'ink> {
+    /// reports the program's start time to the runtime at the builder's current position
+    pub fn end(&self, llvm: &Llvm<'ink>) {
+        llvm.builder.build_call(
+            self.end,
+            &[self.program.into(), self.statistics.into(), self.start.into()],
+            "",
+        );
+    }
+}
@@ -250,6 +250,7 @@ impl<'ink> DebugBuilder<'ink> {
         &mut self,
         name: &str,
         members: &[VariableIndexEntry],
+        is_union: bool,
         index: &Index,
         location: &SymbolLocation,
     ) -> Result<(), Diagnostic> {
@@ -276,6 +277,10 @@ impl<'ink> DebugBuilder<'ink> {
             let type_info = dt.get_type_information();
             let alignment = type_info.get_alignment(index);
             let size = type_info.get_size(index);
+            // all members of a union start at offset 0
+            if is_union {
+                running_offset = MemoryLocation::new(0);
+            }
             running_offset = running_offset.align_to(alignment);
             types.push(
                 self.debug_info
@@ -297,6 +302,23 @@ impl<'ink> DebugBuilder<'ink> {
 
         let struct_dt = index.get_type_information_or_void(name);
 
+        if is_union {
+            let union_type = self.debug_info.create_union_type(
+                file.as_debug_info_scope(),
+                name,
+                file,
+                location.line_number.wrapping_add(1),
+                struct_dt.get_size(index).bits().into(),
+                struct_dt.get_alignment(index).bits(),
+                DIFlags::PUBLIC,
+                types.as_slice(),
+                0,
+                name,
+            );
+            self.register_concrete_type(name, DebugType::Struct(union_type));
+            return Ok(());
+        }
+
         //Create a struct type
         let struct_type = self.debug_info.create_struct_type(
             file.as_debug_info_scope(),
@@ -633,7 +655,7 @@ impl<'ink> Debug<'ink> for DebugBuilder<'ink> {
             let location = &datatype.location;
             match type_info {
                 DataTypeInformation::Struct { members, .. } => {
-                    self.create_struct_type(name, members.as_slice(), index, location)
+                    self.create_struct_type(name, members.as_slice(), type_info.is_union(), index, location)
                 }
                 DataTypeInformation::Array { name, inner_type_name, dimensions, .. } => {
                     self.create_array_type(name, inner_type_name, dimensions, size, alignment, index)
@@ -175,15 +175,19 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
     fn expand_opaque_types(&mut self, data_type: &DataType) -> Result<(), Diagnostic> {
         let information = data_type.get_type_information();
         if let DataTypeInformation::Struct { source, members, .. } = information {
-            let members = members
-                .iter()
-                .filter(|it| !it.is_temp() && !it.is_return())
-                .map(|m| self.types_index.get_associated_type(m.get_type_name()))
-                .collect::<Result<Vec<BasicTypeEnum>, Diagnostic>>()?;
+            let members = if *source == StructSource::Union {
+                self.create_union_body(information, members)
+            } else {
+                members
+                    .iter()
+                    .filter(|it| !it.is_temp() && !it.is_return())
+                    .map(|m| self.types_index.get_associated_type(m.get_type_name()))
+                    .collect::<Result<Vec<BasicTypeEnum>, Diagnostic>>()?
+            };
 
             let struct_type = match source {
                 StructSource::Pou(..) => self.types_index.get_associated_pou_type(data_type.get_name()),
-                StructSource::OriginalDeclaration | StructSource::Internal(_) => {
+                StructSource::OriginalDeclaration | StructSource::Union | StructSource::Internal(_) => {
                     self.types_index.get_associated_type(data_type.get_name())
                 }
             }
@@ -194,6 +198,24 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         Ok(())
     }
 
+    /// creates the body of a union: an array of integers as wide as the union's alignment,
+    /// spanning the size of the union's largest member
+    fn create_union_body(
+        &self,
+        union: &DataTypeInformation,
+        members: &[VariableIndexEntry],
+    ) -> Vec<BasicTypeEnum<'ink>> {
+        let alignment = members
+            .iter()
+            .map(|it| self.index.get_type_information_or_void(it.get_type_name()))
+            .map(|it| it.get_alignment(self.index).value())
+            .max()
+            .unwrap_or(1);
+        let size = union.get_size(self.index).value();
+        let element_type = self.llvm.context.custom_width_int_type(alignment * 8);
+        vec![element_type.array_type(size / alignment).into()]
+    }
+
     /// Creates an llvm type to be associated with the given data type.
     /// Generates only an opaque type for structs.
     /// Eagerly generates but does not associate nested array and referenced aliased types
@@ -203,7 +225,7 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         match information {
             DataTypeInformation::Struct { source, .. } => match source {
                 StructSource::Pou(..) => self.types_index.get_associated_pou_type(data_type.get_name()),
-                StructSource::OriginalDeclaration => {
+                StructSource::OriginalDeclaration | StructSource::Union => {
                     self.types_index.get_associated_type(data_type.get_name())
                 }
                 StructSource::Internal(_) => self.types_index.get_associated_type(data_type.get_name()),
@@ -276,6 +298,14 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
     ) -> Result<Option<BasicValueEnum<'ink>>, Diagnostic> {
         let information = data_type.get_type_information();
         match information {
+            // union members cannot be initialized, a union starts zeroed
+            DataTypeInformation::Struct { source: StructSource::Union, .. } => Ok(Some(
+                self.types_index
+                    .get_associated_type(data_type.get_name())?
+                    .into_struct_type()
+                    .const_zero()
+                    .into(),
+            )),
             DataTypeInformation::Struct { source, members, .. } => {
                 let member_names_and_initializers = members
                     .iter()
@@ -300,7 +330,7 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
 
                 let struct_type = match source {
                     StructSource::Pou(..) => self.types_index.get_associated_pou_type(data_type.get_name()),
-                    StructSource::OriginalDeclaration => {
+                    StructSource::OriginalDeclaration | StructSource::Union => {
                         self.types_index.get_associated_type(data_type.get_name())
                     }
                     StructSource::Internal(_) => self.types_index.get_associated_type(data_type.get_name()),
@@ -26,8 +26,8 @@ use inkwell::{
 };
 use plc_ast::{
     ast::{
-        flatten_expression_list, AstFactory, AstStatement, DirectAccessType, Operator, ReferenceAccess,
-        SourceRange,
+        flatten_expression_list, flatten_struct_literal_list, AstFactory, AstStatement, DirectAccessType,
+        Operator, ReferenceAccess, SourceRange,
     },
     literals::AstLiteral,
 };
@@ -205,6 +205,12 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         // generate the expression
         match expression {
             AstStatement::ReferenceExpr { access, base, .. } => {
+                // reading a property was replaced by a call to its getter
+                if let Some(StatementAnnotation::ReplacementAst { statement }) =
+                    self.annotations.get(expression)
+                {
+                    return self.generate_expression_value(statement);
+                }
                 let res = self.generate_reference_expression(access, base.as_deref(), expression)?;
                 let val = match res {
                     ExpressionValue::LValue(val) => {
@@ -428,6 +434,9 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             // adr, ref, etc.
             return builtin.codegen(self, parameters_list.as_slice(), operator.get_location());
         }
+        if let Some(enum_type) = self.index.find_enum_conversion(implementation_name) {
+            return self.generate_enum_conversion(enum_type, pou, parameters_list.as_slice(), operator);
+        }
 
         let mut arguments_list = self.generate_pou_call_arguments_list(
             pou,
@@ -744,6 +753,54 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         Ok(result.into_iter().map(|(_, v)| v.into()).collect::<Vec<BasicMetadataValueEnum>>())
     }
 
+    /// generates the conversion of an enum to its numeric type or back (e.g. `Color_TO_DINT(x)`).
+    /// Converting a number that is not the value of one of the enum's elements results in the enum's
+    /// initial value.
+    fn generate_enum_conversion(
+        &self,
+        enum_type: &DataType,
+        conversion: &PouIndexEntry,
+        parameters: &[&AstStatement],
+        operator: &AstStatement,
+    ) -> Result<ExpressionValue<'ink>, Diagnostic> {
+        let (&[AstStatement::Assignment { right: parameter, .. }] | &[parameter]) = parameters else {
+            return Err(Diagnostic::codegen_error(
+                "Expected exactly one parameter for an enum conversion",
+                operator.get_location(),
+            ));
+        };
+        // the parameter is cast to the declared type of the conversion's input
+        let value = self.generate_expression(parameter)?.into_int_value();
+        let PouIndexEntry::Function { return_type, .. } = conversion else {
+            unreachable!("enum conversions are functions")
+        };
+        let DataTypeInformation::Enum { name, elements, .. } =
+            self.index.get_effective_type_or_void_by_name(return_type).get_type_information()
+        else {
+            // an enum's value already is its numeric value
+            return Ok(ExpressionValue::RValue(value.as_basic_value_enum()));
+        };
+
+        let builder = &self.llvm.builder;
+        let mut is_element = self.llvm.context.bool_type().const_zero();
+        for element in elements {
+            let element_value = self.index.find_enum_element_value(name, element).ok_or_else(|| {
+                Diagnostic::codegen_error(
+                    &format!("Cannot resolve the value of {name}.{element}"),
+                    operator.get_location(),
+                )
+            })?;
+            let element_value = value.get_type().const_int(element_value as u64, true);
+            let matches = builder.build_int_compare(IntPredicate::EQ, value, element_value, "");
+            is_element = builder.build_or(is_element, matches, "");
+        }
+        let initial_value = match self.llvm_index.find_associated_initial_value(enum_type.get_name()) {
+            Some(initial_value) => initial_value.into_int_value(),
+            None => value.get_type().const_zero(),
+        };
+        Ok(ExpressionValue::RValue(builder.build_select(is_element, value, initial_value, "")))
+    }
+
     fn generate_argument_by_val(
         &self,
         type_name: &str,
@@ -1204,6 +1261,26 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         })
     }
 
+    /// generates an gep-statement and returns the resulting pointer without dereferencing
+    /// auto-deref variables. For a `REFERENCE TO` variable this returns the address where the
+    /// bound pointer is stored instead of the address of the referenced value.
+    ///
+    /// - `reference_statement` - the statement to get an lvalue from
+    pub fn generate_lvalue_without_auto_deref(
+        &self,
+        reference_statement: &AstStatement,
+    ) -> Result<PointerValue<'ink>, Diagnostic> {
+        let AstStatement::ReferenceExpr { access, base, .. } = reference_statement else {
+            return self.generate_lvalue(reference_statement);
+        };
+        self.generate_reference_expression(access, base.as_deref(), reference_statement).and_then(|it| {
+            let v: Result<PointerValue, _> = it.get_basic_value_enum().try_into();
+            v.map_err(|err| {
+                Diagnostic::codegen_error(format!("{err:?}").as_str(), reference_statement.get_location())
+            })
+        })
+    }
+
     /// geneartes a gep for the given reference with an optional qualifier
     ///
     /// - `qualifier` an optional qualifier for a reference (e.g. myStruct.x where myStruct is the qualifier for x)
@@ -1227,11 +1304,20 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                     }
                 }
                 Some(StatementAnnotation::Variable { qualified_name, .. }) => {
-                    let member_location = self
+                    let member = self
                         .index
                         .find_fully_qualified_variable(qualified_name)
-                        .map(VariableIndexEntry::get_location_in_parent)
                         .ok_or_else(|| Diagnostic::unresolved_reference(qualified_name, offset.clone()))?;
+                    if self.is_union_member(member) {
+                        // all members of a union are located at its start
+                        let member_type = self.llvm_index.get_associated_type(member.get_type_name())?;
+                        return Ok(self.llvm.builder.build_pointer_cast(
+                            *qualifier,
+                            member_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)),
+                            name,
+                        ));
+                    }
+                    let member_location = member.get_location_in_parent();
                     let gep: PointerValue<'_> = self.llvm.get_member_pointer_from_struct(
                         *qualifier,
                         member_location,
@@ -1765,22 +1851,18 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                     .map(ExpressionValue::RValue),
                 AstLiteral::Time(t) => self.create_const_int(t.value()).map(ExpressionValue::RValue),
                 AstLiteral::String(s) => self.generate_string_literal(literal_statement, s.value(), location),
-                AstLiteral::Array(arr) => self
-                    .generate_literal_array(arr.elements().ok_or_else(cannot_generate_literal)?)
-                    .map(ExpressionValue::RValue),
+                AstLiteral::Array(arr) => {
+                    self.generate_literal_array(arr.elements().ok_or_else(cannot_generate_literal)?)
+                }
                 AstLiteral::Null { .. } => self.llvm.create_null_ptr().map(ExpressionValue::RValue),
             },
 
-            AstStatement::MultipliedStatement { .. } => {
-                self.generate_literal_array(literal_statement).map(ExpressionValue::RValue)
-            }
+            AstStatement::MultipliedStatement { .. } => self.generate_literal_array(literal_statement),
             // if there is an expression-list this might be a struct-initialization or array-initialization
             AstStatement::ExpressionList { .. } => {
                 let type_hint = self.get_type_hint_info_for(literal_statement)?;
                 match type_hint {
-                    DataTypeInformation::Array { .. } => {
-                        self.generate_literal_array(literal_statement).map(ExpressionValue::RValue)
-                    }
+                    DataTypeInformation::Array { .. } => self.generate_literal_array(literal_statement),
                     _ => self.generate_literal_struct(literal_statement),
                 }
             }
@@ -1895,59 +1977,50 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
             })
     }
 
+    /// returns true if the given variable is a member of a union
+    fn is_union_member(&self, variable: &VariableIndexEntry) -> bool {
+        variable
+            .get_qualified_name()
+            .rsplit_once('.')
+            .and_then(|(container, _)| self.index.find_effective_type_info(container))
+            .map(DataTypeInformation::is_union)
+            .unwrap_or(false)
+    }
+
     /// generates a struct literal value with the given value assignments (ExpressionList)
+    ///
+    /// literals consisting of constant values only result in a constant struct value, all other
+    /// literals are generated into a temporary variable on the stack
     fn generate_literal_struct(
         &self,
         assignments: &AstStatement,
     ) -> Result<ExpressionValue<'ink>, Diagnostic> {
-        if let DataTypeInformation::Struct { name: struct_name, members, .. } =
-            self.get_type_hint_info_for(assignments)?
-        {
+        let type_info = self.get_type_hint_info_for(assignments)?;
+        if type_info.is_union() {
+            return Err(Diagnostic::codegen_error(
+                &format!("Cannot generate a literal for union {}", type_info.get_name()),
+                assignments.get_location(),
+            ));
+        }
+        if let DataTypeInformation::Struct { name: struct_name, members, .. } = type_info {
+            if !self.is_constant_literal(assignments) {
+                return self.generate_literal_struct_temporary(struct_name, members, assignments);
+            }
+
             let mut uninitialized_members: HashSet<&VariableIndexEntry> = HashSet::from_iter(members);
             let mut member_values: Vec<(u32, BasicValueEnum<'ink>)> = Vec::new();
             for assignment in flatten_expression_list(assignments) {
-                if let AstStatement::Assignment { left, right, .. } = assignment {
-                    if let Some(StatementAnnotation::Variable { qualified_name, .. }) =
-                        self.annotations.get(left.as_ref())
-                    {
-                        let member: &VariableIndexEntry =
-                            self.index.find_fully_qualified_variable(qualified_name).ok_or_else(|| {
-                                Diagnostic::unresolved_reference(qualified_name, left.get_location())
-                            })?;
-
-                        let index_in_parent = member.get_location_in_parent();
-                        let value = self.generate_expression(right)?;
+                let (member, right) = self.get_struct_literal_member(assignment)?;
+                let index_in_parent = member.get_location_in_parent();
+                let value = self.generate_expression(right)?;
 
-                        uninitialized_members.remove(member);
-                        member_values.push((index_in_parent, value));
-                    } else {
-                        return Err(Diagnostic::codegen_error(
-                            "struct member lvalue required as left operand of assignment",
-                            left.get_location(),
-                        ));
-                    }
-                } else {
-                    return Err(Diagnostic::codegen_error(
-                        "struct literal must consist of explicit assignments in the form of member := value",
-                        assignment.get_location(),
-                    ));
-                }
+                uninitialized_members.remove(member);
+                member_values.push((index_in_parent, value));
             }
 
             //fill the struct with fields we didnt mention yet
             for member in uninitialized_members {
-                let initial_value = self
-                    .llvm_index
-                    .find_associated_variable_value(member.get_qualified_name())
-                    // .or_else(|| self.index.find_associated_variable_value(name))
-                    .or_else(|| self.llvm_index.find_associated_initial_value(member.get_type_name()))
-                    .ok_or_else(|| {
-                        Diagnostic::cannot_generate_initializer(
-                            member.get_qualified_name(),
-                            assignments.get_location(),
-                        )
-                    })?;
-
+                let initial_value = self.get_struct_member_initial_value(member, assignments)?;
                 member_values.push((member.get_location_in_parent(), initial_value));
             }
             let struct_type = self.llvm_index.get_associated_type(struct_name)?.into_struct_type();
@@ -1978,30 +2051,137 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
         }
     }
 
+    /// generates a struct literal into a temporary variable on the stack and returns a pointer to it.
+    /// The members are stored one by one, so their values do not need to be known at compile time.
+    fn generate_literal_struct_temporary(
+        &self,
+        struct_name: &str,
+        members: &[VariableIndexEntry],
+        assignments: &AstStatement,
+    ) -> Result<ExpressionValue<'ink>, Diagnostic> {
+        let struct_type = self.llvm_index.get_associated_type(struct_name)?;
+        let temporary = self.llvm.builder.build_alloca(struct_type, "");
+
+        let mut uninitialized_members: HashSet<&VariableIndexEntry> = HashSet::from_iter(members);
+        for assignment in flatten_expression_list(assignments) {
+            let (member, right) = self.get_struct_literal_member(assignment)?;
+            let member_pointer = self.llvm.get_member_pointer_from_struct(
+                temporary,
+                member.get_location_in_parent(),
+                member.get_name(),
+                &right.get_location(),
+            )?;
+            let member_type = self.index.get_effective_type_or_void_by_name(member.get_type_name());
+            self.generate_store(member_pointer, member_type.get_type_information(), right)?;
+            uninitialized_members.remove(member);
+        }
+
+        for member in uninitialized_members {
+            let member_pointer = self.llvm.get_member_pointer_from_struct(
+                temporary,
+                member.get_location_in_parent(),
+                member.get_name(),
+                &assignments.get_location(),
+            )?;
+            let initial_value = self.get_struct_member_initial_value(member, assignments)?;
+            self.llvm.builder.build_store(member_pointer, initial_value);
+        }
+        Ok(ExpressionValue::LValue(temporary))
+    }
+
+    /// returns the member initialized by the given `member := value` assignment of a struct literal
+    /// and the value assigned to it
+    fn get_struct_literal_member<'s>(
+        &self,
+        assignment: &'s AstStatement,
+    ) -> Result<(&VariableIndexEntry, &'s AstStatement), Diagnostic> {
+        let AstStatement::Assignment { left, right, .. } = assignment else {
+            return Err(Diagnostic::codegen_error(
+                "struct literal must consist of explicit assignments in the form of member := value",
+                assignment.get_location(),
+            ));
+        };
+        let Some(StatementAnnotation::Variable { qualified_name, .. }) = self.annotations.get(left.as_ref())
+        else {
+            return Err(Diagnostic::codegen_error(
+                "struct member lvalue required as left operand of assignment",
+                left.get_location(),
+            ));
+        };
+        let member = self
+            .index
+            .find_fully_qualified_variable(qualified_name)
+            .ok_or_else(|| Diagnostic::unresolved_reference(qualified_name, left.get_location()))?;
+        Ok((member, right.as_ref()))
+    }
+
+    /// returns the value of a struct member that is not mentioned in a struct literal
+    fn get_struct_member_initial_value(
+        &self,
+        member: &VariableIndexEntry,
+        literal: &AstStatement,
+    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+        self.llvm_index
+            .find_associated_variable_value(member.get_qualified_name())
+            .or_else(|| self.llvm_index.find_associated_initial_value(member.get_type_name()))
+            .ok_or_else(|| {
+                Diagnostic::cannot_generate_initializer(member.get_qualified_name(), literal.get_location())
+            })
+    }
+
+    /// returns true if the given literal consists of literals only and can be generated as a constant.
+    /// Outside of a function (e.g. initial values) all literals are generated as constants.
+    fn is_constant_literal(&self, statement: &AstStatement) -> bool {
+        if self.function_context.is_none() {
+            return true;
+        }
+        match statement {
+            // inside of a function string literals are references to global constants
+            AstStatement::Literal { kind: AstLiteral::String(..), .. } => false,
+            AstStatement::Literal { kind: AstLiteral::Array(array), .. } => {
+                array.elements().map_or(true, |it| self.is_constant_literal(it))
+            }
+            AstStatement::Literal { .. } => true,
+            AstStatement::ExpressionList { expressions, .. } => {
+                expressions.iter().all(|it| self.is_constant_literal(it))
+            }
+            AstStatement::MultipliedStatement { element, .. } => self.is_constant_literal(element),
+            AstStatement::Assignment { right, .. } => self.is_constant_literal(right),
+            AstStatement::CastStatement { target, .. } => self.is_constant_literal(target),
+            // references to scalar constants are replaced by their values
+            AstStatement::ReferenceExpr { .. } => matches!(
+                self.annotations.get(statement),
+                Some(StatementAnnotation::Variable { constant: true, resulting_type, .. })
+                    if !self.index.get_type_information_or_void(resulting_type).is_aggregate()
+            ),
+            _ => false,
+        }
+    }
+
     /// generates an array literal with the given optional elements (represented as an ExpressionList)
     pub fn generate_literal_array(
         &self,
         initializer: &AstStatement,
-    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
-        let array_value = self.generate_literal_array_value(
+    ) -> Result<ExpressionValue<'ink>, Diagnostic> {
+        self.generate_literal_array_value(
             initializer,
             self.get_type_hint_info_for(initializer)?,
             &initializer.get_location(),
-        )?;
-        return Ok(array_value.as_basic_value_enum());
+        )
     }
 
-    /// constructs an ArrayValue (returned as a BasicValueEnum) of the given element-literals constructing an array-value of the
+    /// constructs an ArrayValue of the given element-literals constructing an array-value of the
     /// type described by inner_array_type.
     ///
     /// passing an epxression-lists with LiteralIntegers and inner_array_type is INT-description will return an
-    /// i16-array-value
+    /// i16-array-value. Literals that contain non-constant values are generated into a temporary
+    /// variable on the stack instead.
     fn generate_literal_array_value(
         &self,
         elements: &AstStatement,
         data_type: &DataTypeInformation,
         location: &SourceRange,
-    ) -> Result<BasicValueEnum<'ink>, Diagnostic> {
+    ) -> Result<ExpressionValue<'ink>, Diagnostic> {
         let (inner_type, expected_len) =
             if let DataTypeInformation::Array { inner_type_name, dimensions, .. } = data_type {
                 let len: u32 = dimensions
@@ -2022,17 +2202,17 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
 
         // for arrays of struct we cannot flatten the expression list
         // to generate the passed structs we need an expression list of assignments
-        // flatten_expression_list will will return a vec of only assignments
         let elements =
             if self.index.get_effective_type_or_void_by_name(inner_type.get_name()).information.is_struct() {
-                match elements {
-                    AstStatement::ExpressionList { expressions, .. } => expressions.iter().collect(),
-                    _ => unreachable!("This should always be an expression list"),
-                }
+                flatten_struct_literal_list(elements)
             } else {
                 flatten_expression_list(elements)
             };
 
+        if !elements.iter().all(|it| self.is_constant_literal(it)) {
+            return self.generate_literal_array_temporary(data_type, inner_type, &elements, expected_len);
+        }
+
         let llvm_type = self.llvm_index.get_associated_type(inner_type.get_name())?;
         let mut v = Vec::new();
         for e in elements {
@@ -2073,7 +2253,39 @@ impl<'ink, 'b> ExpressionCodeGenerator<'ink, 'b> {
                 v.iter().map(|it| it.into_vector_value()).collect::<Vec<VectorValue>>().as_slice(),
             ),
         };
-        Ok(array_value.as_basic_value_enum())
+        Ok(ExpressionValue::RValue(array_value.as_basic_value_enum()))
+    }
+
+    /// generates an array literal into a temporary variable on the stack and returns a pointer to it.
+    /// The elements are stored one by one, missing elements are filled with the inner type's default value.
+    fn generate_literal_array_temporary(
+        &self,
+        array_type: &DataTypeInformation,
+        inner_type: &DataType,
+        elements: &[&AstStatement],
+        expected_len: usize,
+    ) -> Result<ExpressionValue<'ink>, Diagnostic> {
+        let llvm_type = self.llvm_index.get_associated_type(array_type.get_name())?;
+        let temporary = self.llvm.builder.build_alloca(llvm_type, "");
+        let initial_value = match self.llvm_index.find_associated_initial_value(inner_type.get_name()) {
+            Some(value) => value,
+            None => self.llvm_index.get_associated_type(inner_type.get_name())?.const_zero(),
+        };
+
+        let zero = self.llvm.i32_type().const_zero();
+        for index in 0..expected_len {
+            let accessor = self.llvm.i32_type().const_int(index as u64, false);
+            let element_pointer = self.llvm.load_array_element(temporary, &[zero, accessor], "")?;
+            match elements.get(index) {
+                Some(element) => {
+                    self.generate_store(element_pointer, inner_type.get_type_information(), element)?
+                }
+                None => {
+                    self.llvm.builder.build_store(element_pointer, initial_value);
+                }
+            }
+        }
+        Ok(ExpressionValue::LValue(temporary))
     }
 
     /// generates a phi-expression (&& or || expression) with respect to short-circuit evaluation
@@ -8,11 +8,16 @@ use super::{
 };
 use crate::{
     codegen::{
+        coverage::CoverageInstrumentation,
+        cycle_monitor::CycleMonitor,
         debug::{Debug, DebugBuilderEnum},
         llvm_index::LlvmTypedIndex,
+        trace::TraceInstrumentation,
     },
+    dynamic_memory,
     index::{self, ImplementationType},
     resolver::{AstAnnotations, Dependency},
+    testing,
     typesystem::{self, DataType, VarArgs},
 };
 
@@ -26,7 +31,7 @@ use crate::index::{ImplementationIndexEntry, VariableIndexEntry};
 use crate::index::Index;
 use indexmap::{IndexMap, IndexSet};
 use inkwell::{
-    module::Module,
+    module::{Linkage, Module},
     types::{BasicMetadataTypeEnum, BasicTypeEnum, FunctionType},
     values::{BasicValue, BasicValueEnum, FunctionValue},
     AddressSpace,
@@ -37,6 +42,7 @@ use inkwell::{
 };
 use plc_ast::ast::{AstStatement, Implementation, NewLines, PouType, SourceRange};
 use plc_diagnostics::diagnostics::{Diagnostic, INTERNAL_LLVM_ERROR};
+use std::cell::RefCell;
 
 pub struct PouGenerator<'ink, 'cg> {
     llvm: Llvm<'ink>,
@@ -57,6 +63,7 @@ pub fn generate_implementation_stubs<'ink>(
     debug: &mut DebugBuilderEnum<'ink>,
 ) -> Result<LlvmTypedIndex<'ink>, Diagnostic> {
     let mut llvm_index = LlvmTypedIndex::default();
+    let context = llvm.context;
     let pou_generator = PouGenerator::new(llvm, index, annotations, types_index);
     let implementations = dependencies
         .into_iter()
@@ -69,16 +76,89 @@ pub fn generate_implementation_stubs<'ink>(
         })
         .collect::<IndexMap<_, _>>();
     for (name, implementation) in implementations {
-        if !implementation.is_generic() {
+        // assertions and enum conversions are generated inline at the call site
+        if !implementation.is_generic()
+            && !testing::is_assertion(name)
+            && index.find_enum_conversion(name).is_none()
+        {
             let curr_f =
                 pou_generator.generate_implementation_stub(implementation, module, debug, &mut llvm_index)?;
             llvm_index.associate_implementation(name, curr_f)?;
         }
     }
 
+    // failed assertions are reported to the test runner
+    if dependencies.iter().any(|it| matches!(it, Dependency::Call(name) if testing::is_assertion(name))) {
+        let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+        let function_type = context
+            .void_type()
+            .fn_type(&[i8_ptr_type.into(), i8_ptr_type.into(), context.i32_type().into()], false);
+        let assert_failed = module
+            .get_function(testing::ASSERT_FAILED_FUNCTION)
+            .unwrap_or_else(|| module.add_function(testing::ASSERT_FAILED_FUNCTION, function_type, None));
+        llvm_index.associate_implementation(testing::ASSERT_FAILED_FUNCTION, assert_failed)?;
+    }
+
+    // __NEW and __DELETE use the allocator runtime
+    if dependencies
+        .iter()
+        .any(|it| matches!(it, Dependency::Call(name) if dynamic_memory::is_dynamic_memory_builtin(name)))
+    {
+        let (alloc, free) = generate_default_allocator(module, &pou_generator.llvm);
+        llvm_index.associate_implementation(dynamic_memory::ALLOC_FUNCTION, alloc)?;
+        llvm_index.associate_implementation(dynamic_memory::FREE_FUNCTION, free)?;
+    }
+
     Ok(llvm_index)
 }
 
+/// Generates weak definitions of the allocator runtime functions based on `calloc` and `free`,
+/// a runtime that defines these functions itself replaces them at link time
+fn generate_default_allocator<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+) -> (FunctionValue<'ink>, FunctionValue<'ink>) {
+    let context = llvm.context;
+    let builder = &llvm.builder;
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC));
+    let size_type = context.i64_type();
+
+    let alloc = module.get_function(dynamic_memory::ALLOC_FUNCTION).unwrap_or_else(|| {
+        let calloc = module.get_function("calloc").unwrap_or_else(|| {
+            let function_type = i8_ptr_type.fn_type(&[size_type.into(), size_type.into()], false);
+            module.add_function("calloc", function_type, None)
+        });
+        let function_type = i8_ptr_type.fn_type(&[size_type.into()], false);
+        let alloc =
+            module.add_function(dynamic_memory::ALLOC_FUNCTION, function_type, Some(Linkage::WeakAny));
+        builder.position_at_end(context.append_basic_block(alloc, "entry"));
+        let size = alloc.get_first_param().expect("the allocator has a size parameter");
+        let memory = builder
+            .build_call(calloc, &[size_type.const_int(1, false).into(), size.into()], "")
+            .try_as_basic_value()
+            .left()
+            .expect("calloc returns a pointer");
+        builder.build_return(Some(&memory));
+        alloc
+    });
+
+    let free = module.get_function(dynamic_memory::FREE_FUNCTION).unwrap_or_else(|| {
+        let libc_free = module.get_function("free").unwrap_or_else(|| {
+            module.add_function("free", context.void_type().fn_type(&[i8_ptr_type.into()], false), None)
+        });
+        let function_type = context.void_type().fn_type(&[i8_ptr_type.into()], false);
+        let free = module.add_function(dynamic_memory::FREE_FUNCTION, function_type, Some(Linkage::WeakAny));
+        builder.position_at_end(context.append_basic_block(free, "entry"));
+        let memory = free.get_first_param().expect("the deallocator has a pointer parameter");
+        builder.build_call(libc_free, &[memory.into()], "");
+        builder.build_return(None);
+        free
+    });
+    builder.clear_insertion_position();
+
+    (alloc, free)
+}
+
 ///Generates a global constant for each initialized pou member
 /// The given constant can then be used to initialize the variable using memcpy without re-evaluating the expression
 /// Retrieves the POUs from the index (implementation)
@@ -278,6 +358,9 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         &self,
         implementation: &Implementation,
         debug: &DebugBuilderEnum<'ink>,
+        coverage: Option<&CoverageInstrumentation<'ink>>,
+        cycle_monitor: Option<&CycleMonitor<'ink>>,
+        trace: Option<&TraceInstrumentation<'ink>>,
         new_lines: &NewLines,
     ) -> Result<(), Diagnostic> {
         let context = self.llvm.context;
@@ -310,6 +393,8 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         self.llvm.builder.position_at_end(block);
         //Set debug location
 
+        let cycle_start = cycle_monitor.and_then(|it| it.begin(&self.llvm, pou_name));
+        let trace = trace.and_then(|it| it.begin(&self.llvm, pou_name));
         let function_context = FunctionContext {
             linking_context: self.index.find_implementation_by_name(&implementation.name).ok_or_else(
                 || {
@@ -321,6 +406,10 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
             )?,
             function: current_function,
             new_lines,
+            coverage,
+            cycle_start,
+            trace,
+            labels: RefCell::default(),
         };
 
         let mut param_index = 0;
@@ -726,6 +815,12 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         function_context: &'a FunctionContext<'ink, 'a>,
         local_index: &'a LlvmTypedIndex<'ink>,
     ) -> Result<(), Diagnostic> {
+        if let Some(cycle_start) = &function_context.cycle_start {
+            cycle_start.end(&self.llvm);
+        }
+        if let Some(trace) = &function_context.trace {
+            trace.record(&self.llvm, local_index)?;
+        }
         if let Some(ret_v) = self.index.find_return_variable(function_context.linking_context.get_type_name())
         {
             if self
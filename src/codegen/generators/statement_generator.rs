@@ -1,14 +1,19 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+use std::{cell::RefCell, collections::HashMap};
+
 use super::{
     expression_generator::{to_i1, ExpressionCodeGenerator},
     llvm::Llvm,
     pou_generator::PouGenerator,
 };
 use crate::{
-    codegen::{debug::Debug, llvm_typesystem::cast_if_needed},
+    codegen::{
+        coverage::CoverageInstrumentation, cycle_monitor::CycleStart, debug::Debug,
+        llvm_typesystem::cast_if_needed, trace::ProgramTrace,
+    },
     codegen::{debug::DebugBuilderEnum, LlvmTypedIndex},
     index::{ImplementationIndexEntry, Index},
-    resolver::{AnnotationMap, AstAnnotations},
+    resolver::{AnnotationMap, AstAnnotations, StatementAnnotation},
     typesystem::{self, DataTypeInformation},
 };
 use inkwell::{
@@ -33,6 +38,14 @@ pub struct FunctionContext<'ink, 'b> {
     pub function: FunctionValue<'ink>,
     /// The new lines marker for the compilation unit containing the POU
     pub new_lines: &'b NewLines,
+    /// the coverage counters of the compilation unit, if coverage instrumentation is enabled
+    pub coverage: Option<&'b CoverageInstrumentation<'ink>>,
+    /// the start time of a program, if cycle time monitoring is enabled
+    pub cycle_start: Option<CycleStart<'ink>>,
+    /// the traced variables of a program, if tracing is enabled
+    pub trace: Option<ProgramTrace<'ink, 'b>>,
+    /// the basic blocks of the jump labels in this POU, a block is created by the first jump or label
+    pub labels: RefCell<HashMap<String, BasicBlock<'ink>>>,
 }
 
 /// the StatementCodeGenerator is used to generate statements (For, If, etc.) or expressions (references, literals, etc.)
@@ -116,12 +129,25 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
     ///
     /// - `statement` the statement to be generated
     pub fn generate_statement(&self, statement: &AstStatement) -> Result<(), Diagnostic> {
+        if let Some(coverage) = self.function_context.coverage {
+            coverage.count_statement(self.llvm, statement);
+        }
         match statement {
             AstStatement::EmptyStatement { .. } => {
                 //nothing to generate
             }
             AstStatement::Assignment { left, right, .. } => {
-                self.generate_assignment_statement(left, right)?;
+                if let Some(StatementAnnotation::ReplacementAst { statement }) =
+                    self.annotations.get(statement)
+                {
+                    // an assignment to a property was replaced by a call to its setter
+                    self.create_expr_generator().generate_expression(statement)?;
+                } else {
+                    self.generate_assignment_statement(left, right)?;
+                }
+            }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                self.generate_reference_assignment(left, right)?;
             }
 
             AstStatement::ControlStatement { kind: ctl_statement, .. } => {
@@ -144,6 +170,17 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
                     ));
                 }
             }
+            AstStatement::LabelStatement { name, .. } => {
+                let label_block = self.get_label_block(name);
+                // fall through into the labeled block
+                self.llvm.builder.build_unconditional_branch(label_block);
+                self.llvm.builder.position_at_end(label_block);
+            }
+            AstStatement::JumpStatement { label, .. } => {
+                self.register_debug_location(statement);
+                self.llvm.builder.build_unconditional_branch(self.get_label_block(label));
+                self.generate_buffer_block();
+            }
             AstStatement::ContinueStatement { location, .. } => {
                 if let Some(cont_block) = &self.current_loop_continue {
                     self.llvm.builder.build_unconditional_branch(*cont_block);
@@ -162,6 +199,16 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         Ok(())
     }
 
+    /// returns the basic block of the given jump label, the block is created if it does not exist yet
+    fn get_label_block(&self, label: &str) -> BasicBlock<'a> {
+        *self
+            .function_context
+            .labels
+            .borrow_mut()
+            .entry(label.to_lowercase())
+            .or_insert_with(|| self.llvm.context.append_basic_block(self.function_context.function, label))
+    }
+
     /// genertes a single statement
     ///
     /// - `statement` the control statement to be generated
@@ -229,6 +276,30 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         Ok(())
     }
 
+    /// generates a reference assignment _left_ REF= _right_
+    ///
+    /// binds the reference _left_ to the address of _right_ instead of storing _right_'s value
+    ///
+    /// `left_statement` the reference to bind
+    /// `right_statement` the variable the reference will point to
+    pub fn generate_reference_assignment(
+        &self,
+        left_statement: &AstStatement,
+        right_statement: &AstStatement,
+    ) -> Result<(), Diagnostic> {
+        self.register_debug_location(left_statement);
+        let exp_gen = self.create_expr_generator();
+        let reference = exp_gen.generate_lvalue_without_auto_deref(left_statement)?;
+        let target = exp_gen.generate_lvalue(right_statement)?;
+        let target = self.llvm.builder.build_pointer_cast(
+            target,
+            reference.get_type().get_element_type().into_pointer_type(),
+            "",
+        );
+        self.llvm.builder.build_store(reference, target);
+        Ok(())
+    }
+
     fn register_debug_location(&self, statement: &AstStatement) {
         let line = self.function_context.new_lines.get_line_nr(statement.get_location().get_start());
         let column = self.function_context.new_lines.get_column(line, statement.get_location().get_start());
@@ -514,12 +585,18 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
             }
             //generate the case's body
             builder.position_at_end(case_block);
+            if let Some(coverage) = self.function_context.coverage {
+                coverage.count_branch(self.llvm, &conditional_block.condition);
+            }
             self.generate_body(&conditional_block.body)?;
             // skiop all other case-bodies
             builder.build_unconditional_branch(continue_block);
         }
         // current-else is the last else-block generated by the range-expressions
         builder.position_at_end(current_else_block);
+        if let Some(coverage) = self.function_context.coverage {
+            coverage.count_else(self.llvm, else_body);
+        }
         self.generate_body(else_body)?;
         builder.build_unconditional_branch(continue_block);
         continue_block.move_after(current_else_block).expect(INTERNAL_LLVM_ERROR);
@@ -719,6 +796,9 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
             //Generate if statement content
 
             builder.position_at_end(conditional_block);
+            if let Some(coverage) = self.function_context.coverage {
+                coverage.count_branch(self.llvm, &block.condition);
+            }
             self.generate_body(&block.body)?;
             builder.build_unconditional_branch(continue_block);
         }
@@ -726,6 +806,9 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
 
         if let Some(else_block) = else_block {
             builder.position_at_end(else_block);
+            if let Some(coverage) = self.function_context.coverage {
+                coverage.count_else(self.llvm, else_body);
+            }
             self.generate_body(else_body)?;
             builder.build_unconditional_branch(continue_block);
         }
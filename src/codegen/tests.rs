@@ -1,19 +1,28 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+mod assertion_tests;
 mod code_gen_tests;
 mod codegen_error_messages_tests;
 mod compare_instructions_tests;
 mod constants_tests;
+mod coverage_tests;
+mod cycle_monitor_tests;
 mod debug_tests;
 mod directaccess_test;
+mod dynamic_memory_tests;
 mod expression_tests;
 mod function_tests;
 mod generics_test;
 mod initialization_test;
+mod jump_tests;
 mod multifile_codegen_tests;
 mod parameters_tests;
+mod property_tests;
+mod reference_tests;
 mod statement_codegen_test;
 mod string_tests;
 #[cfg(feature = "verify")]
 mod switch_case_tests;
+mod trace_tests;
 mod typesystem_test;
+mod union_tests;
 mod vla_tests;
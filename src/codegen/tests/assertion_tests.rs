@@ -0,0 +1,31 @@
+use crate::test_utils::tests::codegen;
+
+#[test]
+fn failed_assertions_call_the_assertion_runtime() {
+    let result = codegen(
+        "
+        {test}
+        FUNCTION test_foo : BOOL
+        VAR x : DINT; y : REAL; END_VAR
+            ASSERT_TRUE(x > 0);
+            ASSERT_FALSE(x < 0);
+            ASSERT_EQUAL(5, x);
+            ASSERT_EQUAL(1.5, y);
+        END_FUNCTION
+        ",
+    );
+
+    assert!(result.contains("declare void @__plc_assert_failed(i8*, i8*, i32)"));
+    assert_eq!(result.matches("call void @__plc_assert_failed(").count(), 4);
+    assert!(result.contains("fcmp oeq"));
+    // the assertions are generated inline
+    assert!(!result.contains("@ASSERT_TRUE"));
+    assert!(!result.contains("@ASSERT_FALSE"));
+    assert!(!result.contains("@ASSERT_EQUAL"));
+}
+
+#[test]
+fn assertion_runtime_is_only_declared_if_used() {
+    let result = codegen("FUNCTION foo : BOOL END_FUNCTION");
+    assert!(!result.contains("__plc_assert_failed"));
+}
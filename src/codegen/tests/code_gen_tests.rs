@@ -2353,6 +2353,32 @@ fn inline_enums_are_generated() {
     insta::assert_snapshot!(result);
 }
 
+#[test]
+fn enum_conversions_are_generated_inline() {
+    let result = codegen(
+        "
+        TYPE Color : (red := 1, yellow := 2, green := 4) := yellow; END_TYPE
+
+        PROGRAM main
+        VAR
+            c : Color;
+            x : DINT;
+        END_VAR
+            x := Color_TO_DINT(c);
+            c := DINT_TO_Color(x);
+        END_PROGRAM
+        ",
+    );
+
+    // no functions are declared for the conversions
+    assert!(!result.contains("@Color_TO_DINT"), "{result}");
+    assert!(!result.contains("@DINT_TO_Color"), "{result}");
+    // invalid values fall back to the initial value of the enum
+    assert_eq!(result.matches("icmp eq i32").count(), 3, "{result}");
+    assert!(result.contains("select i1"), "{result}");
+    assert!(result.contains("i32 2"), "{result}");
+}
+
 #[test]
 fn basic_datatypes_generated() {
     let result = codegen(
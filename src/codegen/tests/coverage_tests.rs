@@ -0,0 +1,50 @@
+use crate::test_utils::tests::{codegen, codegen_with_coverage};
+
+const SOURCE: &str = r#"
+FUNCTION foo : DINT
+VAR_INPUT x : DINT; END_VAR
+    IF x > 0 THEN
+        foo := 1;
+    ELSE
+        foo := 0;
+    END_IF
+    CASE x OF
+        1: foo := 10;
+    END_CASE
+END_FUNCTION
+"#;
+
+#[test]
+fn coverage_is_not_instrumented_by_default() {
+    let result = codegen(SOURCE);
+    assert!(!result.contains("__plc_coverage"));
+}
+
+#[test]
+fn statements_and_branches_increment_counters() {
+    let result = codegen_with_coverage(SOURCE);
+
+    // IF, then, foo := 1, else, foo := 0, CASE, branch 1, foo := 10
+    assert!(result.contains("@__plc_coverage_counters = private global [8 x i64] zeroinitializer"));
+    for counter in 0..8 {
+        assert!(
+            result.contains(&format!("[8 x i64]* @__plc_coverage_counters, i32 0, i32 {counter})")),
+            "counter {counter} is never incremented:\n{result}"
+        );
+    }
+}
+
+#[test]
+fn counters_are_registered_by_a_module_constructor() {
+    let result = codegen_with_coverage(SOURCE);
+
+    assert!(result.contains("@llvm.global_ctors = appending global"));
+    assert!(result.contains("declare void @__plc_coverage_register(i8*, i64*, i64)"));
+    assert!(result.contains("call void @__plc_coverage_register("));
+}
+
+#[test]
+fn units_without_statements_are_not_instrumented() {
+    let result = codegen_with_coverage("FUNCTION foo : DINT END_FUNCTION");
+    assert!(!result.contains("__plc_coverage"));
+}
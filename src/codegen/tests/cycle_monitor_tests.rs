@@ -0,0 +1,54 @@
+use crate::test_utils::tests::{codegen, codegen_with_cycle_monitor};
+
+const SOURCE: &str = r#"
+{attribute 'cycle_budget' := 'T#5ms'}
+PROGRAM PLC_PRG
+VAR x : DINT; END_VAR
+    IF x > 10 THEN
+        RETURN;
+    END_IF
+    x := x + 1;
+END_PROGRAM
+
+PROGRAM unbounded
+END_PROGRAM
+
+FUNCTION foo : DINT
+END_FUNCTION
+"#;
+
+#[test]
+fn cycles_are_not_monitored_by_default() {
+    let result = codegen(SOURCE);
+    assert!(!result.contains("__plc_cycle"));
+    assert!(!result.contains("__CYCLE_"));
+}
+
+#[test]
+fn programs_export_their_statistics_and_budget() {
+    let result = codegen_with_cycle_monitor(SOURCE);
+
+    assert!(result.contains(
+        "@__CYCLE_PLC_PRG = global [8 x i64] [i64 0, i64 0, i64 0, i64 0, i64 0, i64 0, i64 5000000, i64 0]"
+    ));
+    assert!(result.contains("@__CYCLE_unbounded = global [8 x i64] zeroinitializer"));
+    assert!(!result.contains("__CYCLE_foo"));
+}
+
+#[test]
+fn every_return_of_a_program_reports_its_start_time() {
+    let result = codegen_with_cycle_monitor(SOURCE);
+
+    assert!(result.contains("declare i64 @__plc_cycle_begin()"));
+    assert!(result.contains("declare void @__plc_cycle_end(i8*, i64*, i64)"));
+    // one start per program, the RETURN and the end of PLC_PRG and the end of unbounded report it
+    assert_eq!(result.matches("= call i64 @__plc_cycle_begin()").count(), 2);
+    assert_eq!(result.matches("call void @__plc_cycle_end(").count(), 3);
+    assert!(result.contains("@__CYCLE_PLC_PRG to i64*), i64 %cycle_start)"));
+}
+
+#[test]
+fn units_without_programs_are_not_instrumented() {
+    let result = codegen_with_cycle_monitor("FUNCTION foo : DINT END_FUNCTION");
+    assert!(!result.contains("__plc_cycle"));
+}
@@ -0,0 +1,85 @@
+use crate::test_utils::tests::codegen;
+
+#[test]
+fn new_requests_memory_from_the_allocator_runtime() {
+    let result = codegen(
+        "
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+            values : REF_TO DINT;
+        END_VAR
+            value := __NEW(DINT);
+            values := __NEW(DINT, 10);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(result.matches("call i8* @__plc_alloc(").count(), 2, "{result}");
+    assert!(result.contains(" to i32*"), "{result}");
+    // the built-ins are generated inline
+    assert!(!result.contains("@__NEW"), "{result}");
+}
+
+#[test]
+fn new_copies_the_initializer_of_function_blocks() {
+    let result = codegen(
+        "
+        FUNCTION_BLOCK fb
+        VAR
+            x : DINT := 7;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            instances : REF_TO fb;
+        END_VAR
+            instances := __NEW(fb, 3);
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("call i8* @__plc_alloc("), "{result}");
+    assert!(result.contains("bitcast i8* "), "{result}");
+    assert!(result.contains("@__fb__init"), "{result}");
+    assert!(result.contains("call void @llvm.memcpy"), "{result}");
+}
+
+#[test]
+fn delete_releases_the_memory_and_resets_the_pointer() {
+    let result = codegen(
+        "
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+        END_VAR
+            __DELETE(value);
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("call void @__plc_free("), "{result}");
+    assert!(result.contains("store i32* null, i32** %value"), "{result}");
+}
+
+#[test]
+fn default_allocator_is_only_generated_if_used() {
+    let result = codegen(
+        "
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+        END_VAR
+            value := __NEW(DINT);
+        END_PROGRAM
+        ",
+    );
+    // weak definitions can be replaced by the runtime
+    assert!(result.contains("define weak i8* @__plc_alloc(i64 %0)"), "{result}");
+    assert!(result.contains("define weak void @__plc_free(i8* %0)"), "{result}");
+    assert!(result.contains("declare i8* @calloc(i64, i64)"), "{result}");
+
+    let result = codegen("PROGRAM main END_PROGRAM");
+    assert!(!result.contains("__plc_alloc"), "{result}");
+}
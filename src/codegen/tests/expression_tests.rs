@@ -569,3 +569,72 @@ fn allowed_assignable_types() {
 
     insta::assert_snapshot!(result);
 }
+
+#[test]
+fn struct_literal_with_variables_is_generated_as_temporary() {
+    let result = codegen(
+        r#"
+        TYPE Point : STRUCT
+            x : DINT;
+            y : DINT;
+        END_STRUCT END_TYPE
+
+        PROGRAM main
+        VAR
+            p : Point;
+            i : DINT;
+        END_VAR
+            p := (x := i, y := 2);
+        END_PROGRAM
+        "#,
+    );
+
+    assert!(result.contains("alloca %Point"), "{result}");
+    assert!(result.contains("store i32 2"), "{result}");
+}
+
+#[test]
+fn array_literal_with_repetition_can_be_passed_to_a_function() {
+    let result = codegen(
+        r#"
+        FUNCTION foo : DINT
+        VAR_INPUT
+            values : ARRAY[0..3] OF DINT;
+        END_VAR
+        END_FUNCTION
+
+        PROGRAM main
+        VAR
+            i : DINT;
+        END_VAR
+            foo(values := [i, 3(7)]);
+        END_PROGRAM
+        "#,
+    );
+
+    let (_, main) = result.split_once("define void @main").unwrap();
+    assert!(main.contains("alloca [4 x i32]"), "{result}");
+    assert_eq!(main.matches("store i32 7").count(), 3, "{result}");
+}
+
+#[test]
+fn array_of_struct_literals_with_variables_is_generated_as_temporary() {
+    let result = codegen(
+        r#"
+        TYPE Point : STRUCT
+            x : DINT;
+            y : DINT;
+        END_STRUCT END_TYPE
+
+        PROGRAM main
+        VAR
+            points : ARRAY[0..2] OF Point;
+            i : DINT;
+        END_VAR
+            points := [(x := i, y := 2), (y := i)];
+        END_PROGRAM
+        "#,
+    );
+
+    assert!(result.contains("alloca [3 x %Point]"), "{result}");
+}
@@ -121,3 +121,34 @@ fn any_real_function_called_with_ints() {
     //Expecting to REAL/LREAL conversion for every call
     insta::assert_snapshot!(codegen(src));
 }
+
+#[test]
+fn generic_function_block_is_generated_per_type_argument() {
+    let result = codegen(
+        r"
+        FUNCTION_BLOCK Accumulator<T : ANY_NUM>
+        VAR_INPUT
+            value : T;
+        END_VAR
+        VAR_OUTPUT
+            sum : T;
+        END_VAR
+            sum := sum + value;
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            a : Accumulator<INT>;
+            b : Accumulator<REAL>;
+        END_VAR
+            a(value := 1);
+            b(value := 1.5);
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("%Accumulator__INT = type { i16, i16 }"), "{result}");
+    assert!(result.contains("%Accumulator__REAL = type { float, float }"), "{result}");
+    assert!(result.contains("define void @Accumulator__INT(%Accumulator__INT* %0)"), "{result}");
+    assert!(result.contains("call void @Accumulator__REAL(%Accumulator__REAL* %b)"), "{result}");
+}
@@ -0,0 +1,44 @@
+use crate::test_utils::tests::codegen;
+
+#[test]
+fn jumps_are_generated_as_branches_to_the_label_block() {
+    let result = codegen(
+        "
+        FUNCTION main : DINT
+        VAR
+            x : DINT;
+        END_VAR
+        start:
+            x := x + 1;
+            IF x < 10 THEN
+                JMP start;
+            END_IF
+            JMP done;
+            x := 0;
+        done:
+            main := x;
+        END_FUNCTION
+        ",
+    );
+
+    // the label blocks are entered by falling through and by jumping
+    assert_eq!(result.matches("br label %start").count(), 2, "{result}");
+    assert!(result.contains("\nstart:"), "{result}");
+    assert_eq!(result.matches("br label %done").count(), 2, "{result}");
+    assert!(result.contains("\ndone:"), "{result}");
+}
+
+#[test]
+fn labels_are_case_insensitive() {
+    let result = codegen(
+        "
+        PROGRAM main
+            JMP Skip;
+        SKIP:
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(result.matches("br label %Skip").count(), 2, "{result}");
+    assert!(!result.contains("SKIP"), "{result}");
+}
@@ -0,0 +1,60 @@
+use crate::test_utils::tests::codegen;
+
+#[test]
+fn property_accessors_are_generated_as_methods() {
+    let result = codegen(
+        "
+        FUNCTION_BLOCK MyFb
+        VAR
+            speed_ : INT;
+        END_VAR
+            PROPERTY Speed : INT
+                GET
+                    Speed := speed_;
+                END_GET
+                SET
+                    speed_ := Speed;
+                END_SET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    assert!(result.contains("define i16 @MyFb.__get_Speed(%MyFb* %0, %MyFb.__get_Speed* %1)"), "{result}");
+    assert!(result.contains("define void @MyFb.__set_Speed(%MyFb* %0, %MyFb.__set_Speed* %1)"), "{result}");
+}
+
+#[test]
+fn reading_and_writing_a_property_calls_its_accessors() {
+    let result = codegen(
+        "
+        FUNCTION_BLOCK MyFb
+        VAR
+            speed_ : INT;
+        END_VAR
+            PROPERTY Speed : INT
+                GET
+                    Speed := speed_;
+                END_GET
+                SET
+                    speed_ := Speed;
+                END_SET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            fb : MyFb;
+            x : INT;
+        END_VAR
+            fb.Speed := 10;
+            x := fb.Speed;
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("call void @MyFb.__set_Speed(%MyFb* %fb"), "{result}");
+    assert!(result.contains("call i16 @MyFb.__get_Speed(%MyFb* %fb"), "{result}");
+    // the property is no member of the instance
+    assert!(result.contains("%MyFb = type { i16 }"), "{result}");
+}
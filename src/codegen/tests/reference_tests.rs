@@ -0,0 +1,59 @@
+use crate::test_utils::tests::codegen;
+
+#[test]
+fn reference_assignment_stores_the_address_of_the_target() {
+    let result = codegen(
+        "
+        FUNCTION main : DINT
+        VAR
+            x : DINT;
+            r : REFERENCE TO DINT;
+        END_VAR
+            r REF= x;
+            main := r;
+        END_FUNCTION
+        ",
+    );
+
+    assert!(result.contains("%r = alloca i32*"), "{result}");
+    assert!(result.contains("store i32* %x, i32** %r"), "{result}");
+    // reading the reference loads the bound pointer first
+    assert!(result.contains("load i32*, i32** %r"), "{result}");
+}
+
+#[test]
+fn assignment_to_a_reference_writes_to_the_bound_variable() {
+    let result = codegen(
+        "
+        PROGRAM main
+        VAR
+            x : DINT;
+            r : REFERENCE TO DINT;
+        END_VAR
+            r REF= x;
+            r := 42;
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("store i32* %x, i32** %r"), "{result}");
+    assert!(result.contains("%deref = load i32*, i32** %r"), "{result}");
+    assert!(result.contains("store i32 42, i32* %deref"), "{result}");
+}
+
+#[test]
+fn is_valid_ref_compares_the_reference_with_null() {
+    let result = codegen(
+        "
+        FUNCTION main : BOOL
+        VAR
+            r : REFERENCE TO DINT;
+        END_VAR
+            main := __ISVALIDREF(r);
+        END_FUNCTION
+        ",
+    );
+
+    assert!(result.contains("icmp ne i32* "), "{result}");
+    assert!(!result.contains("@__ISVALIDREF"), "{result}");
+}
@@ -0,0 +1,63 @@
+use crate::test_utils::tests::{codegen, codegen_with_trace};
+
+const SOURCE: &str = r#"
+TYPE Mode : (Off, Manual, Auto); END_TYPE
+
+PROGRAM PLC_PRG
+VAR
+    {attribute 'trace'}
+    on : BOOL;
+    {attribute 'trace'}
+    speed : INT;
+    {attribute 'trace'}
+    level : REAL;
+    {attribute 'trace'}
+    mode : Mode;
+    untraced : DINT;
+END_VAR
+    IF on THEN
+        RETURN;
+    END_IF
+    speed := speed + 1;
+END_PROGRAM
+
+PROGRAM untraced
+VAR x : DINT; END_VAR
+END_PROGRAM
+"#;
+
+#[test]
+fn variables_are_not_traced_by_default() {
+    let result = codegen(SOURCE);
+    assert!(!result.contains("__plc_trace_record"));
+    assert!(!result.contains("trace_description"));
+}
+
+#[test]
+fn every_return_of_a_program_records_its_traced_variables() {
+    let result = codegen_with_trace(SOURCE);
+
+    assert!(result.contains("declare void @__plc_trace_record(i8*, i64*, i64)"));
+    assert!(result.contains(r#"c"PLC_PRG\09on:b:1\09speed:i:16\09level:f:32\09mode:i:32\00""#));
+    assert!(result.contains("%trace_values = alloca [4 x i64]"));
+    // the RETURN and the end of PLC_PRG record the variables, the untraced program is not instrumented
+    assert_eq!(result.matches("call void @__plc_trace_record(").count(), 2);
+    assert_eq!(result.matches("alloca [4 x i64]").count(), 1);
+}
+
+#[test]
+fn values_are_widened_to_64_bits() {
+    let result = codegen_with_trace(SOURCE);
+
+    assert!(result.contains("zext i8 %trace_value to i64"));
+    assert!(result.contains("sext i16 %trace_value1 to i64"));
+    assert!(result.contains("fpext float %trace_value2 to double"));
+    assert!(result.contains("bitcast double %trace_sample"));
+    assert!(result.contains("sext i32 %trace_value3 to i64"));
+}
+
+#[test]
+fn units_without_traced_variables_are_not_instrumented() {
+    let result = codegen_with_trace("PROGRAM prg VAR x : DINT; END_VAR END_PROGRAM");
+    assert!(!result.contains("__plc_trace_record"));
+}
@@ -0,0 +1,67 @@
+use crate::test_utils::tests::{codegen, codegen_without_unwrap};
+
+#[test]
+fn union_is_generated_as_an_aligned_integer_array() {
+    let result = codegen(
+        "
+        TYPE Register : UNION
+            raw : DWORD;
+            bytes : ARRAY[0..3] OF BYTE;
+            wide : LWORD;
+        END_UNION
+        END_TYPE
+
+        VAR_GLOBAL
+            r : Register;
+        END_VAR
+        ",
+    );
+
+    // 8 bytes, aligned like the LWORD member
+    assert!(result.contains("%Register = type { [1 x i64] }"), "{result}");
+    assert!(result.contains("@r = global %Register zeroinitializer"), "{result}");
+}
+
+#[test]
+fn union_members_share_the_union_address() {
+    let result = codegen(
+        "
+        TYPE Register : UNION
+            raw : DWORD;
+            bytes : ARRAY[0..3] OF BYTE;
+        END_UNION
+        END_TYPE
+
+        PROGRAM main
+        VAR
+            r : Register;
+            b : BYTE;
+        END_VAR
+            r.raw := 16#01020304;
+            b := r.bytes[0];
+        END_PROGRAM
+        ",
+    );
+
+    assert!(result.contains("bitcast %Register* %r to i32*"), "{result}");
+    assert!(result.contains("bitcast %Register* %r to [4 x i8]*"), "{result}");
+}
+
+#[test]
+fn union_literals_are_not_supported() {
+    let result = codegen_without_unwrap(
+        "
+        TYPE Register : UNION
+            raw : DWORD;
+            low : WORD;
+        END_UNION
+        END_TYPE
+
+        VAR_GLOBAL
+            r : Register := (raw := 1);
+        END_VAR
+        ",
+    );
+
+    assert!(result.is_err());
+}
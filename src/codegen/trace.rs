@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use inkwell::{
+    module::Module,
+    values::{BasicValueEnum, FunctionValue, IntValue, PointerValue},
+    AddressSpace,
+};
+use plc_ast::ast::{CompilationUnit, LinkageType, PouType, SourceRange};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use super::{
+    generators::{llvm::Llvm, ADDRESS_SPACE_GENERIC},
+    LlvmTypedIndex,
+};
+use crate::{
+    index::Index,
+    trace::{get_description, get_traced_variables, SignalKind, TRACE_RECORD_FUNCTION},
+};
+
+/// A traced variable, identified by its qualified name
+struct TracedVariable {
+    name: String,
+    kind: SignalKind,
+}
+
+/// Records the traced variables of the unit's programs before they return (see [`crate::trace`])
+pub struct TraceInstrumentation<'ink> {
+    programs: HashMap<String, (String, Vec<TracedVariable>)>,
+    record: FunctionValue<'ink>,
+}
+
+/// The description and the sample buffer of a traced program
+pub struct ProgramTrace<'ink, 'b> {
+    description: PointerValue<'ink>,
+    values: PointerValue<'ink>,
+    variables: &'b [TracedVariable],
+    record: FunctionValue<'ink>,
+}
+
+impl<'ink> TraceInstrumentation<'ink> {
+    /// collects the traced variables of the unit's programs, returns `None` if no variable is traced
+    pub fn new(
+        llvm: &Llvm<'ink>,
+        module: &Module<'ink>,
+        unit: &CompilationUnit,
+        index: &Index,
+    ) -> Option<Self> {
+        let programs = unit
+            .implementations
+            .iter()
+            .filter(|it| it.pou_type == PouType::Program && it.linkage != LinkageType::External)
+            .filter_map(|program| {
+                let (variables, signals): (Vec<_>, Vec<_>) = get_traced_variables(&program.name, index)
+                    .into_iter()
+                    .map(|(variable, signal)| {
+                        let kind = signal.kind;
+                        (TracedVariable { name: variable.get_qualified_name().to_string(), kind }, signal)
+                    })
+                    .unzip();
+                (!variables.is_empty()).then(|| {
+                    (program.name.to_lowercase(), (get_description(&program.name, &signals), variables))
+                })
+            })
+            .collect::<HashMap<_, _>>();
+        if programs.is_empty() {
+            return None;
+        }
+
+        let context = llvm.context;
+        let i64_type = context.i64_type();
+        let record = module.get_function(TRACE_RECORD_FUNCTION).unwrap_or_else(|| {
+            let record_type = context.void_type().fn_type(
+                &[
+                    context.i8_type().ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)).into(),
+                    i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)).into(),
+                    i64_type.into(),
+                ],
+                false,
+            );
+            module.add_function(TRACE_RECORD_FUNCTION, record_type, None)
+        });
+        Some(TraceInstrumentation { programs, record })
+    }
+
+    /// allocates the sample buffer of the given program at the builder's current position,
+    /// returns `None` if the POU is not a traced program
+    pub fn begin<'b>(&'b self, llvm: &Llvm<'ink>, program: &str) -> Option<ProgramTrace<'ink, 'b>> {
+        let (description, variables) = self.programs.get(&program.to_lowercase())?;
+        let description = llvm.builder.build_global_string_ptr(description, "trace_description");
+        let values = llvm
+            .builder
+            .build_alloca(llvm.context.i64_type().array_type(variables.len() as u32), "trace_values");
+        Some(ProgramTrace {
+            description: description.as_pointer_value(),
+            values,
+            variables,
+            record: self.record,
+        })
+    }
+}
+
+impl<'ink, 'b> ProgramTrace<'ink, 'b> {
+    /// passes the current values of the traced variables to the runtime at the builder's current position
+    pub fn record(&self, llvm: &Llvm<'ink>, local_index: &LlvmTypedIndex<'ink>) -> Result<(), Diagnostic> {
+        let i32_type = llvm.context.i32_type();
+        let i64_type = llvm.context.i64_type();
+        for (position, variable) in self.variables.iter().enumerate() {
+            let pointer =
+                local_index.find_loaded_associated_variable_value(&variable.name).ok_or_else(|| {
+                    Diagnostic::codegen_error(
+                        &format!("Cannot trace variable {}", variable.name),
+                        SourceRange::undefined(),
+                    )
+                })?;
+            let value = to_sample(llvm, variable.kind, llvm.load_pointer(&pointer, "trace_value"));
+            let slot = unsafe {
+                llvm.builder.build_in_bounds_gep(
+                    self.values,
+                    &[i32_type.const_zero(), i32_type.const_int(position as u64, false)],
+                    "trace_slot",
+                )
+            };
+            llvm.builder.build_store(slot, value);
+        }
+        let values = llvm.builder.build_pointer_cast(
+            self.values,
+            i64_type.ptr_type(AddressSpace::from(ADDRESS_SPACE_GENERIC)),
+            "trace_samples",
+        );
+        let count = i64_type.const_int(self.variables.len() as u64, false);
+        llvm.builder.build_call(self.record, &[self.description.into(), values.into(), count.into()], "");
+        Ok(())
+    }
+}
+
+/// widens the value to 64 bits, floats are passed as the bits of an LREAL
+fn to_sample<'ink>(llvm: &Llvm<'ink>, kind: SignalKind, value: BasicValueEnum<'ink>) -> IntValue<'ink> {
+    let i64_type = llvm.context.i64_type();
+    match kind {
+        SignalKind::Signed => {
+            llvm.builder.build_int_s_extend_or_bit_cast(value.into_int_value(), i64_type, "trace_sample")
+        }
+        SignalKind::Bool | SignalKind::Unsigned => {
+            llvm.builder.build_int_z_extend_or_bit_cast(value.into_int_value(), i64_type, "trace_sample")
+        }
+        SignalKind::Float => {
+            let value = llvm.builder.build_float_cast(
+                value.into_float_value(),
+                llvm.context.f64_type(),
+                "trace_sample",
+            );
+            llvm.builder.build_bitcast(value, i64_type, "trace_sample").into_int_value()
+        }
+    }
+}
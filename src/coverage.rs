@@ -0,0 +1,526 @@
+//! Source based code coverage for structured text
+//!
+//! When compiling with `--coverage`, every statement and every branch of an IF or CASE statement
+//! gets a counter. The [`CoverageMap`] of a compilation unit describes which source lines
+//! each counter belongs to. The map is derived from the AST, so the codegen and the report
+//! always agree on the counter numbering.
+//!
+//! At runtime, the counters of each module are registered with the `__plc_coverage_register`
+//! function of the standard library, which appends them to a profile file when the process exits.
+//! The profile contains one line per module: `<file name>\t<counter> <counter> ...`
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use clap::clap_derive::ArgEnum;
+use plc_ast::{
+    ast::{AstId, AstStatement, CompilationUnit, LinkageType, NewLines, SourceRange},
+    control_statements::{AstControlStatement, ConditionalBlock},
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use serde::{Deserialize, Serialize};
+
+/// The function used to register a module's counters with the coverage runtime
+pub const COVERAGE_REGISTER_FUNCTION: &str = "__plc_coverage_register";
+/// The environment variable used to choose the profile file of the coverage runtime
+pub const COVERAGE_FILE_VARIABLE: &str = "PLC_COVERAGE_FILE";
+pub const DEFAULT_PROFILE_FILE: &str = "default.plcprof";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum CoverageFormat {
+    Lcov,
+    Html,
+}
+
+impl CoverageFormat {
+    pub fn get_default_output(&self) -> &'static str {
+        match self {
+            CoverageFormat::Lcov => "coverage.info",
+            CoverageFormat::Html => "coverage.html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionKind {
+    /// A statement, counted every time it is executed
+    Statement,
+    /// An IF or CASE statement, if the statement has no ELSE block, the count of the implicit else
+    /// branch is derived from this statement's count minus the count of all branches
+    Decision { implicit_else: bool },
+    /// A branch of the decision found at the given region index
+    Branch { decision: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageRegion {
+    pub kind: RegionKind,
+    /// The name of the POU containing the region
+    pub pou: String,
+    /// The first line (1 based) of the region
+    pub start_line: u32,
+    /// The last line (1 based) of the region
+    pub end_line: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CounterKey {
+    Statement(AstId),
+    Branch(AstId),
+    Else(AstId),
+}
+
+/// The coverage regions of a compilation unit, the index of a region is the index of its counter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageMap {
+    pub file_name: String,
+    pub regions: Vec<CoverageRegion>,
+    #[serde(skip)]
+    counters: HashMap<CounterKey, usize>,
+}
+
+impl CoverageMap {
+    /// Collects the coverage regions of all implementations that are generated for this unit
+    pub fn new(unit: &CompilationUnit) -> CoverageMap {
+        let mut map =
+            CoverageMap { file_name: unit.file_name.clone(), regions: Vec::new(), counters: HashMap::new() };
+        for implementation in
+            unit.implementations.iter().filter(|it| !it.generic && it.linkage != LinkageType::External)
+        {
+            let mut visitor =
+                RegionVisitor { map: &mut map, new_lines: &unit.new_lines, pou: &implementation.name };
+            visitor.visit_body(&implementation.statements);
+        }
+        map
+    }
+
+    /// the number of counters needed for this unit
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// returns the counter incremented when the given statement is executed
+    pub fn get_statement_counter(&self, statement: &AstStatement) -> Option<usize> {
+        self.counters.get(&CounterKey::Statement(statement.get_id())).copied()
+    }
+
+    /// returns the counter incremented when the branch guarded by the given condition is taken
+    pub fn get_branch_counter(&self, condition: &AstStatement) -> Option<usize> {
+        self.counters.get(&CounterKey::Branch(condition.get_id())).copied()
+    }
+
+    /// returns the counter incremented when the given (non-empty) ELSE block is entered
+    pub fn get_else_counter(&self, else_block: &[AstStatement]) -> Option<usize> {
+        else_block.first().and_then(|it| self.counters.get(&CounterKey::Else(it.get_id()))).copied()
+    }
+}
+
+struct RegionVisitor<'a> {
+    map: &'a mut CoverageMap,
+    new_lines: &'a NewLines,
+    pou: &'a str,
+}
+
+impl RegionVisitor<'_> {
+    fn add_region(&mut self, key: CounterKey, kind: RegionKind, location: &SourceRange) -> usize {
+        let index = self.map.regions.len();
+        self.map.regions.push(CoverageRegion {
+            kind,
+            pou: self.pou.to_string(),
+            start_line: self.new_lines.get_line_nr(location.get_start()) + 1,
+            end_line: self.new_lines.get_line_nr(location.get_end()) + 1,
+        });
+        self.map.counters.insert(key, index);
+        index
+    }
+
+    fn visit_body(&mut self, statements: &[AstStatement]) {
+        for statement in statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &AstStatement) {
+        let key = CounterKey::Statement(statement.get_id());
+        let location = statement.get_location();
+        match statement {
+            AstStatement::EmptyStatement { .. } => {}
+            AstStatement::ControlStatement { kind, .. } => match kind {
+                AstControlStatement::If(stmt) => {
+                    let kind = RegionKind::Decision { implicit_else: stmt.else_block.is_empty() };
+                    let decision = self.add_region(key, kind, &location);
+                    self.visit_branches(decision, &stmt.blocks, &stmt.else_block);
+                }
+                AstControlStatement::Case(stmt) => {
+                    let kind = RegionKind::Decision { implicit_else: stmt.else_block.is_empty() };
+                    let decision = self.add_region(key, kind, &location);
+                    self.visit_branches(decision, &stmt.case_blocks, &stmt.else_block);
+                }
+                AstControlStatement::ForLoop(stmt) => {
+                    self.add_region(key, RegionKind::Statement, &location);
+                    self.visit_body(&stmt.body);
+                }
+                AstControlStatement::WhileLoop(stmt) | AstControlStatement::RepeatLoop(stmt) => {
+                    self.add_region(key, RegionKind::Statement, &location);
+                    self.visit_body(&stmt.body);
+                }
+            },
+            _ => {
+                self.add_region(key, RegionKind::Statement, &location);
+            }
+        }
+    }
+
+    fn visit_branches(&mut self, decision: usize, blocks: &[ConditionalBlock], else_block: &[AstStatement]) {
+        for block in blocks {
+            let condition = block.condition.as_ref();
+            self.add_region(
+                CounterKey::Branch(condition.get_id()),
+                RegionKind::Branch { decision },
+                &condition.get_location(),
+            );
+            self.visit_body(&block.body);
+        }
+        if let Some(first) = else_block.first() {
+            self.add_region(
+                CounterKey::Else(first.get_id()),
+                RegionKind::Branch { decision },
+                &first.get_location(),
+            );
+            self.visit_body(else_block);
+        }
+    }
+}
+
+/// Parses a profile written by the coverage runtime and adds its counters to the given counters
+/// of each module
+pub fn read_profile(profile: &str, result: &mut HashMap<String, Vec<u64>>) -> Result<(), Diagnostic> {
+    for (line_nr, line) in profile.lines().enumerate().filter(|(_, it)| !it.trim().is_empty()) {
+        let invalid =
+            || Diagnostic::param_error(&format!("Invalid coverage profile entry in line {}", line_nr + 1));
+        let (module, counters) = line.split_once('\t').ok_or_else(invalid)?;
+        let counters = counters
+            .split_whitespace()
+            .map(|it| it.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let entry = result.entry(module.to_string()).or_default();
+        if entry.len() < counters.len() {
+            entry.resize(counters.len(), 0);
+        }
+        entry.iter_mut().zip(counters).for_each(|(sum, count)| *sum += count);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCoverage {
+    pub line: u32,
+    /// the index of the decision in the file
+    pub block: usize,
+    pub branch: usize,
+    /// the number of times the branch was taken, `None` if the decision was never executed
+    pub taken: Option<u64>,
+}
+
+/// The collected coverage of a single source file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    pub file_name: String,
+    /// the execution count per line
+    pub lines: BTreeMap<u32, u64>,
+    pub branches: Vec<BranchCoverage>,
+    /// the POUs defined in the file with their first line and execution count
+    pub functions: BTreeMap<String, (u32, u64)>,
+}
+
+impl FileCoverage {
+    /// Applies the counters of a profile to the regions of a map.
+    /// Missing counters (the module was never loaded) are treated as zero
+    pub fn new(map: &CoverageMap, counters: Option<&Vec<u64>>) -> FileCoverage {
+        let count = |index: usize| counters.and_then(|it| it.get(index)).copied().unwrap_or(0);
+        let mut coverage = FileCoverage { file_name: map.file_name.clone(), ..Default::default() };
+        for (index, region) in map.regions.iter().enumerate() {
+            match region.kind {
+                RegionKind::Statement | RegionKind::Decision { .. } => {
+                    let line = coverage.lines.entry(region.start_line).or_default();
+                    *line = (*line).max(count(index));
+                    let function =
+                        coverage.functions.entry(region.pou.clone()).or_insert((region.start_line, 0));
+                    function.0 = function.0.min(region.start_line);
+                    if function.0 == region.start_line {
+                        function.1 = function.1.max(count(index));
+                    }
+                }
+                RegionKind::Branch { .. } => {}
+            }
+        }
+
+        // collect the branches of every decision, including the implicit else branches
+        for (block, (decision_index, decision)) in map
+            .regions
+            .iter()
+            .enumerate()
+            .filter(|(_, it)| matches!(it.kind, RegionKind::Decision { .. }))
+            .enumerate()
+        {
+            let executed = count(decision_index);
+            let taken = |count: u64| (executed > 0).then_some(count);
+            let branches = map
+                .regions
+                .iter()
+                .enumerate()
+                .filter(|(_, it)| it.kind == RegionKind::Branch { decision: decision_index })
+                .map(|(index, _)| count(index))
+                .collect::<Vec<_>>();
+            for (branch, branch_count) in branches.iter().enumerate() {
+                coverage.branches.push(BranchCoverage {
+                    line: decision.start_line,
+                    block,
+                    branch,
+                    taken: taken(*branch_count),
+                });
+            }
+            if decision.kind == (RegionKind::Decision { implicit_else: true }) {
+                coverage.branches.push(BranchCoverage {
+                    line: decision.start_line,
+                    block,
+                    branch: branches.len(),
+                    taken: taken(executed.saturating_sub(branches.iter().sum())),
+                });
+            }
+        }
+        coverage
+    }
+
+    pub fn get_hit_lines(&self) -> usize {
+        self.lines.values().filter(|it| **it > 0).count()
+    }
+}
+
+/// Creates a report in the lcov tracefile format
+pub fn generate_lcov(coverage: &[FileCoverage]) -> String {
+    let mut report = String::new();
+    for file in coverage {
+        // writing into a string does not fail
+        let _ = write_lcov_record(file, &mut report);
+    }
+    report
+}
+
+fn write_lcov_record(file: &FileCoverage, report: &mut String) -> std::fmt::Result {
+    writeln!(report, "TN:")?;
+    writeln!(report, "SF:{}", file.file_name)?;
+    for (name, (line, _)) in &file.functions {
+        writeln!(report, "FN:{line},{name}")?;
+    }
+    for (name, (_, count)) in &file.functions {
+        writeln!(report, "FNDA:{count},{name}")?;
+    }
+    writeln!(report, "FNF:{}", file.functions.len())?;
+    writeln!(report, "FNH:{}", file.functions.values().filter(|(_, count)| *count > 0).count())?;
+    for branch in &file.branches {
+        let taken = branch.taken.map(|it| it.to_string()).unwrap_or_else(|| "-".to_string());
+        writeln!(report, "BRDA:{},{},{},{taken}", branch.line, branch.block, branch.branch)?;
+    }
+    writeln!(report, "BRF:{}", file.branches.len())?;
+    writeln!(report, "BRH:{}", file.branches.iter().filter(|it| it.taken.unwrap_or(0) > 0).count())?;
+    for (line, count) in &file.lines {
+        writeln!(report, "DA:{line},{count}")?;
+    }
+    writeln!(report, "LF:{}", file.lines.len())?;
+    writeln!(report, "LH:{}", file.get_hit_lines())?;
+    writeln!(report, "end_of_record")
+}
+
+/// Creates a single page html report, `read_source` provides the source code of the covered files
+pub fn generate_html<F>(coverage: &[FileCoverage], read_source: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut report = String::new();
+    // writing into a string does not fail
+    let _ = write_html(coverage, read_source, &mut report);
+    report
+}
+
+fn write_html<F>(coverage: &[FileCoverage], read_source: F, report: &mut String) -> std::fmt::Result
+where
+    F: Fn(&str) -> Option<String>,
+{
+    writeln!(report, "<!DOCTYPE html>")?;
+    writeln!(report, "<html><head><meta charset=\"utf-8\"><title>Coverage Report</title><style>")?;
+    writeln!(report, "body {{ font-family: sans-serif; }} pre {{ margin: 0; }}")?;
+    writeln!(report, "td.count {{ text-align: right; padding-right: 1em; color: #666; }}")?;
+    writeln!(report, "tr.hit {{ background: #dfd; }} tr.missed {{ background: #fdd; }}")?;
+    writeln!(report, "</style></head><body>")?;
+    writeln!(report, "<h1>Coverage Report</h1>")?;
+    writeln!(report, "<table><tr><th>File</th><th>Lines</th><th>Branches</th></tr>")?;
+    for (index, file) in coverage.iter().enumerate() {
+        let branches_hit = file.branches.iter().filter(|it| it.taken.unwrap_or(0) > 0).count();
+        writeln!(
+            report,
+            "<tr><td><a href=\"#file{index}\">{}</a></td><td>{}</td><td>{}</td></tr>",
+            escape_html(&file.file_name),
+            format_ratio(file.get_hit_lines(), file.lines.len()),
+            format_ratio(branches_hit, file.branches.len()),
+        )?;
+    }
+    writeln!(report, "</table>")?;
+    for (index, file) in coverage.iter().enumerate() {
+        writeln!(report, "<h2 id=\"file{index}\">{}</h2>", escape_html(&file.file_name))?;
+        let Some(source) = read_source(&file.file_name) else {
+            writeln!(report, "<p>Source not available</p>")?;
+            continue;
+        };
+        writeln!(report, "<table>")?;
+        for (line_nr, line) in source.lines().enumerate() {
+            let line_nr = line_nr as u32 + 1;
+            let (class, count) = match file.lines.get(&line_nr) {
+                Some(0) => ("missed", "0".to_string()),
+                Some(count) => ("hit", count.to_string()),
+                None => ("", String::new()),
+            };
+            write!(report, "<tr class=\"{class}\"><td class=\"count\">{line_nr}</td>")?;
+            writeln!(
+                report,
+                "<td class=\"count\">{count}</td><td><pre>{}</pre></td></tr>",
+                escape_html(line)
+            )?;
+        }
+        writeln!(report, "</table>")?;
+    }
+    writeln!(report, "</body></html>")
+}
+
+fn format_ratio(hit: usize, total: usize) -> String {
+    if total == 0 {
+        "-".to_string()
+    } else {
+        format!("{hit}/{total} ({:.1}%)", hit as f64 * 100.0 / total as f64)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        coverage::{generate_lcov, read_profile, CoverageMap, FileCoverage, RegionKind},
+        test_utils::tests::parse,
+    };
+
+    const SOURCE: &str = "
+FUNCTION foo : DINT
+VAR_INPUT x : DINT; END_VAR
+    IF x > 0 THEN
+        foo := 1;
+    ELSIF x < 0 THEN
+        foo := -1;
+    END_IF
+    CASE x OF
+        1: foo := 10;
+    ELSE
+        foo := 0;
+    END_CASE
+END_FUNCTION
+";
+
+    #[test]
+    fn regions_are_collected_for_statements_and_branches() {
+        let (unit, _) = parse(SOURCE);
+        let map = CoverageMap::new(&unit);
+        let regions = map.regions.iter().map(|it| (it.kind, it.start_line)).collect::<Vec<_>>();
+
+        assert_eq!(
+            regions,
+            vec![
+                (RegionKind::Decision { implicit_else: true }, 4),
+                (RegionKind::Branch { decision: 0 }, 4),
+                (RegionKind::Statement, 5),
+                (RegionKind::Branch { decision: 0 }, 6),
+                (RegionKind::Statement, 7),
+                (RegionKind::Decision { implicit_else: false }, 9),
+                (RegionKind::Branch { decision: 5 }, 10),
+                (RegionKind::Statement, 10),
+                (RegionKind::Branch { decision: 5 }, 12),
+                (RegionKind::Statement, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn counters_are_found_by_statement() {
+        let (unit, _) = parse(SOURCE);
+        let map = CoverageMap::new(&unit);
+        let statements = &unit.implementations[0].statements;
+
+        assert_eq!(map.get_statement_counter(&statements[0]), Some(0));
+        assert_eq!(map.get_statement_counter(&statements[1]), Some(5));
+    }
+
+    #[test]
+    fn external_implementations_have_no_regions() {
+        let (unit, _) = parse("{external} FUNCTION foo : DINT foo := 1; END_FUNCTION");
+        assert!(CoverageMap::new(&unit).is_empty());
+    }
+
+    #[test]
+    fn profiles_of_multiple_runs_are_summed() {
+        let mut profile = HashMap::new();
+        read_profile("a.st\t1 0 2\nb.st\t3\na.st\t1 1 1\n", &mut profile).unwrap();
+        read_profile("b.st\t2\n", &mut profile).unwrap();
+
+        assert_eq!(profile.get("a.st"), Some(&vec![2, 1, 3]));
+        assert_eq!(profile.get("b.st"), Some(&vec![5]));
+        assert!(read_profile("a.st\t1 x", &mut profile).is_err());
+    }
+
+    #[test]
+    fn lcov_report_contains_lines_and_branches() {
+        let (unit, _) = parse(SOURCE);
+        let map = CoverageMap::new(&unit);
+        // foo called twice with x = 1
+        let counters = vec![2, 2, 2, 0, 0, 2, 2, 2, 0, 0];
+        let mut profile = HashMap::new();
+        profile.insert(map.file_name.clone(), counters);
+
+        let coverage = FileCoverage::new(&map, profile.get(&map.file_name));
+        let report = generate_lcov(&[coverage]);
+
+        assert_eq!(
+            report,
+            "TN:
+SF:test.st
+FN:4,foo
+FNDA:2,foo
+FNF:1
+FNH:1
+BRDA:4,0,0,2
+BRDA:4,0,1,0
+BRDA:4,0,2,0
+BRDA:9,1,0,2
+BRDA:9,1,1,0
+BRF:5
+BRH:2
+DA:4,2
+DA:5,2
+DA:7,0
+DA:9,2
+DA:10,2
+DA:12,0
+LF:6
+LH:4
+end_of_record
+"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Cycle time monitoring of programs compiled with `--monitor-cycles`.
+//!
+//! The body of every PROGRAM is wrapped with calls to the runtime of the standard library: the
+//! program takes a timestamp when it is entered and reports it together with its statistics before
+//! it returns. The runtime measures the time using the clock of the timers (`CLOCK_NOW`) and keeps the
+//! statistics of every program in the exported variable `__CYCLE_<program>`:
+//!
+//! ```c
+//! struct CycleStatistics {
+//!     uint64_t cycles;   // the number of completed calls
+//!     int64_t last;      // the execution times in nanoseconds
+//!     int64_t min;
+//!     int64_t max;
+//!     int64_t average;
+//!     int64_t total;
+//!     int64_t budget;    // 0 if the program has no budget
+//!     uint64_t overruns; // the number of calls exceeding the budget
+//! };
+//! ```
+//!
+//! The budget of a program is configured with `{attribute 'cycle_budget' := 'T#5ms'}`. Calls exceeding
+//! the budget are passed to the watchdog handler of the runtime.
+use plc_ast::{
+    ast::{AstStatement, Attribute},
+    literals::AstLiteral,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::{
+    lexer::{self, Token},
+    parser::expressions_parser::parse_expression,
+};
+
+/// `__plc_cycle_begin() -> i64`, returns the timestamp taken when a program is entered
+pub const CYCLE_BEGIN_FUNCTION: &str = "__plc_cycle_begin";
+/// `__plc_cycle_end(program: *const i8, statistics: *mut i64, start: i64)`, updates the statistics
+/// of a program before it returns
+pub const CYCLE_END_FUNCTION: &str = "__plc_cycle_end";
+/// The attribute configuring the budget of a program
+pub const CYCLE_BUDGET_ATTRIBUTE: &str = "cycle_budget";
+/// The number of 64 bit fields of the statistics
+pub const CYCLE_STATISTICS_FIELDS: u32 = 8;
+/// The position of the budget in the statistics
+pub const CYCLE_BUDGET_FIELD: u32 = 6;
+
+/// Returns the name of the exported statistics of the given program
+pub fn get_statistics_name(program: &str) -> String {
+    format!("__CYCLE_{program}")
+}
+
+/// Returns the budget configured with the `cycle_budget` attribute in nanoseconds, `None` if the
+/// program has no budget
+pub fn get_budget(attributes: &[Attribute]) -> Option<Result<i64, Diagnostic>> {
+    let attribute = attributes.iter().find(|it| it.name.eq_ignore_ascii_case(CYCLE_BUDGET_ATTRIBUTE))?;
+    let value = attribute.value.as_deref().unwrap_or_default();
+    let mut session = lexer::lex(value);
+    let budget = match parse_expression(&mut session) {
+        AstStatement::Literal { kind: AstLiteral::Time(time), .. }
+            if session.token == Token::End && session.diagnostics.is_empty() =>
+        {
+            Some(time.value())
+        }
+        _ => None,
+    };
+    Some(
+        budget
+            .filter(|it| *it > 0)
+            .ok_or_else(|| Diagnostic::invalid_cycle_budget(value, attribute.location.clone())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::ast::{Attribute, SourceRange};
+
+    use super::get_budget;
+
+    fn budget(value: Option<&str>) -> Option<Result<i64, String>> {
+        let attribute = Attribute {
+            name: "cycle_budget".to_string(),
+            value: value.map(str::to_string),
+            location: SourceRange::undefined(),
+        };
+        get_budget(&[attribute]).map(|it| it.map_err(|err| err.get_message().to_string()))
+    }
+
+    #[test]
+    fn budgets_are_time_literals() {
+        assert_eq!(budget(Some("T#5ms")), Some(Ok(5_000_000)));
+        assert_eq!(budget(Some("LTIME#1s500ms")), Some(Ok(1_500_000_000)));
+        assert!(get_budget(&[]).is_none());
+    }
+
+    #[test]
+    fn invalid_budgets_are_reported() {
+        for value in [None, Some("5"), Some("T#5ms + T#1ms"), Some("T#0s"), Some("T#-1ms")] {
+            assert_eq!(
+                budget(value),
+                Some(Err(format!(
+                    "Invalid cycle budget '{}', expected a positive TIME literal (e.g. T#5ms)",
+                    value.unwrap_or_default()
+                )))
+            );
+        }
+    }
+}
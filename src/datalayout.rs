@@ -188,4 +188,31 @@ mod tests {
         assert_eq!(struct_type.get_size(&index).bits(), 192);
         assert_eq!(struct_type.get_alignment(&index), Bytes::new(8)) //Struct alignment is 64 by default
     }
+
+    #[test]
+    fn union_is_as_large_as_its_largest_member() {
+        //Given the default data layout
+        //When a union with different member sizes is created
+        let (_, index) = index(
+            "
+        TYPE MyStruct : STRUCT
+            a : DWORD; //32bit - offset 0 -> 32
+            b : WORD; //16bit - offset 32 -> 48
+        END_STRUCT
+        END_TYPE
+
+        TYPE MyUnion : UNION
+            a : BYTE; //8bit
+            b : DWORD; //32bit
+            c : MyStruct; //48bit, aligned to 64
+        END_UNION
+        END_TYPE
+        ",
+        );
+
+        let union_type = index.get_effective_type_by_name("MyUnion").unwrap().get_type_information();
+        // The union size is the largest member's size, rounded up to the largest alignment
+        assert_eq!(union_type.get_size(&index).bits(), 64);
+        assert_eq!(union_type.get_alignment(&index), Bytes::new(8))
+    }
 }
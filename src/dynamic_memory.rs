@@ -0,0 +1,21 @@
+//! Support for dynamic memory.
+//!
+//! The `__NEW` and `__DELETE` built-ins request memory from and return it to the allocator runtime
+//! through [`ALLOC_FUNCTION`] and [`FREE_FUNCTION`]. Every module that uses dynamic memory contains
+//! weak default implementations based on `calloc` and `free`, a runtime can replace them by linking
+//! its own definitions of these symbols.
+
+/// The runtime function that allocates memory: `__plc_alloc(size: u64) -> *mut i8`
+///
+/// The returned memory must be zero-initialized, a null pointer is returned if no memory is available.
+pub const ALLOC_FUNCTION: &str = "__plc_alloc";
+
+/// The runtime function that releases memory allocated by [`ALLOC_FUNCTION`]: `__plc_free(ptr: *mut i8)`
+pub const FREE_FUNCTION: &str = "__plc_free";
+
+const DYNAMIC_MEMORY_BUILTINS: [&str; 2] = ["__NEW", "__DELETE"];
+
+/// returns true if the given call name is one of the built-ins that use dynamic memory
+pub fn is_dynamic_memory_builtin(name: &str) -> bool {
+    DYNAMIC_MEMORY_BUILTINS.iter().any(|it| it.eq_ignore_ascii_case(name))
+}
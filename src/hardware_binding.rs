@@ -1,11 +1,15 @@
-use plc_ast::ast::{DirectAccessType, HardwareAccessType};
+use plc_ast::ast::{Attribute, DirectAccessType, HardwareAccessType};
 use plc_diagnostics::{diagnostics::Diagnostic, errno::ErrNo};
 use serde::{
     ser::{SerializeSeq, SerializeStruct},
     Serialize, Serializer,
 };
 
-use crate::{expression_path::ExpressionPath, index::Index, ConfigFormat};
+use crate::{
+    expression_path::ExpressionPath,
+    index::{self, Index},
+    ConfigFormat,
+};
 
 trait SerializeWithContext {
     fn serialize<S>(&self, ctx: &Index, serializer: S) -> Result<S::Ok, S::Error>
@@ -32,6 +36,7 @@ where
 pub struct HardwareConfiguration<'idx> {
     index: &'idx Index,
     hardware_binding: Vec<HardwareBinding<'idx>>,
+    attributes: Vec<AttributeBinding<'idx>>,
 }
 
 struct WithContext<'a, T: SerializeWithContext> {
@@ -66,8 +71,15 @@ impl Serialize for HardwareConfiguration<'_> {
     {
         let bindings: Vec<WithContext<HardwareBinding>> =
             self.hardware_binding.iter().map(|it| WithContext::new(self.index, it)).collect();
-        let mut config = serializer.serialize_struct("Configuration", 1)?;
+        let attributes: Vec<WithContext<AttributeBinding>> =
+            self.attributes.iter().map(|it| WithContext::new(self.index, it)).collect();
+        //only mention attributes if there are any to keep the configuration of plain projects unchanged
+        let mut config =
+            serializer.serialize_struct("Configuration", if attributes.is_empty() { 1 } else { 2 })?;
         config.serialize_field("HardwareConfiguration", &bindings)?;
+        if !attributes.is_empty() {
+            config.serialize_field("Attributes", &attributes)?;
+        }
         config.end()
     }
 }
@@ -83,12 +95,27 @@ impl<'idx> HardwareBinding<'idx> {
     fn expand(&self, index: &'idx Index) -> Vec<ExpandedHardwareBinding> {
         let names = self.qualifed_name.expand(index);
         names
-            .iter()
-            .map(|it| ExpandedHardwareBinding {
-                name: it.clone(),
-                direction: self.direction,
-                access_type: self.access_type,
-                address: self.address.clone(),
+            .into_iter()
+            .map(|name| {
+                // template addresses are listed with the address assigned in a VAR_CONFIG block
+                let configured = index
+                    .find_config_variable(&name)
+                    .filter(|_| self.access_type == DirectAccessType::Template)
+                    .map(|it| it.get_hardware_binding());
+                match configured.map(|binding| (binding, evaluate_address(binding, index))) {
+                    Some((binding, Ok(address))) => ExpandedHardwareBinding {
+                        name,
+                        direction: binding.direction,
+                        access_type: binding.access,
+                        address,
+                    },
+                    _ => ExpandedHardwareBinding {
+                        name,
+                        direction: self.direction,
+                        access_type: self.access_type,
+                        address: self.address.clone(),
+                    },
+                }
             })
             .collect()
     }
@@ -118,6 +145,55 @@ impl SerializeWithContext for HardwareBinding<'_> {
     }
 }
 
+/// The user defined attributes (`{attribute 'name' := 'value'}`) of a variable
+#[derive(Debug, PartialEq, Eq)]
+pub struct AttributeBinding<'idx> {
+    qualifed_name: ExpressionPath<'idx>,
+    attributes: &'idx [Attribute],
+}
+
+impl<'idx> AttributeBinding<'idx> {
+    fn expand(&self, index: &'idx Index) -> Vec<ExpandedAttributeBinding> {
+        let attributes: Vec<ExpandedAttribute> = self
+            .attributes
+            .iter()
+            .map(|it| ExpandedAttribute { name: it.name.clone(), value: it.value.clone() })
+            .collect();
+        self.qualifed_name
+            .expand(index)
+            .into_iter()
+            .map(|name| ExpandedAttributeBinding { name, attributes: attributes.clone() })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+struct ExpandedAttributeBinding {
+    name: String,
+    attributes: Vec<ExpandedAttribute>,
+}
+
+#[derive(Serialize, Clone)]
+struct ExpandedAttribute {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+}
+
+impl SerializeWithContext for AttributeBinding<'_> {
+    fn serialize<S>(&self, ctx: &Index, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let bindings = self.expand(ctx);
+        let mut ser = serializer.serialize_seq(Some(bindings.len()))?;
+        for binding in bindings {
+            ser.serialize_element(&binding)?;
+        }
+        ser.end()
+    }
+}
+
 /// Retrieves hardware bindings from all defined instances in the program
 pub fn collect_hardware_configuration(index: &Index) -> Result<HardwareConfiguration, Diagnostic> {
     let conf: Result<Vec<HardwareBinding>, String> = index
@@ -126,25 +202,37 @@ pub fn collect_hardware_configuration(index: &Index) -> Result<HardwareConfigura
         .filter(|(_, instance)| instance.has_hardware_binding())
         .map(|(name, instance)| {
             let binding = instance.get_hardware_binding().expect("Instance should have a binding");
-            binding
-                .entries
-                .iter()
-                .map(|it| index.get_const_expressions().get_constant_int_statement_value(it))
-                .map(|it| it.map(|it| it.to_string()))
-                .collect::<Result<Vec<String>, String>>()
-                .map(|address| HardwareBinding {
-                    qualifed_name: name,
-                    access_type: binding.access,
-                    address,
-                    direction: binding.direction,
-                })
+            evaluate_address(binding, index).map(|address| HardwareBinding {
+                qualifed_name: name,
+                access_type: binding.access,
+                address,
+                direction: binding.direction,
+            })
+        })
+        .collect();
+
+    let attributes = index
+        .find_instances()
+        .filter(|(_, instance)| !instance.get_attributes().is_empty())
+        .map(|(name, instance)| AttributeBinding {
+            qualifed_name: name,
+            attributes: instance.get_attributes(),
         })
         .collect();
 
-    conf.map(|hardware_binding| HardwareConfiguration { index, hardware_binding })
+    conf.map(|hardware_binding| HardwareConfiguration { index, hardware_binding, attributes })
         .map_err(|message| Diagnostic::GeneralError { err_no: ErrNo::general__io_err, message })
 }
 
+fn evaluate_address(binding: &index::HardwareBinding, index: &Index) -> Result<Vec<String>, String> {
+    binding
+        .entries
+        .iter()
+        .map(|it| index.get_const_expressions().get_constant_int_statement_value(it))
+        .map(|it| it.map(|it| it.to_string()))
+        .collect()
+}
+
 pub fn generate_hardware_configuration(
     config: &HardwareConfiguration,
     format: ConfigFormat,
@@ -279,4 +367,82 @@ mod tests {
         let res = generate_hardware_configuration(&config, ConfigFormat::TOML).unwrap();
         insta::assert_snapshot!(res);
     }
+
+    #[test]
+    fn configured_template_addresses_are_printed() {
+        let (_, index) = index(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            a AT %I*: DWORD;
+            b AT %Q*: BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL
+            aFb : ARRAY[0..1] OF fb;
+        END_VAR
+        VAR_CONFIG
+            aFb[0].a AT %ID4 : DWORD;
+            aFb[1].b AT %QX2.1 : BOOL;
+        END_VAR",
+        );
+        let config = collect_hardware_configuration(&index).unwrap();
+        let res = generate_hardware_configuration(&config, ConfigFormat::JSON).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&res).unwrap();
+        let bindings = json["HardwareConfiguration"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|it| it.as_array().unwrap().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bindings,
+            vec![
+                serde_json::json!({ "name": "aFb[0].a", "direction": "Input", "type": "DWord", "address": ["4"] }),
+                serde_json::json!({ "name": "aFb[1].a", "direction": "Input", "type": "Template", "address": [] }),
+                serde_json::json!({ "name": "aFb[0].b", "direction": "Output", "type": "Template", "address": [] }),
+                serde_json::json!({ "name": "aFb[1].b", "direction": "Output", "type": "Bit", "address": ["2", "1"] }),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_are_printed_with_the_hardware_configuration() {
+        let (_, index) = index(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            {attribute 'opc'}
+            x : INT;
+            y : INT;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL
+            {attribute 'unit' := 'mm'}
+            a AT %IW1 : INT;
+            aFb : ARRAY[0..1] OF fb;
+        END_VAR",
+        );
+        let config = collect_hardware_configuration(&index).unwrap();
+        let res = generate_hardware_configuration(&config, ConfigFormat::JSON).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&res).unwrap();
+        let mut attributes = json["Attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|it| it.as_array().unwrap().clone())
+            .collect::<Vec<_>>();
+        attributes.sort_by_key(|it| it["name"].as_str().unwrap().to_string());
+        assert_eq!(
+            attributes,
+            vec![
+                serde_json::json!({ "name": "a", "attributes": [{ "name": "unit", "value": "mm" }] }),
+                serde_json::json!({ "name": "aFb[0].x", "attributes": [{ "name": "opc" }] }),
+                serde_json::json!({ "name": "aFb[1].x", "attributes": [{ "name": "opc" }] }),
+            ]
+        );
+        //the TOML output carries the same attributes
+        let res = generate_hardware_configuration(&config, ConfigFormat::TOML).unwrap();
+        assert!(res.contains("Attributes") && res.contains("unit") && res.contains("opc"));
+    }
 }
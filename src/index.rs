@@ -7,8 +7,8 @@ use crate::{
 use indexmap::IndexMap;
 use itertools::Itertools;
 use plc_ast::ast::{
-    AstStatement, DirectAccessType, GenericBinding, HardwareAccessType, LinkageType, PouType, SourceRange,
-    TypeNature,
+    AccessModifier, AccessorKind, AstStatement, Attribute, DirectAccessType, GenericBinding,
+    HardwareAccessType, LinkageType, PouType, PropertyAccessor, SourceRange, TypeNature,
 };
 use plc_diagnostics::diagnostics::Diagnostic;
 use plc_util::convention::qualified_name;
@@ -50,6 +50,8 @@ pub struct VariableIndexEntry {
     pub source_location: SymbolLocation,
     /// Variadic information placeholder for the variable, if any
     varargs: Option<VarArgs>,
+    /// the user defined attributes of the variable (`{attribute 'name' := 'value'}`)
+    attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -97,6 +99,7 @@ pub struct MemberInfo<'b> {
     binding: Option<HardwareBinding>,
     is_constant: bool,
     varargs: Option<VarArgs>,
+    attributes: Vec<Attribute>,
 }
 
 impl VariableIndexEntry {
@@ -120,6 +123,7 @@ impl VariableIndexEntry {
             binding: None,
             source_location,
             varargs: None,
+            attributes: vec![],
         }
     }
 
@@ -141,6 +145,7 @@ impl VariableIndexEntry {
             binding: None,
             source_location,
             varargs: None,
+            attributes: vec![],
         }
     }
 
@@ -169,6 +174,11 @@ impl VariableIndexEntry {
         self
     }
 
+    pub fn set_attributes(mut self, attributes: Vec<Attribute>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     /// Creates a new VariableIndexEntry from the current entry with a new container and type
     /// This is used to create new entries from previously generic entries
     pub fn into_typed(&self, container: &str, new_type: &str) -> Self {
@@ -263,6 +273,10 @@ impl VariableIndexEntry {
         self.varargs.as_ref()
     }
 
+    pub fn get_attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
     fn has_parent(&self, context: &str) -> bool {
         let name = qualified_name(context, &self.name);
         self.qualified_name.eq_ignore_ascii_case(&name)
@@ -384,6 +398,85 @@ impl From<&PouType> for ImplementationType {
     }
 }
 
+/// a property of a class or function block. A property is no member of the instance, reading
+/// and writing it calls the property's getter and setter methods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyIndexEntry {
+    name: String,
+    owner: String,
+    data_type: String,
+    access: AccessModifier,
+    getter: Option<String>,
+    setter: Option<String>,
+    location: SymbolLocation,
+}
+
+impl PropertyIndexEntry {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// returns the qualified name of the property (e.g. `MyFb.Speed`)
+    pub fn get_qualified_name(&self) -> String {
+        qualified_name(&self.owner, &self.name)
+    }
+
+    pub fn get_owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn get_type_name(&self) -> &str {
+        &self.data_type
+    }
+
+    pub fn get_access(&self) -> AccessModifier {
+        self.access
+    }
+
+    /// the name of the method implementing the GET accessor, if there is one
+    pub fn get_getter(&self) -> Option<&str> {
+        self.getter.as_deref()
+    }
+
+    /// the name of the method implementing the SET accessor, if there is one
+    pub fn get_setter(&self) -> Option<&str> {
+        self.setter.as_deref()
+    }
+
+    pub fn get_location(&self) -> &SymbolLocation {
+        &self.location
+    }
+}
+
+/// an address assigned to a template variable (`AT %I*`) of an instance in a `VAR_CONFIG` block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigVariableIndexEntry {
+    /// the configured instance as it is listed in the hardware configuration (e.g. `prg.fbs[1].a`)
+    instance_path: String,
+    /// the declared datatype, None for inline type definitions
+    data_type: Option<String>,
+    binding: HardwareBinding,
+    location: SourceRange,
+}
+
+impl ConfigVariableIndexEntry {
+    pub fn get_instance_path(&self) -> &str {
+        &self.instance_path
+    }
+
+    pub fn get_type_name(&self) -> Option<&str> {
+        self.data_type.as_deref()
+    }
+
+    pub fn get_hardware_binding(&self) -> &HardwareBinding {
+        &self.binding
+    }
+
+    pub fn get_location(&self) -> &SourceRange {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum PouIndexEntry {
     Program {
@@ -392,12 +485,14 @@ pub enum PouIndexEntry {
         instance_variable: VariableIndexEntry,
         linkage: LinkageType,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
     },
     FunctionBlock {
         name: String,
         instance_struct_name: String,
         linkage: LinkageType,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
         super_class: Option<String>,
     },
     Function {
@@ -407,6 +502,7 @@ pub enum PouIndexEntry {
         linkage: LinkageType,
         is_variadic: bool,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
         is_generated: bool, // true if this entry was added automatically (e.g. by generics)
     },
     Class {
@@ -414,6 +510,7 @@ pub enum PouIndexEntry {
         instance_struct_name: String,
         linkage: LinkageType,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
         super_class: Option<String>,
     },
     Method {
@@ -423,6 +520,7 @@ pub enum PouIndexEntry {
         instance_struct_name: String,
         linkage: LinkageType,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
     },
     Action {
         name: String,
@@ -430,6 +528,7 @@ pub enum PouIndexEntry {
         instance_struct_name: String,
         linkage: LinkageType,
         location: SymbolLocation,
+        attributes: Vec<Attribute>,
     },
 }
 
@@ -450,6 +549,7 @@ impl PouIndexEntry {
             instance_variable,
             linkage,
             location,
+            attributes: vec![],
         }
     }
 
@@ -468,6 +568,7 @@ impl PouIndexEntry {
             instance_struct_name: pou_name.into(),
             linkage,
             location,
+            attributes: vec![],
             super_class: super_class.map(|s| s.to_owned()),
         }
     }
@@ -488,6 +589,7 @@ impl PouIndexEntry {
             linkage,
             is_variadic,
             location,
+            attributes: vec![],
             is_generated: false,
         }
     }
@@ -509,6 +611,7 @@ impl PouIndexEntry {
             linkage,
             is_variadic,
             location,
+            attributes: vec![],
             is_generated: true,
         }
     }
@@ -529,6 +632,7 @@ impl PouIndexEntry {
             instance_struct_name: pou_name.into(),
             linkage,
             location,
+            attributes: vec![],
         }
     }
 
@@ -546,6 +650,7 @@ impl PouIndexEntry {
             instance_struct_name: pou_name.into(),
             linkage,
             location,
+            attributes: vec![],
             super_class,
         }
     }
@@ -569,6 +674,7 @@ impl PouIndexEntry {
             return_type: return_type.into(),
             linkage,
             location,
+            attributes: vec![],
         }
     }
 
@@ -584,6 +690,31 @@ impl PouIndexEntry {
         }
     }
 
+    /// returns the user defined attributes of this pou (`{attribute 'name' := 'value'}`)
+    pub fn get_attributes(&self) -> &[Attribute] {
+        match self {
+            PouIndexEntry::Program { attributes, .. }
+            | PouIndexEntry::FunctionBlock { attributes, .. }
+            | PouIndexEntry::Function { attributes, .. }
+            | PouIndexEntry::Method { attributes, .. }
+            | PouIndexEntry::Action { attributes, .. }
+            | PouIndexEntry::Class { attributes, .. } => attributes,
+        }
+    }
+
+    /// replaces the user defined attributes of this pou
+    pub fn with_attributes(mut self, new_attributes: Vec<Attribute>) -> Self {
+        match &mut self {
+            PouIndexEntry::Program { attributes, .. }
+            | PouIndexEntry::FunctionBlock { attributes, .. }
+            | PouIndexEntry::Function { attributes, .. }
+            | PouIndexEntry::Method { attributes, .. }
+            | PouIndexEntry::Action { attributes, .. }
+            | PouIndexEntry::Class { attributes, .. } => *attributes = new_attributes,
+        }
+        self
+    }
+
     /// returns the super class of this pou if supported
     pub fn get_super_class(&self) -> Option<&str> {
         match self {
@@ -796,6 +927,13 @@ pub struct Index {
     // is handled by the `pous` SymbolMap
     implementations: IndexMap<String, ImplementationIndexEntry>,
 
+    /// all properties with their qualified names <owner>.<property-name>
+    // the accessors of a property are registered as methods, duplicates are reported on them
+    properties: IndexMap<String, PropertyIndexEntry>,
+
+    /// all addresses assigned in `VAR_CONFIG` blocks with the lowercase path of their instance
+    config_variables: SymbolMap<String, ConfigVariableIndexEntry>,
+
     /// an index with all type-information
     type_index: TypeIndex,
 
@@ -903,6 +1041,21 @@ impl Index {
         //implementations
         self.implementations.extend(other.implementations);
 
+        //properties
+        self.properties.extend(other.properties);
+
+        //config variables
+        for (name, elements) in other.config_variables.drain(..) {
+            let elements = elements
+                .into_iter()
+                .map(|mut e| {
+                    e.binding = self.transfer_binding(&e.binding, &mut other.constant_expressions);
+                    e
+                })
+                .collect::<Vec<_>>();
+            self.config_variables.insert_many(name, elements);
+        }
+
         //pous
         for (name, elements) in other.pous.drain(..) {
             for ele in elements {
@@ -924,27 +1077,31 @@ impl Index {
     ) -> VariableIndexEntry {
         variable.initial_value = self.maybe_import_const_expr(import_from, &variable.initial_value);
 
-        let binding = if let Some(HardwareBinding { direction, access, entries, location }) =
-            variable.get_hardware_binding()
-        {
-            let mut new_entries = vec![];
-            for entry in entries {
-                if let Some(e) = self.maybe_import_const_expr(import_from, &Some(*entry)) {
-                    new_entries.push(e);
-                }
-            }
-            Some(HardwareBinding {
-                direction: *direction,
-                access: *access,
-                entries: new_entries,
-                location: location.clone(),
-            })
-        } else {
-            None
-        };
+        let binding = variable.get_hardware_binding().map(|it| self.transfer_binding(it, import_from));
         variable.set_hardware_binding(binding)
     }
 
+    /// imports the constant expressions of the given binding's address
+    fn transfer_binding(
+        &mut self,
+        binding: &HardwareBinding,
+        import_from: &mut ConstExpressions,
+    ) -> HardwareBinding {
+        let HardwareBinding { direction, access, entries, location } = binding;
+        let mut new_entries = vec![];
+        for entry in entries {
+            if let Some(e) = self.maybe_import_const_expr(import_from, &Some(*entry)) {
+                new_entries.push(e);
+            }
+        }
+        HardwareBinding {
+            direction: *direction,
+            access: *access,
+            entries: new_entries,
+            location: location.clone(),
+        }
+    }
+
     /// imports the corresponding const-expression (according to the given initializer-id) from the given ConstExpressions
     /// into self's const-expressions and returns the new Id
     fn maybe_import_const_expr(
@@ -1058,6 +1215,43 @@ impl Index {
         }
     }
 
+    /// Searches for the property in the given container, if not found, attempts to search for it in super class
+    pub fn find_property(&self, container_name: &str, property_name: &str) -> Option<&PropertyIndexEntry> {
+        if let Some(property) =
+            self.properties.get(&qualified_name(container_name, property_name).to_lowercase())
+        {
+            Some(property)
+        } else if let Some(super_class) = self.find_pou(container_name).and_then(|it| it.get_super_class()) {
+            self.find_property(super_class, property_name)
+        } else {
+            None
+        }
+    }
+
+    /// returns the address assigned to the given instance (e.g. `prg.fbs[1].a`) in a `VAR_CONFIG` block
+    pub fn find_config_variable(&self, instance_path: &str) -> Option<&ConfigVariableIndexEntry> {
+        self.config_variables.get(&instance_path.to_lowercase())
+    }
+
+    pub fn get_config_variables(&self) -> &SymbolMap<String, ConfigVariableIndexEntry> {
+        &self.config_variables
+    }
+
+    /// returns the hardware binding of the given instance of a variable. Template addresses (`AT %I*`)
+    /// are resolved with the address assigned in a `VAR_CONFIG` block, None if there is none
+    pub fn get_instance_binding<'a>(
+        &'a self,
+        instance_path: &str,
+        variable: &'a VariableIndexEntry,
+    ) -> Option<&'a HardwareBinding> {
+        let binding = variable.get_hardware_binding()?;
+        if binding.access == DirectAccessType::Template {
+            self.find_config_variable(instance_path).map(ConfigVariableIndexEntry::get_hardware_binding)
+        } else {
+            Some(binding)
+        }
+    }
+
     /// return the `VariableIndexEntry` associated with the given fully qualified name using `.` as
     /// a delimiter. (e.g. "PLC_PRG.x", or "MyClass.MyMethod.x")
     pub fn find_fully_qualified_variable(&self, fully_qualified_name: &str) -> Option<&VariableIndexEntry> {
@@ -1103,6 +1297,13 @@ impl Index {
         self.enum_qualified_variables.get(&qualified_name(enum_name, element_name).to_lowercase())
     }
 
+    /// returns the value of the enum-element `element_name` of the enum-type `enum_name`
+    /// or None if the element does not exist or its value cannot be resolved
+    pub fn find_enum_element_value(&self, enum_name: &str, element_name: &str) -> Option<i128> {
+        let initial_value = self.find_enum_element(enum_name, element_name)?.initial_value?;
+        self.get_const_expressions().get_constant_int_statement_value(&initial_value).ok()
+    }
+
     /// returns the index entry of the enum-element denoted by the given fully `qualified_name` (e.g. "Color.RED")
     /// or None if the requested Enum-Type or -Element does not exist
     pub fn find_qualified_enum_element(&self, qualified_name: &str) -> Option<&VariableIndexEntry> {
@@ -1334,12 +1535,19 @@ impl Index {
         self.pous.get(&pou_name.to_lowercase())
     }
 
-    pub fn register_program(&mut self, name: &str, location: SymbolLocation, linkage: LinkageType) {
+    pub fn register_program(
+        &mut self,
+        name: &str,
+        location: SymbolLocation,
+        linkage: LinkageType,
+        attributes: Vec<Attribute>,
+    ) {
         let instance_variable =
             VariableIndexEntry::create_global(&format!("{}_instance", &name), name, name, location.clone()) // TODO: Naming convention (see plc_util/src/convention.rs)
                 .set_linkage(linkage);
         // self.register_global_variable(name, instance_variable.clone());
-        let entry = PouIndexEntry::create_program_entry(name, instance_variable, linkage, location);
+        let entry = PouIndexEntry::create_program_entry(name, instance_variable, linkage, location)
+            .with_attributes(attributes);
         self.pous.insert(entry.get_name().to_lowercase(), entry);
     }
 
@@ -1355,6 +1563,33 @@ impl Index {
         self.find_pou(pou_name).and_then(|it| it.find_implementation(self))
     }
 
+    /// registers the given accessor method of a property. The property is registered with its first accessor
+    pub fn register_property_accessor(
+        &mut self,
+        owner: &str,
+        accessor: &PropertyAccessor,
+        method_name: &str,
+        type_name: &str,
+        location: SymbolLocation,
+    ) {
+        let property = self
+            .properties
+            .entry(qualified_name(owner, &accessor.property).to_lowercase())
+            .or_insert_with(|| PropertyIndexEntry {
+                name: accessor.property.clone(),
+                owner: owner.to_string(),
+                data_type: type_name.to_string(),
+                access: accessor.access,
+                getter: None,
+                setter: None,
+                location,
+            });
+        match accessor.kind {
+            AccessorKind::Get => property.getter = Some(method_name.to_string()),
+            AccessorKind::Set => property.setter = Some(method_name.to_string()),
+        }
+    }
+
     /// creates a member-variable of a container to be accessed in a qualified name.
     /// e.g. "POU.member", "StructName.member", etc.
     ///
@@ -1391,6 +1626,7 @@ impl Index {
         .set_initial_value(initial_value)
         .set_hardware_binding(member_info.binding)
         .set_varargs(member_info.varargs)
+        .set_attributes(member_info.attributes)
     }
 
     pub fn register_enum_element(
@@ -1414,6 +1650,22 @@ impl Index {
         self.global_variables.insert(name.to_lowercase(), variable);
     }
 
+    pub fn register_config_variable(
+        &mut self,
+        instance_path: &str,
+        data_type: Option<&str>,
+        binding: HardwareBinding,
+        location: SourceRange,
+    ) {
+        let entry = ConfigVariableIndexEntry {
+            instance_path: instance_path.to_string(),
+            data_type: data_type.map(str::to_string),
+            binding,
+            location,
+        };
+        self.config_variables.insert(instance_path.to_lowercase(), entry);
+    }
+
     pub fn register_global_initializer(&mut self, name: &str, variable: VariableIndexEntry) {
         self.global_initializers.insert(name.to_lowercase(), variable);
     }
@@ -1514,6 +1766,23 @@ impl Index {
         }
     }
 
+    /// If the provided name is a conversion between an enum and its numeric type (e.g. `Color_TO_DINT`
+    /// or `DINT_TO_Color`), returns the converted enum
+    pub fn find_enum_conversion(&self, name: &str) -> Option<&DataType> {
+        let Some(PouIndexEntry::Function { linkage: LinkageType::BuiltIn, return_type, .. }) =
+            self.find_pou(name)
+        else {
+            return None;
+        };
+        self.get_declared_parameters(name)
+            .first()
+            .map(|it| it.get_type_name())
+            .into_iter()
+            .chain(std::iter::once(return_type.as_str()))
+            .filter_map(|it| self.find_effective_type_by_name(it))
+            .find(|it| it.get_type_information().is_enum())
+    }
+
     pub fn get_type_layout(&self) -> &DataLayout {
         &self.data_layout
     }
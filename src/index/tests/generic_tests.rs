@@ -1,7 +1,7 @@
 use plc_ast::ast::{GenericBinding, TypeNature};
 use pretty_assertions::assert_eq;
 
-use crate::{index::PouIndexEntry, test_utils::tests::index};
+use crate::{index::PouIndexEntry, test_utils::tests::index, typesystem::DataTypeInformation};
 
 #[test]
 fn generics_saved_in_index() {
@@ -20,3 +20,85 @@ fn generics_saved_in_index() {
         panic!("{foo_info:#?} not a generic function");
     }
 }
+
+#[test]
+fn generic_function_blocks_are_instantiated_per_type_argument() {
+    let (_, index) = index(
+        r"
+        FUNCTION_BLOCK FIFO<T : ANY_NUM>
+        VAR
+            buffer : ARRAY[0..9] OF T;
+            last : Box<T>;
+        END_VAR
+            METHOD push : BOOL
+            VAR_INPUT
+                value : T;
+            END_VAR
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        CLASS Box<T : ANY>
+        VAR
+            value : T;
+        END_VAR
+        END_CLASS
+
+        PROGRAM main
+        VAR
+            a : FIFO<INT>;
+            b : FIFO<REAL>;
+            c : FIFO<INT>;
+        END_VAR
+        END_PROGRAM
+    ",
+    );
+
+    // the templates themselves are not indexed
+    assert!(index.find_pou("FIFO").is_none());
+    assert!(index.find_pou("Box").is_none());
+
+    assert!(matches!(index.find_pou("FIFO__INT"), Some(PouIndexEntry::FunctionBlock { .. })));
+    assert!(matches!(index.find_pou("FIFO__REAL.push"), Some(PouIndexEntry::Method { .. })));
+    assert_eq!(index.find_member("FIFO__REAL.push", "value").unwrap().get_type_name(), "REAL");
+
+    let buffer_type = index.find_member("FIFO__INT", "buffer").unwrap().get_type_name();
+    assert!(matches!(
+        index.find_effective_type_info(buffer_type),
+        Some(DataTypeInformation::Array { inner_type_name, .. }) if inner_type_name == "INT"
+    ));
+
+    // instances used inside of a template are instantiated with the template's type arguments
+    assert_eq!(index.find_member("FIFO__REAL", "last").unwrap().get_type_name(), "Box__REAL");
+    assert_eq!(index.find_member("Box__REAL", "value").unwrap().get_type_name(), "REAL");
+
+    assert_eq!(index.find_member("main", "a").unwrap().get_type_name(), "FIFO__INT");
+    assert_eq!(index.find_member("main", "c").unwrap().get_type_name(), "FIFO__INT");
+}
+
+#[test]
+fn generic_types_are_instantiated_per_type_argument() {
+    let (_, index) = index(
+        r"
+        TYPE Pair<K : ANY_INT, V : ANY> :
+            STRUCT
+                key : K;
+                value : V;
+            END_STRUCT
+        END_TYPE
+
+        TYPE IntPair : Pair<INT, REAL>; END_TYPE
+
+        VAR_GLOBAL
+            entry : Pair<DINT, STRING>;
+        END_VAR
+    ",
+    );
+
+    assert!(index.find_type("Pair").is_none());
+    assert_eq!(index.find_member("Pair__DINT__STRING", "key").unwrap().get_type_name(), "DINT");
+    assert_eq!(index.find_member("Pair__DINT__STRING", "value").unwrap().get_type_name(), "STRING");
+    assert_eq!(index.find_global_variable("entry").unwrap().get_type_name(), "Pair__DINT__STRING");
+
+    // a named instance is instantiated under its own name
+    assert_eq!(index.find_member("IntPair", "value").unwrap().get_type_name(), "REAL");
+}
@@ -613,6 +613,7 @@ fn pre_processing_generates_inline_structs_global() {
                 location: (54..55).into(),
                 initializer: None,
                 address: None,
+                attributes: vec![],
             },],
         },
         new_struct_type
@@ -692,6 +693,7 @@ fn pre_processing_generates_inline_structs() {
                 location: (67..68).into(),
                 initializer: None,
                 address: None,
+                attributes: vec![],
             }],
         },
         new_struct_type
@@ -731,10 +733,13 @@ fn pre_processing_generates_inline_pointers() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_pointer_type:?}"));
 
@@ -768,10 +773,13 @@ fn pre_processing_generates_pointer_to_pointer_type() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_pointer_type:?}"));
 
@@ -784,10 +792,13 @@ fn pre_processing_generates_pointer_to_pointer_type() {
                 referenced_type: "__pointer_to_pointer".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{original:?}"));
 }
@@ -816,10 +827,13 @@ fn pre_processing_generates_inline_pointer_to_pointer() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_pointer_type:?}"));
 
@@ -832,10 +846,13 @@ fn pre_processing_generates_inline_pointer_to_pointer() {
                 referenced_type: "__foo_inline_pointer_".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_pointer_type:?}"));
 
@@ -890,6 +907,8 @@ fn pre_processing_generates_inline_arrays() {
         initializer: None,
         location: (59..77).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -938,6 +957,8 @@ fn pre_processing_generates_inline_array_of_array() {
         initializer: None,
         location: (59..92).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -960,6 +981,8 @@ fn pre_processing_generates_inline_array_of_array() {
         initializer: None,
         location: (59..92).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -1000,6 +1023,8 @@ fn pre_processing_generates_array_of_array_type() {
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_type:?}"));
 
@@ -1022,6 +1047,8 @@ fn pre_processing_generates_array_of_array_type() {
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{original:?}"));
 }
@@ -1061,11 +1088,14 @@ fn pre_processing_nested_array_in_struct() {
                 location: SourceRange::undefined(),
                 initializer: None,
                 address: None,
+                attributes: vec![],
             }],
         },
         initializer: None,
         location: (14..97).into(),
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -1096,6 +1126,8 @@ fn pre_processing_nested_array_in_struct() {
         initializer: None,
         location: (59..77).into(),
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 }
@@ -1142,6 +1174,8 @@ fn pre_processing_generates_inline_array_of_array_of_array() {
         initializer: None,
         location: (74..107).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -1172,6 +1206,8 @@ fn pre_processing_generates_inline_array_of_array_of_array() {
         initializer: None,
         location: (59..107).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -1202,6 +1238,8 @@ fn pre_processing_generates_inline_array_of_array_of_array() {
         initializer: None,
         location: (59..107).into(),
         scope: Some("foo".into()),
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:?}"), format!("{new_array_type:?}"));
 
@@ -1240,6 +1278,8 @@ fn pre_processing_generates_generic_types() {
         initializer: None,
         location: SourceRange::undefined(),
         scope: Some("myFunc".into()),
+        generics: vec![],
+        attributes: vec![],
     };
 
     assert_eq!(format!("{expected:?}"), format!("{:?}", ast.user_types[0]));
@@ -1274,6 +1314,8 @@ fn pre_processing_generates_nested_generic_types() {
         initializer: None,
         location: SourceRange::undefined(),
         scope: Some("myFunc".into()),
+        generics: vec![],
+        attributes: vec![],
     };
 
     assert_eq!(format!("{expected:?}"), format!("{:?}", ast.user_types[0]));
@@ -1730,6 +1772,7 @@ fn a_program_pou_is_indexed() {
             instance_struct_name: "myProgram".into(),
             linkage: LinkageType::Internal,
             location: SymbolLocation { source_range: (17..26).into(), line_number: 1 },
+            attributes: vec![],
 
             instance_variable: VariableIndexEntry {
                 name: "myProgram_instance".into(),
@@ -1743,6 +1786,7 @@ fn a_program_pou_is_indexed() {
                 binding: None,
                 source_location: SymbolLocation { source_range: (17..26).into(), line_number: 1 },
                 varargs: None,
+                attributes: vec![],
             }
         }),
         index.find_pou("myProgram"),
@@ -1756,6 +1800,7 @@ fn a_program_pou_is_indexed() {
             return_type: "INT".into(),
             is_variadic: false,
             location: SymbolLocation { source_range: (65..75).into(), line_number: 4 },
+            attributes: vec![],
             is_generated: false,
         }),
         index.find_pou("myFunction"),
@@ -1767,6 +1812,7 @@ fn a_program_pou_is_indexed() {
             linkage: LinkageType::Internal,
             instance_struct_name: "myFunctionBlock".into(),
             location: SymbolLocation { source_range: (139..154).into(), line_number: 7 },
+            attributes: vec![],
             super_class: None,
         }),
         index.find_pou("myFunctionBlock"),
@@ -1778,6 +1824,7 @@ fn a_program_pou_is_indexed() {
             linkage: LinkageType::Internal,
             instance_struct_name: "myClass".into(),
             location: SymbolLocation { source_range: (197..204).into(), line_number: 10 },
+            attributes: vec![],
             super_class: None,
         }),
         index.find_pou("myClass"),
@@ -1790,6 +1837,7 @@ fn a_program_pou_is_indexed() {
             linkage: LinkageType::Internal,
             instance_struct_name: "myProgram".into(),
             location: SymbolLocation { source_range: (269..272).into(), line_number: 14 },
+            attributes: vec![],
         }),
         index.find_pou("myProgram.act"),
     );
@@ -2013,7 +2061,8 @@ fn internal_vla_struct_type_is_indexed_correctly() {
                     linkage: LinkageType::Internal,
                     binding: None,
                     source_location: SymbolLocation { source_range: (0..0).into(), line_number: 0 },
-                    varargs: None
+                    varargs: None,
+                    attributes: vec![]
                 },
                 VariableIndexEntry {
                     name: "dimensions".to_string(),
@@ -2026,7 +2075,8 @@ fn internal_vla_struct_type_is_indexed_correctly() {
                     linkage: LinkageType::Internal,
                     binding: None,
                     source_location: SymbolLocation { source_range: (0..0).into(), line_number: 0 },
-                    varargs: None
+                    varargs: None,
+                    attributes: vec![]
                 }
             ],
             source: StructSource::Internal(InternalType::VariableLengthArray {
@@ -2060,3 +2110,45 @@ fn string_type_alias_without_size_is_indexed() {
     let dt = index.find_effective_type_by_name(my_alias).unwrap();
     assert_eq!("WSTRING", dt.get_name());
 }
+
+#[test]
+fn attributes_are_indexed_with_pous_and_variables() {
+    let (_, index) = index(
+        "
+        {attribute 'cyclic' := '10ms'}
+        PROGRAM prg
+        VAR
+            {attribute 'unit' := 'mm'} x : INT;
+        END_VAR
+        END_PROGRAM
+
+        TYPE Point : STRUCT
+            {attribute 'hide'} x : INT;
+        END_STRUCT END_TYPE
+
+        VAR_GLOBAL
+            {attribute 'opc'} g : BOOL;
+        END_VAR
+        ",
+    );
+
+    let names = |attributes: &[plc_ast::ast::Attribute]| {
+        attributes.iter().map(|it| (it.name.clone(), it.value.clone())).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(index.find_pou("prg").unwrap().get_attributes()),
+        vec![("cyclic".to_string(), Some("10ms".to_string()))]
+    );
+    assert_eq!(
+        names(index.find_member("prg", "x").unwrap().get_attributes()),
+        vec![("unit".to_string(), Some("mm".to_string()))]
+    );
+    assert_eq!(
+        names(index.find_member("Point", "x").unwrap().get_attributes()),
+        vec![("hide".to_string(), None)]
+    );
+    assert_eq!(
+        names(index.find_global_variable("g").unwrap().get_attributes()),
+        vec![("opc".to_string(), None)]
+    );
+}
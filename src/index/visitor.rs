@@ -4,8 +4,9 @@ use super::{HardwareBinding, PouIndexEntry, VariableIndexEntry, VariableType};
 use crate::index::{ArgumentType, Index, MemberInfo};
 use crate::typesystem::{self, *};
 use plc_ast::ast::{
-    self, ArgumentProperty, AstStatement, CompilationUnit, DataType, DataTypeDeclaration, Implementation,
-    Pou, PouType, SourceRange, TypeNature, UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
+    self, AccessModifier, AccessorKind, ArgumentProperty, AstStatement, CompilationUnit, ConfigVariable,
+    DataType, DataTypeDeclaration, Implementation, LinkageType, Pou, PouType, SourceRange, TypeNature,
+    UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
 };
 use plc_ast::literals::AstLiteral;
 use plc_diagnostics::diagnostics::Diagnostic;
@@ -34,6 +35,11 @@ pub fn visit(unit: &CompilationUnit) -> Index {
     for implementation in &unit.implementations {
         visit_implementation(&mut index, implementation, &symbol_location_factory);
     }
+
+    //Register the addresses assigned to template variables
+    for config_variable in &unit.var_config {
+        visit_config_variable(&mut index, config_variable);
+    }
     index
 }
 
@@ -92,6 +98,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou, symbol_location_factory: &SymbolL
                     is_constant: block.constant,
                     binding,
                     varargs,
+                    attributes: var.attributes.clone(),
                 },
                 initial_value,
                 symbol_location_factory.create_symbol_location(&var.location),
@@ -114,6 +121,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou, symbol_location_factory: &SymbolL
                 is_constant: false, //return variables are not constants
                 binding: None,
                 varargs: None,
+                attributes: vec![],
             },
             None,
             symbol_location_factory.create_symbol_location(&pou.name_location),
@@ -141,6 +149,7 @@ pub fn visit_pou(index: &mut Index, pou: &Pou, symbol_location_factory: &SymbolL
                 &pou.name,
                 symbol_location_factory.create_symbol_location(&pou.name_location),
                 pou.linkage,
+                pou.attributes.clone(),
             );
             index.register_pou_type(datatype);
         }
@@ -154,12 +163,15 @@ pub fn visit_pou(index: &mut Index, pou: &Pou, symbol_location_factory: &SymbolL
             )
             .set_constant(true);
             index.register_global_initializer(&global_struct_name, variable);
-            index.register_pou(PouIndexEntry::create_function_block_entry(
-                &pou.name,
-                pou.linkage,
-                symbol_location_factory.create_symbol_location(&pou.name_location),
-                pou.super_class.clone().as_deref(),
-            ));
+            index.register_pou(
+                PouIndexEntry::create_function_block_entry(
+                    &pou.name,
+                    pou.linkage,
+                    symbol_location_factory.create_symbol_location(&pou.name_location),
+                    pou.super_class.clone().as_deref(),
+                )
+                .with_attributes(pou.attributes.clone()),
+            );
             index.register_pou_type(datatype);
         }
         PouType::Class => {
@@ -172,34 +184,64 @@ pub fn visit_pou(index: &mut Index, pou: &Pou, symbol_location_factory: &SymbolL
             )
             .set_constant(true);
             index.register_global_initializer(&global_struct_name, variable);
-            index.register_pou(PouIndexEntry::create_class_entry(
-                &pou.name,
-                pou.linkage,
-                symbol_location_factory.create_symbol_location(&pou.name_location),
-                pou.super_class.clone(),
-            ));
+            index.register_pou(
+                PouIndexEntry::create_class_entry(
+                    &pou.name,
+                    pou.linkage,
+                    symbol_location_factory.create_symbol_location(&pou.name_location),
+                    pou.super_class.clone(),
+                )
+                .with_attributes(pou.attributes.clone()),
+            );
             index.register_pou_type(datatype);
         }
         PouType::Function => {
-            index.register_pou(PouIndexEntry::create_function_entry(
-                &pou.name,
-                return_type_name,
-                &pou.generics,
-                pou.linkage,
-                has_varargs,
-                symbol_location_factory.create_symbol_location(&pou.name_location),
-            ));
+            index.register_pou(
+                PouIndexEntry::create_function_entry(
+                    &pou.name,
+                    return_type_name,
+                    &pou.generics,
+                    pou.linkage,
+                    has_varargs,
+                    symbol_location_factory.create_symbol_location(&pou.name_location),
+                )
+                .with_attributes(pou.attributes.clone()),
+            );
             index.register_pou_type(datatype);
         }
         PouType::Method { owner_class } => {
-            index.register_pou(PouIndexEntry::create_method_entry(
-                &pou.name,
-                return_type_name,
-                owner_class,
-                pou.linkage,
-                symbol_location_factory.create_symbol_location(&pou.name_location),
-            ));
+            index.register_pou(
+                PouIndexEntry::create_method_entry(
+                    &pou.name,
+                    return_type_name,
+                    owner_class,
+                    pou.linkage,
+                    symbol_location_factory.create_symbol_location(&pou.name_location),
+                )
+                .with_attributes(pou.attributes.clone()),
+            );
             index.register_pou_type(datatype);
+
+            // a property's accessor registers the property as a pseudo-member of the owner
+            if let Some(accessor) = &pou.accessor {
+                let type_name = match accessor.kind {
+                    AccessorKind::Get => return_type_name,
+                    AccessorKind::Set => pou
+                        .variable_blocks
+                        .iter()
+                        .flat_map(|it| it.variables.iter())
+                        .find(|it| it.name == accessor.property)
+                        .and_then(|it| it.data_type_declaration.get_name())
+                        .unwrap_or(VOID_TYPE),
+                };
+                index.register_property_accessor(
+                    owner_class,
+                    accessor,
+                    &pou.name,
+                    type_name,
+                    symbol_location_factory.create_symbol_location(&pou.name_location),
+                );
+            }
         }
         _ => {}
     };
@@ -316,11 +358,84 @@ fn visit_global_var_block(
         .set_linkage(linkage)
         .set_hardware_binding(
             var.address.as_ref().and_then(|it| HardwareBinding::from_statement(index, it, None)),
-        );
+        )
+        .set_attributes(var.attributes.clone());
         index.register_global_variable(&var.name, variable);
     }
 }
 
+fn visit_config_variable(index: &mut Index, config_variable: &ConfigVariable) {
+    let Some(instance_path) = config_variable.get_instance_path() else { return };
+    if let Some(binding) = HardwareBinding::from_statement(index, &config_variable.address, None) {
+        index.register_config_variable(
+            &instance_path,
+            config_variable.data_type_declaration.get_name(),
+            binding,
+            config_variable.location.clone(),
+        );
+    }
+}
+
+/// registers a builtin function converting an enum to its numeric type or back
+/// (e.g. `Color_TO_DINT` and `DINT_TO_Color`). The conversion is generated inline at the call site.
+fn visit_enum_conversion(
+    index: &mut Index,
+    name: &str,
+    input_type: &str,
+    return_type: &str,
+    location: &SourceRange,
+    symbol_location_factory: &SymbolLocationFactory,
+) {
+    let type_reference = |type_name: &str| DataTypeDeclaration::DataTypeReference {
+        referenced_type: type_name.to_string(),
+        location: location.clone(),
+    };
+    let pou = Pou {
+        name: name.to_string(),
+        variable_blocks: vec![VariableBlock {
+            access: AccessModifier::Public,
+            constant: false,
+            retain: false,
+            variables: vec![Variable {
+                name: "in".to_string(),
+                data_type_declaration: type_reference(input_type),
+                initializer: None,
+                address: None,
+                location: location.clone(),
+                attributes: vec![],
+            }],
+            variable_block_type: VariableBlockType::Input(ArgumentProperty::ByVal),
+            linkage: LinkageType::BuiltIn,
+            location: location.clone(),
+        }],
+        pou_type: PouType::Function,
+        return_type: Some(type_reference(return_type)),
+        location: location.clone(),
+        name_location: location.clone(),
+        poly_mode: None,
+        generics: vec![],
+        linkage: LinkageType::BuiltIn,
+        super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
+    };
+    let implementation = Implementation {
+        name: name.to_string(),
+        type_name: name.to_string(),
+        linkage: LinkageType::BuiltIn,
+        pou_type: PouType::Function,
+        statements: vec![],
+        location: location.clone(),
+        name_location: location.clone(),
+        overriding: false,
+        generic: false,
+        access: None,
+    };
+    visit_pou(index, &pou, symbol_location_factory);
+    visit_implementation(index, &implementation, symbol_location_factory);
+}
+
 fn get_variable_type_from_block(block: &VariableBlock) -> VariableType {
     match block.variable_block_type {
         VariableBlockType::Local => VariableType::Local,
@@ -353,6 +468,18 @@ fn visit_data_type(
             );
         }
 
+        DataType::UnionType { name: Some(name), variables } => {
+            visit_struct(
+                name,
+                variables,
+                index,
+                symbol_location_factory,
+                scope,
+                type_declaration,
+                StructSource::Union,
+            );
+        }
+
         DataType::EnumType { name: Some(name), elements, numeric_type, .. } => {
             let enum_name = name.as_str();
 
@@ -360,6 +487,8 @@ fn visit_data_type(
                 name: enum_name.to_string(),
                 elements: ast::get_enum_element_names(elements),
                 referenced_type: numeric_type.clone(),
+                // `{attribute 'strict'}` turns on strict typing for a single enum
+                strict: type_declaration.attributes.iter().any(|it| it.name.eq_ignore_ascii_case("strict")),
             };
 
             for ele in ast::flatten_expression_list(elements) {
@@ -393,6 +522,23 @@ fn visit_data_type(
                 nature: TypeNature::Int,
                 location: symbol_location_factory.create_symbol_location(&type_declaration.location),
             });
+
+            visit_enum_conversion(
+                index,
+                &format!("{enum_name}_TO_{numeric_type}"),
+                enum_name,
+                numeric_type,
+                &type_declaration.location,
+                symbol_location_factory,
+            );
+            visit_enum_conversion(
+                index,
+                &format!("{numeric_type}_TO_{enum_name}"),
+                numeric_type,
+                enum_name,
+                &type_declaration.location,
+                symbol_location_factory,
+            );
         }
 
         DataType::SubRangeType { name: Some(name), referenced_type, bounds } => {
@@ -442,12 +588,12 @@ fn visit_data_type(
                 symbol_location_factory,
             );
         }
-        DataType::PointerType { name: Some(name), referenced_type, .. } => {
+        DataType::PointerType { name: Some(name), referenced_type, auto_deref } => {
             let inner_type_name = referenced_type.get_name().expect("named datatype");
             let information = DataTypeInformation::Pointer {
                 name: name.clone(),
                 inner_type_name: inner_type_name.into(),
-                auto_deref: false,
+                auto_deref: *auto_deref,
             };
 
             let init = index.get_mut_const_expressions().maybe_add_constant_expression(
@@ -535,6 +681,7 @@ fn visit_data_type(
                 location: symbol_location_factory.create_symbol_location(&type_declaration.location),
             });
         }
+        DataType::GenericInstance { .. } => {} //Generic instances are indexed through their instantiated declarations
 
         _ => { /* unnamed datatypes are ignored */ }
     };
@@ -615,6 +762,7 @@ fn visit_variable_length_array(
                             referenced_type: dummy_array_name,
                             location: SourceRange::undefined(),
                         }),
+                        auto_deref: false,
                     },
                     location: SourceRange::undefined(),
                     scope: None,
@@ -661,6 +809,7 @@ fn visit_variable_length_array(
             initializer: None,
             address: None,
             location: SourceRange::undefined(),
+            attributes: vec![],
         },
         // Dimensions Array
         Variable {
@@ -669,6 +818,7 @@ fn visit_variable_length_array(
             initializer: None,
             address: None,
             location: SourceRange::undefined(),
+            attributes: vec![],
         },
     ];
 
@@ -678,6 +828,8 @@ fn visit_variable_length_array(
         initializer: None,
         location: type_declaration.location.clone(),
         scope: type_declaration.scope.clone(),
+        generics: vec![],
+        attributes: vec![],
     };
 
     // visit the internally created struct type to also index its members
@@ -797,6 +949,8 @@ fn visit_struct(
                         initializer: None,
                         location: SourceRange::undefined(),
                         scope: scope.clone(),
+                        generics: vec![],
+                        attributes: vec![],
                     },
                     symbol_location_factory,
                 )
@@ -821,6 +975,7 @@ fn visit_struct(
                     is_constant: false, //struct members are not constants //TODO thats probably not true (you can define a struct in an CONST-block?!)
                     binding,
                     varargs: None,
+                    attributes: var.attributes.clone(),
                 },
                 init,
                 symbol_location_factory.create_symbol_location(&var.location),
@@ -1,4 +1,5 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+pub use conditional::{Defines, DEFAULT_DEFINE_VALUE};
 use core::ops::Range;
 use logos::{Filter, Lexer, Logos};
 use plc_ast::ast::{AstId, DirectAccessType, HardwareAccessType, SourceRange, SourceRangeFactory};
@@ -6,6 +7,9 @@ use plc_ast::provider::IdProvider;
 use plc_diagnostics::diagnostics::Diagnostic;
 pub use tokens::Token;
 
+use self::conditional::{Branch, Conditional};
+
+mod conditional;
 #[cfg(test)]
 mod tests;
 mod tokens;
@@ -23,6 +27,10 @@ pub struct ParseSession<'a> {
     id_provider: IdProvider,
     pub source_range_factory: SourceRangeFactory,
     pub scope: Option<String>,
+    /// the symbols tested by conditional compilation pragmas
+    defines: Defines,
+    /// the `{IF ...}` pragmas that were not closed yet, innermost last
+    conditionals: Vec<Conditional>,
 }
 
 #[macro_export]
@@ -44,6 +52,7 @@ impl<'a> ParseSession<'a> {
         l: Lexer<'a, Token>,
         id_provider: IdProvider,
         source_range_factory: SourceRangeFactory,
+        defines: Defines,
     ) -> ParseSession<'a> {
         let mut lexer = ParseSession {
             lexer: l,
@@ -56,6 +65,8 @@ impl<'a> ParseSession<'a> {
             id_provider,
             scope: None,
             source_range_factory,
+            defines,
+            conditionals: vec![],
         };
         lexer.advance();
         lexer
@@ -114,13 +125,15 @@ impl<'a> ParseSession<'a> {
 
     pub fn advance(&mut self) {
         self.last_range = self.range();
-        self.last_token = std::mem::replace(&mut self.token, self.lexer.next().unwrap_or(Token::End));
+        let next = self.next_token();
+        self.last_token = std::mem::replace(&mut self.token, next);
         self.parse_progress += 1;
 
         match self.token {
             Token::KeywordVarInput
             | Token::KeywordVarOutput
             | Token::KeywordVarGlobal
+            | Token::KeywordVarConfig
             | Token::KeywordVarInOut
             | Token::KeywordRef
             | Token::KeywordVarTemp
@@ -132,6 +145,7 @@ impl<'a> ParseSession<'a> {
             | Token::KeywordFunctionBlock
             | Token::KeywordEndFunctionBlock
             | Token::KeywordEndStruct
+            | Token::KeywordEndUnion
             | Token::KeywordEndAction
             | Token::KeywordEndActions
             | Token::KeywordEndIf
@@ -150,6 +164,95 @@ impl<'a> ParseSession<'a> {
         }
     }
 
+    /// returns the next token of the code selected by the conditional compilation pragmas,
+    /// the tokens of inactive branches are skipped
+    fn next_token(&mut self) -> Token {
+        loop {
+            let token = self.lexer.next().unwrap_or(Token::End);
+            match token {
+                Token::PragmaIf | Token::PragmaElsif | Token::PragmaElse | Token::PragmaEndIf => {
+                    self.handle_conditional_pragma(&token)
+                }
+                Token::End => {
+                    for conditional in std::mem::take(&mut self.conditionals) {
+                        let location = self.source_range_factory.create_range(conditional.range);
+                        self.accept_diagnostic(Diagnostic::unclosed_conditional_pragma(location));
+                    }
+                    return token;
+                }
+                _ if self.is_in_inactive_branch() => {}
+                _ => return token,
+            }
+        }
+    }
+
+    fn is_in_inactive_branch(&self) -> bool {
+        self.conditionals.last().is_some_and(|it| it.branch != Branch::Active)
+    }
+
+    fn handle_conditional_pragma(&mut self, pragma: &Token) {
+        let range = self.lexer.span();
+        let branch = self.conditionals.last().map(|it| (it.branch, it.has_else));
+        match (pragma, branch) {
+            (Token::PragmaIf, _) => {
+                let branch = if self.is_in_inactive_branch() {
+                    Branch::Done
+                } else if self.evaluate_condition() {
+                    Branch::Active
+                } else {
+                    Branch::Pending
+                };
+                self.conditionals.push(Conditional { branch, has_else: false, range });
+            }
+            (Token::PragmaEndIf, Some(_)) => {
+                self.conditionals.pop();
+            }
+            (Token::PragmaElsif | Token::PragmaElse, Some((_, true))) => {
+                self.accept_diagnostic(Diagnostic::unexpected_token_found(
+                    "{END_IF}",
+                    self.lexer.slice(),
+                    self.source_range_factory.create_range(range),
+                ));
+            }
+            (Token::PragmaElsif, Some((branch, false))) => {
+                let branch = match branch {
+                    Branch::Pending if self.evaluate_condition() => Branch::Active,
+                    Branch::Pending => Branch::Pending,
+                    _ => Branch::Done,
+                };
+                self.set_branch(branch, false);
+            }
+            (Token::PragmaElse, Some((branch, false))) => {
+                let branch = if branch == Branch::Pending { Branch::Active } else { Branch::Done };
+                self.set_branch(branch, true);
+            }
+            _ => {
+                self.accept_diagnostic(Diagnostic::unmatched_conditional_pragma(
+                    self.lexer.slice(),
+                    self.source_range_factory.create_range(range),
+                ));
+            }
+        }
+    }
+
+    fn set_branch(&mut self, branch: Branch, has_else: bool) {
+        if let Some(conditional) = self.conditionals.last_mut() {
+            conditional.branch = branch;
+            conditional.has_else = has_else;
+        }
+    }
+
+    /// evaluates the condition of the current `{IF ...}` or `{ELSIF ...}` pragma,
+    /// an invalid condition is reported and treated as `FALSE`
+    fn evaluate_condition(&mut self) -> bool {
+        let condition = conditional::get_condition(self.lexer.slice());
+        conditional::evaluate(condition, &self.defines).unwrap_or_else(|reason| {
+            let location = self.source_range_factory.create_range(self.lexer.span());
+            self.accept_diagnostic(Diagnostic::invalid_pragma_condition(&reason, location));
+            false
+        })
+    }
+
     pub fn slice(&self) -> &str {
         self.lexer.slice()
     }
@@ -341,7 +444,12 @@ fn parse_hardware_access_type(lexer: &mut Lexer<Token>) -> Option<(HardwareAcces
 
 #[cfg(test)]
 pub fn lex(source: &str) -> ParseSession {
-    ParseSession::new(Token::lexer(source), IdProvider::default(), SourceRangeFactory::internal())
+    ParseSession::new(
+        Token::lexer(source),
+        IdProvider::default(),
+        SourceRangeFactory::internal(),
+        Defines::default(),
+    )
 }
 
 pub fn lex_with_ids(
@@ -349,5 +457,16 @@ pub fn lex_with_ids(
     id_provider: IdProvider,
     location_factory: SourceRangeFactory,
 ) -> ParseSession {
-    ParseSession::new(Token::lexer(source), id_provider, location_factory)
+    lex_with_defines(source, id_provider, location_factory, Defines::default())
+}
+
+/// Creates a `ParseSession` that selects the code of the conditional compilation pragmas
+/// (`{IF ...}`) according to the given defines
+pub fn lex_with_defines(
+    source: &str,
+    id_provider: IdProvider,
+    location_factory: SourceRangeFactory,
+    defines: Defines,
+) -> ParseSession {
+    ParseSession::new(Token::lexer(source), id_provider, location_factory, defines)
 }
@@ -0,0 +1,266 @@
+// Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+use std::collections::HashMap;
+
+use logos::{Lexer, Logos};
+
+use super::Token;
+
+/// The value given to a define that was declared without a value (e.g. `-D DEBUG`)
+pub const DEFAULT_DEFINE_VALUE: &str = "TRUE";
+
+/// The symbols that can be tested by conditional compilation pragmas (`{IF ...}`)
+///
+/// Names are case insensitive, values are kept as written
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Defines {
+    values: HashMap<String, String>,
+}
+
+impl Defines {
+    /// Defines `name` with the given value, replacing an earlier definition of the same name
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_lowercase(), value.to_string());
+    }
+
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        self.define(name, value);
+        self
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.values.contains_key(&name.to_lowercase())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> Extend<(K, V)> for Defines {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (name, value) in iter {
+            self.define(name.as_ref(), value.as_ref());
+        }
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FromIterator<(K, V)> for Defines {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut defines = Defines::default();
+        defines.extend(iter);
+        defines
+    }
+}
+
+impl IntoIterator for Defines {
+    type Item = (String, String);
+    type IntoIter = std::collections::hash_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// The state of the innermost `{IF ...}` pragma that has not been closed yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Branch {
+    /// the code of the current branch is compiled
+    Active,
+    /// no branch was taken so far, a following `{ELSIF ...}` or `{ELSE}` may still be taken
+    Pending,
+    /// a branch was already taken or the whole block is nested in inactive code
+    Done,
+}
+
+#[derive(Debug)]
+pub(crate) struct Conditional {
+    pub branch: Branch,
+    pub has_else: bool,
+    /// the range of the opening `{IF ...}` pragma
+    pub range: std::ops::Range<usize>,
+}
+
+/// Returns the condition of an `{IF ...}` or `{ELSIF ...}` pragma, i.e. the text between
+/// the keyword and the closing brace
+pub(crate) fn get_condition(pragma: &str) -> &str {
+    let content = pragma.trim_start_matches('{').trim_end_matches('}').trim_start();
+    let keyword_length =
+        content.find(|c: char| !c.is_ascii_alphabetic() && c != '_').unwrap_or(content.len());
+    &content[keyword_length..]
+}
+
+/// Evaluates the condition of an `{IF ...}` or `{ELSIF ...}` pragma
+///
+/// Supported are `defined(NAME)`, `TRUE`, `FALSE`, comparisons (`=`, `<>`, `<`, `>`, `<=`, `>=`)
+/// between defines, strings and integers and the boolean operators `NOT`, `AND`, `XOR` and `OR`.
+/// An undefined name has no value, comparing it always results in `FALSE`.
+pub(crate) fn evaluate(condition: &str, defines: &Defines) -> Result<bool, String> {
+    let mut evaluator = Evaluator { lexer: Token::lexer(condition), token: Token::End, defines };
+    evaluator.advance();
+    if evaluator.token == Token::End {
+        return Err("missing condition".into());
+    }
+    let result = evaluator.parse_or()?;
+    if evaluator.token != Token::End {
+        return Err(format!("unexpected {}", evaluator.found()));
+    }
+    Ok(result)
+}
+
+struct Evaluator<'a> {
+    lexer: Lexer<'a, Token>,
+    token: Token,
+    defines: &'a Defines,
+}
+
+impl Evaluator<'_> {
+    fn advance(&mut self) {
+        self.token = self.lexer.next().unwrap_or(Token::End);
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), String> {
+        if self.token != token {
+            return Err(format!("expected `{expected}` but found {}", self.found()));
+        }
+        self.advance();
+        Ok(())
+    }
+
+    /// describes the current token for error messages
+    fn found(&self) -> String {
+        if self.token == Token::End {
+            "the end of the condition".into()
+        } else {
+            format!("`{}`", self.lexer.slice())
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<bool, String> {
+        let mut result = self.parse_xor()?;
+        while self.token == Token::OperatorOr {
+            self.advance();
+            result |= self.parse_xor()?;
+        }
+        Ok(result)
+    }
+
+    fn parse_xor(&mut self) -> Result<bool, String> {
+        let mut result = self.parse_and()?;
+        while self.token == Token::OperatorXor {
+            self.advance();
+            result ^= self.parse_and()?;
+        }
+        Ok(result)
+    }
+
+    fn parse_and(&mut self) -> Result<bool, String> {
+        let mut result = self.parse_not()?;
+        while matches!(self.token, Token::OperatorAnd | Token::OperatorAmp) {
+            self.advance();
+            result &= self.parse_not()?;
+        }
+        Ok(result)
+    }
+
+    fn parse_not(&mut self) -> Result<bool, String> {
+        if self.token == Token::OperatorNot {
+            self.advance();
+            return self.parse_not().map(|it| !it);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<bool, String> {
+        match self.token {
+            Token::KeywordParensOpen => {
+                self.advance();
+                let result = self.parse_or()?;
+                self.expect(Token::KeywordParensClose, ")")?;
+                return Ok(result);
+            }
+            Token::LiteralTrue => {
+                self.advance();
+                return Ok(true);
+            }
+            Token::LiteralFalse => {
+                self.advance();
+                return Ok(false);
+            }
+            Token::Identifier if self.lexer.slice().eq_ignore_ascii_case("defined") => {
+                self.advance();
+                self.expect(Token::KeywordParensOpen, "(")?;
+                let name = self.lexer.slice().to_string();
+                self.expect(Token::Identifier, "Identifier")?;
+                self.expect(Token::KeywordParensClose, ")")?;
+                return Ok(self.defines.is_defined(&name));
+            }
+            _ => {}
+        }
+
+        let left = self.parse_value()?;
+        let operator = self.token.clone();
+        match operator {
+            Token::OperatorEqual
+            | Token::OperatorNotEqual
+            | Token::OperatorLess
+            | Token::OperatorGreater
+            | Token::OperatorLessOrEqual
+            | Token::OperatorGreaterOrEqual => {
+                self.advance();
+                let right = self.parse_value()?;
+                compare(left.as_deref(), &operator, right.as_deref())
+            }
+            //a single value is used as a flag
+            _ => match left.as_deref() {
+                None => Ok(false),
+                Some(value) if value.eq_ignore_ascii_case("TRUE") || value == "1" => Ok(true),
+                Some(value) if value.eq_ignore_ascii_case("FALSE") || value == "0" => Ok(false),
+                Some(value) => Err(format!("`{value}` is not a boolean value")),
+            },
+        }
+    }
+
+    /// parses a define, string or integer, returns `None` for undefined names
+    fn parse_value(&mut self) -> Result<Option<String>, String> {
+        let slice = self.lexer.slice();
+        let value = match self.token {
+            Token::Identifier => self.defines.get(slice).map(str::to_string),
+            Token::LiteralString | Token::LiteralWideString => Some(slice[1..slice.len() - 1].to_string()),
+            Token::LiteralInteger => Some(slice.replace('_', "")),
+            Token::OperatorMinus => {
+                self.advance();
+                let number = self.lexer.slice().replace('_', "");
+                if self.token != Token::LiteralInteger {
+                    return Err(format!("expected a number but found {}", self.found()));
+                }
+                Some(format!("-{number}"))
+            }
+            _ => return Err(format!("unexpected {}", self.found())),
+        };
+        self.advance();
+        Ok(value)
+    }
+}
+
+fn compare(left: Option<&str>, operator: &Token, right: Option<&str>) -> Result<bool, String> {
+    let (Some(left), Some(right)) = (left, right) else {
+        //undefined names are neither equal nor unequal to anything
+        return Ok(false);
+    };
+    let numbers = left.parse::<i128>().ok().zip(right.parse::<i128>().ok());
+    let ordering = match numbers {
+        Some((left, right)) => left.cmp(&right),
+        None if matches!(operator, Token::OperatorEqual | Token::OperatorNotEqual) => {
+            left.to_lowercase().cmp(&right.to_lowercase())
+        }
+        None => return Err(format!("cannot compare `{left}` and `{right}`, only numbers can be ordered")),
+    };
+    Ok(match operator {
+        Token::OperatorEqual => ordering.is_eq(),
+        Token::OperatorNotEqual => ordering.is_ne(),
+        Token::OperatorLess => ordering.is_lt(),
+        Token::OperatorGreater => ordering.is_gt(),
+        Token::OperatorLessOrEqual => ordering.is_le(),
+        _ => ordering.is_ge(),
+    })
+}
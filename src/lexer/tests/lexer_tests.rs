@@ -1,9 +1,13 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 
-use plc_ast::ast::{DirectAccessType, HardwareAccessType, NewLines};
+use plc_ast::{
+    ast::{DirectAccessType, HardwareAccessType, NewLines, SourceRangeFactory},
+    provider::IdProvider,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
 use pretty_assertions::{assert_eq, assert_ne};
 
-use crate::lexer::{lex, Token::*};
+use crate::lexer::{lex, lex_with_defines, Defines, Token, Token::*};
 
 #[test]
 fn generic_properties() {
@@ -150,9 +154,26 @@ fn action_tokens() {
     assert_eq!(lexer.token, KeywordEndActions);
 }
 
+#[test]
+fn property_tokens() {
+    let mut lexer = lex("PROPERTY GET END_GET SET END_SET END_PROPERTY");
+    assert_eq!(lexer.token, KeywordProperty);
+    lexer.advance();
+    // GET and SET are only keywords inside of a property, `SET` is a common variable name
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndGet);
+    lexer.advance();
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndSet);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndProperty);
+}
+
 #[test]
 fn var_tokens() {
-    let mut lexer = lex("VAR VAR_INPUT VAR_OUTPUT VAR_GLOBAL VAR_IN_OUT END_VAR");
+    let mut lexer = lex("VAR VAR_INPUT VAR_OUTPUT VAR_GLOBAL VAR_IN_OUT VAR_CONFIG END_VAR");
     assert_eq!(lexer.token, KeywordVar);
     lexer.advance();
     assert_eq!(lexer.token, KeywordVarInput);
@@ -163,6 +184,8 @@ fn var_tokens() {
     lexer.advance();
     assert_eq!(lexer.token, KeywordVarInOut);
     lexer.advance();
+    assert_eq!(lexer.token, KeywordVarConfig);
+    lexer.advance();
     assert_eq!(lexer.token, KeywordEndVar);
 }
 
@@ -678,6 +701,35 @@ fn pointers_and_references_keyword() {
     lexer.advance();
 }
 
+#[test]
+fn auto_deref_references_keyword() {
+    let mut lexer = lex(r#"
+    REFERENCE TO x
+    r REF= x
+    r ref= x
+    "#);
+
+    // REFERENCE is not a keyword on its own, it can still be used as a name
+    assert_eq!(lexer.token, Identifier);
+    assert_eq!(lexer.slice(), "REFERENCE");
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordTo);
+    lexer.advance();
+    assert_eq!(lexer.slice(), "x");
+    lexer.advance();
+    assert_eq!(lexer.slice(), "r");
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordReferenceAssignment);
+    lexer.advance();
+    assert_eq!(lexer.slice(), "x");
+    lexer.advance();
+    assert_eq!(lexer.slice(), "r");
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordReferenceAssignment);
+    lexer.advance();
+    assert_eq!(lexer.slice(), "x");
+}
+
 #[test]
 fn direct_access_test() {
     let mut lexer = lex(r"
@@ -882,3 +934,95 @@ fn new_lines_and_columns_test() {
     assert_eq!(nl.get_column(2, text.find('B').unwrap()), 9);
     assert_eq!(nl.get_column(4, text.find('C').unwrap()), 9);
 }
+
+fn lex_tokens(source: &str, defines: Defines) -> (Vec<Token>, Vec<Diagnostic>) {
+    let mut lexer = lex_with_defines(source, IdProvider::default(), SourceRangeFactory::internal(), defines);
+    let mut tokens = vec![];
+    while !lexer.is_end_of_stream() {
+        tokens.push(lexer.token.clone());
+        lexer.advance();
+    }
+    (tokens, lexer.diagnostics)
+}
+
+#[test]
+fn conditional_pragmas_select_the_branch_of_the_first_true_condition() {
+    let source = "
+        {IF defined(PRESS)} PROGRAM
+        {ELSIF MACHINE = 'lathe'} FUNCTION
+        {ELSE} CLASS
+        {END_IF} END_PROGRAM";
+
+    let defines = Defines::default().with("PRESS", "TRUE");
+    assert_eq!(lex_tokens(source, defines).0, vec![KeywordProgram, KeywordEndProgram]);
+
+    let defines = Defines::default().with("machine", "Lathe");
+    assert_eq!(lex_tokens(source, defines).0, vec![KeywordFunction, KeywordEndProgram]);
+
+    assert_eq!(lex_tokens(source, Defines::default()).0, vec![KeywordClass, KeywordEndProgram]);
+}
+
+#[test]
+fn conditional_pragmas_can_be_nested() {
+    let source = "
+        {IF defined(A)}
+            {IF defined(B)} PROGRAM {ELSE} FUNCTION {END_IF}
+        {ELSE}
+            {IF defined(B)} CLASS {END_IF}
+        {END_IF}";
+
+    let defines = Defines::default().with("A", "TRUE");
+    assert_eq!(lex_tokens(source, defines).0, vec![KeywordFunction]);
+
+    let defines = Defines::default().with("B", "TRUE");
+    assert_eq!(lex_tokens(source, defines).0, vec![KeywordClass]);
+}
+
+#[test]
+fn conditional_pragma_expressions() {
+    let defines = Defines::default()
+        .with("__TARGET", "x86_64-linux-gnu")
+        .with("VERSION", "3")
+        .with("DEBUG", "TRUE")
+        .with("SIMULATION", "FALSE");
+
+    let conditions = [
+        ("defined(VERSION) AND NOT defined(UNKNOWN)", true),
+        ("__TARGET = 'x86_64-linux-gnu'", true),
+        ("__TARGET <> \"aarch64-linux-gnu\"", true),
+        ("VERSION >= 2 AND VERSION < 4", true),
+        ("VERSION = -3", false),
+        ("DEBUG & NOT SIMULATION", true),
+        ("UNKNOWN = 'x' OR UNKNOWN <> 'x'", false),
+        ("(DEBUG XOR TRUE) OR (SIMULATION OR FALSE)", false),
+    ];
+    for (condition, expected) in conditions {
+        let source = format!("{{IF {condition}}} PROGRAM {{END_IF}}");
+        let (tokens, diagnostics) = lex_tokens(&source, defines.clone());
+        assert_eq!(diagnostics, vec![], "{condition}");
+        assert_eq!(!tokens.is_empty(), expected, "{condition}");
+    }
+}
+
+#[test]
+fn invalid_conditional_pragmas_are_reported() {
+    let source = "
+        {IF __TARGET < 'x86'} PROGRAM {END_IF}
+        {IF defined(A} FUNCTION {END_IF}
+        {ELSE}
+        {IF TRUE} {ELSE} {ELSIF TRUE} {END_IF}
+        {IF TRUE} CLASS";
+
+    let (tokens, diagnostics) = lex_tokens(source, Defines::default().with("__TARGET", "x86_64-linux-gnu"));
+    assert_eq!(tokens, vec![KeywordClass]);
+    assert_eq!(
+        diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>(),
+        vec![
+            "Invalid pragma condition: cannot compare `x86_64-linux-gnu` and `x86`, only numbers can be ordered",
+            "Invalid pragma condition: expected `)` but found the end of the condition",
+            "`{ELSE}` has no matching `{IF ...}`",
+            "Unexpected token: expected {END_IF} but found {ELSIF TRUE}",
+            "Missing `{END_IF}` for conditional pragma",
+        ]
+    );
+}
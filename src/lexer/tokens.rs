@@ -22,6 +22,24 @@ pub enum Token {
     #[token("{sized}")]
     PropertySized,
 
+    #[token("{test}")]
+    PropertyTest,
+
+    #[regex(r"\{[ \t]*attribute[ \t][^}]*\}", ignore(case))]
+    PropertyAttribute,
+
+    #[regex(r"\{[ \t]*IF[ \t(][^}]*\}", ignore(case))]
+    PragmaIf,
+
+    #[regex(r"\{[ \t]*ELSIF[ \t(][^}]*\}", ignore(case))]
+    PragmaElsif,
+
+    #[regex(r"\{[ \t]*ELSE[ \t]*\}", ignore(case))]
+    PragmaElse,
+
+    #[regex(r"\{[ \t]*END_IF[ \t]*\}", ignore(case))]
+    PragmaEndIf,
+
     #[token("PROGRAM", ignore(case))]
     KeywordProgram,
 
@@ -73,6 +91,21 @@ pub enum Token {
     #[token("ENDMETHOD", ignore(case))]
     KeywordEndMethod,
 
+    #[token("PROPERTY", ignore(case))]
+    KeywordProperty,
+
+    #[token("END_PROPERTY", ignore(case))]
+    #[token("ENDPROPERTY", ignore(case))]
+    KeywordEndProperty,
+
+    #[token("END_GET", ignore(case))]
+    #[token("ENDGET", ignore(case))]
+    KeywordEndGet,
+
+    #[token("END_SET", ignore(case))]
+    #[token("ENDSET", ignore(case))]
+    KeywordEndSet,
+
     #[token("PUBLIC", ignore(case))]
     KeywordAccessPublic,
 
@@ -92,6 +125,10 @@ pub enum Token {
     #[token("VARGLOBAL", ignore(case))]
     KeywordVarGlobal,
 
+    #[token("VAR_CONFIG", ignore(case))]
+    #[token("VARCONFIG", ignore(case))]
+    KeywordVarConfig,
+
     #[token("VAR_IN_OUT", ignore(case))]
     #[token("VARINOUT", ignore(case))]
     KeywordVarInOut,
@@ -133,6 +170,13 @@ pub enum Token {
     #[token("ENDSTRUCT", ignore(case))]
     KeywordEndStruct,
 
+    #[token("UNION", ignore(case))]
+    KeywordUnion,
+
+    #[token("END_UNION", ignore(case))]
+    #[token("ENDUNION", ignore(case))]
+    KeywordEndUnion,
+
     #[token("ACTIONS", ignore(case))]
     KeywordActions,
 
@@ -159,6 +203,9 @@ pub enum Token {
     #[token("=>")]
     KeywordOutputAssignment,
 
+    #[token("REF=", ignore(case))]
+    KeywordReferenceAssignment,
+
     #[token("(")]
     KeywordParensOpen,
 
@@ -245,6 +292,9 @@ pub enum Token {
     #[token("CONTINUE", ignore(case))]
     KeywordContinue,
 
+    #[token("JMP", ignore(case))]
+    KeywordJmp,
+
     #[token("POINTER", ignore(case))]
     KeywordPointer,
 
@@ -33,7 +33,10 @@ use validation::Validator;
 
 pub mod builtins;
 pub mod codegen;
+pub mod coverage;
+pub mod cycle_monitor;
 mod datalayout;
+pub mod dynamic_memory;
 pub mod expression_path;
 pub mod hardware_binding;
 pub mod index;
@@ -41,8 +44,13 @@ pub mod lexer;
 pub mod linker;
 pub mod output;
 pub mod parser;
+pub mod pretty_printers;
+pub mod process_image;
 pub mod resolver;
+pub mod symbols;
 mod test_utils;
+pub mod testing;
+pub mod trace;
 
 pub mod typesystem;
 pub mod validation;
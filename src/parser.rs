@@ -1,9 +1,10 @@
 use plc_ast::{
     ast::{
-        AccessModifier, ArgumentProperty, AstStatement, CompilationUnit, DataType, DataTypeDeclaration,
-        DirectAccessType, GenericBinding, HardwareAccessType, Implementation, LinkageType, NewLines,
-        PolymorphismMode, Pou, PouType, ReferenceAccess, SourceRange, SourceRangeFactory, TypeNature,
-        UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
+        AccessModifier, AccessorKind, ArgumentProperty, AstStatement, Attribute, CompilationUnit,
+        ConfigVariable, DataType, DataTypeDeclaration, DirectAccessType, GenericBinding, HardwareAccessType,
+        Implementation, LinkageType, NewLines, PolymorphismMode, Pou, PouType, PropertyAccessor,
+        ReferenceAccess, SourceRange, SourceRangeFactory, TypeNature, UserTypeDeclaration, Variable,
+        VariableBlock, VariableBlockType,
     },
     provider::IdProvider,
 };
@@ -13,7 +14,7 @@ use plc_util::convention::qualified_name;
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::{
     expect_token,
-    lexer::{self, ParseSession, Token, Token::*},
+    lexer::{self, Defines, ParseSession, Token, Token::*},
     typesystem::DINT_TYPE,
 };
 
@@ -34,10 +35,12 @@ pub fn parse_file(
     location: &'static str,
     linkage: LinkageType,
     id_provider: IdProvider,
+    defines: &Defines,
     diagnostician: &mut Diagnostician,
 ) -> CompilationUnit {
     let location_factory = SourceRangeFactory::for_file(location);
-    let (unit, errors) = parse(lexer::lex_with_ids(source, id_provider, location_factory), linkage, location);
+    let lexer = lexer::lex_with_defines(source, id_provider, location_factory, defines.clone());
+    let (unit, errors) = parse(lexer, linkage, location);
     //Register the source file with the diagnostician
     //TODO: We should reduce the clone here
     diagnostician.register_file(location.to_string(), source.to_string());
@@ -49,6 +52,8 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &str) -> Pars
     let mut unit = CompilationUnit::new(file_name, NewLines::build(lexer.get_src()));
 
     let mut linkage = lnk;
+    let mut test_pragma = None;
+    let mut attributes = vec![];
     loop {
         match lexer.token {
             PropertyExternal => {
@@ -57,7 +62,19 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &str) -> Pars
                 //Don't reset linkage
                 continue;
             }
+            PropertyTest => {
+                test_pragma = Some(lexer.location());
+                lexer.advance();
+                //Don't reset the test pragma
+                continue;
+            }
+            PropertyAttribute => {
+                attributes.append(&mut parse_attributes(&mut lexer));
+                //Don't reset the attributes
+                continue;
+            }
             KeywordVarGlobal => unit.global_vars.push(parse_variable_block(&mut lexer, linkage)),
+            KeywordVarConfig => unit.var_config.append(&mut parse_config_variables(&mut lexer)),
             KeywordProgram | KeywordClass | KeywordFunction | KeywordFunctionBlock => {
                 let params = match lexer.token {
                     KeywordProgram => (PouType::Program, KeywordEndProgram),
@@ -67,6 +84,18 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &str) -> Pars
                 };
 
                 let (mut pou, mut implementation) = parse_pou(&mut lexer, params.0, linkage, params.1);
+                if let Some(location) = test_pragma.take() {
+                    match pou.first_mut() {
+                        Some(test) if test.pou_type == PouType::Function => test.is_test = true,
+                        _ => lexer.accept_diagnostic(Diagnostic::invalid_pragma_location(
+                            "Only functions can be marked as {test}",
+                            location,
+                        )),
+                    }
+                }
+                if let Some(pou) = pou.first_mut() {
+                    pou.attributes = std::mem::take(&mut attributes);
+                }
 
                 unit.units.append(&mut pou);
                 unit.implementations.append(&mut implementation);
@@ -83,11 +112,17 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &str) -> Pars
             }
             KeywordType => {
                 let unit_type = parse_type(&mut lexer);
-                for utype in unit_type {
+                // attributes in front of the TYPE keyword apply to all of its declarations
+                let block_attributes = std::mem::take(&mut attributes);
+                for mut utype in unit_type {
+                    utype.attributes.splice(0..0, block_attributes.iter().cloned());
                     unit.user_types.push(utype);
                 }
             }
-            KeywordEndActions | End => return (unit, lexer.diagnostics),
+            KeywordEndActions | End => {
+                report_unattached_attributes(&mut lexer, attributes);
+                return (unit, lexer.diagnostics);
+            }
             _ => {
                 lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
                     "StartKeyword",
@@ -97,6 +132,13 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &str) -> Pars
                 lexer.advance();
             }
         };
+        if let Some(location) = test_pragma.take() {
+            lexer.accept_diagnostic(Diagnostic::invalid_pragma_location(
+                "Only functions can be marked as {test}",
+                location,
+            ));
+        }
+        report_unattached_attributes(&mut lexer, std::mem::take(&mut attributes));
         linkage = lnk;
     }
     //the match in the loop will always return
@@ -197,10 +239,23 @@ fn parse_pou(
             // implementations. Note that function blocks have to start with the method
             // declarations before their implementation.
             // all other Pous need to be checked in the validator if they can have methods.
-            while lexer.token == KeywordMethod {
-                if let Some((pou, implementation)) = parse_method(lexer, &name, linkage) {
-                    impl_pous.push(pou);
-                    implementations.push(implementation);
+            // properties are lowered into one method per accessor.
+            loop {
+                let attributes = parse_attributes(lexer);
+                if lexer.token == KeywordMethod {
+                    if let Some((pou, implementation)) = parse_method(lexer, &name, linkage) {
+                        impl_pous.push(Pou { attributes, ..pou });
+                        implementations.push(implementation);
+                    }
+                } else if lexer.token == KeywordProperty {
+                    // the attributes of a property apply to each of its accessors
+                    for (pou, implementation) in parse_property(lexer, &name, linkage) {
+                        impl_pous.push(Pou { attributes: attributes.clone(), ..pou });
+                        implementations.push(implementation);
+                    }
+                } else {
+                    report_unattached_attributes(lexer, attributes);
+                    break;
                 }
             }
 
@@ -227,6 +282,9 @@ fn parse_pou(
                 generics,
                 linkage,
                 super_class,
+                is_test: false,
+                accessor: None,
+                attributes: vec![],
             }];
             pous.append(&mut impl_pous);
 
@@ -343,7 +401,10 @@ fn parse_return_type(lexer: &mut ParseSession, pou_type: &PouType) -> Option<Dat
             }
 
             if let DataTypeDeclaration::DataTypeDefinition { data_type, .. } = &declaration {
-                if matches!(data_type, DataType::EnumType { .. } | DataType::StructType { .. }) {
+                if matches!(
+                    data_type,
+                    DataType::EnumType { .. } | DataType::StructType { .. } | DataType::UnionType { .. }
+                ) {
                     lexer.accept_diagnostic(Diagnostic::function_unsupported_return_type(&declaration))
                 }
             }
@@ -422,12 +483,196 @@ fn parse_method(
                 generics,
                 linkage,
                 super_class: None,
+                is_test: false,
+                accessor: None,
+                attributes: vec![],
             },
             implementation,
         ))
     })
 }
 
+fn parse_property(
+    lexer: &mut ParseSession,
+    class_name: &str,
+    linkage: LinkageType,
+) -> Vec<(Pou, Implementation)> {
+    parse_any_in_region(lexer, vec![KeywordEndProperty], |lexer| {
+        // Property declarations look like this:
+        // PROPERTY [AccessModifier] name : type
+        //    [GET ... END_GET]
+        //    [SET ... END_SET]
+        // END_PROPERTY
+        lexer.advance(); // eat PROPERTY keyword
+
+        // other than methods, properties are public unless declared otherwise
+        let access = if matches!(
+            lexer.token,
+            KeywordAccessPublic | KeywordAccessPrivate | KeywordAccessProtected | KeywordAccessInternal
+        ) {
+            parse_access_modifier(lexer)
+        } else {
+            AccessModifier::Public
+        };
+        let Some((name, name_location)) = parse_identifier(lexer) else { return vec![] };
+        expect_token!(lexer, KeywordColon, vec![]);
+        lexer.advance();
+        let Some((data_type, _)) = parse_data_type_definition(lexer, None) else {
+            lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                "Datatype",
+                lexer.slice(),
+                lexer.source_range_factory.create_range(lexer.range()),
+            ));
+            return vec![];
+        };
+
+        let mut accessors = vec![];
+        while lexer.token == Identifier {
+            // GET and SET are no reserved keywords, they only start an accessor inside of a property
+            let (kind, closing_token) = if lexer.slice().eq_ignore_ascii_case("GET") {
+                (AccessorKind::Get, KeywordEndGet)
+            } else if lexer.slice().eq_ignore_ascii_case("SET") {
+                (AccessorKind::Set, KeywordEndSet)
+            } else {
+                break;
+            };
+            let accessor = PropertyAccessor { property: name.clone(), kind, access };
+            accessors.push(parse_any_in_region(lexer, vec![closing_token], |lexer| {
+                parse_property_accessor(lexer, class_name, accessor, &data_type, &name_location, linkage)
+            }));
+        }
+
+        if accessors.is_empty() {
+            lexer.accept_diagnostic(Diagnostic::missing_property_accessor(
+                &name,
+                "GET or SET",
+                name_location,
+            ));
+        }
+        accessors
+    })
+}
+
+/// parses the GET or SET block of a property into a method of the property's owner.
+/// The getter returns the property's type, the setter receives the new value as an
+/// input named after the property.
+fn parse_property_accessor(
+    lexer: &mut ParseSession,
+    class_name: &str,
+    accessor: PropertyAccessor,
+    data_type: &DataTypeDeclaration,
+    name_location: &SourceRange,
+    linkage: LinkageType,
+) -> (Pou, Implementation) {
+    let accessor_start = lexer.location().get_start();
+    lexer.advance(); // eat GET or SET
+
+    let (accessor_name, return_type, mut variable_blocks) = match accessor.kind {
+        AccessorKind::Get => (format!("__get_{}", accessor.property), Some(data_type.clone()), vec![]),
+        AccessorKind::Set => {
+            let input = VariableBlock {
+                access: AccessModifier::Protected,
+                constant: false,
+                retain: false,
+                variables: vec![Variable {
+                    name: accessor.property.clone(),
+                    data_type_declaration: data_type.clone(),
+                    initializer: None,
+                    address: None,
+                    location: name_location.clone(),
+                    attributes: vec![],
+                }],
+                variable_block_type: VariableBlockType::Input(ArgumentProperty::ByVal),
+                linkage: LinkageType::Internal,
+                location: name_location.clone(),
+            };
+            (format!("__set_{}", accessor.property), None, vec![input])
+        }
+    };
+    while matches!(lexer.token, KeywordVar | KeywordVarTemp) {
+        variable_blocks.push(parse_variable_block(lexer, LinkageType::Internal));
+    }
+
+    let call_name = qualified_name(class_name, &accessor_name);
+    let pou_type = PouType::Method { owner_class: class_name.into() };
+    let implementation = parse_implementation(
+        lexer,
+        linkage,
+        pou_type.clone(),
+        &call_name,
+        &call_name,
+        false,
+        name_location.clone(),
+    );
+    let implementation = Implementation { access: Some(accessor.access), ..implementation };
+
+    let accessor_end = lexer.location().get_end();
+    (
+        Pou {
+            name: call_name,
+            pou_type,
+            variable_blocks,
+            return_type,
+            location: lexer.source_range_factory.create_range(accessor_start..accessor_end),
+            name_location: name_location.clone(),
+            poly_mode: None,
+            generics: vec![],
+            linkage,
+            super_class: None,
+            is_test: false,
+            accessor: Some(accessor),
+            attributes: vec![],
+        },
+        implementation,
+    )
+}
+
+/// parses the attribute pragmas (`{attribute 'name' := 'value'}`) in front of a declaration
+fn parse_attributes(lexer: &mut ParseSession) -> Vec<Attribute> {
+    let mut attributes = vec![];
+    while lexer.token == PropertyAttribute {
+        let location = lexer.location();
+        match split_attribute(lexer.slice()) {
+            Some((name, value)) => attributes.push(Attribute { name, value, location }),
+            None => lexer.accept_diagnostic(Diagnostic::invalid_attribute(location)),
+        }
+        lexer.advance();
+    }
+    attributes
+}
+
+/// splits an attribute pragma into its name and its optional value
+fn split_attribute(pragma: &str) -> Option<(String, Option<String>)> {
+    let content = pragma.trim_start_matches('{').trim_end_matches('}').trim_start();
+    //the lexer only produces attribute pragmas that start with the keyword
+    let (name, rest) = split_quoted(content["attribute".len()..].trim_start())?;
+    if name.is_empty() {
+        return None;
+    }
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Some((name, None));
+    }
+    let (value, rest) = split_quoted(rest.strip_prefix(":=")?.trim_start())?;
+    rest.trim().is_empty().then_some((name, Some(value)))
+}
+
+/// splits a leading `'quoted'` text from the rest of the given text
+fn split_quoted(text: &str) -> Option<(String, &str)> {
+    let text = text.strip_prefix('\'')?;
+    let end = text.find('\'')?;
+    Some((text[..end].to_string(), &text[end + 1..]))
+}
+
+fn report_unattached_attributes(lexer: &mut ParseSession, attributes: Vec<Attribute>) {
+    for attribute in attributes {
+        lexer.accept_diagnostic(Diagnostic::invalid_pragma_location(
+            "Attributes can only be attached to POUs, types and variables",
+            attribute.location,
+        ));
+    }
+}
+
 fn parse_access_modifier(lexer: &mut ParseSession) -> AccessModifier {
     if lexer.try_consume(&KeywordAccessPublic) {
         AccessModifier::Public
@@ -539,8 +784,14 @@ fn parse_type(lexer: &mut ParseSession) -> Vec<UserTypeDeclaration> {
     parse_any_in_region(lexer, vec![KeywordEndType], |lexer| {
         let mut declarations = vec![];
         while !lexer.closes_open_region(&lexer.token) {
+            let attributes = parse_attributes(lexer);
+            if lexer.closes_open_region(&lexer.token) {
+                report_unattached_attributes(lexer, attributes);
+                break;
+            }
             let name = lexer.slice_and_advance();
             let name_location = lexer.last_location();
+            let generics = parse_generics(lexer);
             lexer.consume_or_report(KeywordColon);
 
             let result = parse_full_data_type_definition(lexer, Some(name));
@@ -551,6 +802,8 @@ fn parse_type(lexer: &mut ParseSession) -> Vec<UserTypeDeclaration> {
                     initializer,
                     location: name_location,
                     scope: lexer.scope.clone(),
+                    generics,
+                    attributes,
                 });
             }
         }
@@ -564,7 +817,11 @@ fn parse_full_data_type_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
 ) -> Option<DataTypeWithInitializer> {
-    let end_keyword = if lexer.token == KeywordStruct { KeywordEndStruct } else { KeywordSemicolon };
+    let end_keyword = match lexer.token {
+        KeywordStruct => KeywordEndStruct,
+        KeywordUnion => KeywordEndUnion,
+        _ => KeywordSemicolon,
+    };
     parse_any_in_region(lexer, vec![end_keyword], |lexer| {
         let sized = lexer.try_consume(&PropertySized);
         if lexer.try_consume(&KeywordDotDotDot) {
@@ -595,7 +852,7 @@ fn parse_full_data_type_definition(
     })
 }
 
-// TYPE xxx : 'STRUCT' | '(' | IDENTIFIER
+// TYPE xxx : 'STRUCT' | 'UNION' | '(' | IDENTIFIER
 fn parse_data_type_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
@@ -612,6 +869,17 @@ fn parse_data_type_definition(
             },
             None,
         ))
+    } else if lexer.try_consume(&KeywordUnion) {
+        // Parse union
+        let variables = parse_variable_list(lexer);
+        Some((
+            DataTypeDeclaration::DataTypeDefinition {
+                data_type: DataType::UnionType { name, variables },
+                location: (start..lexer.range().end).into(),
+                scope: lexer.scope.clone(),
+            },
+            None,
+        ))
     } else if lexer.try_consume(&KeywordArray) {
         parse_array_type_definition(lexer, name)
     } else if lexer.try_consume(&KeywordPointer) {
@@ -626,9 +894,9 @@ fn parse_data_type_definition(
         } else {
             lexer.advance();
         }
-        parse_pointer_definition(lexer, name, start_pos)
+        parse_pointer_definition(lexer, name, start_pos, false)
     } else if lexer.try_consume(&KeywordRef) {
-        parse_pointer_definition(lexer, name, lexer.last_range.start)
+        parse_pointer_definition(lexer, name, lexer.last_range.start, false)
     } else if lexer.try_consume(&KeywordParensOpen) {
         //enum without datatype
         parse_enum_type_definition(lexer, name)
@@ -651,11 +919,12 @@ fn parse_pointer_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
     start_pos: usize,
+    auto_deref: bool,
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     parse_data_type_definition(lexer, None).map(|(decl, initializer)| {
         (
             DataTypeDeclaration::DataTypeDefinition {
-                data_type: DataType::PointerType { name, referenced_type: Box::new(decl) },
+                data_type: DataType::PointerType { name, referenced_type: Box::new(decl), auto_deref },
                 location: (start_pos..lexer.last_range.end).into(),
                 scope: lexer.scope.clone(),
             },
@@ -672,6 +941,29 @@ fn parse_type_reference_type_definition(
     //Subrange
     let referenced_type = lexer.slice_and_advance();
 
+    // REFERENCE is only a keyword in front of TO, so it can still be used as a name
+    if referenced_type.eq_ignore_ascii_case("REFERENCE") && lexer.try_consume(&KeywordTo) {
+        return parse_pointer_definition(lexer, name, start, true);
+    }
+
+    if lexer.try_consume(&OperatorLess) {
+        // FIFO<INT>
+        let type_arguments = parse_generic_type_arguments(lexer);
+        let initial_value =
+            if lexer.try_consume(&KeywordAssignment) { Some(parse_expression(lexer)) } else { None };
+        let data_type = DataTypeDeclaration::DataTypeDefinition {
+            data_type: DataType::GenericInstance {
+                name,
+                generic_type: referenced_type,
+                type_arguments,
+                generics: vec![],
+            },
+            location: (start..lexer.last_range.end).into(),
+            scope: lexer.scope.clone(),
+        };
+        return Some((data_type, initial_value));
+    }
+
     let bounds = if lexer.try_consume(&KeywordParensOpen) {
         // INT (..) :=
         let bounds = parse_expression(lexer);
@@ -728,6 +1020,29 @@ fn parse_type_reference_type_definition(
     }
 }
 
+fn parse_generic_type_arguments(lexer: &mut ParseSession) -> Vec<String> {
+    parse_any_in_region(lexer, vec![OperatorGreater], |lexer| {
+        let mut type_arguments = vec![];
+        loop {
+            if matches!(lexer.token, Identifier | KeywordString | KeywordWideString) {
+                type_arguments.push(lexer.slice_and_advance());
+            } else {
+                lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                    "Identifier",
+                    lexer.slice(),
+                    lexer.location(),
+                ));
+                break;
+            }
+
+            if !lexer.try_consume(&KeywordComma) {
+                break;
+            }
+        }
+        type_arguments
+    })
+}
+
 fn parse_string_size_expression(lexer: &mut ParseSession) -> Option<AstStatement> {
     let opening_token = lexer.token.clone();
     if lexer.try_consume(&KeywordSquareParensOpen) || lexer.try_consume(&KeywordParensOpen) {
@@ -882,6 +1197,15 @@ fn parse_body_in_region(lexer: &mut ParseSession, end_keywords: Vec<Token>) -> V
 }
 
 fn parse_body_standalone(lexer: &mut ParseSession) -> Vec<AstStatement> {
+    parse_statements_standalone(lexer).into_iter().map(to_label_statement).collect()
+}
+
+/// parse the body of a CASE statement, where `x:` denotes a case-condition instead of a label
+fn parse_case_body_in_region(lexer: &mut ParseSession, end_keywords: Vec<Token>) -> Vec<AstStatement> {
+    parse_any_in_region(lexer, end_keywords, parse_statements_standalone)
+}
+
+fn parse_statements_standalone(lexer: &mut ParseSession) -> Vec<AstStatement> {
     let mut statements = Vec::new();
     while !lexer.closes_open_region(&lexer.token) {
         statements.push(parse_control(lexer));
@@ -889,6 +1213,21 @@ fn parse_body_standalone(lexer: &mut ParseSession) -> Vec<AstStatement> {
     statements
 }
 
+/// outside of CASE statements a simple identifier followed by a ':' is a jump label (`label:`)
+fn to_label_statement(statement: AstStatement) -> AstStatement {
+    if let AstStatement::CaseCondition { condition, id } = &statement {
+        if let AstStatement::ReferenceExpr { access: ReferenceAccess::Member(member), base: None, .. } =
+            condition.as_ref()
+        {
+            if let AstStatement::Identifier { name, .. } = member.as_ref() {
+                let location = condition.get_location();
+                return AstStatement::LabelStatement { name: name.clone(), location, id: *id };
+            }
+        }
+    }
+    statement
+}
+
 /// parses a statement ending with a ';'
 fn parse_statement(lexer: &mut ParseSession) -> AstStatement {
     let result = parse_any_in_region(lexer, vec![KeywordSemicolon, KeywordColon], parse_expression);
@@ -995,8 +1334,14 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
 
 fn parse_variable_list(lexer: &mut ParseSession) -> Vec<Variable> {
     let mut variables = vec![];
-    while lexer.token == Identifier {
+    while lexer.token == Identifier || lexer.token == PropertyAttribute {
+        let attributes = parse_attributes(lexer);
+        if lexer.token != Identifier {
+            report_unattached_attributes(lexer, attributes);
+            break;
+        }
         let mut line_vars = parse_variable_line(lexer);
+        line_vars.iter_mut().for_each(|it| it.attributes = attributes.clone());
         variables.append(&mut line_vars);
     }
     variables
@@ -1060,12 +1405,71 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
                 location,
                 initializer: initializer.clone(),
                 address: address.clone(),
+                attributes: vec![],
             });
         }
     }
     variables
 }
 
+/// parses a `VAR_CONFIG` block assigning addresses to the template variables of instances
+fn parse_config_variables(lexer: &mut ParseSession) -> Vec<ConfigVariable> {
+    lexer.advance(); //Consume VAR_CONFIG
+    parse_any_in_region(lexer, vec![KeywordEndVar], |lexer| {
+        let mut variables = vec![];
+        while lexer.token == Identifier {
+            if let Some(variable) = parse_config_variable(lexer) {
+                variables.push(variable);
+            }
+        }
+        variables
+    })
+}
+
+// prg.fbs[1].a AT %IX1.0 : BOOL;
+fn parse_config_variable(lexer: &mut ParseSession) -> Option<ConfigVariable> {
+    let start = lexer.location();
+    let reference = parse_reference(lexer);
+    let reference_location = reference.get_location();
+
+    let address = if lexer.try_consume(&KeywordAt) {
+        if let HardwareAccess((direction, access_type)) = lexer.token {
+            parse_hardware_access(lexer, direction, access_type)
+                .map_err(|err| lexer.accept_diagnostic(err))
+                .ok()
+        } else {
+            lexer.accept_diagnostic(Diagnostic::missing_token("Hardware Access", lexer.location()));
+            None
+        }
+    } else {
+        lexer.accept_diagnostic(Diagnostic::missing_token(
+            format!("{KeywordAt:?}").as_str(),
+            lexer.location(),
+        ));
+        None
+    };
+
+    if !lexer.try_consume(&KeywordColon) {
+        lexer.accept_diagnostic(Diagnostic::missing_token(
+            format!("{KeywordColon:?}").as_str(),
+            lexer.location(),
+        ));
+    }
+
+    let (data_type_declaration, _) = parse_full_data_type_definition(lexer, None)?;
+    let variable = ConfigVariable {
+        reference,
+        data_type_declaration,
+        address: address?,
+        location: start.span(&lexer.last_location()),
+    };
+    if variable.get_instance_path().is_none() {
+        lexer.accept_diagnostic(Diagnostic::invalid_instance_path(reference_location));
+        return None;
+    }
+    Some(variable)
+}
+
 fn parse_hardware_access(
     lexer: &mut ParseSession,
     hardware_access_type: HardwareAccessType,
@@ -8,7 +8,7 @@ use plc_diagnostics::diagnostics::Diagnostic;
 use crate::{
     expect_token,
     lexer::Token::*,
-    parser::{parse_any_in_region, parse_body_in_region},
+    parser::{parse_any_in_region, parse_body_in_region, parse_case_body_in_region},
 };
 
 use super::ParseSession;
@@ -24,6 +24,7 @@ pub fn parse_control_statement(lexer: &mut ParseSession) -> AstStatement {
         KeywordReturn => parse_return_statement(lexer),
         KeywordContinue => parse_continue_statement(lexer),
         KeywordExit => parse_exit_statement(lexer),
+        KeywordJmp => parse_jump_statement(lexer),
         _ => parse_statement(lexer),
     }
 }
@@ -46,6 +47,22 @@ fn parse_continue_statement(lexer: &mut ParseSession) -> AstStatement {
     AstStatement::ContinueStatement { location, id: lexer.next_id() }
 }
 
+fn parse_jump_statement(lexer: &mut ParseSession) -> AstStatement {
+    let start = lexer.range().start;
+    lexer.advance(); // JMP
+
+    parse_any_in_region(lexer, vec![KeywordSemicolon], |lexer| {
+        expect_token!(
+            lexer,
+            Identifier,
+            AstStatement::EmptyStatement { location: lexer.location(), id: lexer.next_id() }
+        );
+        let label = lexer.slice_and_advance();
+        let location = lexer.source_range_factory.create_range(start..lexer.last_range.end);
+        AstStatement::JumpStatement { label, location, id: lexer.next_id() }
+    })
+}
+
 fn parse_if_statement(lexer: &mut ParseSession) -> AstStatement {
     let start = lexer.range().start;
     lexer.advance(); //If
@@ -175,7 +192,7 @@ fn parse_case_statement(lexer: &mut ParseSession) -> AstStatement {
 
     let mut case_blocks = Vec::new();
     if lexer.token != KeywordEndCase && lexer.token != KeywordElse {
-        let body = parse_body_in_region(lexer, vec![KeywordEndCase, KeywordElse]);
+        let body = parse_case_body_in_region(lexer, vec![KeywordEndCase, KeywordElse]);
 
         let mut current_condition = None;
         let mut current_body = vec![];
@@ -247,6 +247,13 @@ fn parse_leaf_expression(lexer: &mut ParseSession) -> AstStatement {
                     right: Box::new(parse_range_statement(lexer)),
                     id: lexer.next_id(),
                 }
+            } else if lexer.token == KeywordReferenceAssignment {
+                lexer.advance();
+                AstStatement::ReferenceAssignment {
+                    left: Box::new(statement),
+                    right: Box::new(parse_range_statement(lexer)),
+                    id: lexer.next_id(),
+                }
             } else {
                 statement
             }
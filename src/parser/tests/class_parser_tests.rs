@@ -1,4 +1,7 @@
-use plc_ast::ast::{AccessModifier, ArgumentProperty, PolymorphismMode, PouType, VariableBlockType};
+use plc_ast::ast::{
+    AccessModifier, AccessorKind, ArgumentProperty, PolymorphismMode, PouType, PropertyAccessor,
+    VariableBlockType,
+};
 
 use crate::test_utils::tests::parse;
 
@@ -340,3 +343,61 @@ fn fb_method_with_return_type_can_be_parsed() {
     assert_ne!(method_pou.return_type, None);
     assert_eq!(method.overriding, true);
 }
+
+#[test]
+fn property_accessors_are_lowered_into_methods() {
+    let src = r#"
+        FUNCTION_BLOCK MyFb
+            VAR speed_ : INT; END_VAR
+            PROPERTY PRIVATE Speed : INT
+                GET
+                    Speed := speed_;
+                END_GET
+                SET
+                    VAR tmp : INT; END_VAR
+                    speed_ := Speed;
+                END_SET
+            END_PROPERTY
+            PROPERTY Ready : BOOL
+                GET Ready := TRUE; END_GET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+    "#;
+    let (unit, diagnostics) = parse(src);
+    assert_eq!(diagnostics, vec![]);
+
+    // the fb, 2 accessors of Speed and the getter of Ready
+    assert_eq!(unit.units.len(), 4);
+    assert_eq!(unit.implementations.len(), 4);
+
+    let getter = &unit.units[1];
+    assert_eq!(getter.name, "MyFb.__get_Speed");
+    assert_eq!(getter.pou_type, PouType::Method { owner_class: "MyFb".into() });
+    assert_eq!(
+        getter.accessor,
+        Some(PropertyAccessor {
+            property: "Speed".into(),
+            kind: AccessorKind::Get,
+            access: AccessModifier::Private
+        })
+    );
+    // the property's name is the getter's return variable
+    assert_ne!(getter.return_type, None);
+    assert_eq!(getter.get_return_name(), "Speed");
+
+    let setter = &unit.units[2];
+    assert_eq!(setter.name, "MyFb.__set_Speed");
+    assert_eq!(setter.return_type, None);
+    assert_eq!(
+        setter.variable_blocks[0].variable_block_type,
+        VariableBlockType::Input(ArgumentProperty::ByVal)
+    );
+    assert_eq!(setter.variable_blocks[0].variables[0].name, "Speed");
+    assert_eq!(setter.variable_blocks[1].variables[0].name, "tmp");
+    assert_eq!(unit.implementations[1].access, Some(AccessModifier::Private));
+
+    // properties are public by default
+    let ready = &unit.units[3];
+    assert_eq!(ready.name, "MyFb.__get_Ready");
+    assert_eq!(ready.accessor.as_ref().map(|it| it.access), Some(AccessModifier::Public));
+}
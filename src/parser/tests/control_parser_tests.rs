@@ -577,3 +577,36 @@ fn call_stmnt_location_test() {
         );
     }
 }
+
+#[test]
+fn jump_and_label_statements() {
+    let src = "
+        PROGRAM exp 
+        start:
+            x := x + 1;
+            JMP start;
+            CASE x OF
+            a: JMP done;
+            END_CASE
+        done:
+        END_PROGRAM
+        ";
+    let (result, diagnostics) = parse(src);
+    assert_eq!(diagnostics, vec![]);
+
+    let statements = &result.implementations[0].statements;
+    assert_eq!(format!("{:#?}", statements[0]), "LabelStatement {\n    name: \"start\",\n}");
+    assert_eq!(format!("{:#?}", statements[2]), "JumpStatement {\n    label: \"start\",\n}");
+    assert_eq!(format!("{:#?}", statements[4]), "LabelStatement {\n    name: \"done\",\n}");
+
+    // inside of a CASE statement `a:` remains a case-condition
+    if let AstStatement::ControlStatement { kind: AstControlStatement::Case(case), .. } = &statements[3] {
+        assert_eq!(case.case_blocks.len(), 1);
+        assert_eq!(
+            format!("{:#?}", case.case_blocks[0].body),
+            "[\n    JumpStatement {\n        label: \"done\",\n    },\n]"
+        );
+    } else {
+        panic!("expected a case statement, found {:#?}", statements[3]);
+    }
+}
@@ -883,6 +883,25 @@ fn assignment_to_null() {
     assert_debug_snapshot!(statement);
 }
 
+#[test]
+fn reference_assignment_test() {
+    let src = "
+        PROGRAM exp 
+        x REF= y;
+        END_PROGRAM
+        ";
+    let (result, diagnostics) = parse(src);
+    assert_eq!(diagnostics, vec![]);
+
+    let statement = &result.implementations[0].statements[0];
+    if let AstStatement::ReferenceAssignment { left, right, .. } = statement {
+        assert_eq!(left.get_flat_reference_name(), Some("x"));
+        assert_eq!(right.get_flat_reference_name(), Some("y"));
+    } else {
+        panic!("expected a reference assignment, found {statement:#?}");
+    }
+}
+
 #[test]
 fn assignment_to_number_with_implicit_and_explicit_plus_sign() {
     let src = "
@@ -1625,6 +1644,9 @@ fn sized_string_as_function_return() {
         generics: vec![],
         linkage: LinkageType::Internal,
         super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
     };
 
     assert_eq!(format!("{:?}", ast.units[0]), format!("{expected:?}"));
@@ -1675,6 +1697,9 @@ fn array_type_as_function_return() {
         generics: vec![],
         linkage: LinkageType::Internal,
         super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
     };
 
     assert_eq!(format!("{:?}", ast.units[0]), format!("{expected:?}"));
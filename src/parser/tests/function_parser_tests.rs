@@ -218,6 +218,7 @@ fn varargs_parameters_can_be_parsed() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 },
                 Variable {
                     name: "args2".into(),
@@ -235,6 +236,7 @@ fn varargs_parameters_can_be_parsed() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 },
             ],
         }],
@@ -244,6 +246,9 @@ fn varargs_parameters_can_be_parsed() {
         generics: vec![],
         linkage: LinkageType::Internal,
         super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{x:#?}").as_str());
 }
@@ -288,6 +293,7 @@ fn sized_varargs_parameters_can_be_parsed() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 },
                 Variable {
                     name: "args2".into(),
@@ -305,6 +311,7 @@ fn sized_varargs_parameters_can_be_parsed() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 },
             ],
         }],
@@ -314,6 +321,9 @@ fn sized_varargs_parameters_can_be_parsed() {
         generics: vec![],
         linkage: LinkageType::Internal,
         super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{x:#?}").as_str());
 }
@@ -480,6 +490,7 @@ fn function_inline_struct_return_unsupported() {
                         },
                         initializer: None,
                         address: None,
+                        attributes: vec![],
                     },
                     Variable {
                         name: "y".into(),
@@ -490,6 +501,7 @@ fn function_inline_struct_return_unsupported() {
                         },
                         initializer: None,
                         address: None,
+                        attributes: vec![],
                     }
                 ],
             },
@@ -568,3 +580,29 @@ fn var_input_by_ref_parsed() {
     }
     "###)
 }
+
+#[test]
+fn functions_can_be_marked_as_tests() {
+    let src = "
+    {test}
+    FUNCTION test_foo : BOOL END_FUNCTION
+    FUNCTION foo : BOOL END_FUNCTION
+    ";
+    let (result, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    assert!(result.units[0].is_test);
+    assert!(!result.units[1].is_test);
+}
+
+#[test]
+fn test_pragma_on_a_program_is_reported() {
+    let src = "{test} PROGRAM prg END_PROGRAM";
+    let (result, diagnostics) = parse(src);
+
+    assert!(!result.units[0].is_test);
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::invalid_pragma_location("Only functions can be marked as {test}", (0..6).into())]
+    );
+}
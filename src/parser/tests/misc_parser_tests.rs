@@ -68,6 +68,7 @@ fn exponent_literals_parsed_as_variables() {
                 initializer: None,
                 address: None,
                 location: SourceRange::undefined(),
+                attributes: vec![],
             }],
         }],
         location: SourceRange::undefined(),
@@ -75,6 +76,9 @@ fn exponent_literals_parsed_as_variables() {
         generics: vec![],
         linkage: LinkageType::Internal,
         super_class: None,
+        is_test: false,
+        accessor: None,
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{pou:#?}").as_str());
     let implementation = &parse_result.implementations[0];
@@ -656,3 +660,94 @@ fn location_implementation_for_all_statements() {
         (1..5).into()
     );
 }
+
+fn attribute_values(attributes: &[plc_ast::ast::Attribute]) -> Vec<(&str, Option<&str>)> {
+    attributes.iter().map(|it| (it.name.as_str(), it.value.as_deref())).collect()
+}
+
+#[test]
+fn attributes_are_attached_to_pous_and_variables() {
+    let src = "
+    {attribute 'task' := 'fast'}
+    {attribute 'hide'}
+    FUNCTION_BLOCK fb
+    VAR
+        {attribute 'unit' := 'mm'}
+        a, b : INT;
+        c : INT;
+    END_VAR
+    {attribute 'call'}
+    METHOD m END_METHOD
+    {attribute 'observed'}
+    PROPERTY p : INT
+        GET END_GET
+        SET END_SET
+    END_PROPERTY
+    END_FUNCTION_BLOCK
+
+    VAR_GLOBAL
+        {ATTRIBUTE 'opc' := 'ns=2;s=g'} g : BOOL;
+    END_VAR
+    ";
+    let (result, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    let fb = &result.units[0];
+    assert_eq!(attribute_values(&fb.attributes), vec![("task", Some("fast")), ("hide", None)]);
+    let variables = &fb.variable_blocks[0].variables;
+    assert_eq!(attribute_values(&variables[0].attributes), vec![("unit", Some("mm"))]);
+    assert_eq!(attribute_values(&variables[1].attributes), vec![("unit", Some("mm"))]);
+    assert_eq!(attribute_values(&variables[2].attributes), vec![]);
+    //the method and both accessors of the property
+    assert_eq!(attribute_values(&result.units[1].attributes), vec![("call", None)]);
+    assert_eq!(attribute_values(&result.units[2].attributes), vec![("observed", None)]);
+    assert_eq!(attribute_values(&result.units[3].attributes), vec![("observed", None)]);
+    assert_eq!(
+        attribute_values(&result.global_vars[0].variables[0].attributes),
+        vec![("opc", Some("ns=2;s=g"))]
+    );
+}
+
+#[test]
+fn attributes_are_attached_to_types_and_struct_members() {
+    let src = "
+    {attribute 'shared'}
+    TYPE
+        {attribute 'point'}
+        Point : STRUCT
+            {attribute 'unit' := 'mm'}
+            x : INT;
+            y : INT;
+        END_STRUCT
+        Color : (red, green);
+    END_TYPE
+    ";
+    let (result, diagnostics) = parse(src);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(attribute_values(&result.user_types[0].attributes), vec![("shared", None), ("point", None)]);
+    assert_eq!(attribute_values(&result.user_types[1].attributes), vec![("shared", None)]);
+    let plc_ast::ast::DataType::StructType { variables, .. } = &result.user_types[0].data_type else {
+        panic!("expected a struct");
+    };
+    assert_eq!(attribute_values(&variables[0].attributes), vec![("unit", Some("mm"))]);
+    assert_eq!(attribute_values(&variables[1].attributes), vec![]);
+}
+
+#[test]
+fn malformed_and_misplaced_attributes_are_reported() {
+    let src = "{attribute foo} PROGRAM prg VAR x : INT; {attribute 'a'} END_VAR END_PROGRAM";
+    let (result, diagnostics) = parse(src);
+
+    assert_eq!(attribute_values(&result.units[0].attributes), vec![]);
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_attribute((0..15).into()),
+            Diagnostic::invalid_pragma_location(
+                "Attributes can only be attached to POUs, types and variables",
+                (41..56).into()
+            ),
+        ]
+    );
+}
@@ -201,6 +201,7 @@ fn unclosed_var_container() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 }],
                 linkage: LinkageType::Internal,
             }
@@ -158,6 +158,8 @@ fn string_with_round_parens_can_be_parsed() {
                 initializer: None,
                 location: (18..42).into(),
                 scope: None,
+                generics: vec![],
+                attributes: vec![],
             },
             UserTypeDeclaration {
                 data_type: DataType::StringType {
@@ -176,6 +178,8 @@ fn string_with_round_parens_can_be_parsed() {
                 }),
                 location: SourceRange::undefined(),
                 scope: None,
+                generics: vec![],
+                attributes: vec![],
             },
             UserTypeDeclaration {
                 data_type: DataType::StringType {
@@ -190,6 +194,8 @@ fn string_with_round_parens_can_be_parsed() {
                 initializer: None,
                 location: SourceRange::undefined(),
                 scope: None,
+                generics: vec![],
+                attributes: vec![],
             }
         ]
     );
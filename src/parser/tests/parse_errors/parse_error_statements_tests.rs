@@ -272,6 +272,7 @@ fn invalid_variable_name_error_recovery() {
                     initializer: None,
                     address: None,
                     location: SourceRange::undefined(),
+                    attributes: vec![],
                 },],
                 variable_block_type: VariableBlockType::Local,
                 linkage: LinkageType::Internal,
@@ -1282,10 +1283,13 @@ fn pointer_type_without_to_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{pointer_type:#?}").as_str());
 
@@ -1317,10 +1321,13 @@ fn pointer_type_with_wrong_keyword_to_test() {
                 referenced_type: "tu".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{pointer_type:#?}").as_str());
     assert_eq!(
@@ -1,4 +1,4 @@
-use plc_ast::ast::{DataTypeDeclaration, GenericBinding, TypeNature, Variable};
+use plc_ast::ast::{DataType, DataTypeDeclaration, GenericBinding, TypeNature, Variable};
 
 use crate::test_utils::tests::parse;
 
@@ -91,6 +91,7 @@ fn generic_parameters_are_datatypes() {
                 initializer: None,
                 address: None,
                 location: (49..50).into(),
+                attributes: vec![],
             },
             Variable {
                 name: "y".into(),
@@ -101,6 +102,7 @@ fn generic_parameters_are_datatypes() {
                 initializer: None,
                 address: None,
                 location: (56..57).into(),
+                attributes: vec![],
             },
         ],
         variables
@@ -125,6 +127,7 @@ fn generic_method_parameters_are_datatypes() {
                 initializer: None,
                 address: None,
                 location: (57..58).into(),
+                attributes: vec![],
             },
             Variable {
                 name: "y".into(),
@@ -136,8 +139,54 @@ fn generic_method_parameters_are_datatypes() {
                 address: None,
 
                 location: (64..65).into(),
+                attributes: vec![],
             },
         ],
         variables
     );
 }
+
+#[test]
+fn generic_function_blocks_and_types_can_be_instantiated() {
+    let src = "
+        FUNCTION_BLOCK FIFO<T : ANY_NUM> VAR buffer : ARRAY[0..9] OF T; END_VAR END_FUNCTION_BLOCK
+        TYPE Pair<K : ANY_INT, V : ANY> : STRUCT key : K; value : V; END_STRUCT END_TYPE
+        PROGRAM main VAR f : FIFO<INT>; p : Pair<DINT, STRING>; END_VAR END_PROGRAM
+        ";
+    let (parse_result, diagnostics) = parse(src);
+    assert_eq!(diagnostics, vec![]);
+
+    assert_eq!(
+        parse_result.user_types[0].generics,
+        vec![
+            GenericBinding { name: "K".into(), nature: TypeNature::Int },
+            GenericBinding { name: "V".into(), nature: TypeNature::Any },
+        ]
+    );
+
+    let variables = &parse_result.units[1].variable_blocks[0].variables;
+    let instances = variables
+        .iter()
+        .map(|it| match &it.data_type_declaration {
+            DataTypeDeclaration::DataTypeDefinition { data_type, .. } => data_type.clone(),
+            _ => panic!("{it:?} is no generic instance"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        instances,
+        vec![
+            DataType::GenericInstance {
+                name: None,
+                generic_type: "FIFO".into(),
+                type_arguments: vec!["INT".into()],
+                generics: vec![],
+            },
+            DataType::GenericInstance {
+                name: None,
+                generic_type: "Pair".into(),
+                type_arguments: vec!["DINT".into(), "STRING".into()],
+                generics: vec![],
+            },
+        ]
+    );
+}
@@ -145,6 +145,7 @@ fn inline_enum_declaration_can_be_parsed() {
         initializer: None,
         address: None,
         location: SourceRange::undefined(),
+        attributes: vec![],
     };
     let expected_ast = format!("{:#?}", &v);
     assert_eq!(ast_string, expected_ast);
@@ -54,6 +54,7 @@ fn simple_struct_type_can_be_parsed() {
                         initializer: None,
                         address: None,
                         location: SourceRange::undefined(),
+                        attributes: vec![],
                     },
                     Variable {
                         name: "Two".to_string(),
@@ -64,6 +65,7 @@ fn simple_struct_type_can_be_parsed() {
                         initializer: None,
                         address: None,
                         location: SourceRange::undefined(),
+                        attributes: vec![],
                     },
                     Variable {
                         name: "Three".to_string(),
@@ -74,12 +76,58 @@ fn simple_struct_type_can_be_parsed() {
                         initializer: None,
                         address: None,
                         location: SourceRange::undefined(),
+                        attributes: vec![],
                     },
                 ),
             },
             initializer: None,
             location: SourceRange::undefined(),
             scope: None,
+            generics: vec![],
+            attributes: vec![],
+        }
+    );
+    assert_eq!(ast_string, expected_ast);
+}
+
+#[test]
+fn simple_union_type_can_be_parsed() {
+    let (result, diagnostics) = parse(
+        r#"
+        TYPE SampleUnion :
+            UNION
+                raw : DWORD;
+                low, high : WORD;
+            END_UNION
+        END_TYPE
+        "#,
+    );
+
+    assert_eq!(diagnostics, vec![]);
+    let ast_string = format!("{:#?}", &result.user_types[0]);
+    let member = |name: &str, type_name: &str| Variable {
+        name: name.to_string(),
+        data_type_declaration: DataTypeDeclaration::DataTypeReference {
+            referenced_type: type_name.to_string(),
+            location: SourceRange::undefined(),
+        },
+        initializer: None,
+        address: None,
+        location: SourceRange::undefined(),
+        attributes: vec![],
+    };
+    let expected_ast = format!(
+        "{:#?}",
+        &UserTypeDeclaration {
+            data_type: DataType::UnionType {
+                name: Some("SampleUnion".to_string()),
+                variables: vec![member("raw", "DWORD"), member("low", "WORD"), member("high", "WORD")],
+            },
+            initializer: None,
+            location: SourceRange::undefined(),
+            scope: None,
+            generics: vec![],
+            attributes: vec![],
         }
     );
     assert_eq!(ast_string, expected_ast);
@@ -165,6 +213,8 @@ fn type_alias_can_be_parsed() {
             initializer: None,
             location: SourceRange::undefined(),
             scope: None,
+            generics: vec![],
+            attributes: vec![],
         }
     );
 
@@ -208,6 +258,8 @@ fn array_type_can_be_parsed_test() {
             initializer: None,
             location: SourceRange::undefined(),
             scope: None,
+            generics: vec![],
+            attributes: vec![],
         }
     );
 
@@ -241,6 +293,8 @@ fn string_type_can_be_parsed_test() {
                 initializer: None,
                 location: SourceRange::undefined(),
                 scope: None,
+                generics: vec![],
+                attributes: vec![],
             },
             UserTypeDeclaration {
                 data_type: DataType::StringType {
@@ -259,6 +313,8 @@ fn string_type_can_be_parsed_test() {
                 }),
                 location: SourceRange::undefined(),
                 scope: None,
+                generics: vec![],
+                attributes: vec![],
             }
         ]
     );
@@ -291,6 +347,8 @@ fn wide_string_type_can_be_parsed_test() {
             initializer: None,
             location: SourceRange::undefined(),
             scope: None,
+            generics: vec![],
+            attributes: vec![],
         }
     );
 
@@ -333,6 +391,7 @@ fn subrangetype_can_be_parsed() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{x:#?}").as_str());
 }
@@ -402,10 +461,13 @@ fn pointer_type_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{pointer_type:#?}").as_str());
     assert_eq!(diagnostics.len(), 1);
@@ -433,15 +495,81 @@ fn ref_type_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            auto_deref: false,
+        },
+        location: SourceRange::undefined(),
+        initializer: None,
+        scope: None,
+        generics: vec![],
+        attributes: vec![],
+    };
+    assert_eq!(format!("{expected:#?}"), format!("{reference_type:#?}").as_str());
+    assert_eq!(diagnostics.len(), 0)
+}
+
+#[test]
+fn reference_to_type_test() {
+    let (result, diagnostics) = parse(
+        r#"
+        TYPE SampleReference :
+            REFERENCE TO INT;
+        END_TYPE 
+        "#,
+    );
+    let reference_type = &result.user_types[0];
+    let expected = UserTypeDeclaration {
+        data_type: DataType::PointerType {
+            name: Some("SampleReference".into()),
+            referenced_type: Box::new(DataTypeDeclaration::DataTypeReference {
+                referenced_type: "INT".to_string(),
+                location: SourceRange::undefined(),
+            }),
+            auto_deref: true,
         },
         location: SourceRange::undefined(),
         initializer: None,
         scope: None,
+        generics: vec![],
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{reference_type:#?}").as_str());
     assert_eq!(diagnostics.len(), 0)
 }
 
+#[test]
+fn reference_can_still_be_used_as_a_name() {
+    let (result, diagnostics) = parse(
+        r#"
+        FUNCTION test : DINT
+        VAR_INPUT
+            reference : REF_TO BOOL;
+            r : REFERENCE TO BOOL;
+        END_VAR
+            reference^ := TRUE;
+            test(reference := reference, r := reference);
+        END_FUNCTION
+        "#,
+    );
+    assert_eq!(diagnostics.len(), 0, "{diagnostics:?}");
+    let variables = &result.units[0].variable_blocks[0].variables;
+    assert_eq!(variables[0].name, "reference");
+    assert!(matches!(
+        variables[0].data_type_declaration,
+        DataTypeDeclaration::DataTypeDefinition {
+            data_type: DataType::PointerType { auto_deref: false, .. },
+            ..
+        }
+    ));
+    assert!(matches!(
+        variables[1].data_type_declaration,
+        DataTypeDeclaration::DataTypeDefinition {
+            data_type: DataType::PointerType { auto_deref: true, .. },
+            ..
+        }
+    ));
+    assert_eq!(result.implementations[0].statements.len(), 2);
+}
+
 #[test]
 fn global_pointer_declaration() {
     let (result, diagnostics) = parse(
@@ -462,6 +590,7 @@ fn global_pointer_declaration() {
                     referenced_type: "INT".to_string(),
                     location: SourceRange::undefined(),
                 }),
+                auto_deref: false,
             },
             location: SourceRange::undefined(),
             scope: None,
@@ -469,6 +598,7 @@ fn global_pointer_declaration() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{reference_type:#?}").as_str());
     let pointer_type = &result.global_vars[0].variables[1];
@@ -481,6 +611,7 @@ fn global_pointer_declaration() {
                     referenced_type: "INT".to_string(),
                     location: SourceRange::undefined(),
                 }),
+                auto_deref: false,
             },
             location: SourceRange::undefined(),
             scope: None,
@@ -488,6 +619,7 @@ fn global_pointer_declaration() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{pointer_type:#?}").as_str());
     assert_eq!(diagnostics.len(), 1);
@@ -528,6 +660,7 @@ fn variable_length_array_can_be_parsed() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{x:#?}").as_str());
 }
@@ -570,6 +703,7 @@ fn multi_dimensional_variable_length_arrays_can_be_parsed() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{var:#?}").as_str());
 
@@ -600,6 +734,7 @@ fn multi_dimensional_variable_length_arrays_can_be_parsed() {
         initializer: None,
         address: None,
         location: (0..0).into(),
+        attributes: vec![],
     };
     assert_eq!(format!("{expected:#?}"), format!("{var:#?}").as_str());
 }
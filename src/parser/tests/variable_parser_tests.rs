@@ -1,4 +1,5 @@
-use plc_ast::ast::{LinkageType, VariableBlock};
+use plc_ast::ast::{AstStatement, DirectAccessType, HardwareAccessType, LinkageType, VariableBlock};
+use plc_diagnostics::diagnostics::Diagnostic;
 
 use crate::test_utils::tests::parse;
 
@@ -220,6 +221,48 @@ fn struct_with_address() {
     insta::assert_snapshot!(format!("{result:?}"));
 }
 
+#[test]
+fn var_config_can_be_parsed() {
+    let src = "VAR_CONFIG
+            prg.fb.a AT %IX1.2 : BOOL;
+            aFb[1, -2].b AT %QW2 : INT;
+            nested[0][1].c AT %MD3 : DINT;
+    END_VAR
+    ";
+    let (result, diag) = parse(src);
+
+    assert_eq!(diag, vec![]);
+    let paths = result.var_config.iter().map(|it| it.get_instance_path().unwrap()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["prg.fb.a", "aFb[1,-2].b", "nested[0][1].c"]);
+    let types =
+        result.var_config.iter().map(|it| it.data_type_declaration.get_name().unwrap()).collect::<Vec<_>>();
+    assert_eq!(types, vec!["BOOL", "INT", "DINT"]);
+    assert!(matches!(
+        result.var_config[0].address,
+        AstStatement::HardwareAccess {
+            direction: HardwareAccessType::Input,
+            access: DirectAccessType::Bit,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn var_config_with_invalid_instance_path() {
+    let src = "VAR_CONFIG
+            prg.fb^.a AT %IX1.2 : BOOL;
+            prg.b AT %IX1.3 : BOOL;
+    END_VAR
+    ";
+    let (result, diag) = parse(src);
+
+    assert_eq!(
+        diag.iter().map(Diagnostic::get_message).collect::<Vec<_>>(),
+        vec!["Expected an instance path of members and literal array indexes (e.g. prg.fbs[1].a)"]
+    );
+    assert_eq!(result.var_config.len(), 1);
+}
+
 #[test]
 fn date_and_time_constants_test() {
     let src = r#"
@@ -0,0 +1,249 @@
+//! Generates python pretty-printers for gdb and lldb based on the type information in the [`Index`]
+//!
+//! Debug information only describes STRINGs as character arrays and TIME/DATE types as plain
+//! integers. The generated scripts use the index' knowledge of these types to display them as
+//! structured text literals (e.g. `T#1h2m`), to stop strings at their terminator and to show the
+//! dimensions of variable length arrays.
+use std::fmt::Write;
+
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::{
+    index::Index,
+    typesystem::{
+        DataTypeInformation, InternalType, StringEncoding, StructSource, DATE_AND_TIME_TYPE, DATE_TYPE,
+        TIME_OF_DAY_TYPE, TIME_TYPE,
+    },
+};
+
+const COMMON_SCRIPT: &str = include_str!("pretty_printers/common.py");
+const GDB_SCRIPT: &str = include_str!("pretty_printers/gdb.py");
+const LLDB_SCRIPT: &str = include_str!("pretty_printers/lldb.py");
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Debugger {
+    Gdb,
+    Lldb,
+}
+
+impl Debugger {
+    /// The file name under which the script is expected next to the given binary.
+    /// gdb automatically loads `<binary>-gdb.py` scripts, lldb scripts have to be imported using
+    /// `command script import <binary>_lldb.py`
+    pub fn get_script_name(&self, binary: &str) -> String {
+        match self {
+            Debugger::Gdb => format!("{binary}-gdb.py"),
+            //lldb imports the script as a python module, dots are not allowed in its name
+            Debugger::Lldb => format!("{}_lldb.py", binary.replace('.', "_")),
+        }
+    }
+}
+
+/// The structured text literal kind a date or time type is displayed as
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TimeKind {
+    Time,
+    Date,
+    DateAndTime,
+    TimeOfDay,
+}
+
+impl TimeKind {
+    fn for_type_name(name: &str) -> Option<TimeKind> {
+        match name {
+            TIME_TYPE => Some(TimeKind::Time),
+            DATE_TYPE => Some(TimeKind::Date),
+            DATE_AND_TIME_TYPE => Some(TimeKind::DateAndTime),
+            TIME_OF_DAY_TYPE => Some(TimeKind::TimeOfDay),
+            _ => None,
+        }
+    }
+
+    fn get_name(&self) -> &'static str {
+        match self {
+            TimeKind::Time => TIME_TYPE,
+            TimeKind::Date => DATE_TYPE,
+            TimeKind::DateAndTime => DATE_AND_TIME_TYPE,
+            TimeKind::TimeOfDay => TIME_OF_DAY_TYPE,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VlaPrinter {
+    pub name: String,
+    pub inner_type_name: String,
+    pub ndims: usize,
+}
+
+/// The types that need a custom pretty-printer
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PrettyPrinters {
+    /// Date and time types including their aliases (e.g. `LTIME`, `DT`)
+    pub time_types: Vec<(String, TimeKind)>,
+    /// Character types, strings are represented as arrays of these types
+    pub char_types: Vec<(String, StringEncoding)>,
+    pub vla_types: Vec<VlaPrinter>,
+}
+
+/// Collects all types in the index that need a custom pretty-printer
+pub fn collect_pretty_printers(index: &Index) -> PrettyPrinters {
+    let mut printers = PrettyPrinters::default();
+    for data_type in index.get_types().values() {
+        let name = data_type.get_name();
+        let effective_type = index.find_effective_type_info(name).unwrap_or(data_type.get_type_information());
+        if let Some(kind) = TimeKind::for_type_name(effective_type.get_name()) {
+            printers.time_types.push((name.to_string(), kind));
+            continue;
+        }
+        match data_type.get_type_information() {
+            info @ DataTypeInformation::Integer { size, .. } if info.is_character() => {
+                let encoding = if *size == 8 { StringEncoding::Utf8 } else { StringEncoding::Utf16 };
+                printers.char_types.push((name.to_string(), encoding));
+            }
+            DataTypeInformation::Struct {
+                source: StructSource::Internal(InternalType::VariableLengthArray { inner_type_name, ndims }),
+                ..
+            } => printers.vla_types.push(VlaPrinter {
+                name: name.to_string(),
+                inner_type_name: inner_type_name.to_string(),
+                ndims: *ndims,
+            }),
+            _ => {}
+        }
+    }
+    printers.time_types.sort_by(|(a, _), (b, _)| a.cmp(b));
+    printers.char_types.sort_by(|(a, _), (b, _)| a.cmp(b));
+    printers.vla_types.sort_by(|a, b| a.name.cmp(&b.name));
+    printers
+}
+
+/// Renders the python script for the given debugger
+pub fn generate_pretty_printers(printers: &PrettyPrinters, debugger: Debugger) -> Result<String, Diagnostic> {
+    let mut script = String::new();
+    render_tables(printers, &mut script)
+        .map_err(|err| Diagnostic::io_write_error("pretty printer", &err.to_string()))?;
+    script.push_str(COMMON_SCRIPT);
+    script.push_str(match debugger {
+        Debugger::Gdb => GDB_SCRIPT,
+        Debugger::Lldb => LLDB_SCRIPT,
+    });
+    Ok(script)
+}
+
+fn render_tables(printers: &PrettyPrinters, script: &mut String) -> std::fmt::Result {
+    writeln!(script, "# Generated by plc, do not edit.")?;
+    writeln!(script)?;
+    writeln!(script, "TIME_TYPES = {{")?;
+    for (name, kind) in &printers.time_types {
+        writeln!(script, "    \"{name}\": \"{}\",", kind.get_name())?;
+    }
+    writeln!(script, "}}")?;
+    writeln!(script)?;
+    writeln!(script, "CHAR_TYPES = {{")?;
+    for (name, encoding) in &printers.char_types {
+        let encoding = match encoding {
+            StringEncoding::Utf8 => "utf-8",
+            StringEncoding::Utf16 => "utf-16",
+        };
+        writeln!(script, "    \"{name}\": \"{encoding}\",")?;
+    }
+    writeln!(script, "}}")?;
+    writeln!(script)?;
+    writeln!(script, "VLA_TYPES = {{")?;
+    for VlaPrinter { name, inner_type_name, ndims } in &printers.vla_types {
+        writeln!(script, "    \"{name}\": (\"{inner_type_name}\", {ndims}),")?;
+    }
+    writeln!(script, "}}")?;
+    writeln!(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pretty_printers::{
+            collect_pretty_printers, generate_pretty_printers, Debugger, TimeKind, VlaPrinter,
+        },
+        test_utils::tests::index,
+        typesystem::StringEncoding,
+    };
+
+    #[test]
+    fn time_types_and_aliases_are_collected() {
+        let (_, index) = index(
+            "
+        TYPE MyTime : TIME; END_TYPE
+        TYPE MyStamp : DT; END_TYPE
+        ",
+        );
+        let printers = collect_pretty_printers(&index);
+        let find = |name: &str| {
+            printers.time_types.iter().find(|(it, _)| it.eq_ignore_ascii_case(name)).map(|(_, kind)| *kind)
+        };
+
+        assert_eq!(find("TIME"), Some(TimeKind::Time));
+        assert_eq!(find("LTIME"), Some(TimeKind::Time));
+        assert_eq!(find("MyTime"), Some(TimeKind::Time));
+        assert_eq!(find("DATE"), Some(TimeKind::Date));
+        assert_eq!(find("DT"), Some(TimeKind::DateAndTime));
+        assert_eq!(find("MyStamp"), Some(TimeKind::DateAndTime));
+        assert_eq!(find("TOD"), Some(TimeKind::TimeOfDay));
+        assert_eq!(find("DINT"), None);
+    }
+
+    #[test]
+    fn char_types_are_collected() {
+        let (_, index) = index("");
+        let printers = collect_pretty_printers(&index);
+
+        assert_eq!(
+            printers.char_types,
+            vec![("CHAR".to_string(), StringEncoding::Utf8), ("WCHAR".to_string(), StringEncoding::Utf16)]
+        );
+    }
+
+    #[test]
+    fn vla_types_are_collected_with_dimensions() {
+        let (_, index) = index(
+            "
+        FUNCTION foo : DINT
+        VAR_IN_OUT
+            a : ARRAY[*] OF INT;
+            b : ARRAY[*, *] OF REAL;
+        END_VAR
+        END_FUNCTION
+        ",
+        );
+        let printers = collect_pretty_printers(&index);
+        let vlas = printers
+            .vla_types
+            .iter()
+            .map(|VlaPrinter { inner_type_name, ndims, .. }| (inner_type_name.as_str(), *ndims))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vlas.len(), 2);
+        assert!(vlas.contains(&("INT", 1)));
+        assert!(vlas.contains(&("REAL", 2)));
+    }
+
+    #[test]
+    fn generated_scripts_contain_type_tables() {
+        let (_, index) = index("TYPE MyTime : TIME; END_TYPE");
+        let printers = collect_pretty_printers(&index);
+
+        let gdb = generate_pretty_printers(&printers, Debugger::Gdb).unwrap();
+        assert!(gdb.contains(r#"    "MyTime": "TIME","#));
+        assert!(gdb.contains(r#"    "WCHAR": "utf-16","#));
+        assert!(gdb.contains("import gdb"));
+
+        let lldb = generate_pretty_printers(&printers, Debugger::Lldb).unwrap();
+        assert!(lldb.contains(r#"    "MyTime": "TIME","#));
+        assert!(lldb.contains("def __lldb_init_module"));
+    }
+
+    #[test]
+    fn script_names_follow_debugger_conventions() {
+        assert_eq!(Debugger::Gdb.get_script_name("app.so"), "app.so-gdb.py");
+        assert_eq!(Debugger::Lldb.get_script_name("app.so"), "app_so_lldb.py");
+    }
+}
@@ -0,0 +1,87 @@
+import datetime
+import struct
+
+NANOS_PER_SECOND = 1000000000
+EPOCH = datetime.datetime(1970, 1, 1)
+DURATION_UNITS = (
+    ("d", 86400 * NANOS_PER_SECOND),
+    ("h", 3600 * NANOS_PER_SECOND),
+    ("m", 60 * NANOS_PER_SECOND),
+    ("s", NANOS_PER_SECOND),
+    ("ms", 1000000),
+    ("us", 1000),
+    ("ns", 1),
+)
+
+TIME_KINDS = dict((name.lower(), kind) for name, kind in TIME_TYPES.items())
+CHAR_ENCODINGS = dict((name.lower(), encoding) for name, encoding in CHAR_TYPES.items())
+VLA_LAYOUTS = dict((name.lower(), layout) for name, layout in VLA_TYPES.items())
+
+
+def format_fraction(nanos):
+    return ("." + ("%09d" % nanos).rstrip("0")) if nanos else ""
+
+
+def format_duration(nanos):
+    sign = "-" if nanos < 0 else ""
+    nanos = abs(nanos)
+    parts = []
+    for unit, factor in DURATION_UNITS:
+        value, nanos = divmod(nanos, factor)
+        if value:
+            parts.append("%d%s" % (value, unit))
+    return sign + ("".join(parts) or "0s")
+
+
+def format_time_value(kind, nanos):
+    """Formats the nanoseconds stored in a date or time variable as a structured text literal"""
+    if kind == "TIME":
+        return "T#" + format_duration(nanos)
+    seconds, fraction = divmod(nanos, NANOS_PER_SECOND)
+    if kind == "TIME_OF_DAY":
+        minutes, seconds = divmod(seconds, 60)
+        hours, minutes = divmod(minutes, 60)
+        return "TOD#%02d:%02d:%02d%s" % (hours, minutes, seconds, format_fraction(fraction))
+    try:
+        stamp = EPOCH + datetime.timedelta(seconds=seconds)
+    except OverflowError:
+        return "%s#<invalid: %d>" % (kind, nanos)
+    if kind == "DATE":
+        return "D#" + stamp.strftime("%Y-%m-%d")
+    return "DT#" + stamp.strftime("%Y-%m-%d-%H:%M:%S") + format_fraction(fraction)
+
+
+def format_string(units, encoding):
+    """Decodes the given character codes up to the terminator into a structured text literal"""
+    chars = []
+    for unit in units:
+        if unit == 0:
+            break
+        chars.append(unit)
+    if encoding == "utf-16":
+        text = struct.pack("<%dH" % len(chars), *chars).decode("utf-16-le", "replace")
+        return '"%s"' % text.replace("$", "$$").replace('"', '$"')
+    text = bytes(bytearray(chars)).decode("utf-8", "replace")
+    return "'%s'" % text.replace("$", "$$").replace("'", "$'")
+
+
+def format_vla(inner_type_name, dimensions):
+    ranges = ", ".join("%d..%d" % dimension for dimension in dimensions)
+    return "ARRAY[%s] OF %s" % (ranges, inner_type_name)
+
+
+def vla_indices(dimensions, index):
+    """Converts a flat (row-major) element index into the indices of each dimension"""
+    indices = []
+    for start, end in reversed(dimensions):
+        length = max(end - start + 1, 1)
+        index, offset = divmod(index, length)
+        indices.append(start + offset)
+    return "[%s]" % ", ".join(str(it) for it in reversed(indices))
+
+
+def vla_length(dimensions):
+    length = 1
+    for start, end in dimensions:
+        length *= max(end - start + 1, 0)
+    return length
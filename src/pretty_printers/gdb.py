@@ -0,0 +1,85 @@
+
+import gdb
+
+
+def type_key(gdb_type):
+    name = gdb_type.name or gdb_type.tag
+    return name.lower() if name else None
+
+
+class TimePrinter(object):
+    def __init__(self, kind, val):
+        self.kind = kind
+        self.val = val
+
+    def to_string(self):
+        return format_time_value(self.kind, int(self.val))
+
+
+class StringPrinter(object):
+    def __init__(self, encoding, val):
+        self.encoding = encoding
+        self.val = val
+
+    def to_string(self):
+        start, end = self.val.type.strip_typedefs().range()
+        return format_string((int(self.val[i]) for i in range(start, end + 1)), self.encoding)
+
+
+class VlaPrinter(object):
+    def __init__(self, layout, val):
+        self.inner_type_name, self.ndims = layout
+        self.val = val
+        fields = val.type.strip_typedefs().fields()
+        self.data = val[fields[0].name]
+        bounds = val[fields[1].name]
+        self.dimensions = [(int(bounds[i][0]), int(bounds[i][1])) for i in range(self.ndims)]
+
+    def element_type(self):
+        for name in (self.inner_type_name, self.inner_type_name.lower()):
+            try:
+                return gdb.lookup_type(name)
+            except gdb.error:
+                pass
+        return None
+
+    def to_string(self):
+        return format_vla(self.inner_type_name, self.dimensions)
+
+    def children(self):
+        element_type = self.element_type()
+        if element_type is None or int(self.data) == 0:
+            return
+        elements = self.data.cast(element_type.pointer())
+        for index in range(vla_length(self.dimensions)):
+            yield vla_indices(self.dimensions, index), (elements + index).dereference()
+
+    def display_hint(self):
+        return "array"
+
+
+def lookup_printer(val):
+    kind = TIME_KINDS.get(type_key(val.type))
+    if kind is not None:
+        return TimePrinter(kind, val)
+    base_type = val.type.strip_typedefs()
+    kind = TIME_KINDS.get(type_key(base_type))
+    if kind is not None:
+        return TimePrinter(kind, val)
+    layout = VLA_LAYOUTS.get(type_key(base_type))
+    if layout is not None:
+        return VlaPrinter(layout, val)
+    if base_type.code == gdb.TYPE_CODE_ARRAY:
+        encoding = CHAR_ENCODINGS.get(type_key(base_type.target().strip_typedefs()))
+        if encoding is not None:
+            return StringPrinter(encoding, val)
+    return None
+
+
+def register_printers(objfile):
+    if objfile is None:
+        objfile = gdb
+    objfile.pretty_printers.append(lookup_printer)
+
+
+register_printers(gdb.current_objfile())
@@ -0,0 +1,109 @@
+
+import lldb
+
+CATEGORY = "plc"
+
+
+def type_key(sb_type):
+    name = sb_type.GetName()
+    return name.lower() if name else None
+
+
+def time_summary(valobj, internal_dict):
+    kind = TIME_KINDS.get(type_key(valobj.GetType())) or TIME_KINDS.get(
+        type_key(valobj.GetType().GetCanonicalType())
+    )
+    return format_time_value(kind, valobj.GetValueAsSigned())
+
+
+def string_summary(valobj, internal_dict):
+    element_type = valobj.GetType().GetCanonicalType().GetArrayElementType()
+    encoding = CHAR_ENCODINGS.get(type_key(element_type.GetCanonicalType()), "utf-8")
+    units = (valobj.GetChildAtIndex(i).GetValueAsUnsigned() for i in range(valobj.GetNumChildren()))
+    return format_string(units, encoding)
+
+
+def vla_dimensions(valobj, ndims):
+    bounds = valobj.GetChildAtIndex(1)
+    return [
+        (
+            bounds.GetChildAtIndex(i).GetChildAtIndex(0).GetValueAsSigned(),
+            bounds.GetChildAtIndex(i).GetChildAtIndex(1).GetValueAsSigned(),
+        )
+        for i in range(ndims)
+    ]
+
+
+def vla_summary(valobj, internal_dict):
+    valobj = valobj.GetNonSyntheticValue()
+    inner_type_name, ndims = VLA_LAYOUTS[type_key(valobj.GetType().GetCanonicalType())]
+    return format_vla(inner_type_name, vla_dimensions(valobj, ndims))
+
+
+class VlaProvider(object):
+    def __init__(self, valobj, internal_dict):
+        self.valobj = valobj
+        self.update()
+
+    def update(self):
+        inner_type_name, ndims = VLA_LAYOUTS[type_key(self.valobj.GetType().GetCanonicalType())]
+        self.dimensions = vla_dimensions(self.valobj, ndims)
+        self.address = self.valobj.GetChildAtIndex(0).GetValueAsUnsigned()
+        self.element_type = None
+        for name in (inner_type_name, inner_type_name.lower()):
+            element_type = self.valobj.GetTarget().FindFirstType(name)
+            if element_type.IsValid():
+                self.element_type = element_type
+                break
+        return False
+
+    def has_children(self):
+        return True
+
+    def num_children(self):
+        if self.element_type is None or self.address == 0:
+            return 0
+        return vla_length(self.dimensions)
+
+    def get_child_index(self, name):
+        for index in range(self.num_children()):
+            if vla_indices(self.dimensions, index) == name:
+                return index
+        return -1
+
+    def get_child_at_index(self, index):
+        if index < 0 or index >= self.num_children():
+            return None
+        address = self.address + index * self.element_type.GetByteSize()
+        return self.valobj.CreateValueFromAddress(
+            vla_indices(self.dimensions, index), address, self.element_type
+        )
+
+
+def type_names(names):
+    result = set()
+    for name in names:
+        result.add(name)
+        result.add(name.lower())
+    return sorted(result)
+
+
+def __lldb_init_module(debugger, internal_dict):
+    module = __name__
+    for name in type_names(TIME_TYPES):
+        debugger.HandleCommand(
+            'type summary add -w %s -F %s.time_summary "%s"' % (CATEGORY, module, name)
+        )
+    if CHAR_TYPES:
+        pattern = "^(%s) ?\\[[0-9]+\\]$" % "|".join(type_names(CHAR_TYPES))
+        debugger.HandleCommand(
+            "type summary add -w %s -F %s.string_summary -x '%s'" % (CATEGORY, module, pattern)
+        )
+    for name in type_names(VLA_TYPES):
+        debugger.HandleCommand(
+            'type summary add -w %s -F %s.vla_summary "%s"' % (CATEGORY, module, name)
+        )
+        debugger.HandleCommand(
+            'type synthetic add -w %s -l %s.VlaProvider "%s"' % (CATEGORY, module, name)
+        )
+    debugger.HandleCommand("type category enable %s" % CATEGORY)
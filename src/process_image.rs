@@ -0,0 +1,348 @@
+//! Process image of the hardware bound variables.
+//!
+//! Variables declared `AT %I`, `AT %Q` or `AT %M` with a fixed address are mapped into one contiguous
+//! buffer per area: `__PI_INPUT`, `__PI_OUTPUT` and `__PI_MEMORY`. The size of each area is exported
+//! as `__PI_INPUT_SIZE`, `__PI_OUTPUT_SIZE` and `__PI_MEMORY_SIZE`.
+//!
+//! Once per cycle a runtime copies the inputs into `__PI_INPUT` and calls [`READ_INPUTS_FUNCTION`],
+//! which copies the buffer into the bound variables. After the cycle it calls [`WRITE_OUTPUTS_FUNCTION`]
+//! and copies `__PI_OUTPUT` to the outputs. The memory area is read with the inputs and written with
+//! the outputs.
+//!
+//! Addresses are counted in units of their access size: `%IX1.2` is bit 2 of byte 1, `%IB1` is byte 1,
+//! `%IW1` covers the bytes 2 and 3, `%ID1` the bytes 4 to 7 and `%IL1` the bytes 8 to 15.
+//! Values are stored in the byte order of the target.
+//! Template addresses (`%I*`) are mapped to the address assigned to their instance in a `VAR_CONFIG` block.
+//!
+//! The process image is generated as structured text and compiled like any other unit.
+use std::fmt::Write;
+
+use indexmap::{IndexMap, IndexSet};
+use plc_ast::{
+    ast::{
+        pre_process, CompilationUnit, DirectAccessType, HardwareAccessType, LinkageType, SourceRange,
+        SourceRangeFactory,
+    },
+    provider::IdProvider,
+};
+
+use crate::{
+    index::{HardwareBinding, Index},
+    lexer, parser,
+};
+
+/// The file name of the generated process image unit
+pub const PROCESS_IMAGE_FILE: &str = "__process_image.st";
+/// The function copying the input and memory areas into the bound variables
+pub const READ_INPUTS_FUNCTION: &str = "__PI_READ_INPUTS";
+/// The function copying the bound variables into the output and memory areas
+pub const WRITE_OUTPUTS_FUNCTION: &str = "__PI_WRITE_OUTPUTS";
+
+const AREAS: [(HardwareAccessType, &str); 3] = [
+    (HardwareAccessType::Input, "__PI_INPUT"),
+    (HardwareAccessType::Output, "__PI_OUTPUT"),
+    (HardwareAccessType::Memory, "__PI_MEMORY"),
+];
+
+/// The location of a hardware address in the process image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageAddress {
+    pub area: HardwareAccessType,
+    /// the first byte of the address in its area
+    pub byte: u64,
+    /// the bit of a bit address (`%IX1.2`)
+    pub bit: Option<u64>,
+    /// the number of bytes covered by the address
+    pub size: u64,
+}
+
+impl ImageAddress {
+    /// Returns the location of the given binding in the process image
+    /// or None for template addresses (`%I*`) and addresses outside of the input, output and memory area
+    pub fn from_binding(binding: &HardwareBinding, index: &Index) -> Option<ImageAddress> {
+        if !matches!(
+            binding.direction,
+            HardwareAccessType::Input | HardwareAccessType::Output | HardwareAccessType::Memory
+        ) {
+            return None;
+        }
+        let address = binding
+            .entries
+            .iter()
+            .map(|it| index.get_const_expressions().get_constant_int_statement_value(it))
+            .map(|it| it.ok().and_then(|it| u64::try_from(it).ok()))
+            .collect::<Option<Vec<_>>>()?;
+        let first = *address.first()?;
+        let size = match binding.access {
+            DirectAccessType::Bit => {
+                let bit = address.get(1).copied().unwrap_or_default();
+                return Some(ImageAddress { area: binding.direction, byte: first, bit: Some(bit), size: 1 });
+            }
+            DirectAccessType::Byte => 1,
+            DirectAccessType::Word => 2,
+            DirectAccessType::DWord => 4,
+            DirectAccessType::LWord => 8,
+            DirectAccessType::Template => return None,
+        };
+        Some(ImageAddress { area: binding.direction, byte: first * size, bit: None, size })
+    }
+}
+
+/// A variable that is copied from or to the process image
+#[derive(Debug, PartialEq, Eq)]
+pub struct MappedVariable {
+    /// the name of the instance, e.g. `prg.fbs[1].x`
+    pub name: String,
+    pub type_name: String,
+    pub address: ImageAddress,
+    /// the number of bytes copied, the size of the variable if it is larger than the address
+    pub size: u64,
+    /// the location of the address in the declaration
+    pub location: SourceRange,
+}
+
+impl MappedVariable {
+    /// the first byte after the variable
+    pub fn get_end(&self) -> u64 {
+        self.address.byte + self.size
+    }
+}
+
+/// Collects all instances of variables bound to a fixed address of the input, output or memory area.
+/// Template addresses (`%I*`) are mapped to the address assigned in a `VAR_CONFIG` block
+pub fn collect_mapped_variables(index: &Index) -> Vec<MappedVariable> {
+    index
+        .find_instances()
+        .filter(|(_, instance)| instance.has_hardware_binding())
+        .flat_map(|(name, instance)| name.expand(index).into_iter().map(move |name| (name, instance)))
+        .filter_map(|(name, instance)| {
+            let binding = index.get_instance_binding(&name, instance)?;
+            let address = ImageAddress::from_binding(binding, index)?;
+            let size = if address.bit.is_some() {
+                1
+            } else {
+                let type_size = index
+                    .get_effective_type_or_void_by_name(instance.get_type_name())
+                    .get_type_information()
+                    .get_size(index)
+                    .value();
+                address.size.max(type_size.into())
+            };
+            Some(MappedVariable {
+                name,
+                type_name: instance.get_type_name().to_string(),
+                address,
+                size,
+                location: binding.location.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Generates the process image for the hardware bound variables of the given index,
+/// returns None if there are no variables bound to a fixed address
+pub fn generate_process_image(index: &Index, id_provider: IdProvider) -> Option<CompilationUnit> {
+    let variables = collect_mapped_variables(index);
+    if variables.is_empty() {
+        return None;
+    }
+    let source = generate_source(&variables);
+    let (mut unit, _) = parser::parse(
+        lexer::lex_with_ids(&source, id_provider.clone(), SourceRangeFactory::internal()),
+        LinkageType::Internal,
+        PROCESS_IMAGE_FILE,
+    );
+    pre_process(&mut unit, id_provider);
+    Some(unit)
+}
+
+fn generate_source(variables: &[MappedVariable]) -> String {
+    let mut buffers = String::new();
+    let mut sizes = String::new();
+    for (area, name) in AREAS {
+        let size = variables
+            .iter()
+            .filter(|it| it.address.area == area)
+            .map(MappedVariable::get_end)
+            .max()
+            .unwrap_or(0);
+        // an empty area still gets a buffer, so the runtime can rely on its symbol
+        let _ = writeln!(buffers, "    {name} : ARRAY[0..{}] OF BYTE;", size.max(1) - 1);
+        let _ = writeln!(sizes, "    {name}_SIZE : UDINT := {size};");
+    }
+
+    // one pointer for every type that is copied as a whole
+    let pointers = variables
+        .iter()
+        .filter(|it| it.address.bit.is_none())
+        .map(|it| it.type_name.as_str())
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, type_name)| (type_name, format!("__ptr{idx}")))
+        .collect::<IndexMap<_, _>>();
+    let pointer_declarations = pointers
+        .iter()
+        .map(|(type_name, pointer)| format!("    {pointer} : REF_TO {type_name};\n"))
+        .collect::<String>();
+
+    let mut read = String::new();
+    let mut write = String::new();
+    for variable in variables {
+        let (_, buffer) = AREAS.iter().find(|(area, _)| *area == variable.address.area).expect("Known area");
+        let ImageAddress { area, byte, bit, .. } = variable.address;
+        let name = &variable.name;
+        let (read_statement, write_statement) = if let Some(bit) = bit {
+            let image = format!("{buffer}[{byte}].%X{bit}");
+            (format!("{name} := {image};"), format!("{image} := {name};"))
+        } else {
+            let pointer = &pointers[variable.type_name.as_str()];
+            let address = format!("{pointer} := ADR({buffer}[{byte}]);");
+            (format!("{address} {name} := {pointer}^;"), format!("{address} {pointer}^ := {name};"))
+        };
+        if area != HardwareAccessType::Output {
+            let _ = writeln!(read, "    {read_statement}");
+        }
+        if area != HardwareAccessType::Input {
+            let _ = writeln!(write, "    {write_statement}");
+        }
+    }
+
+    format!(
+        "VAR_GLOBAL\n{buffers}END_VAR\n\
+        VAR_GLOBAL CONSTANT\n{sizes}END_VAR\n\
+        FUNCTION {READ_INPUTS_FUNCTION}\nVAR\n{pointer_declarations}END_VAR\n{read}END_FUNCTION\n\
+        FUNCTION {WRITE_OUTPUTS_FUNCTION}\nVAR\n{pointer_declarations}END_VAR\n{write}END_FUNCTION\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::ast::HardwareAccessType;
+
+    use crate::{
+        process_image::{collect_mapped_variables, generate_source, ImageAddress},
+        test_utils::tests::{codegen, index},
+    };
+
+    #[test]
+    fn addresses_are_counted_in_units_of_their_access_size() {
+        let (_, index) = index(
+            "
+        VAR_GLOBAL
+            a AT %IX1.2 : BOOL;
+            b AT %IB3 : BYTE;
+            c AT %IW2 : INT;
+            d AT %QD1 : DINT;
+            e AT %ML1 : LINT;
+            t AT %I* : DWORD;
+        END_VAR",
+        );
+
+        let addresses =
+            collect_mapped_variables(&index).into_iter().map(|it| (it.name, it.address)).collect::<Vec<_>>();
+        let address = |area, byte, bit, size| ImageAddress { area, byte, bit, size };
+        assert_eq!(
+            addresses,
+            vec![
+                ("a".to_string(), address(HardwareAccessType::Input, 1, Some(2), 1)),
+                ("b".to_string(), address(HardwareAccessType::Input, 3, None, 1)),
+                ("c".to_string(), address(HardwareAccessType::Input, 4, None, 2)),
+                ("d".to_string(), address(HardwareAccessType::Output, 4, None, 4)),
+                ("e".to_string(), address(HardwareAccessType::Memory, 8, None, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn instances_of_programs_and_function_blocks_are_mapped() {
+        let (_, index) = index(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            x AT %QX0.1 : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+        PROGRAM prg
+        VAR
+            y AT %IW1 : WORD;
+            fbs : ARRAY[0..1] OF fb;
+        END_VAR
+        END_PROGRAM",
+        );
+
+        let names = collect_mapped_variables(&index).into_iter().map(|it| it.name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["prg.y", "prg.fbs[0].x", "prg.fbs[1].x"]);
+    }
+
+    #[test]
+    fn process_image_copies_the_bound_variables() {
+        let src = "
+        VAR_GLOBAL
+            a AT %IX1.2 : BOOL;
+            c AT %IW2 : INT;
+            d AT %QD1 : DINT;
+            m AT %MB0 : BYTE;
+        END_VAR";
+        let (_, index) = index(src);
+        let source = generate_source(&collect_mapped_variables(&index));
+
+        assert!(source.contains("__PI_INPUT : ARRAY[0..5] OF BYTE;"), "{source}");
+        assert!(source.contains("__PI_OUTPUT_SIZE : UDINT := 8;"), "{source}");
+        assert!(source.contains("__PI_MEMORY_SIZE : UDINT := 1;"), "{source}");
+        assert!(source.contains("a := __PI_INPUT[1].%X2;"), "{source}");
+        assert!(source.contains("__ptr0 := ADR(__PI_INPUT[4]); c := __ptr0^;"), "{source}");
+        assert!(source.contains("__ptr1 := ADR(__PI_OUTPUT[4]); __ptr1^ := d;"), "{source}");
+        // inputs are only read, outputs only written and memory both
+        assert!(!source.contains("d := "), "{source}");
+        assert!(!source.contains("^ := c;"), "{source}");
+        assert!(source.contains("m := __ptr2^;") && source.contains("__ptr2^ := m;"), "{source}");
+
+        let result = codegen(&format!("{src}\n{source}"));
+        assert!(result.contains("@__PI_INPUT = global [6 x i8]"), "{result}");
+        assert!(result.contains("@__PI_OUTPUT_SIZE = unnamed_addr constant i32 8"), "{result}");
+        assert!(result.contains("define void @__PI_READ_INPUTS()"), "{result}");
+        assert!(result.contains("define void @__PI_WRITE_OUTPUTS()"), "{result}");
+    }
+
+    #[test]
+    fn template_addresses_are_not_mapped() {
+        let (_, index) = index(
+            "
+        VAR_GLOBAL
+            t AT %I* : DWORD;
+            u : DWORD;
+        END_VAR",
+        );
+
+        assert!(collect_mapped_variables(&index).is_empty());
+    }
+
+    #[test]
+    fn template_addresses_are_mapped_to_their_configured_address() {
+        let (_, index) = index(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            x AT %Q* : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL
+            fbs : ARRAY[0..1] OF fb;
+        END_VAR
+        VAR_CONFIG
+            fbs[1].x AT %QX2.3 : BOOL;
+        END_VAR",
+        );
+
+        let addresses =
+            collect_mapped_variables(&index).into_iter().map(|it| (it.name, it.address)).collect::<Vec<_>>();
+        assert_eq!(
+            addresses,
+            vec![(
+                "fbs[1].x".to_string(),
+                ImageAddress { area: HardwareAccessType::Output, byte: 2, bit: Some(3), size: 1 }
+            )]
+        );
+    }
+}
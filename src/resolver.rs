@@ -13,9 +13,9 @@ use std::{
 use indexmap::{IndexMap, IndexSet};
 use plc_ast::{
     ast::{
-        self, flatten_expression_list, AstFactory, AstId, AstStatement, CompilationUnit, DataType,
-        DataTypeDeclaration, DirectAccessType, Operator, Pou, ReferenceAccess, TypeNature,
-        UserTypeDeclaration, Variable,
+        self, flatten_expression_list, flatten_struct_literal_list, AstFactory, AstId, AstStatement,
+        CompilationUnit, DataType, DataTypeDeclaration, DirectAccessType, Operator, Pou, ReferenceAccess,
+        SourceRange, TypeNature, UserTypeDeclaration, Variable,
     },
     control_statements::AstControlStatement,
     literals::{Array, AstLiteral, StringValue},
@@ -28,7 +28,10 @@ pub mod generics;
 
 use crate::{
     builtins::{self, BuiltIn},
-    index::{symbol::SymbolLocation, ArgumentType, Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    index::{
+        symbol::SymbolLocation, ArgumentType, Index, PouIndexEntry, PropertyIndexEntry, VariableIndexEntry,
+        VariableType,
+    },
     typesystem::{
         self, get_bigger_type, DataTypeInformation, InternalType, StringEncoding, StructSource, BOOL_TYPE,
         BYTE_TYPE, DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, DWORD_TYPE, LINT_TYPE, LREAL_TYPE, LWORD_TYPE,
@@ -801,6 +804,11 @@ impl<'i> TypeAnnotator<'i> {
                 {
                     if let Some(v) = self.index.find_member(qualifier, variable_name) {
                         if let Some(target_type) = self.index.find_effective_type_by_name(v.get_type_name()) {
+                            // a single assignment may be a struct-literal on its own, e.g. `(a := 1)`
+                            self.annotation_map.annotate_type_hint(
+                                statement,
+                                StatementAnnotation::value(expected_type.get_name()),
+                            );
                             self.annotate(left.as_ref(), to_variable_annotation(v, self.index, false));
                             self.annotation_map.annotate_type_hint(
                                 right.as_ref(),
@@ -814,8 +822,22 @@ impl<'i> TypeAnnotator<'i> {
             AstStatement::MultipliedStatement { element: elements, .. } => {
                 // n(elements)
                 //annotate the type to all multiplied elements
-                for ele in AstStatement::get_as_list(elements) {
-                    self.update_expected_types(expected_type, ele);
+                self.update_expected_types(expected_type, elements);
+            }
+            AstStatement::ExpressionList { .. } if expected_type.get_type_information().is_struct() => {
+                // either a single struct-literal (a := 1, b := 2) or a list of struct-literals
+                for literal in flatten_struct_literal_list(statement) {
+                    if let AstStatement::ExpressionList { expressions, .. } = literal {
+                        self.annotation_map.annotate_type_hint(
+                            literal,
+                            StatementAnnotation::value(expected_type.get_name()),
+                        );
+                        for assignment in expressions {
+                            self.update_expected_types(expected_type, assignment);
+                        }
+                    } else {
+                        self.update_expected_types(expected_type, literal);
+                    }
                 }
             }
             AstStatement::ExpressionList { expressions, .. } => {
@@ -1018,7 +1040,8 @@ impl<'i> TypeAnnotator<'i> {
             self.dependencies.insert(Dependency::Datatype(name.to_string()));
         }
         match data_type {
-            DataType::StructType { name: Some(name), variables, .. } => {
+            DataType::StructType { name: Some(name), variables, .. }
+            | DataType::UnionType { name: Some(name), variables } => {
                 let ctx = ctx.with_qualifier(name.clone());
                 variables.iter().for_each(|v| self.visit_variable(&ctx, v))
             }
@@ -1252,6 +1275,7 @@ impl<'i> TypeAnnotator<'i> {
                 }
                 // give a type hint that we want the right side to be stored in the left's type
                 self.update_right_hand_side_expected_type(ctx, left, right);
+                self.visit_property_assignment(ctx, statement, left, right);
             }
             AstStatement::OutputAssignment { left, right, .. } => {
                 visit_all_statements!(self, ctx, left, right);
@@ -1263,6 +1287,10 @@ impl<'i> TypeAnnotator<'i> {
                 }
                 self.update_right_hand_side_expected_type(ctx, left, right);
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                // the right side is not assigned but bound, so there is no type hint for it
+                visit_all_statements!(self, ctx, right, left);
+            }
             AstStatement::CallStatement { .. } => {
                 self.visit_call_statement(statement, ctx);
             }
@@ -1349,7 +1377,18 @@ impl<'i> TypeAnnotator<'i> {
                 let new_ctx = base.map(|base| ctx.with_const(self.is_const_reference(base, ctx)));
                 let new_ctx = new_ctx.as_ref().unwrap_or(ctx);
 
-                if let Some(annotation) =
+                if let Some((property, base)) = self.find_property(reference, qualifier.as_deref()).zip(base)
+                {
+                    // reading a property calls its getter
+                    if let Some(getter) = property.get_getter() {
+                        let call = create_accessor_call(getter, base, None, &stmt.get_location(), ctx);
+                        self.visit_statement(ctx, &call);
+                        self.annotate(stmt, StatementAnnotation::ReplacementAst { statement: call });
+                    } else {
+                        // a write-only property, the validation reports reading it
+                        self.annotate(stmt, StatementAnnotation::value(property.get_type_name()));
+                    }
+                } else if let Some(annotation) =
                     self.resolve_reference_expression(reference.as_ref(), qualifier.as_deref(), new_ctx)
                 {
                     self.annotate(stmt, annotation.clone());
@@ -1423,6 +1462,47 @@ impl<'i> TypeAnnotator<'i> {
         }
     }
 
+    /// returns the property accessed by the given member reference under the given qualifier.
+    /// Members of the qualifier take precedence over properties with the same name.
+    fn find_property(
+        &self,
+        reference: &AstStatement,
+        qualifier: Option<&str>,
+    ) -> Option<&'i PropertyIndexEntry> {
+        let (AstStatement::Identifier { name, .. }, Some(qualifier)) = (reference, qualifier) else {
+            return None;
+        };
+        if self.index.find_member(qualifier, name).is_some() {
+            return None;
+        }
+        self.index.find_property(qualifier, name)
+    }
+
+    /// replaces an assignment to a property (`fb.property := value`) with a call to the property's setter.
+    /// A property without a setter is not replaced, the validation reports writing it
+    fn visit_property_assignment(
+        &mut self,
+        ctx: &VisitorContext,
+        assignment: &AstStatement,
+        left: &AstStatement,
+        right: &AstStatement,
+    ) {
+        let AstStatement::ReferenceExpr {
+            access: ReferenceAccess::Member(reference), base: Some(base), ..
+        } = left
+        else {
+            return;
+        };
+        let qualifier = self.annotation_map.get_type(base, self.index).map(|it| it.get_name().to_string());
+        let Some(setter) = self.find_property(reference, qualifier.as_deref()).and_then(|it| it.get_setter())
+        else {
+            return;
+        };
+        let call = create_accessor_call(setter, base, Some(right.clone()), &assignment.get_location(), ctx);
+        self.visit_statement(ctx, &call);
+        self.annotate(assignment, StatementAnnotation::ReplacementAst { statement: call });
+    }
+
     fn is_const_reference(&self, stmt: &AstStatement, ctx: &VisitorContext<'_>) -> bool {
         self.annotation_map
             .get(stmt)
@@ -1699,7 +1779,12 @@ impl<'i> TypeAnnotator<'i> {
             if let Some(effective_member_type) = self.index.find_effective_type_by_name(type_name) {
                 //update the type hint
                 self.annotation_map
-                    .annotate_type_hint(p, StatementAnnotation::value(effective_member_type.get_name()))
+                    .annotate_type_hint(p, StatementAnnotation::value(effective_member_type.get_name()));
+                // the elements of an array-literal are typed by the parameter's type
+                if matches!(p, AstStatement::Literal { kind: AstLiteral::Array(..), .. }) {
+                    let effective_member_type = effective_member_type.clone();
+                    self.update_expected_types(&effective_member_type, p);
+                }
             }
         }
     }
@@ -1826,6 +1911,28 @@ pub(crate) fn add_pointer_type(index: &mut Index, inner_type_name: String) -> St
     new_type_name
 }
 
+/// creates the call `base.accessor(parameter)` to the given accessor method of a property
+fn create_accessor_call(
+    accessor: &str,
+    base: &AstStatement,
+    parameter: Option<AstStatement>,
+    location: &SourceRange,
+    ctx: &VisitorContext,
+) -> AstStatement {
+    let mut id_provider = ctx.id_provider.clone();
+    let method_name = accessor.rsplit('.').next().unwrap_or(accessor);
+    AstStatement::CallStatement {
+        operator: Box::new(AstFactory::create_member_reference(
+            AstFactory::create_identifier(method_name, location, id_provider.next_id()),
+            Some(base.clone()),
+            id_provider.next_id(),
+        )),
+        parameters: Box::new(parameter),
+        location: location.clone(),
+        id: id_provider.next_id(),
+    }
+}
+
 fn to_pou_annotation(p: &PouIndexEntry, index: &Index) -> Option<StatementAnnotation> {
     match p {
         PouIndexEntry::Program { name, .. } => {
@@ -1,5 +1,6 @@
 use plc_ast::{
-    ast::{AstStatement, ReferenceAccess, TypeNature},
+    ast::{flatten_expression_list, flatten_struct_literal_list, AstStatement, ReferenceAccess, TypeNature},
+    literals::AstLiteral,
     provider::IdProvider,
 };
 
@@ -278,6 +279,7 @@ fn enum_literals_target_are_annotated() {
             name: "Color".into(),
             elements: vec!["Green".into(), "Yellow".into(), "Red".into()],
             referenced_type: DINT_TYPE.into(),
+            strict: false,
         },
         annotations.get_type_or_void(color_red, &index).get_type_information()
     );
@@ -289,6 +291,7 @@ fn enum_literals_target_are_annotated() {
                 name: "Color".into(),
                 elements: vec!["Green".into(), "Yellow".into(), "Red".into()],
                 referenced_type: DINT_TYPE.into(),
+                strict: false,
             },
             annotations.get_type_or_void(target, &index).get_type_information()
         );
@@ -500,3 +503,46 @@ fn expression_list_as_array_initilization_is_annotated_correctly() {
         unreachable!();
     }
 }
+
+#[test]
+fn struct_literals_in_array_literals_are_annotated_in_bodies() {
+    // GIVEN an array of structs assigned with struct-literals containing variables
+    let id_provider = IdProvider::default();
+    let (unit, mut index) = index_with_ids(
+        "
+        TYPE Point : STRUCT
+            x : DINT;
+            y : DINT;
+        END_STRUCT END_TYPE
+
+        PROGRAM PRG
+        VAR
+            points : ARRAY[0..3] OF Point;
+            i : INT;
+        END_VAR
+            points := [(x := i, y := 2), 2((y := i))];
+        END_PROGRAM
+        ",
+        id_provider.clone(),
+    );
+
+    // WHEN annotation is done
+    let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+
+    // THEN every struct-literal is hinted with the struct type
+    let AstStatement::Assignment { right, .. } = &unit.implementations[0].statements[0] else {
+        unreachable!()
+    };
+    let AstStatement::Literal { kind: AstLiteral::Array(array), .. } = right.as_ref() else { unreachable!() };
+    let literals = flatten_struct_literal_list(array.elements().unwrap());
+    assert_eq!(literals.len(), 3);
+    for literal in literals {
+        assert_eq!(annotations.get_type_hint(literal, &index).map(DataType::get_name), Some("Point"));
+
+        // AND the assigned values are hinted with the member's type
+        for assignment in flatten_expression_list(literal) {
+            let AstStatement::Assignment { right, .. } = assignment else { unreachable!() };
+            assert_eq!(annotations.get_type_hint(right, &index).map(DataType::get_name), Some("DINT"));
+        }
+    }
+}
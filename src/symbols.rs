@@ -0,0 +1,1086 @@
+//! Collects the variables accessible by HMI, SCADA and OPC UA clients into a symbol table
+//!
+//! The table lists every instance of the global variables, the programs and their nested struct and
+//! function block members under the names used by the hardware configuration (e.g. `PLC_PRG.fbs[1].x`).
+//! Each symbol carries its type, its byte offset in the owning struct, function block or program
+//! (`0` for globals and programs), its size and the access granted to clients.
+//!
+//! The access is set with the `symbol` attribute (`{attribute 'symbol' := 'read'}`, `'write'`,
+//! `'readwrite'` or `'none'`) and applies to the variable and all of its members.
+//! Variables marked with `{attribute 'hide'}` are not listed and constants can only be read.
+//!
+//! With `--embed-symbols` a compact form of the table is compiled into the program as the exported
+//! string constant [`SYMBOL_TABLE_VARIABLE`]. Instead of listing every instance it describes the global
+//! variables, the program instances and the layout of their types, which allows a runtime to resolve
+//! paths like `PLC_PRG.motors[2].speed` to the address of the variable (see `libs/symbols`).
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use plc_ast::{
+    ast::{pre_process, Attribute, CompilationUnit, LinkageType, SourceRangeFactory},
+    provider::IdProvider,
+};
+use plc_diagnostics::{diagnostics::Diagnostic, errno::ErrNo};
+use serde::Serialize;
+
+use crate::{
+    datalayout::{Bytes, MemoryLocation},
+    index::{Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    lexer, parser,
+    typesystem::{DataTypeInformation, StringEncoding, StructSource, DEFAULT_STRING_LEN},
+};
+
+/// The file name of the generated unit containing the embedded symbol table
+pub const SYMBOL_TABLE_FILE: &str = "__symbol_table.st";
+/// The exported constant holding the embedded symbol table as a null terminated JSON string
+pub const SYMBOL_TABLE_VARIABLE: &str = "__SYMBOL_TABLE";
+
+/// the attribute setting the access of clients to a variable
+pub const SYMBOL_ATTRIBUTE: &str = "symbol";
+/// the attribute excluding a variable from the symbol table
+pub const HIDE_ATTRIBUTE: &str = "hide";
+
+/// the elementary types of PLCopen TC6, all other types are referenced as derived types
+pub const PLCOPEN_ELEMENTARY_TYPES: &[&str] = &[
+    "BOOL", "BYTE", "WORD", "DWORD", "LWORD", "SINT", "INT", "DINT", "LINT", "USINT", "UINT", "UDINT",
+    "ULINT", "REAL", "LREAL", "TIME", "DATE", "DT", "TOD",
+];
+
+/// the built-in OPC UA data types and their node ids
+const OPC_UA_DATA_TYPES: &[(&str, &str)] = &[
+    ("Boolean", "i=1"),
+    ("SByte", "i=2"),
+    ("Byte", "i=3"),
+    ("Int16", "i=4"),
+    ("UInt16", "i=5"),
+    ("Int32", "i=6"),
+    ("UInt32", "i=7"),
+    ("Int64", "i=8"),
+    ("UInt64", "i=9"),
+    ("Float", "i=10"),
+    ("Double", "i=11"),
+    ("String", "i=12"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFormat {
+    Json,
+    PlcOpenXml,
+    NodeSet,
+}
+
+impl SymbolFormat {
+    /// the name of the file the symbol table of the given binary is written to
+    pub fn get_file_name(&self, binary: &str) -> String {
+        match self {
+            SymbolFormat::Json => format!("{binary}.symbols.json"),
+            SymbolFormat::PlcOpenXml => format!("{binary}.symbols.xml"),
+            SymbolFormat::NodeSet => format!("{binary}.NodeSet2.xml"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SymbolAccess {
+    None,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl SymbolAccess {
+    /// the access set with the value of a `symbol` attribute, unknown values are ignored
+    fn from_attribute(value: Option<&str>) -> Option<SymbolAccess> {
+        match value.map(str::to_lowercase).as_deref() {
+            None | Some("readwrite") => Some(SymbolAccess::ReadWrite),
+            Some("read") => Some(SymbolAccess::Read),
+            Some("write") => Some(SymbolAccess::Write),
+            Some("none") => Some(SymbolAccess::None),
+            _ => None,
+        }
+    }
+
+    fn read_only(self) -> SymbolAccess {
+        match self {
+            SymbolAccess::Read | SymbolAccess::Write | SymbolAccess::ReadWrite => SymbolAccess::Read,
+            SymbolAccess::None => SymbolAccess::None,
+        }
+    }
+
+    /// the OPC UA access level (bit 0: read, bit 1: write)
+    fn get_access_level(self) -> u8 {
+        match self {
+            SymbolAccess::None => 0,
+            SymbolAccess::Read => 1,
+            SymbolAccess::Write => 2,
+            SymbolAccess::ReadWrite => 3,
+        }
+    }
+}
+
+/// An instance accessible by clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Symbol {
+    /// the qualified name of the instance, e.g. `PLC_PRG.fbs[1].x`
+    pub name: String,
+    /// the declared type, e.g. `INT`, `MyStruct` or `ARRAY[0..2] OF INT`
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// the byte offset in the owning struct, function block or program
+    pub offset: u32,
+    pub size: u32,
+    pub access: SymbolAccess,
+    /// the name of the type in the index
+    #[serde(skip)]
+    data_type_name: String,
+}
+
+pub struct SymbolTable<'idx> {
+    index: &'idx Index,
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable<'_> {
+    pub fn get_symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+}
+
+/// Collects all instances accessible by clients
+pub fn collect_symbols(index: &Index) -> SymbolTable {
+    let mut access_by_name: HashMap<String, SymbolAccess> = HashMap::new();
+    let mut symbols = vec![];
+    // hidden variables are neither listed nor expanded
+    for (path, variable) in index.filter_instances(is_listed).filter(|(_, it)| is_listed(it, index)) {
+        let configured = get_configured_access(variable, index);
+        let size = get_layout(variable.get_type_name(), index).size;
+        let offset = get_offset(variable, index);
+        let type_name = get_type_name(variable.get_type_name(), index);
+        for name in path.expand(index) {
+            let inherited =
+                get_parent_name(&name).and_then(|it| access_by_name.get(&it.to_lowercase())).copied();
+            let access = configured.or(inherited).unwrap_or(SymbolAccess::ReadWrite);
+            let access = if variable.is_constant() { access.read_only() } else { access };
+            access_by_name.insert(name.to_lowercase(), access);
+            if access != SymbolAccess::None {
+                symbols.push(Symbol {
+                    name,
+                    type_name: type_name.clone(),
+                    offset,
+                    size,
+                    access,
+                    data_type_name: variable.get_type_name().to_string(),
+                });
+            }
+        }
+    }
+    SymbolTable { index, symbols }
+}
+
+/// Writes the symbol table of the given application in the given format
+pub fn generate_symbols(
+    table: &SymbolTable,
+    format: SymbolFormat,
+    application: &str,
+) -> Result<String, Diagnostic> {
+    match format {
+        SymbolFormat::Json => {
+            #[derive(Serialize)]
+            struct SymbolFile<'a> {
+                #[serde(rename = "Symbols")]
+                symbols: &'a [Symbol],
+            }
+            serde_json::to_string_pretty(&SymbolFile { symbols: &table.symbols }).map_err(|e| {
+                Diagnostic::GeneralError { message: e.to_string(), err_no: ErrNo::general__io_err }
+            })
+        }
+        SymbolFormat::PlcOpenXml => Ok(generate_plcopen_xml(table, application)),
+        SymbolFormat::NodeSet => Ok(generate_nodeset(table, application)),
+    }
+}
+
+/// A global variable or program instance of the embedded symbol table
+#[derive(Debug, Serialize)]
+struct EmbeddedVariable {
+    name: String,
+    /// the name of the exported symbol
+    symbol: String,
+    #[serde(rename = "type")]
+    data_type: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access: Option<SymbolAccess>,
+}
+
+/// A member of a struct, function block or program, members without an access inherit the access of
+/// their container
+#[derive(Debug, Serialize)]
+struct EmbeddedMember {
+    name: String,
+    offset: u32,
+    #[serde(rename = "type")]
+    data_type: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access: Option<SymbolAccess>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
+enum EmbeddedTypeKind {
+    Bool,
+    Signed,
+    Unsigned,
+    Float,
+    String {
+        wide: bool,
+    },
+    Struct {
+        members: Vec<EmbeddedMember>,
+    },
+    /// the elements are stored row major, `stride` bytes apart
+    Array {
+        dimensions: Vec<[i64; 2]>,
+        element: usize,
+        stride: u32,
+    },
+    Other,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddedType {
+    name: String,
+    size: u32,
+    #[serde(flatten)]
+    kind: EmbeddedTypeKind,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct EmbeddedSymbolTable {
+    variables: Vec<EmbeddedVariable>,
+    /// the types referenced by the variables, every type is listed once and referenced by its position
+    types: Vec<EmbeddedType>,
+    #[serde(skip)]
+    type_ids: HashMap<String, usize>,
+}
+
+impl EmbeddedSymbolTable {
+    fn new(index: &Index) -> EmbeddedSymbolTable {
+        let mut table = EmbeddedSymbolTable::default();
+        let variables = index
+            .get_globals()
+            .values()
+            .chain(index.get_program_instances())
+            .filter(|it| is_listed(it, index))
+            .collect::<Vec<_>>();
+        for variable in variables {
+            let embedded = EmbeddedVariable {
+                name: variable.get_qualified_name().to_string(),
+                symbol: variable.get_name().to_string(),
+                data_type: table.get_type_id(variable.get_type_name(), index),
+                access: get_access(variable, index),
+            };
+            table.variables.push(embedded);
+        }
+        table
+    }
+
+    /// returns the position of the given type, registering it and the types of its members on first use
+    fn get_type_id(&mut self, type_name: &str, index: &Index) -> usize {
+        if let Some(id) = self.type_ids.get(&type_name.to_lowercase()) {
+            return *id;
+        }
+        let kind = match index.get_type_information_or_void(type_name) {
+            // aliases, enums and sub ranges are stored like the type they are based on
+            DataTypeInformation::Alias { referenced_type, .. }
+            | DataTypeInformation::Enum { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. } => {
+                let id = self.get_type_id(referenced_type, index);
+                self.type_ids.insert(type_name.to_lowercase(), id);
+                return id;
+            }
+            DataTypeInformation::Struct { members, .. } => EmbeddedTypeKind::Struct {
+                members: members
+                    .iter()
+                    .filter(|it| is_listed(it, index))
+                    .map(|it| EmbeddedMember {
+                        name: it.get_name().to_string(),
+                        offset: get_offset(it, index),
+                        data_type: self.get_type_id(it.get_type_name(), index),
+                        access: get_access(it, index),
+                    })
+                    .collect(),
+            },
+            DataTypeInformation::Array { inner_type_name, dimensions, .. } => EmbeddedTypeKind::Array {
+                dimensions: dimensions
+                    .iter()
+                    .filter_map(|it| it.get_range_inclusive(index).ok())
+                    .map(|it| [*it.start(), *it.end()])
+                    .collect(),
+                element: self.get_type_id(inner_type_name, index),
+                stride: get_layout(inner_type_name, index).size,
+            },
+            DataTypeInformation::Integer { semantic_size: Some(1), .. } => EmbeddedTypeKind::Bool,
+            DataTypeInformation::Integer { signed: true, .. } => EmbeddedTypeKind::Signed,
+            DataTypeInformation::Integer { .. } => EmbeddedTypeKind::Unsigned,
+            DataTypeInformation::Float { .. } => EmbeddedTypeKind::Float,
+            DataTypeInformation::String { encoding, .. } => {
+                EmbeddedTypeKind::String { wide: *encoding == StringEncoding::Utf16 }
+            }
+            _ => EmbeddedTypeKind::Other,
+        };
+        let embedded_type = EmbeddedType {
+            name: get_type_name(type_name, index),
+            size: get_layout(type_name, index).size,
+            kind,
+        };
+        self.types.push(embedded_type);
+        self.type_ids.insert(type_name.to_lowercase(), self.types.len() - 1);
+        self.types.len() - 1
+    }
+}
+
+/// Generates the unit embedding the symbol table of the given index as [`SYMBOL_TABLE_VARIABLE`]
+pub fn generate_symbol_table(index: &Index, id_provider: IdProvider) -> CompilationUnit {
+    let source = generate_symbol_table_source(index);
+    let (mut unit, _) = parser::parse(
+        lexer::lex_with_ids(&source, id_provider.clone(), SourceRangeFactory::internal()),
+        LinkageType::Internal,
+        SYMBOL_TABLE_FILE,
+    );
+    pre_process(&mut unit, id_provider);
+    unit
+}
+
+fn generate_symbol_table_source(index: &Index) -> String {
+    let table = serde_json::to_string(&EmbeddedSymbolTable::new(index))
+        .expect("the symbol table consists of serializable values only");
+    let length = table.len().max(1);
+    let table = table.replace('$', "$$").replace('\'', "$'");
+    format!("VAR_GLOBAL CONSTANT\n    {SYMBOL_TABLE_VARIABLE} : STRING[{length}] := '{table}';\nEND_VAR\n")
+}
+
+fn is_listed(variable: &VariableIndexEntry, index: &Index) -> bool {
+    !variable.is_temp()
+        && !variable.is_return()
+        && !variable.get_name().starts_with("__")
+        && !get_attributes(variable, index).iter().any(|it| it.name.eq_ignore_ascii_case(HIDE_ATTRIBUTE))
+        && get_configured_access(variable, index) != Some(SymbolAccess::None)
+}
+
+/// the attributes of the variable, program instances carry the attributes of their program
+fn get_attributes<'idx>(variable: &'idx VariableIndexEntry, index: &'idx Index) -> &'idx [Attribute] {
+    match index.find_pou(variable.get_qualified_name()) {
+        Some(pou @ PouIndexEntry::Program { .. }) if variable.get_variable_type() == VariableType::Global => {
+            pou.get_attributes()
+        }
+        _ => variable.get_attributes(),
+    }
+}
+
+fn get_configured_access(variable: &VariableIndexEntry, index: &Index) -> Option<SymbolAccess> {
+    get_attributes(variable, index)
+        .iter()
+        .find(|it| it.name.eq_ignore_ascii_case(SYMBOL_ATTRIBUTE))
+        .and_then(|it| SymbolAccess::from_attribute(it.value.as_deref()))
+}
+
+/// the configured access of the variable, constants can only be read
+fn get_access(variable: &VariableIndexEntry, index: &Index) -> Option<SymbolAccess> {
+    let access = get_configured_access(variable, index);
+    if variable.is_constant() {
+        Some(access.unwrap_or(SymbolAccess::ReadWrite).read_only())
+    } else {
+        access
+    }
+}
+
+/// The size and alignment of a type in the generated code.
+///
+/// The generated structs are laid out by LLVM like C structs: every member is aligned to its type and a
+/// struct is aligned to its most aligned member, its size is rounded up to that alignment. Arrays and
+/// strings are aligned like their elements, other types like their size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    size: u32,
+    alignment: u32,
+}
+
+fn get_layout(type_name: &str, index: &Index) -> Layout {
+    let data_type = index.get_type_information_or_void(type_name);
+    match data_type {
+        DataTypeInformation::Struct { members, source, .. } => {
+            let (size, alignment) = get_member_offsets(members, source, index).into_iter().fold(
+                (0, 1),
+                |(size, alignment), (member, offset)| {
+                    let layout = get_layout(member.get_type_name(), index);
+                    (size.max(offset + layout.size), alignment.max(layout.alignment))
+                },
+            );
+            Layout { size: align_to(size, alignment), alignment }
+        }
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            let element = get_layout(inner_type_name, index);
+            let length: u32 = dimensions.iter().filter_map(|it| it.get_length(index).ok()).product();
+            Layout { size: element.size * length, alignment: element.alignment }
+        }
+        DataTypeInformation::String { encoding, .. } => {
+            Layout { size: data_type.get_size(index).value(), alignment: encoding.get_bytes_per_char() }
+        }
+        DataTypeInformation::Alias { referenced_type, .. }
+        | DataTypeInformation::Enum { referenced_type, .. }
+        | DataTypeInformation::SubRange { referenced_type, .. } => get_layout(referenced_type, index),
+        _ => {
+            let size = data_type.get_size(index).value();
+            Layout { size, alignment: size.max(1) }
+        }
+    }
+}
+
+/// the members of a struct, function block or program stored in the generated struct with their
+/// byte offsets, the members of a union all start at `0`
+fn get_member_offsets<'idx>(
+    members: &'idx [VariableIndexEntry],
+    source: &StructSource,
+    index: &Index,
+) -> Vec<(&'idx VariableIndexEntry, u32)> {
+    let mut offset = 0;
+    members
+        .iter()
+        .filter(|it| !it.is_temp() && !it.is_return())
+        .map(|member| {
+            if *source == StructSource::Union {
+                return (member, 0);
+            }
+            let layout = get_layout(member.get_type_name(), index);
+            let member_offset = align_to(offset, layout.alignment);
+            offset = member_offset + layout.size;
+            (member, member_offset)
+        })
+        .collect()
+}
+
+fn align_to(offset: u32, alignment: u32) -> u32 {
+    MemoryLocation::new(offset).align_to(Bytes::new(alignment)).value()
+}
+
+/// the byte offset of the variable in its container
+fn get_offset(variable: &VariableIndexEntry, index: &Index) -> u32 {
+    let Some((container, _)) = variable.get_qualified_name().rsplit_once('.') else {
+        return 0;
+    };
+    match index.find_effective_type_info(container) {
+        Some(DataTypeInformation::Struct { members, source, .. }) => {
+            get_member_offsets(members, source, index)
+                .into_iter()
+                .find(|(member, _)| {
+                    member.get_qualified_name().eq_ignore_ascii_case(variable.get_qualified_name())
+                })
+                .map_or(0, |(_, offset)| offset)
+        }
+        _ => 0,
+    }
+}
+
+/// the name of the node containing the given instance: `a[1]` for `a[1].b` and `a` for `a[1]`
+fn get_parent_node(name: &str) -> Option<&str> {
+    if let Some(stripped) = name.strip_suffix(']') {
+        return stripped.rfind('[').map(|it| &name[..it]);
+    }
+    name.rsplit_once('.').map(|(parent, _)| parent)
+}
+
+/// the name of the variable containing the given instance: `a` for `a[1].b`
+fn get_parent_name(name: &str) -> Option<&str> {
+    let mut parent = get_parent_node(name)?;
+    while parent.ends_with(']') {
+        parent = get_parent_node(parent)?;
+    }
+    Some(parent)
+}
+
+/// the name of the type as declared, types declared inline with the variable are described in full
+fn get_type_name(type_name: &str, index: &Index) -> String {
+    if !type_name.starts_with("__") {
+        return type_name.to_string();
+    }
+    match index.get_type_information_or_void(type_name) {
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            let dimensions = dimensions
+                .iter()
+                .filter_map(|it| it.get_range_inclusive(index).ok())
+                .map(|it| format!("{}..{}", it.start(), it.end()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("ARRAY[{dimensions}] OF {}", get_type_name(inner_type_name, index))
+        }
+        DataTypeInformation::String { size, encoding } => {
+            let name = if *encoding == StringEncoding::Utf8 { "STRING" } else { "WSTRING" };
+            format!("{name}[{}]", get_string_length(size.as_int_value(index).ok()))
+        }
+        DataTypeInformation::Pointer { inner_type_name, auto_deref: false, .. } => {
+            format!("REF_TO {}", get_type_name(inner_type_name, index))
+        }
+        DataTypeInformation::Pointer { inner_type_name, .. } => get_type_name(inner_type_name, index),
+        _ => type_name.to_string(),
+    }
+}
+
+/// the number of characters of a string, its size includes the terminator
+fn get_string_length(size: Option<i64>) -> i64 {
+    size.map(|it| it - 1).unwrap_or(DEFAULT_STRING_LEN as i64)
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Writes the symbols as global variables of a PLCopen TC6 configuration,
+/// the offset, size and access of each symbol are added as additional data
+fn generate_plcopen_xml(table: &SymbolTable, application: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<project xmlns=\"http://www.plcopen.org/xml/tc6_0201\">\n");
+    xml.push_str("  <instances>\n    <configurations>\n");
+    let _ = writeln!(xml, "      <configuration name=\"{}\">", escape(application));
+    xml.push_str("        <globalVars>\n");
+    for symbol in &table.symbols {
+        let _ = writeln!(xml, "          <variable name=\"{}\">", escape(&symbol.name));
+        let _ = writeln!(
+            xml,
+            "            <type>{}</type>",
+            get_plcopen_type(&symbol.data_type_name, table.index)
+        );
+        xml.push_str("            <addData>\n");
+        xml.push_str("              <data name=\"urn:rusty:symbol\" handleUnknown=\"discard\">\n");
+        let _ = writeln!(
+            xml,
+            "                <symbol offset=\"{}\" size=\"{}\" access=\"{:?}\"/>",
+            symbol.offset, symbol.size, symbol.access
+        );
+        xml.push_str("              </data>\n            </addData>\n          </variable>\n");
+    }
+    xml.push_str("        </globalVars>\n      </configuration>\n");
+    xml.push_str("    </configurations>\n  </instances>\n</project>\n");
+    xml
+}
+
+/// the PLCopen TC6 type element of the given type
+fn get_plcopen_type(type_name: &str, index: &Index) -> String {
+    match index.get_type_information_or_void(type_name) {
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            let dimensions = dimensions
+                .iter()
+                .filter_map(|it| it.get_range_inclusive(index).ok())
+                .map(|it| format!("<dimension lower=\"{}\" upper=\"{}\"/>", it.start(), it.end()))
+                .collect::<String>();
+            format!(
+                "<array>{dimensions}<baseType>{}</baseType></array>",
+                get_plcopen_type(inner_type_name, index)
+            )
+        }
+        DataTypeInformation::String { size, encoding } => {
+            let element = if *encoding == StringEncoding::Utf8 { "string" } else { "wstring" };
+            format!("<{element} length=\"{}\"/>", get_string_length(size.as_int_value(index).ok()))
+        }
+        DataTypeInformation::Pointer { inner_type_name, auto_deref: false, .. } => {
+            format!("<pointer><baseType>{}</baseType></pointer>", get_plcopen_type(inner_type_name, index))
+        }
+        DataTypeInformation::Pointer { inner_type_name, .. } => get_plcopen_type(inner_type_name, index),
+        _ => match type_name.to_uppercase().as_str() {
+            "DATE_AND_TIME" | "DT" => "<DT/>".to_string(),
+            "TIME_OF_DAY" | "TOD" => "<TOD/>".to_string(),
+            name if PLCOPEN_ELEMENTARY_TYPES.contains(&name) => format!("<{name}/>"),
+            _ => format!("<derived name=\"{}\"/>", escape(type_name)),
+        },
+    }
+}
+
+/// How an instance is represented in the OPC UA address space
+enum NodeKind {
+    /// structs, function blocks, programs and arrays of them, their members are child nodes
+    Object,
+    /// a variable of a built-in data type with the dimensions of its array (empty for scalars)
+    Variable(&'static str, Vec<u32>),
+    /// pointers and other types without an OPC UA representation
+    Unsupported,
+}
+
+fn get_node_kind(type_name: &str, index: &Index) -> NodeKind {
+    let data_type = match index.get_type_information_or_void(type_name) {
+        DataTypeInformation::Struct { .. } => return NodeKind::Object,
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            return match get_node_kind(inner_type_name, index) {
+                NodeKind::Variable(data_type, inner_dimensions) => {
+                    let mut lengths = dimensions
+                        .iter()
+                        .map(|it| it.get_length(index).unwrap_or_default())
+                        .collect::<Vec<_>>();
+                    lengths.extend(inner_dimensions);
+                    NodeKind::Variable(data_type, lengths)
+                }
+                // arrays of structs are objects containing an object per element
+                kind => kind,
+            };
+        }
+        DataTypeInformation::Enum { referenced_type, .. }
+        | DataTypeInformation::SubRange { referenced_type, .. }
+        | DataTypeInformation::Alias { referenced_type, .. } => return get_node_kind(referenced_type, index),
+        DataTypeInformation::Integer { semantic_size: Some(1), .. } => "Boolean",
+        DataTypeInformation::Integer { signed, size, .. } => match (signed, size) {
+            (true, 8) => "SByte",
+            (false, 8) => "Byte",
+            (true, 16) => "Int16",
+            (false, 16) => "UInt16",
+            (true, 32) => "Int32",
+            (false, 32) => "UInt32",
+            (true, _) => "Int64",
+            (false, _) => "UInt64",
+        },
+        DataTypeInformation::Float { size: 32, .. } => "Float",
+        DataTypeInformation::Float { .. } => "Double",
+        DataTypeInformation::String { .. } => "String",
+        _ => return NodeKind::Unsupported,
+    };
+    NodeKind::Variable(data_type, vec![])
+}
+
+/// Writes the symbols as OPC UA NodeSet2. The nodes of the application are organized in a folder
+/// named after the application, structured instances are objects containing their members
+fn generate_nodeset(table: &SymbolTable, application: &str) -> String {
+    let root = format!("ns=1;s={}", escape(application));
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<UANodeSet xmlns=\"http://opcfoundation.org/UA/2011/03/UANodeSet.xsd\">\n");
+    let _ = writeln!(
+        xml,
+        "  <NamespaceUris>\n    <Uri>urn:rusty:{}</Uri>\n  </NamespaceUris>",
+        escape(application)
+    );
+    xml.push_str("  <Aliases>\n");
+    for (alias, id) in OPC_UA_DATA_TYPES {
+        let _ = writeln!(xml, "    <Alias Alias=\"{alias}\">{id}</Alias>");
+    }
+    xml.push_str("    <Alias Alias=\"Organizes\">i=35</Alias>\n");
+    xml.push_str("    <Alias Alias=\"HasTypeDefinition\">i=40</Alias>\n");
+    xml.push_str("    <Alias Alias=\"HasComponent\">i=47</Alias>\n");
+    xml.push_str("  </Aliases>\n");
+    // the application folder is organized below the objects folder (i=85)
+    write_object(&mut xml, &root, application, "Organizes", "i=85", "i=61");
+
+    let mut nodes = HashSet::new();
+    for symbol in &table.symbols {
+        let kind = get_node_kind(&symbol.data_type_name, table.index);
+        if matches!(kind, NodeKind::Unsupported) {
+            continue;
+        }
+        let (reference, parent) = match get_parent_node(&symbol.name) {
+            Some(parent) => ("HasComponent", create_parent_nodes(&mut xml, &mut nodes, parent, &root)),
+            None => ("Organizes", root.clone()),
+        };
+        let node_id = format!("ns=1;s={}", escape(&symbol.name));
+        let browse_name = get_browse_name(&symbol.name);
+        match kind {
+            NodeKind::Variable(data_type, dimensions) => {
+                let access_level = symbol.access.get_access_level();
+                let _ = write!(
+                    xml,
+                    "  <UAVariable NodeId=\"{node_id}\" BrowseName=\"1:{browse_name}\" ParentNodeId=\"{parent}\" \
+                     DataType=\"{data_type}\" AccessLevel=\"{access_level}\" UserAccessLevel=\"{access_level}\""
+                );
+                if !dimensions.is_empty() {
+                    let lengths = dimensions.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(",");
+                    let _ = write!(xml, " ValueRank=\"{}\" ArrayDimensions=\"{lengths}\"", dimensions.len());
+                }
+                xml.push_str(">\n");
+                let _ = writeln!(xml, "    <DisplayName>{browse_name}</DisplayName>");
+                xml.push_str("    <References>\n");
+                // BaseDataVariableType
+                xml.push_str("      <Reference ReferenceType=\"HasTypeDefinition\">i=63</Reference>\n");
+                let _ = writeln!(
+                    xml,
+                    "      <Reference ReferenceType=\"{reference}\" IsForward=\"false\">{parent}</Reference>"
+                );
+                xml.push_str("    </References>\n  </UAVariable>\n");
+            }
+            // BaseObjectType
+            _ => write_object(&mut xml, &node_id, &browse_name, reference, &parent, "i=58"),
+        }
+        nodes.insert(symbol.name.to_lowercase());
+    }
+    xml.push_str("</UANodeSet>\n");
+    xml
+}
+
+/// creates the object nodes of array elements containing the given instance, returns the id of its node
+fn create_parent_nodes(xml: &mut String, nodes: &mut HashSet<String>, name: &str, root: &str) -> String {
+    let node_id = format!("ns=1;s={}", escape(name));
+    if nodes.insert(name.to_lowercase()) && name.ends_with(']') {
+        let (reference, parent) = match get_parent_node(name) {
+            Some(parent) => ("HasComponent", create_parent_nodes(xml, nodes, parent, root)),
+            None => ("Organizes", root.to_string()),
+        };
+        write_object(xml, &node_id, &get_browse_name(name), reference, &parent, "i=58");
+    }
+    node_id
+}
+
+/// the name of the instance in its parent node: `b` for `a[1].b` and `[1]` for `a[1]`
+fn get_browse_name(name: &str) -> String {
+    let own_name = get_parent_node(name).map(|it| name[it.len()..].trim_start_matches('.')).unwrap_or(name);
+    escape(own_name)
+}
+
+fn write_object(
+    xml: &mut String,
+    node_id: &str,
+    browse_name: &str,
+    reference: &str,
+    parent: &str,
+    type_id: &str,
+) {
+    let _ = writeln!(xml, "  <UAObject NodeId=\"{node_id}\" BrowseName=\"1:{browse_name}\">");
+    let _ = writeln!(xml, "    <DisplayName>{browse_name}</DisplayName>");
+    xml.push_str("    <References>\n");
+    let _ = writeln!(xml, "      <Reference ReferenceType=\"HasTypeDefinition\">{type_id}</Reference>");
+    let _ = writeln!(
+        xml,
+        "      <Reference ReferenceType=\"{reference}\" IsForward=\"false\">{parent}</Reference>"
+    );
+    xml.push_str("    </References>\n  </UAObject>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::targets::{InitializationConfig, Target};
+
+    use crate::{
+        codegen::CodegenContext,
+        symbols::{
+            collect_symbols, generate_symbol_table_source, generate_symbols, EmbeddedSymbolTable,
+            SymbolAccess, SymbolFormat,
+        },
+        test_utils::tests::{codegen_into_modules, index},
+        DebugLevel,
+    };
+
+    fn describe(source: &str) -> Vec<(String, String, u32, u32, SymbolAccess)> {
+        let (_, index) = index(source);
+        collect_symbols(&index)
+            .get_symbols()
+            .iter()
+            .map(|it| (it.name.clone(), it.type_name.clone(), it.offset, it.size, it.access))
+            .collect()
+    }
+
+    fn symbol(
+        name: &str,
+        type_name: &str,
+        offset: u32,
+        size: u32,
+    ) -> (String, String, u32, u32, SymbolAccess) {
+        (name.to_string(), type_name.to_string(), offset, size, SymbolAccess::ReadWrite)
+    }
+
+    #[test]
+    fn globals_programs_and_their_members_are_listed() {
+        let symbols = describe(
+            "
+        TYPE Point : STRUCT x : INT; y : LREAL; END_STRUCT END_TYPE
+        VAR_GLOBAL
+            counter : DINT;
+        END_VAR
+        PROGRAM PLC_PRG
+        VAR_INPUT
+            start : BOOL;
+        END_VAR
+        VAR
+            position : Point;
+            names : ARRAY[0..1] OF STRING[10];
+        END_VAR
+        VAR_TEMP
+            tmp : INT;
+        END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(
+            symbols,
+            vec![
+                symbol("counter", "DINT", 0, 4),
+                symbol("PLC_PRG", "PLC_PRG", 0, 48),
+                symbol("PLC_PRG.start", "BOOL", 0, 1),
+                symbol("PLC_PRG.position", "Point", 8, 16),
+                symbol("PLC_PRG.position.x", "INT", 0, 2),
+                symbol("PLC_PRG.position.y", "LREAL", 8, 8),
+                symbol("PLC_PRG.names", "ARRAY[0..1] OF STRING[10]", 24, 22),
+            ]
+        );
+    }
+
+    #[test]
+    fn function_block_instances_in_arrays_are_expanded() {
+        let symbols = describe(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            a : BYTE;
+            b : WORD;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL
+            fbs : ARRAY[1..2] OF fb;
+        END_VAR
+        ",
+        );
+
+        let names = symbols.iter().map(|it| (it.0.as_str(), it.2)).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![("fbs", 0), ("fbs[1].a", 0), ("fbs[2].a", 0), ("fbs[1].b", 2), ("fbs[2].b", 2)]
+        );
+    }
+
+    #[test]
+    fn access_is_set_with_attributes_and_inherited_by_members() {
+        let symbols = describe(
+            "
+        TYPE Motor : STRUCT speed : INT; {attribute 'symbol' := 'readwrite'} setpoint : INT; END_STRUCT END_TYPE
+        {attribute 'symbol' := 'read'}
+        PROGRAM PLC_PRG
+        VAR
+            motor : Motor;
+            {attribute 'symbol' := 'write'}
+            command : INT;
+            {attribute 'symbol' := 'none'}
+            internal : Motor;
+            {attribute 'hide'}
+            hidden : INT;
+        END_VAR
+        VAR CONSTANT
+            {attribute 'symbol' := 'readwrite'}
+            limit : INT := 10;
+        END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        let access = symbols.iter().map(|it| (it.0.as_str(), it.4)).collect::<Vec<_>>();
+        assert_eq!(
+            access,
+            vec![
+                ("PLC_PRG", SymbolAccess::Read),
+                ("PLC_PRG.motor", SymbolAccess::Read),
+                ("PLC_PRG.motor.speed", SymbolAccess::Read),
+                ("PLC_PRG.motor.setpoint", SymbolAccess::ReadWrite),
+                ("PLC_PRG.command", SymbolAccess::Write),
+                ("PLC_PRG.limit", SymbolAccess::Read),
+            ]
+        );
+    }
+
+    #[test]
+    fn symbols_are_written_as_json() {
+        let (_, index) = index(
+            "
+        VAR_GLOBAL
+            {attribute 'symbol' := 'read'}
+            level : UINT;
+        END_VAR
+        ",
+        );
+        let json = generate_symbols(&collect_symbols(&index), SymbolFormat::Json, "app").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "Symbols": [{ "name": "level", "type": "UINT", "offset": 0, "size": 2, "access": "Read" }]
+            })
+        );
+    }
+
+    #[test]
+    fn symbols_are_written_as_plcopen_xml() {
+        let (_, index) = index(
+            "
+        TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+        VAR_GLOBAL
+            p : Point;
+            values : ARRAY[0..3] OF REAL;
+            name : STRING[20];
+            stamp : DATE_AND_TIME;
+        END_VAR
+        ",
+        );
+        let xml = generate_symbols(&collect_symbols(&index), SymbolFormat::PlcOpenXml, "app").unwrap();
+        let types = xml.lines().map(str::trim).filter(|it| it.starts_with("<type>")).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                r#"<type><derived name="Point"/></type>"#,
+                r#"<type><INT/></type>"#,
+                r#"<type><INT/></type>"#,
+                r#"<type><array><dimension lower="0" upper="3"/><baseType><REAL/></baseType></array></type>"#,
+                r#"<type><string length="20"/></type>"#,
+                r#"<type><DT/></type>"#,
+            ]
+        );
+        assert!(xml.contains(r#"<configuration name="app">"#));
+        assert!(xml.contains(r#"<variable name="p.y">"#));
+        assert!(xml.contains(r#"<symbol offset="2" size="2" access="ReadWrite"/>"#));
+    }
+
+    #[test]
+    fn symbols_are_written_as_opc_ua_nodeset() {
+        let (_, index) = index(
+            "
+        FUNCTION_BLOCK fb
+        VAR
+            on : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+        PROGRAM PLC_PRG
+        VAR
+            fbs : ARRAY[0..1] OF fb;
+            {attribute 'symbol' := 'read'}
+            values : ARRAY[0..1, 1..3] OF DINT;
+            ptr : REF_TO INT;
+        END_VAR
+        END_PROGRAM
+        ",
+        );
+        let xml = generate_symbols(&collect_symbols(&index), SymbolFormat::NodeSet, "app").unwrap();
+        let nodes = xml
+            .lines()
+            .map(str::trim)
+            .filter(|it| it.starts_with("<UAObject") || it.starts_with("<UAVariable"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            vec![
+                r#"<UAObject NodeId="ns=1;s=app" BrowseName="1:app">"#,
+                r#"<UAObject NodeId="ns=1;s=PLC_PRG" BrowseName="1:PLC_PRG">"#,
+                r#"<UAObject NodeId="ns=1;s=PLC_PRG.fbs" BrowseName="1:fbs">"#,
+                r#"<UAObject NodeId="ns=1;s=PLC_PRG.fbs[0]" BrowseName="1:[0]">"#,
+                r#"<UAVariable NodeId="ns=1;s=PLC_PRG.fbs[0].on" BrowseName="1:on" ParentNodeId="ns=1;s=PLC_PRG.fbs[0]" DataType="Boolean" AccessLevel="3" UserAccessLevel="3">"#,
+                r#"<UAObject NodeId="ns=1;s=PLC_PRG.fbs[1]" BrowseName="1:[1]">"#,
+                r#"<UAVariable NodeId="ns=1;s=PLC_PRG.fbs[1].on" BrowseName="1:on" ParentNodeId="ns=1;s=PLC_PRG.fbs[1]" DataType="Boolean" AccessLevel="3" UserAccessLevel="3">"#,
+                r#"<UAVariable NodeId="ns=1;s=PLC_PRG.values" BrowseName="1:values" ParentNodeId="ns=1;s=PLC_PRG" DataType="Int32" AccessLevel="1" UserAccessLevel="1" ValueRank="2" ArrayDimensions="2,3">"#,
+            ]
+        );
+        //programs are organized in the application folder, members are components of their parent
+        assert!(
+            xml.contains(r#"<Reference ReferenceType="Organizes" IsForward="false">ns=1;s=app</Reference>"#)
+        );
+        assert!(xml.contains(
+            r#"<Reference ReferenceType="HasComponent" IsForward="false">ns=1;s=PLC_PRG.fbs</Reference>"#
+        ));
+    }
+
+    #[test]
+    fn embedded_symbol_table_describes_variables_and_type_layouts() {
+        let (_, index) = index(
+            "
+        TYPE Point : STRUCT x : INT; y : LREAL; END_STRUCT END_TYPE
+        VAR_GLOBAL CONSTANT
+            limit : DINT := 5;
+        END_VAR
+        PROGRAM PLC_PRG
+        VAR
+            points : ARRAY[1..2] OF Point;
+        END_VAR
+        END_PROGRAM
+        ",
+        );
+        let table = serde_json::to_value(EmbeddedSymbolTable::new(&index)).unwrap();
+        assert_eq!(
+            table,
+            serde_json::json!({
+                "variables": [
+                    { "name": "limit", "symbol": "limit", "type": 0, "access": "Read" },
+                    { "name": "PLC_PRG", "symbol": "PLC_PRG_instance", "type": 5 },
+                ],
+                "types": [
+                    { "name": "DINT", "size": 4, "kind": "Signed" },
+                    { "name": "INT", "size": 2, "kind": "Signed" },
+                    { "name": "LREAL", "size": 8, "kind": "Float" },
+                    { "name": "Point", "size": 16, "kind": "Struct", "members": [
+                        { "name": "x", "offset": 0, "type": 1 },
+                        { "name": "y", "offset": 8, "type": 2 },
+                    ]},
+                    { "name": "ARRAY[1..2] OF Point", "size": 32, "kind": "Array",
+                      "dimensions": [[1, 2]], "element": 3, "stride": 16 },
+                    { "name": "PLC_PRG", "size": 32, "kind": "Struct", "members": [
+                        { "name": "points", "offset": 0, "type": 4 },
+                    ]},
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn embedded_offsets_match_the_generated_code() {
+        let source = "
+        TYPE Mixed : STRUCT
+            flag : BOOL;
+            text : STRING[4];
+            count : LINT;
+            wide : WSTRING[2];
+            last : BOOL;
+        END_STRUCT END_TYPE
+        VAR_GLOBAL
+            mixed : ARRAY[0..1] OF Mixed;
+        END_VAR
+        FUNCTION offset_flag : LINT offset_flag := ADR(mixed[0].flag) - ADR(mixed[0]); END_FUNCTION
+        FUNCTION offset_text : LINT offset_text := ADR(mixed[0].text) - ADR(mixed[0]); END_FUNCTION
+        FUNCTION offset_count : LINT offset_count := ADR(mixed[0].count) - ADR(mixed[0]); END_FUNCTION
+        FUNCTION offset_wide : LINT offset_wide := ADR(mixed[0].wide) - ADR(mixed[0]); END_FUNCTION
+        FUNCTION offset_last : LINT offset_last := ADR(mixed[0].last) - ADR(mixed[0]); END_FUNCTION
+        FUNCTION stride : LINT stride := ADR(mixed[1]) - ADR(mixed[0]); END_FUNCTION
+        ";
+        let (_, index) = index(source);
+        let table = serde_json::to_value(EmbeddedSymbolTable::new(&index)).unwrap();
+        let types = table["types"].as_array().unwrap();
+        let mixed = types.iter().find(|it| it["name"] == "Mixed").unwrap();
+        let array = types.iter().find(|it| it["kind"] == "Array").unwrap();
+
+        Target::initialize_native(&InitializationConfig::default()).unwrap();
+        let context = CodegenContext::create();
+        let modules = codegen_into_modules(&context, source, DebugLevel::None).unwrap();
+        let module = &modules[0];
+
+        for member in mixed["members"].as_array().unwrap() {
+            let name = member["name"].as_str().unwrap();
+            let offset: i64 = module.run_no_param(&format!("offset_{name}"));
+            assert_eq!(member["offset"], offset, "offset of {name}");
+        }
+        let stride: i64 = module.run_no_param("stride");
+        assert_eq!(array["stride"], stride);
+        assert_eq!(mixed["size"], stride);
+    }
+
+    #[test]
+    fn embedded_symbol_table_is_declared_as_string_constant() {
+        let (_, index) = index(
+            "
+        VAR_GLOBAL
+            {attribute 'symbol' := 'read'}
+            p : INT;
+        END_VAR
+        ",
+        );
+        let source = generate_symbol_table_source(&index);
+        let table = r#"{"variables":[{"name":"p","symbol":"p","type":0,"access":"Read"}],"types":[{"name":"INT","size":2,"kind":"Signed"}]}"#;
+        assert_eq!(
+            source,
+            format!(
+                "VAR_GLOBAL CONSTANT\n    __SYMBOL_TABLE : STRING[{}] := '{table}';\nEND_VAR\n",
+                table.len()
+            )
+        );
+    }
+}
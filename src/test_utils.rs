@@ -4,7 +4,7 @@ pub mod tests {
     use std::{cell::RefCell, path::PathBuf, rc::Rc, str::FromStr};
 
     use plc_ast::{
-        ast::{pre_process, CompilationUnit, LinkageType, SourceRangeFactory},
+        ast::{instantiate_generics, pre_process, CompilationUnit, LinkageType, SourceRangeFactory},
         provider::IdProvider,
     };
     use plc_diagnostics::{
@@ -16,11 +16,12 @@ pub mod tests {
 
     use crate::{
         builtins,
-        codegen::{CodegenContext, GeneratedModule},
+        codegen::{CodeGen, CodegenContext, GeneratedModule},
         index::{self, Index},
         lexer, parser,
         resolver::{const_evaluator::evaluate_constants, AnnotationMapImpl, AstAnnotations, TypeAnnotator},
         typesystem::get_builtin_types,
+        validation::ValidationOptions,
         DebugLevel, Validator,
     };
 
@@ -89,6 +90,7 @@ pub mod tests {
             LinkageType::Internal,
             source_path,
         );
+        instantiate_generics(std::slice::from_mut(&mut unit), id_provider.clone());
         pre_process(&mut unit, id_provider);
         index.import(index::visitor::visit(&unit));
         (unit, index)
@@ -126,6 +128,10 @@ pub mod tests {
     }
 
     pub fn parse_and_validate(src: &str) -> Vec<Diagnostic> {
+        parse_and_validate_with_options(src, ValidationOptions::default())
+    }
+
+    pub fn parse_and_validate_with_options(src: &str, options: ValidationOptions) -> Vec<Diagnostic> {
         let id_provider = IdProvider::default();
         let (unit, index) = index_with_ids(src, id_provider.clone());
 
@@ -133,7 +139,7 @@ pub mod tests {
         let (mut annotations, ..) = TypeAnnotator::visit_unit(&index, &unit, id_provider);
         index.import(std::mem::take(&mut annotations.new_index));
 
-        let mut validator = Validator::new();
+        let mut validator = Validator::with_options(options);
         validator.perform_global_validation(&index);
         validator.visit_unit(&annotations, &index, &unit);
         validator.diagnostics()
@@ -148,6 +154,16 @@ pub mod tests {
     /// TODO: This should not be so, we should have a diagnostic type that holds multiple new
     /// issues.
     pub fn codegen_debug_without_unwrap(src: &str, debug_level: DebugLevel) -> Result<String, Diagnostic> {
+        do_codegen(src, debug_level, |it| it)
+    }
+
+    /// generates the given source with a code generator configured by `configure`
+    /// (e.g. `|it| it.with_coverage(true)`)
+    fn do_codegen(
+        src: &str,
+        debug_level: DebugLevel,
+        configure: impl for<'ink> FnOnce(CodeGen<'ink>) -> CodeGen<'ink>,
+    ) -> Result<String, Diagnostic> {
         let mut id_provider = IdProvider::default();
         let (unit, index) = do_index(src, id_provider.clone());
 
@@ -158,13 +174,13 @@ pub mod tests {
 
         let context = CodegenContext::create();
         let path = PathBuf::from_str("src").ok();
-        let mut code_generator = crate::codegen::CodeGen::new(
+        let mut code_generator = configure(CodeGen::new(
             &context,
             path.as_deref(),
             "main",
             crate::OptimizationLevel::None,
             debug_level,
-        );
+        ));
         let annotations = AstAnnotations::new(annotations, id_provider.next_id());
         let llvm_index =
             code_generator.generate_llvm_index(&context, &annotations, &literals, &dependencies, &index)?;
@@ -182,7 +198,19 @@ pub mod tests {
         codegen_without_unwrap(src).unwrap()
     }
 
-    fn codegen_into_modules<T: Compilable>(
+    pub fn codegen_with_coverage(src: &str) -> String {
+        do_codegen(src, DebugLevel::None, |it| it.with_coverage(true)).unwrap()
+    }
+
+    pub fn codegen_with_cycle_monitor(src: &str) -> String {
+        do_codegen(src, DebugLevel::None, |it| it.with_cycle_monitor(true)).unwrap()
+    }
+
+    pub fn codegen_with_trace(src: &str) -> String {
+        do_codegen(src, DebugLevel::None, |it| it.with_trace(true)).unwrap()
+    }
+
+    pub fn codegen_into_modules<T: Compilable>(
         context: &CodegenContext,
         sources: T,
         debug_level: DebugLevel,
@@ -0,0 +1,267 @@
+//! Support for unit tests written in structured text.
+//!
+//! Functions marked with the `{test}` pragma are collected as test cases. Tests use the
+//! `ASSERT_TRUE`, `ASSERT_FALSE` and `ASSERT_EQUAL` built-ins, which report failed assertions
+//! to the test runner through [`ASSERT_FAILED_FUNCTION`].
+use std::{fmt::Write, time::Duration};
+
+use plc_ast::ast::{CompilationUnit, PouType};
+
+use crate::index::Index;
+
+/// The runtime function called by a failed assertion:
+/// `__plc_assert_failed(message: *const i8, file: *const i8, line: i32)`
+pub const ASSERT_FAILED_FUNCTION: &str = "__plc_assert_failed";
+
+const ASSERTIONS: [&str; 3] = ["ASSERT_TRUE", "ASSERT_FALSE", "ASSERT_EQUAL"];
+
+/// returns true if the given call name is one of the assertion built-ins
+pub fn is_assertion(name: &str) -> bool {
+    ASSERTIONS.iter().any(|it| it.eq_ignore_ascii_case(name))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    /// the name of the test function
+    pub name: String,
+    pub file: String,
+    /// the 1-based line of the test's declaration
+    pub line: u32,
+    pub return_type: TestReturnType,
+}
+
+/// The type returned by a test function, the test runner has to call a test with the type
+/// it was declared with. The returned value itself is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestReturnType {
+    Void,
+    /// an integer of the given bits, this includes `BOOL`, enums, date and time types and pointers
+    Integer(u32),
+    /// a floating point number of the given bits
+    Float(u32),
+}
+
+impl TestReturnType {
+    /// returns the return type of the function with the given name
+    pub fn of(function: &str, index: &Index) -> TestReturnType {
+        let Some(return_type) = index.find_return_type(function) else {
+            return TestReturnType::Void;
+        };
+        let information = index.get_intrinsic_type_by_name(return_type.get_name()).get_type_information();
+        match information.get_size_in_bits(index) {
+            0 => TestReturnType::Void,
+            bits if information.is_float() => TestReturnType::Float(bits),
+            bits => TestReturnType::Integer(bits),
+        }
+    }
+}
+
+/// returns all functions of the given unit that are marked with the `{test}` pragma
+pub fn collect_tests(unit: &CompilationUnit, index: &Index) -> Vec<TestCase> {
+    unit.units
+        .iter()
+        .filter(|it| it.is_test && it.pou_type == PouType::Function)
+        .map(|it| TestCase {
+            name: it.name.clone(),
+            file: unit.file_name.clone(),
+            line: unit.new_lines.get_line_nr(it.name_location.get_start()) + 1,
+            return_type: TestReturnType::of(&it.name, index),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionFailure {
+    pub message: String,
+    pub file: String,
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub test: TestCase,
+    /// the assertions that failed while running the test, the test passed if this is empty
+    pub failures: Vec<AssertionFailure>,
+    pub duration: Duration,
+}
+
+impl TestResult {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// creates a JUnit XML report for the given results, the report contains a single test suite
+pub fn generate_junit(suite: &str, results: &[TestResult]) -> String {
+    let failures = results.iter().filter(|it| !it.is_success()).count();
+    let time: Duration = results.iter().map(|it| it.duration).sum();
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        report,
+        "<testsuites tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">",
+        results.len(),
+        time.as_secs_f64()
+    );
+    let _ = writeln!(
+        report,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" time=\"{:.3}\">",
+        escape(suite),
+        results.len(),
+        time.as_secs_f64()
+    );
+    for result in results {
+        let _ = write!(
+            report,
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\" time=\"{:.3}\"",
+            escape(&result.test.name),
+            escape(&get_class_name(&result.test.file)),
+            escape(&result.test.file),
+            result.test.line,
+            result.duration.as_secs_f64()
+        );
+        if result.is_success() {
+            report.push_str("/>\n");
+            continue;
+        }
+        report.push_str(">\n");
+        for failure in &result.failures {
+            let _ = writeln!(
+                report,
+                "      <failure message=\"{}\" type=\"AssertionFailure\">{}:{}: {}</failure>",
+                escape(&failure.message),
+                escape(&failure.file),
+                failure.line,
+                escape(&failure.message)
+            );
+        }
+        report.push_str("    </testcase>\n");
+    }
+    report.push_str("  </testsuite>\n</testsuites>\n");
+    report
+}
+
+/// the file name without its extension, used to group the tests of a file in the report
+fn get_class_name(file: &str) -> String {
+    std::path::Path::new(file)
+        .file_stem()
+        .map(|it| it.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{collect_tests, generate_junit, AssertionFailure, TestCase, TestResult, TestReturnType};
+    use crate::test_utils::tests::index;
+
+    #[test]
+    fn marked_functions_are_collected_as_tests() {
+        let (unit, index) = index(
+            "
+            FUNCTION helper : BOOL END_FUNCTION
+
+            {test}
+            FUNCTION test_helper : BOOL
+                test_helper := helper();
+            END_FUNCTION
+            ",
+        );
+
+        assert_eq!(
+            collect_tests(&unit, &index),
+            vec![TestCase {
+                name: "test_helper".into(),
+                file: "test.st".into(),
+                line: 5,
+                return_type: TestReturnType::Integer(8)
+            }]
+        );
+    }
+
+    #[test]
+    fn tests_are_called_with_their_declared_return_type() {
+        let (unit, index) = index(
+            "
+            TYPE Level : (low, high); END_TYPE
+            TYPE Percent : INT(0..100); END_TYPE
+
+            {test} FUNCTION test_bool : BOOL END_FUNCTION
+            {test} FUNCTION test_int : INT END_FUNCTION
+            {test} FUNCTION test_subrange : Percent END_FUNCTION
+            {test} FUNCTION test_enum : Level END_FUNCTION
+            {test} FUNCTION test_time : TIME END_FUNCTION
+            {test} FUNCTION test_real : REAL END_FUNCTION
+            {test} FUNCTION test_lreal : LREAL END_FUNCTION
+            {test} FUNCTION test_pointer : REF_TO INT END_FUNCTION
+            ",
+        );
+
+        assert_eq!(
+            collect_tests(&unit, &index).into_iter().map(|it| it.return_type).collect::<Vec<_>>(),
+            vec![
+                TestReturnType::Integer(8),
+                TestReturnType::Integer(16),
+                TestReturnType::Integer(16),
+                TestReturnType::Integer(32),
+                TestReturnType::Integer(64),
+                TestReturnType::Float(32),
+                TestReturnType::Float(64),
+                TestReturnType::Integer(64),
+            ]
+        );
+    }
+
+    #[test]
+    fn junit_report_contains_passed_and_failed_tests() {
+        let results = vec![
+            TestResult {
+                test: TestCase {
+                    name: "test_ok".into(),
+                    file: "src/math.st".into(),
+                    line: 2,
+                    return_type: TestReturnType::Integer(8),
+                },
+                failures: vec![],
+                duration: Duration::from_millis(1),
+            },
+            TestResult {
+                test: TestCase {
+                    name: "test_fail".into(),
+                    file: "src/math.st".into(),
+                    line: 8,
+                    return_type: TestReturnType::Integer(8),
+                },
+                failures: vec![AssertionFailure {
+                    message: "ASSERT_TRUE failed".into(),
+                    file: "src/math.st".into(),
+                    line: 10,
+                }],
+                duration: Duration::from_millis(2),
+            },
+        ];
+
+        assert_eq!(
+            generate_junit("project <1>", &results),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="2" failures="1" time="0.003">
+  <testsuite name="project &lt;1&gt;" tests="2" failures="1" errors="0" time="0.003">
+    <testcase name="test_ok" classname="math" file="src/math.st" line="2" time="0.001"/>
+    <testcase name="test_fail" classname="math" file="src/math.st" line="8" time="0.002">
+      <failure message="ASSERT_TRUE failed" type="AssertionFailure">src/math.st:10: ASSERT_TRUE failed</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
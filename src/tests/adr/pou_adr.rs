@@ -79,6 +79,7 @@ fn programs_state_is_stored_in_a_struct() {
                             },
                         },
                         varargs: None,
+                        attributes: [],
                     },
                     VariableIndexEntry {
                         name: "io",
@@ -99,6 +100,7 @@ fn programs_state_is_stored_in_a_struct() {
                             },
                         },
                         varargs: None,
+                        attributes: [],
                     },
                     VariableIndexEntry {
                         name: "o",
@@ -119,6 +121,7 @@ fn programs_state_is_stored_in_a_struct() {
                             },
                         },
                         varargs: None,
+                        attributes: [],
                     },
                     VariableIndexEntry {
                         name: "v",
@@ -139,6 +142,7 @@ fn programs_state_is_stored_in_a_struct() {
                             },
                         },
                         varargs: None,
+                        attributes: [],
                     },
                     VariableIndexEntry {
                         name: "vt",
@@ -159,6 +163,7 @@ fn programs_state_is_stored_in_a_struct() {
                             },
                         },
                         varargs: None,
+                        attributes: [],
                     },
                 ],
                 source: Pou(
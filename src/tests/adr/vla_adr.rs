@@ -54,6 +54,7 @@ fn representation() {
                         },
                     },
                     varargs: None,
+                    attributes: [],
                 },
                 VariableIndexEntry {
                     name: "dimensions",
@@ -74,6 +75,7 @@ fn representation() {
                         },
                     },
                     varargs: None,
+                    attributes: [],
                 },
             ],
             source: Internal(
@@ -281,6 +283,7 @@ fn pass() {
                     },
                 },
                 varargs: None,
+                attributes: [],
             },
             VariableIndexEntry {
                 name: "dimensions",
@@ -301,6 +304,7 @@ fn pass() {
                     },
                 },
                 varargs: None,
+                attributes: [],
             },
         ],
         source: Internal(
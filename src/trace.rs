@@ -0,0 +1,400 @@
+//! Execution tracing of programs compiled with `--trace`
+//!
+//! Members of a PROGRAM marked with `{attribute 'trace'}` are recorded at the end of every call of the
+//! program. Before the program returns, the values of its traced variables are widened to 64 bits
+//! (floats are stored as the bits of an LREAL) and passed to the `__plc_trace_record` function of the
+//! standard library together with a description of the program's signals:
+//! `<program>\t<name>:<kind>:<bits>\t...`, where the kind is one of `b` (BOOL), `i` (signed),
+//! `u` (unsigned) or `f` (float).
+//!
+//! The runtime keeps the latest samples in a ring buffer (`PLC_TRACE_SAMPLES`, 1024 samples by default)
+//! and writes them to the file named by `PLC_TRACE_FILE` (`default.plctrace` if unset) when the
+//! process exits. The file contains one line per traced program, `program\t<description>`, followed by
+//! one line per sample: `sample\t<time in ns>\t<program>\t<value> <value> ...`.
+//! The `trace` subcommand turns such a file into a value change dump (VCD).
+use std::{collections::HashMap, fmt::Write};
+
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::{
+    index::{Index, VariableIndexEntry},
+    typesystem::DataTypeInformation,
+};
+
+/// The function recording the traced variables of a program
+pub const TRACE_RECORD_FUNCTION: &str = "__plc_trace_record";
+/// The environment variable used to choose the trace file of the runtime
+pub const TRACE_FILE_VARIABLE: &str = "PLC_TRACE_FILE";
+pub const DEFAULT_TRACE_FILE: &str = "default.plctrace";
+/// The attribute marking a variable as traced
+pub const TRACE_ATTRIBUTE: &str = "trace";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalKind {
+    Bool,
+    Signed,
+    Unsigned,
+    Float,
+}
+
+impl SignalKind {
+    fn get_code(&self) -> char {
+        match self {
+            SignalKind::Bool => 'b',
+            SignalKind::Signed => 'i',
+            SignalKind::Unsigned => 'u',
+            SignalKind::Float => 'f',
+        }
+    }
+
+    fn from_code(code: &str) -> Option<SignalKind> {
+        match code {
+            "b" => Some(SignalKind::Bool),
+            "i" => Some(SignalKind::Signed),
+            "u" => Some(SignalKind::Unsigned),
+            "f" => Some(SignalKind::Float),
+            _ => None,
+        }
+    }
+}
+
+/// A traced variable of a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    pub name: String,
+    pub kind: SignalKind,
+    /// the number of bits represented by the variable's type
+    pub bits: u32,
+}
+
+impl Signal {
+    /// returns the signal of a variable of the given type, enums and subranges are traced as their
+    /// referenced type. Returns `None` if values of the type cannot be traced
+    pub fn new(name: &str, type_name: &str, index: &Index) -> Option<Signal> {
+        let (kind, bits) = match index.get_intrinsic_type_by_name(type_name).get_type_information() {
+            DataTypeInformation::Integer { semantic_size: Some(1), .. } => (SignalKind::Bool, 1),
+            DataTypeInformation::Integer { signed: true, size, .. } => (SignalKind::Signed, *size),
+            DataTypeInformation::Integer { signed: false, size, .. } => (SignalKind::Unsigned, *size),
+            DataTypeInformation::Float { size, .. } => (SignalKind::Float, *size),
+            _ => return None,
+        };
+        Some(Signal { name: name.to_string(), kind, bits })
+    }
+}
+
+/// returns true if the variable is marked with the `trace` attribute
+pub fn is_traced(variable: &VariableIndexEntry) -> bool {
+    variable.get_attributes().iter().any(|it| it.name.eq_ignore_ascii_case(TRACE_ATTRIBUTE))
+}
+
+/// Returns the traced members of the given program with their signals. Members of types that
+/// cannot be traced are reported by the validation and skipped.
+pub fn get_traced_variables<'idx>(
+    program: &str,
+    index: &'idx Index,
+) -> Vec<(&'idx VariableIndexEntry, Signal)> {
+    index
+        .get_pou_members(program)
+        .iter()
+        .filter(|it| is_traced(it))
+        .filter_map(|it| Signal::new(it.get_name(), it.get_type_name(), index).map(|signal| (it, signal)))
+        .collect()
+}
+
+/// Returns the description of a program's signals passed to the runtime
+pub fn get_description(program: &str, signals: &[Signal]) -> String {
+    let mut description = program.to_string();
+    for signal in signals {
+        let _ = write!(description, "\t{}:{}:{}", signal.name, signal.kind.get_code(), signal.bits);
+    }
+    description
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedProgram {
+    pub name: String,
+    pub signals: Vec<Signal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    /// the time of the sample in nanoseconds
+    pub time: i64,
+    /// the index of the sampled program
+    pub program: usize,
+    pub values: Vec<i64>,
+}
+
+/// The contents of a trace file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub programs: Vec<TracedProgram>,
+    pub samples: Vec<Sample>,
+}
+
+/// Reads a trace file written by the tracing runtime
+pub fn read_trace(content: &str) -> Result<Trace, Diagnostic> {
+    let mut trace = Trace::default();
+    let mut programs = HashMap::new();
+    for (line_nr, line) in content.lines().enumerate().filter(|(_, it)| !it.trim().is_empty()) {
+        let invalid = || Diagnostic::param_error(&format!("Invalid trace entry in line {}", line_nr + 1));
+        match line.split_once('\t').ok_or_else(invalid)? {
+            ("program", description) => {
+                let program = read_description(description).ok_or_else(invalid)?;
+                match programs.get(&program.name) {
+                    Some(known) if trace.programs[*known] != program => return Err(invalid()),
+                    Some(_) => {}
+                    None => {
+                        programs.insert(program.name.clone(), trace.programs.len());
+                        trace.programs.push(program);
+                    }
+                }
+            }
+            ("sample", sample) => {
+                let mut fields = sample.splitn(3, '\t');
+                let time = fields.next().and_then(|it| it.parse::<i64>().ok()).ok_or_else(invalid)?;
+                let program = fields.next().and_then(|it| programs.get(it)).copied().ok_or_else(invalid)?;
+                let values = fields
+                    .next()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(|it| it.parse::<i64>().map_err(|_| invalid()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if values.len() != trace.programs[program].signals.len() {
+                    return Err(invalid());
+                }
+                trace.samples.push(Sample { time, program, values });
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(trace)
+}
+
+fn read_description(description: &str) -> Option<TracedProgram> {
+    let mut fields = description.split('\t');
+    let name = fields.next().filter(|it| !it.is_empty())?.to_string();
+    let signals = fields
+        .map(|field| {
+            let mut parts = field.split(':');
+            let name = parts.next().filter(|it| !it.is_empty())?.to_string();
+            let kind = parts.next().and_then(SignalKind::from_code)?;
+            let bits =
+                parts.next().and_then(|it| it.parse::<u32>().ok()).filter(|it| (1..=64).contains(it))?;
+            parts.next().is_none().then_some(Signal { name, kind, bits })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(TracedProgram { name, signals })
+}
+
+/// Creates a value change dump of the trace, every program is a module containing its signals
+pub fn generate_vcd(trace: &Trace) -> String {
+    let mut vcd = String::new();
+    // writing into a string does not fail
+    let _ = write_vcd(trace, &mut vcd);
+    vcd
+}
+
+fn write_vcd(trace: &Trace, vcd: &mut String) -> std::fmt::Result {
+    writeln!(vcd, "$timescale 1ns $end")?;
+    // the identifiers of the first signal of every program
+    let mut offsets = Vec::with_capacity(trace.programs.len());
+    let mut next = 0;
+    for program in &trace.programs {
+        offsets.push(next);
+        writeln!(vcd, "$scope module {} $end", program.name)?;
+        for signal in &program.signals {
+            let var_type = match signal.kind {
+                SignalKind::Bool => "wire",
+                SignalKind::Signed | SignalKind::Unsigned => "integer",
+                SignalKind::Float => "real",
+            };
+            let bits = if signal.kind == SignalKind::Float { 64 } else { signal.bits };
+            writeln!(vcd, "$var {var_type} {bits} {} {} $end", get_identifier(next), signal.name)?;
+            next += 1;
+        }
+        writeln!(vcd, "$upscope $end")?;
+    }
+    writeln!(vcd, "$enddefinitions $end")?;
+
+    let mut samples = trace.samples.iter().collect::<Vec<_>>();
+    samples.sort_by_key(|it| it.time);
+    let mut current_values: Vec<Option<i64>> = vec![None; next];
+    let mut current_time = None;
+    for sample in samples {
+        let program = &trace.programs[sample.program];
+        for (index, (signal, value)) in program.signals.iter().zip(&sample.values).enumerate() {
+            let id = offsets[sample.program] + index;
+            if current_values[id] == Some(*value) {
+                continue;
+            }
+            if current_time != Some(sample.time) {
+                writeln!(vcd, "#{}", sample.time)?;
+                current_time = Some(sample.time);
+            }
+            current_values[id] = Some(*value);
+            let identifier = get_identifier(id);
+            match signal.kind {
+                SignalKind::Bool => writeln!(vcd, "{}{identifier}", u8::from(*value != 0))?,
+                SignalKind::Signed | SignalKind::Unsigned => {
+                    let mask = if signal.bits >= 64 { u64::MAX } else { (1 << signal.bits) - 1 };
+                    writeln!(vcd, "b{:b} {identifier}", *value as u64 & mask)?
+                }
+                SignalKind::Float => writeln!(vcd, "r{} {identifier}", f64::from_bits(*value as u64))?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// returns the VCD identifier of the signal with the given number, made of the printable ASCII characters
+fn get_identifier(mut number: usize) -> String {
+    const FIRST: u8 = b'!';
+    const COUNT: usize = (b'~' - b'!') as usize + 1;
+    let mut identifier = String::new();
+    loop {
+        identifier.push((FIRST + (number % COUNT) as u8) as char);
+        number /= COUNT;
+        if number == 0 {
+            return identifier;
+        }
+        number -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::tests::index;
+
+    use super::{
+        generate_vcd, get_description, get_identifier, get_traced_variables, read_trace, Sample, Signal,
+        SignalKind, TracedProgram,
+    };
+
+    #[test]
+    fn traced_members_are_described_with_their_signal_kinds() {
+        let (_, index) = index(
+            "
+            TYPE Mode : (Off, Manual, Auto); END_TYPE
+            TYPE Percent : INT(0..100); END_TYPE
+            PROGRAM PLC_PRG
+            VAR
+                {attribute 'trace'}
+                on : BOOL;
+                {attribute 'trace'}
+                speed : Percent;
+                {attribute 'trace'}
+                mode : Mode;
+                {attribute 'trace'}
+                level : REAL;
+                {attribute 'trace'}
+                counter : UDINT;
+                {attribute 'trace'}
+                name : STRING;
+                untraced : INT;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let signals =
+            get_traced_variables("PLC_PRG", &index).into_iter().map(|(_, it)| it).collect::<Vec<_>>();
+
+        assert_eq!(
+            get_description("PLC_PRG", &signals),
+            "PLC_PRG\ton:b:1\tspeed:i:16\tmode:i:32\tlevel:f:32\tcounter:u:32"
+        );
+    }
+
+    #[test]
+    fn trace_files_are_read() {
+        let trace = read_trace(
+            "program\tPLC_PRG\ton:b:1\tlevel:f:64\nsample\t100\tPLC_PRG\t1 4612811918334230528\nsample\t200\tPLC_PRG\t0 0\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            trace.programs,
+            vec![TracedProgram {
+                name: "PLC_PRG".to_string(),
+                signals: vec![
+                    Signal { name: "on".to_string(), kind: SignalKind::Bool, bits: 1 },
+                    Signal { name: "level".to_string(), kind: SignalKind::Float, bits: 64 },
+                ]
+            }]
+        );
+        assert_eq!(
+            trace.samples,
+            vec![
+                Sample { time: 100, program: 0, values: vec![1, 2.5f64.to_bits() as i64] },
+                Sample { time: 200, program: 0, values: vec![0, 0] },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_trace_entries_are_reported() {
+        for (content, line) in [
+            ("program\tPLC_PRG\ton:x:1", 1),
+            ("program\tPLC_PRG\ton:b:1\nsample\t100\tmain\t1", 2),
+            ("program\tPLC_PRG\ton:b:1\n\nsample\t100\tPLC_PRG\t1 2", 3),
+            ("program\tPLC_PRG\ton:b:1\nprogram\tPLC_PRG\ton:i:8", 2),
+            ("PLC_PRG\t1", 1),
+        ] {
+            assert_eq!(
+                read_trace(content).unwrap_err().get_message(),
+                format!("Invalid trace entry in line {line}")
+            );
+        }
+    }
+
+    #[test]
+    fn value_changes_are_dumped_per_program() {
+        let trace = read_trace(
+            "program\tPLC_PRG\ton:b:1\tspeed:i:16\tlevel:f:32
+program\tmain\tcount:u:8
+sample\t100\tPLC_PRG\t1 -2 4612811918334230528
+sample\t100\tmain\t255
+sample\t200\tPLC_PRG\t1 -2 0
+sample\t300\tPLC_PRG\t1 -2 0
+sample\t400\tPLC_PRG\t0 3 0
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            generate_vcd(&trace),
+            r#"$timescale 1ns $end
+$scope module PLC_PRG $end
+$var wire 1 ! on $end
+$var integer 16 " speed $end
+$var real 64 # level $end
+$upscope $end
+$scope module main $end
+$var integer 8 $ count $end
+$upscope $end
+$enddefinitions $end
+#100
+1!
+b1111111111111110 "
+r2.5 #
+b11111111 $
+#200
+r0 #
+#400
+0!
+b11 "
+"#
+        );
+    }
+
+    #[test]
+    fn identifiers_are_unique() {
+        assert_eq!(get_identifier(0), "!");
+        assert_eq!(get_identifier(93), "~");
+        assert_eq!(get_identifier(94), "!!");
+        assert_eq!(get_identifier(95), "\"!");
+        let identifiers = (0..10_000).map(get_identifier).collect::<std::collections::HashSet<_>>();
+        assert_eq!(identifiers.len(), 10_000);
+    }
+}
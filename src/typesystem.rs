@@ -322,6 +322,8 @@ impl TypeSize {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StructSource {
     OriginalDeclaration,
+    /// a UNION declaration, all members are located at offset 0
+    Union,
     Pou(PouType),
     Internal(InternalType),
 }
@@ -372,6 +374,8 @@ pub enum DataTypeInformation {
         name: TypeId,
         referenced_type: TypeId,
         elements: Vec<String>,
+        /// strict enums may only be compared, assigned and switched against their own type
+        strict: bool,
     },
     Float {
         name: TypeId,
@@ -464,6 +468,10 @@ impl DataTypeInformation {
         matches!(self, DataTypeInformation::Struct { .. })
     }
 
+    pub fn is_union(&self) -> bool {
+        matches!(self, DataTypeInformation::Struct { source: StructSource::Union, .. })
+    }
+
     pub fn is_array(&self) -> bool {
         matches!(self, DataTypeInformation::Array { .. })
     }
@@ -559,6 +567,17 @@ impl DataTypeInformation {
                 .map(|size| encoding.get_bytes_per_char() * size as u32)
                 .map(Bytes::from_bits)
                 .unwrap(),
+            // a union is as large as its largest member, rounded up to the largest member alignment
+            DataTypeInformation::Struct { members, source: StructSource::Union, .. } => {
+                let (size, alignment) = members
+                    .iter()
+                    .map(|it| index.get_type_information_or_void(it.get_type_name()))
+                    .map(|it| (it.get_size(index).value(), it.get_alignment(index).value()))
+                    .fold((0, 1), |(size, alignment), (member_size, member_alignment)| {
+                        (size.max(member_size), alignment.max(member_alignment))
+                    });
+                MemoryLocation::new(size).align_to(Bytes::new(alignment)).into()
+            }
             DataTypeInformation::Struct { members, .. } => members
                 .iter()
                 .map(|it| it.get_type_name())
@@ -18,10 +18,16 @@ use self::{
     variable::visit_variable_block,
 };
 
+pub(crate) use self::reference::find_reference_inner_type;
+
 mod array;
 mod global;
+mod hardware;
+mod jump;
 mod pou;
+mod property;
 mod recursive;
+mod reference;
 mod statement;
 mod types;
 mod variable;
@@ -83,12 +89,28 @@ pub trait Validators {
     fn take_diagnostics(&mut self) -> Vec<Diagnostic>;
 }
 
+/// Options that change which programs the validator accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// whether the `__NEW` and `__DELETE` built-ins may be used
+    pub allow_dynamic_memory: bool,
+    /// whether all enums are strict, otherwise only enums marked with `{attribute 'strict'}` are
+    pub strict_enums: bool,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions { allow_dynamic_memory: true, strict_enums: false }
+    }
+}
+
 #[derive(Validators)]
 pub struct Validator {
     //context: ValidationContext<'s>,
     diagnostics: Vec<Diagnostic>,
     global_validator: GlobalValidator,
     recursive_validator: RecursiveValidator,
+    options: ValidationOptions,
 }
 
 impl Default for Validator {
@@ -99,13 +121,22 @@ impl Default for Validator {
 
 impl Validator {
     pub fn new() -> Validator {
+        Validator::with_options(ValidationOptions::default())
+    }
+
+    pub fn with_options(options: ValidationOptions) -> Validator {
         Validator {
             diagnostics: Vec::new(),
             global_validator: GlobalValidator::new(),
             recursive_validator: RecursiveValidator::new(),
+            options,
         }
     }
 
+    pub fn get_options(&self) -> &ValidationOptions {
+        &self.options
+    }
+
     pub fn diagnostics(&mut self) -> Vec<Diagnostic> {
         let mut all_diagnostics = Vec::new();
         all_diagnostics.append(&mut self.take_diagnostics());
@@ -117,6 +148,7 @@ impl Validator {
     pub fn perform_global_validation(&mut self, index: &Index) {
         self.global_validator.validate(index);
         self.recursive_validator.validate(index);
+        hardware::validate_hardware_bindings(self, index);
 
         // XXX: To avoid bloating up this function any further, maybe package logic into seperate module or
         //      function if another global check is introduced (including the overflow checks)?
@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use plc_ast::ast::{PouType, SourceRange};
+use plc_ast::ast::{LinkageType, PouType, SourceRange};
 use plc_diagnostics::diagnostics::Diagnostic;
 
 use crate::{
@@ -164,7 +164,7 @@ impl GlobalValidator {
                         | PouIndexEntry::Function { .. }
                         | PouIndexEntry::Method { .. }
                         | PouIndexEntry::Action { .. }
-                )
+                ) && !is_duplicated_builtin(index, p)
             })
             .map(|it| (it.get_name(), &it.get_location().source_range));
 
@@ -186,6 +186,9 @@ impl GlobalValidator {
             .get_pous()
             .entries()
             .filter(|(_, entries_per_name)| entries_per_name.iter().filter(only_toplevel_pous).count() > 1)
+            .filter(|(_, entries_per_name)| {
+                !entries_per_name.iter().all(|it| is_duplicated_builtin(index, it))
+            })
             .map(|(name, pous)| {
                 (
                     name.as_str(),
@@ -211,3 +214,13 @@ impl GlobalValidator {
         }
     }
 }
+
+/// builtins are unique by construction, only the conversions of duplicated enums are duplicated as well.
+/// They are not reported, since the duplicated enum already is.
+fn is_duplicated_builtin(index: &Index, pou: &PouIndexEntry) -> bool {
+    pou.get_linkage() == &LinkageType::BuiltIn
+        && index
+            .get_pous()
+            .get_all(&pou.get_name().to_lowercase())
+            .is_some_and(|it| it.iter().all(|it| it.get_linkage() == &LinkageType::BuiltIn))
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use plc_ast::ast::{DirectAccessType, HardwareAccessType};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use super::{Validator, Validators};
+use crate::{
+    index::{HardwareBinding, Index, VariableIndexEntry, VariableType},
+    process_image::{collect_mapped_variables, ImageAddress, MappedVariable},
+};
+
+/// validates the hardware addresses (`AT %IX1.2`) of all declared variables and reports
+/// output addresses shared by several instances
+pub fn validate_hardware_bindings(validator: &mut Validator, index: &Index) {
+    let variables = index
+        .get_globals()
+        .values()
+        .chain(index.get_pou_types().values().flat_map(|it| it.get_members()))
+        .chain(index.get_types().values().flat_map(|it| it.get_members()));
+    for variable in variables {
+        if let Some(binding) = variable.get_hardware_binding() {
+            validate_binding(validator, index, variable, binding);
+        }
+    }
+
+    validate_template_addresses(validator, index);
+    validate_overlapping_outputs(validator, index);
+}
+
+fn validate_binding(
+    validator: &mut Validator,
+    index: &Index,
+    variable: &VariableIndexEntry,
+    binding: &HardwareBinding,
+) {
+    if let Some(ImageAddress { bit: Some(bit), .. }) = ImageAddress::from_binding(binding, index) {
+        if bit > 7 {
+            validator.push_diagnostic(Diagnostic::invalid_hardware_bit(bit, binding.location.clone()));
+        }
+    }
+
+    let data_type = index.get_effective_type_or_void_by_name(variable.get_type_name());
+    let type_info = data_type.get_type_information();
+    let size = match binding.access {
+        DirectAccessType::Bit => None,
+        DirectAccessType::Byte => Some(1),
+        DirectAccessType::Word => Some(2),
+        DirectAccessType::DWord => Some(4),
+        DirectAccessType::LWord => Some(8),
+        DirectAccessType::Template => return,
+    };
+    // arrays, structs and strings may span several addresses
+    let is_mismatch = match size {
+        None => !type_info.is_bool(),
+        Some(size) => !type_info.is_aggregate() && type_info.get_size(index).value() != size,
+    };
+    if is_mismatch {
+        validator.push_diagnostic(Diagnostic::hardware_size_mismatch(
+            data_type.get_name(),
+            &format!("{:?}", binding.access),
+            binding.location.clone(),
+        ));
+    }
+
+    let conflict = match (variable.get_variable_type(), binding.direction) {
+        (VariableType::Input, HardwareAccessType::Output) => Some(("input", "output")),
+        (VariableType::Output, HardwareAccessType::Input) => Some(("output", "input")),
+        _ => None,
+    };
+    if let Some((kind, area)) = conflict {
+        validator.push_diagnostic(Diagnostic::hardware_direction_conflict(
+            variable.get_qualified_name(),
+            kind,
+            area,
+            binding.location.clone(),
+        ));
+    }
+}
+
+/// validates the addresses assigned to template variables (`AT %I*`) in `VAR_CONFIG` blocks and
+/// reports instances of template variables without an assigned address
+fn validate_template_addresses(validator: &mut Validator, index: &Index) {
+    let mut templates = HashMap::new();
+    for (name, instance) in index.find_instances() {
+        let Some(binding) = instance.get_hardware_binding() else { continue };
+        if binding.access != DirectAccessType::Template {
+            continue;
+        }
+        for name in name.expand(index) {
+            if index.find_config_variable(&name).is_none() {
+                validator.push_diagnostic(Diagnostic::unresolved_template_address(
+                    &name,
+                    binding.location.clone(),
+                ));
+            }
+            templates.insert(name.to_lowercase(), (instance, binding));
+        }
+    }
+
+    for (path, entries) in index.get_config_variables().entries() {
+        let config = &entries[0];
+        let name = config.get_instance_path();
+        if entries.len() > 1 {
+            validator.push_diagnostic(Diagnostic::invalid_config_variable(
+                name,
+                "the instance is configured more than once",
+                entries.iter().map(|it| it.get_location().clone()).collect(),
+            ));
+        }
+
+        let Some((variable, template)) = templates.get(path) else {
+            validator.push_diagnostic(Diagnostic::invalid_config_variable(
+                name,
+                "the instance is not declared with a template address",
+                vec![config.get_location().clone()],
+            ));
+            continue;
+        };
+        let binding = config.get_hardware_binding();
+        let reason = if binding.access == DirectAccessType::Template {
+            Some("the assigned address must not be a template".to_string())
+        } else if binding.direction != template.direction {
+            Some(format!(
+                "the assigned address must be in the {:?} area like its template",
+                template.direction
+            ))
+        } else {
+            config
+                .get_type_name()
+                .map(|it| index.get_effective_type_or_void_by_name(it).get_name())
+                .filter(|it| {
+                    *it != index.get_effective_type_or_void_by_name(variable.get_type_name()).get_name()
+                })
+                .map(|it| format!("{it} does not match the declared type {}", variable.get_type_name()))
+        };
+        match reason {
+            Some(reason) => validator.push_diagnostic(Diagnostic::invalid_config_variable(
+                name,
+                &reason,
+                vec![binding.location.clone()],
+            )),
+            None => validate_binding(validator, index, variable, binding),
+        }
+    }
+}
+
+/// reports outputs written by several instances, e.g. the outputs of a function block
+/// with a fixed address that is instantiated more than once
+fn validate_overlapping_outputs(validator: &mut Validator, index: &Index) {
+    let mut outputs = collect_mapped_variables(index)
+        .into_iter()
+        .filter(|it| it.address.area == HardwareAccessType::Output)
+        .collect::<Vec<_>>();
+    outputs.sort_by_key(|it| it.address.byte);
+
+    for (idx, output) in outputs.iter().enumerate() {
+        let overlapping = outputs[idx + 1..]
+            .iter()
+            .take_while(|other| other.address.byte < output.get_end())
+            .filter(|other| overlaps(output, other));
+        for other in overlapping {
+            let mut range = vec![output.location.clone()];
+            if other.location != output.location {
+                range.push(other.location.clone());
+            }
+            validator.push_diagnostic(Diagnostic::overlapping_hardware_addresses(
+                &output.name,
+                &other.name,
+                range,
+            ));
+        }
+    }
+}
+
+fn overlaps(output: &MappedVariable, other: &MappedVariable) -> bool {
+    let intersect = output.address.byte < other.get_end() && other.address.byte < output.get_end();
+    match (output.address.bit, other.address.bit) {
+        // two bits of the same byte only overlap if they are the same bit
+        (Some(bit), Some(other_bit)) => intersect && bit == other_bit,
+        _ => intersect,
+    }
+}
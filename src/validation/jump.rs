@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use plc_ast::{
+    ast::{AstId, AstStatement, Implementation, SourceRange},
+    control_statements::AstControlStatement,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use super::{Validator, Validators};
+
+/// validates the `JMP` statements and labels of an implementation. Every label must be unique and every
+/// jump must target a label of the same implementation which is not located inside of a loop the jump is
+/// not part of.
+pub fn validate_jumps(validator: &mut Validator, implementation: &Implementation) {
+    let mut collector = JumpCollector::default();
+    collector.visit_body(&implementation.statements);
+
+    let mut labels: HashMap<String, &Target> = HashMap::new();
+    for label in &collector.labels {
+        if let Some(first) = labels.get(&label.name.to_lowercase()) {
+            validator.push_diagnostic(Diagnostic::duplicate_label(
+                &label.name,
+                label.location.clone(),
+                first.location.clone(),
+            ));
+        } else {
+            labels.insert(label.name.to_lowercase(), label);
+        }
+    }
+
+    for jump in &collector.jumps {
+        match labels.get(&jump.name.to_lowercase()) {
+            None => validator.push_diagnostic(Diagnostic::undefined_label(&jump.name, jump.location.clone())),
+            Some(label) if !jump.loops.starts_with(&label.loops) => {
+                validator.push_diagnostic(Diagnostic::jump_into_loop(&jump.name, jump.location.clone()))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// a label or the target of a jump
+struct Target {
+    name: String,
+    location: SourceRange,
+    /// the ids of the loops surrounding the statement, the outermost loop first
+    loops: Vec<AstId>,
+}
+
+#[derive(Default)]
+struct JumpCollector {
+    labels: Vec<Target>,
+    jumps: Vec<Target>,
+    loops: Vec<AstId>,
+}
+
+impl JumpCollector {
+    fn visit_body(&mut self, statements: &[AstStatement]) {
+        statements.iter().for_each(|it| self.visit(it));
+    }
+
+    fn visit_loop(&mut self, id: AstId, body: &[AstStatement]) {
+        self.loops.push(id);
+        self.visit_body(body);
+        self.loops.pop();
+    }
+
+    fn visit(&mut self, statement: &AstStatement) {
+        match statement {
+            AstStatement::LabelStatement { name, location, .. } => self.labels.push(Target {
+                name: name.clone(),
+                location: location.clone(),
+                loops: self.loops.clone(),
+            }),
+            AstStatement::JumpStatement { label, location, .. } => self.jumps.push(Target {
+                name: label.clone(),
+                location: location.clone(),
+                loops: self.loops.clone(),
+            }),
+            AstStatement::ControlStatement { kind, id, .. } => match kind {
+                AstControlStatement::If(stmt) => {
+                    stmt.blocks.iter().for_each(|it| self.visit_body(&it.body));
+                    self.visit_body(&stmt.else_block);
+                }
+                AstControlStatement::Case(stmt) => {
+                    stmt.case_blocks.iter().for_each(|it| self.visit_body(&it.body));
+                    self.visit_body(&stmt.else_block);
+                }
+                AstControlStatement::ForLoop(stmt) => self.visit_loop(*id, &stmt.body),
+                AstControlStatement::WhileLoop(stmt) | AstControlStatement::RepeatLoop(stmt) => {
+                    self.visit_loop(*id, &stmt.body)
+                }
+            },
+            _ => {}
+        }
+    }
+}
@@ -2,9 +2,10 @@ use plc_ast::ast::{Implementation, LinkageType, Pou, PouType, VariableBlockType}
 use plc_diagnostics::diagnostics::Diagnostic;
 
 use super::{
-    statement::visit_statement, variable::visit_variable_block, ValidationContext, Validator, Validators,
+    jump::validate_jumps, reference::validate_reference_bindings, statement::visit_statement,
+    variable::visit_variable_block, ValidationContext, Validator, Validators,
 };
-use crate::resolver::AnnotationMap;
+use crate::{cycle_monitor::get_budget, resolver::AnnotationMap};
 
 pub fn visit_pou<T: AnnotationMap>(validator: &mut Validator, pou: &Pou, context: &ValidationContext<'_, T>) {
     if pou.linkage != LinkageType::External {
@@ -32,6 +33,8 @@ pub fn visit_implementation<T: AnnotationMap>(
         implementation.statements.iter().for_each(|s| {
             visit_statement(validator, s, &context.with_qualifier(implementation.name.as_str()))
         });
+        validate_reference_bindings(validator, implementation, context);
+        validate_jumps(validator, implementation);
     }
 }
 
@@ -45,6 +48,9 @@ fn validate_pou<T: AnnotationMap>(validator: &mut Validator, pou: &Pou, context:
     if pou.pou_type == PouType::Program {
         validate_program(validator, pou);
     }
+    if pou.is_test {
+        validate_test(validator, pou, context);
+    }
 }
 
 fn validate_class<T: AnnotationMap>(validator: &mut Validator, pou: &Pou, context: &ValidationContext<T>) {
@@ -94,6 +100,37 @@ fn validate_program(validator: &mut Validator, pou: &Pou) {
             pou.name_location.to_owned(),
         ));
     }
+    if let Some(Err(diagnostic)) = get_budget(&pou.attributes) {
+        validator.push_diagnostic(diagnostic);
+    }
+}
+
+fn validate_test<T: AnnotationMap>(validator: &mut Validator, pou: &Pou, context: &ValidationContext<T>) {
+    // tests are called without arguments by the test runner
+    if pou.variable_blocks.iter().any(|it| {
+        matches!(
+            it.variable_block_type,
+            VariableBlockType::InOut | VariableBlockType::Input(_) | VariableBlockType::Output
+        )
+    }) {
+        validator.push_diagnostic(Diagnostic::invalid_test_function(
+            "A test cannot have parameters",
+            pou.name_location.to_owned(),
+        ));
+    }
+
+    // aggregate return values would need to be passed as an out-pointer by the test runner
+    if context
+        .index
+        .find_return_type(&pou.name)
+        .map(|it| context.index.get_effective_type_or_void_by_name(it.get_name()).is_aggregate_type())
+        .unwrap_or_default()
+    {
+        validator.push_diagnostic(Diagnostic::invalid_test_function(
+            "A test must return an elementary type",
+            pou.name_location.to_owned(),
+        ));
+    }
 }
 
 pub fn validate_action_container(validator: &mut Validator, implementation: &Implementation) {
@@ -0,0 +1,99 @@
+use plc_ast::ast::{AccessModifier, AstStatement, ReferenceAccess};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use super::{ValidationContext, Validator, Validators};
+use crate::{
+    index::{Index, PropertyIndexEntry},
+    resolver::AnnotationMap,
+};
+
+/// returns the property accessed by the given reference (e.g. `fb.Speed`) or `None` if the
+/// reference does not access a property
+pub fn find_accessed_property<'i, T: AnnotationMap>(
+    statement: &AstStatement,
+    context: &ValidationContext<'i, T>,
+) -> Option<&'i PropertyIndexEntry> {
+    let AstStatement::ReferenceExpr { access: ReferenceAccess::Member(reference), base: Some(base), .. } =
+        statement
+    else {
+        return None;
+    };
+    let AstStatement::Identifier { name, .. } = reference.as_ref() else { return None };
+    let qualifier = context.annotations.get_type(base, context.index)?.get_name();
+    if context.index.find_member(qualifier, name).is_some() {
+        return None;
+    }
+    context.index.find_property(qualifier, name)
+}
+
+/// validates reading the given property, it needs a GET accessor and must be accessible
+pub fn validate_property_read<T: AnnotationMap>(
+    validator: &mut Validator,
+    property: &PropertyIndexEntry,
+    statement: &AstStatement,
+    context: &ValidationContext<T>,
+) {
+    if property.get_getter().is_none() {
+        validator.push_diagnostic(Diagnostic::missing_property_accessor(
+            &property.get_qualified_name(),
+            "GET",
+            statement.get_location(),
+        ));
+    }
+    validate_property_access(validator, property, statement, context);
+}
+
+/// validates writing the given property, it needs a SET accessor and must be accessible
+pub fn validate_property_write<T: AnnotationMap>(
+    validator: &mut Validator,
+    property: &PropertyIndexEntry,
+    statement: &AstStatement,
+    context: &ValidationContext<T>,
+) {
+    if property.get_setter().is_none() {
+        validator.push_diagnostic(Diagnostic::missing_property_accessor(
+            &property.get_qualified_name(),
+            "SET",
+            statement.get_location(),
+        ));
+    }
+    validate_property_access(validator, property, statement, context);
+}
+
+/// private properties can only be accessed by their owner, protected properties by their owner and
+/// the classes extending it
+fn validate_property_access<T: AnnotationMap>(
+    validator: &mut Validator,
+    property: &PropertyIndexEntry,
+    statement: &AstStatement,
+    context: &ValidationContext<T>,
+) {
+    let container = context.qualifier.and_then(|it| context.index.find_pou(it)).map(|it| it.get_container());
+    let is_accessible = match property.get_access() {
+        AccessModifier::Public | AccessModifier::Internal => true,
+        AccessModifier::Private => {
+            container.map_or(false, |it| it.eq_ignore_ascii_case(property.get_owner()))
+        }
+        AccessModifier::Protected => {
+            container.map_or(false, |it| is_same_or_derived_class(context.index, it, property.get_owner()))
+        }
+    };
+    if !is_accessible {
+        let access = format!("{:?}", property.get_access()).to_lowercase();
+        validator.push_diagnostic(Diagnostic::illegal_property_access(
+            &property.get_qualified_name(),
+            &access,
+            statement.get_location(),
+        ));
+    }
+}
+
+fn is_same_or_derived_class(index: &Index, class: &str, base_class: &str) -> bool {
+    if class.eq_ignore_ascii_case(base_class) {
+        return true;
+    }
+    index
+        .find_pou(class)
+        .and_then(|it| it.get_super_class())
+        .map_or(false, |super_class| is_same_or_derived_class(index, super_class, base_class))
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+
+use plc_ast::{
+    ast::{AstStatement, Implementation, PouType, ReferenceAccess},
+    control_statements::{AstControlStatement, ConditionalBlock},
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use super::{ValidationContext, Validator, Validators};
+use crate::{
+    index::{ArgumentType, Index, VariableType},
+    resolver::{AnnotationMap, StatementAnnotation},
+    typesystem::DataTypeInformation,
+};
+
+/// the builtin that checks whether a reference is bound, its argument does not count as a use
+const IS_VALID_REF: &str = "__ISVALIDREF";
+
+/// returns the referenced type's name if the given statement is a `REFERENCE TO` variable.
+/// Auto-deref pointers of `VAR_IN_OUT` and `{ref}` parameters are not references that can be bound.
+pub(crate) fn find_reference_inner_type<'i>(
+    statement: &AstStatement,
+    annotations: &dyn AnnotationMap,
+    index: &'i Index,
+) -> Option<&'i str> {
+    let qualified_name = match annotations.get(statement) {
+        Some(StatementAnnotation::Variable { qualified_name, is_auto_deref: true, .. }) => qualified_name,
+        _ => return None,
+    };
+    let variable = index.find_fully_qualified_variable(qualified_name)?;
+    if matches!(variable.get_declaration_type(), ArgumentType::ByRef(_)) {
+        return None;
+    }
+    match index.find_effective_type_info(variable.get_type_name()) {
+        Some(DataTypeInformation::Pointer { inner_type_name, auto_deref: true, .. }) => {
+            Some(inner_type_name.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// validates the binding `left REF= right`. `left` needs to be a `REFERENCE TO` variable and `right`
+/// a non-constant variable of the referenced type.
+pub fn validate_reference_assignment<T: AnnotationMap>(
+    validator: &mut Validator,
+    left: &AstStatement,
+    right: &AstStatement,
+    context: &ValidationContext<T>,
+) {
+    let Some(inner_type_name) = find_reference_inner_type(left, context.annotations, context.index) else {
+        validator.push_diagnostic(Diagnostic::invalid_reference_binding(
+            "only REFERENCE TO variables can be bound",
+            left.get_location(),
+        ));
+        return;
+    };
+
+    match context.annotations.get(right) {
+        Some(StatementAnnotation::Variable { constant: true, .. }) => {
+            validator.push_diagnostic(Diagnostic::invalid_reference_binding(
+                "cannot bind a reference to a constant",
+                right.get_location(),
+            ));
+        }
+        Some(StatementAnnotation::Variable { resulting_type, .. }) => {
+            let expected = context.index.get_effective_type_or_void_by_name(inner_type_name);
+            let actual = context.index.get_effective_type_or_void_by_name(resulting_type);
+            if expected.get_name() != actual.get_name() {
+                validator.push_diagnostic(Diagnostic::invalid_reference_binding(
+                    &format!(
+                        "expected a variable of type {}, found {}",
+                        expected.get_name(),
+                        actual.get_name()
+                    ),
+                    right.get_location(),
+                ));
+            }
+        }
+        _ => validator.push_diagnostic(Diagnostic::invalid_reference_binding(
+            "a reference can only be bound to a variable",
+            right.get_location(),
+        )),
+    }
+}
+
+/// reports `REFERENCE TO` variables that are used before they are bound with `REF=`.
+///
+/// Only references that start out unbound on every call are checked, these are `VAR_TEMP` variables and
+/// the local variables of functions and methods. The check follows the statements in their textual
+/// order, a binding inside a conditional block counts as binding the reference.
+pub fn validate_reference_bindings<T: AnnotationMap>(
+    validator: &mut Validator,
+    implementation: &Implementation,
+    context: &ValidationContext<T>,
+) {
+    let locals_are_temporary = matches!(implementation.pou_type, PouType::Function | PouType::Method { .. });
+    let references = context
+        .index
+        .get_pou_members(&implementation.type_name)
+        .iter()
+        .filter(|it| match it.get_variable_type() {
+            VariableType::Temp => true,
+            VariableType::Local => locals_are_temporary,
+            _ => false,
+        })
+        .filter(|it| {
+            matches!(
+                context.index.find_effective_type_info(it.get_type_name()),
+                Some(DataTypeInformation::Pointer { auto_deref: true, .. })
+            )
+        })
+        .map(|it| (it.get_qualified_name().to_string(), it.get_name()))
+        .collect::<HashMap<_, _>>();
+    if references.is_empty() {
+        return;
+    }
+
+    let mut bindings =
+        ReferenceBindings { references, bound: HashSet::new(), annotations: context.annotations };
+    bindings.visit_body(validator, &implementation.statements);
+}
+
+struct ReferenceBindings<'a, T: AnnotationMap> {
+    /// the qualified names of the checked references and their names
+    references: HashMap<String, &'a str>,
+    /// the references that are bound (or already reported)
+    bound: HashSet<String>,
+    annotations: &'a T,
+}
+
+impl<'a, T: AnnotationMap> ReferenceBindings<'a, T> {
+    fn get_reference(&self, statement: &AstStatement) -> Option<&String> {
+        match self.annotations.get(statement) {
+            Some(StatementAnnotation::Variable { qualified_name, .. }) => {
+                self.references.get_key_value(qualified_name).map(|(key, _)| key)
+            }
+            _ => None,
+        }
+    }
+
+    fn visit_body(&mut self, validator: &mut Validator, statements: &[AstStatement]) {
+        statements.iter().for_each(|it| self.visit(validator, it));
+    }
+
+    fn visit_blocks(&mut self, validator: &mut Validator, blocks: &[ConditionalBlock]) {
+        for block in blocks {
+            self.visit(validator, &block.condition);
+            self.visit_body(validator, &block.body);
+        }
+    }
+
+    fn visit(&mut self, validator: &mut Validator, statement: &AstStatement) {
+        match statement {
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                self.visit(validator, right);
+                if let Some(reference) = self.get_reference(left).cloned() {
+                    self.bound.insert(reference);
+                } else {
+                    self.visit(validator, left);
+                }
+            }
+            AstStatement::ReferenceExpr { access, base, .. } => {
+                if let Some(reference) = self.get_reference(statement).cloned() {
+                    if self.bound.insert(reference.clone()) {
+                        validator.push_diagnostic(Diagnostic::unbound_reference(
+                            self.references[&reference],
+                            statement.get_location(),
+                        ));
+                    }
+                }
+                if let Some(base) = base {
+                    self.visit(validator, base);
+                }
+                if let ReferenceAccess::Index(index) = access {
+                    self.visit(validator, index);
+                }
+            }
+            AstStatement::CallStatement { operator, parameters, .. } => {
+                if operator
+                    .get_flat_reference_name()
+                    .map_or(false, |it| it.eq_ignore_ascii_case(IS_VALID_REF))
+                {
+                    return;
+                }
+                self.visit(validator, operator);
+                if let Some(parameters) = parameters.as_ref() {
+                    self.visit(validator, parameters);
+                }
+            }
+            AstStatement::Assignment { left, right, .. }
+            | AstStatement::OutputAssignment { left, right, .. } => {
+                self.visit(validator, right);
+                self.visit(validator, left);
+            }
+            AstStatement::BinaryExpression { left, right, .. }
+            | AstStatement::RangeStatement { start: left, end: right, .. } => {
+                self.visit(validator, left);
+                self.visit(validator, right);
+            }
+            AstStatement::UnaryExpression { value, .. } => self.visit(validator, value),
+            AstStatement::ExpressionList { expressions, .. } => self.visit_body(validator, expressions),
+            AstStatement::ControlStatement { kind, .. } => match kind {
+                AstControlStatement::If(stmt) => {
+                    self.visit_blocks(validator, &stmt.blocks);
+                    self.visit_body(validator, &stmt.else_block);
+                }
+                AstControlStatement::ForLoop(stmt) => {
+                    self.visit(validator, &stmt.counter);
+                    self.visit(validator, &stmt.start);
+                    self.visit(validator, &stmt.end);
+                    if let Some(by_step) = &stmt.by_step {
+                        self.visit(validator, by_step);
+                    }
+                    self.visit_body(validator, &stmt.body);
+                }
+                AstControlStatement::WhileLoop(stmt) => {
+                    self.visit(validator, &stmt.condition);
+                    self.visit_body(validator, &stmt.body);
+                }
+                AstControlStatement::RepeatLoop(stmt) => {
+                    self.visit_body(validator, &stmt.body);
+                    self.visit(validator, &stmt.condition);
+                }
+                AstControlStatement::Case(stmt) => {
+                    self.visit(validator, &stmt.selector);
+                    self.visit_blocks(validator, &stmt.case_blocks);
+                    self.visit_body(validator, &stmt.else_block);
+                }
+            },
+            _ => {}
+        }
+    }
+}
@@ -9,6 +9,8 @@ use plc_diagnostics::diagnostics::Diagnostic;
 
 use super::{
     array::{validate_array_assignment, Wrapper},
+    property::{find_accessed_property, validate_property_read, validate_property_write},
+    reference::validate_reference_assignment,
     ValidationContext, Validator, Validators,
 };
 use crate::{
@@ -65,7 +67,11 @@ pub fn visit_statement<T: AnnotationMap>(
                 visit_statement(validator, base, context);
             }
 
-            validate_reference_expression(access, validator, context, statement, base);
+            if let Some(property) = find_accessed_property(statement, context) {
+                validate_property_read(validator, property, statement, context);
+            } else {
+                validate_reference_expression(access, validator, context, statement, base);
+            }
         }
         AstStatement::BinaryExpression { operator, left, right, .. } => {
             visit_all_statements!(validator, context, left, right);
@@ -81,17 +87,34 @@ pub fn visit_statement<T: AnnotationMap>(
             visit_all_statements!(validator, context, start, end);
         }
         AstStatement::Assignment { left, right, .. } => {
+            if let Some(property) = find_accessed_property(left, context) {
+                // assigning a property calls its setter, the property itself is not read
+                if let AstStatement::ReferenceExpr { base: Some(base), .. } = left.as_ref() {
+                    visit_statement(validator, base, context);
+                }
+                visit_statement(validator, right, context);
+
+                validate_property_write(validator, property, left, context);
+                validate_assignment(validator, right, None, &statement.get_location(), context);
+            } else {
+                visit_statement(validator, left, context);
+                visit_statement(validator, right, context);
+
+                validate_assignment(validator, right, Some(left), &statement.get_location(), context);
+                validate_array_assignment(validator, context, Wrapper::Statement(statement));
+            }
+        }
+        AstStatement::OutputAssignment { left, right, .. } => {
             visit_statement(validator, left, context);
             visit_statement(validator, right, context);
 
             validate_assignment(validator, right, Some(left), &statement.get_location(), context);
-            validate_array_assignment(validator, context, Wrapper::Statement(statement));
         }
-        AstStatement::OutputAssignment { left, right, .. } => {
+        AstStatement::ReferenceAssignment { left, right, .. } => {
             visit_statement(validator, left, context);
             visit_statement(validator, right, context);
 
-            validate_assignment(validator, right, Some(left), &statement.get_location(), context);
+            validate_reference_assignment(validator, left, right, context);
         }
         AstStatement::CallStatement { operator, parameters, .. } => {
             validate_call(validator, operator, parameters, &context.set_is_call());
@@ -506,6 +529,15 @@ fn visit_binary_expression<T: AnnotationMap>(
     right: &AstStatement,
     context: &ValidationContext<T>,
 ) {
+    if operator.is_comparison_operator() {
+        validate_strict_enum_mix(
+            validator,
+            get_effective_type_info(left, context),
+            get_effective_type_info(right, context),
+            statement.get_location(),
+        );
+    }
+
     match operator {
         Operator::NotEqual => {
             validate_binary_expression(validator, statement, &Operator::Equal, left, right, context)
@@ -696,6 +728,15 @@ fn validate_assignment<T: AnnotationMap>(
             return;
         }
 
+        let effective_type = |it: &DataType| {
+            context.index.get_effective_type_or_void_by_name(it.get_name()).get_type_information()
+        };
+        let (left_info, right_info) = (effective_type(left_type), effective_type(right_type));
+        // assignments to enum variables are already validated by `validate_enum_variant_assignment`
+        if left.is_none() || !left_info.is_enum() {
+            validate_strict_enum_mix(validator, left_info, right_info, location.clone());
+        }
+
         if !(left_type.is_compatible_with_type(right_type)
             && is_valid_assignment(left_type, right_type, right, context.index, location, validator))
         {
@@ -723,6 +764,45 @@ pub(crate) fn validate_enum_variant_assignment(
     }
 }
 
+/// validates that a strict enum is only mixed with values of its own type
+pub(crate) fn validate_strict_enum_mix(
+    validator: &mut Validator,
+    left: &DataTypeInformation,
+    right: &DataTypeInformation,
+    location: SourceRange,
+) {
+    let is_void = |it: &DataTypeInformation| matches!(it, DataTypeInformation::Void);
+    if left.get_name() == right.get_name() || is_void(left) || is_void(right) {
+        return;
+    }
+    let (strict_enum, other) = if is_strict_enum(validator, left) {
+        (left, right)
+    } else if is_strict_enum(validator, right) {
+        (right, left)
+    } else {
+        return;
+    };
+    validator.push_diagnostic(Diagnostic::strict_enum_mismatch(
+        strict_enum.get_name(),
+        other.get_name(),
+        location,
+    ));
+}
+
+/// returns true if the given type is an enum that may only be mixed with its own type, either because
+/// it is marked with `{attribute 'strict'}` or because all enums are strict
+fn is_strict_enum(validator: &Validator, data_type: &DataTypeInformation) -> bool {
+    matches!(data_type, DataTypeInformation::Enum { strict, .. } if *strict || validator.get_options().strict_enums)
+}
+
+fn get_effective_type_info<'i, T: AnnotationMap>(
+    statement: &AstStatement,
+    context: &ValidationContext<'i, T>,
+) -> &'i DataTypeInformation {
+    let data_type = context.annotations.get_type_or_void(statement, context.index);
+    context.index.get_effective_type_or_void_by_name(data_type.get_name()).get_type_information()
+}
+
 fn validate_variable_length_array_assignment<T: AnnotationMap>(
     validator: &mut Validator,
     context: &ValidationContext<T>,
@@ -993,6 +1073,80 @@ fn validate_case_statement<T: AnnotationMap>(
     });
 
     else_block.iter().for_each(|s| visit_statement(validator, s, context));
+
+    let selector_type = get_effective_type_info(selector, context);
+    if is_strict_enum(validator, selector_type) {
+        validate_strict_enum_case_statement(
+            validator,
+            selector,
+            selector_type,
+            case_blocks,
+            else_block,
+            context,
+        );
+    }
+}
+
+/// validates that a CASE over a strict enum only uses the enum's elements as conditions and warns about
+/// elements that are not handled if there is no ELSE block
+fn validate_strict_enum_case_statement<T: AnnotationMap>(
+    validator: &mut Validator,
+    selector: &AstStatement,
+    selector_type: &DataTypeInformation,
+    case_blocks: &[ConditionalBlock],
+    else_block: &[AstStatement],
+    context: &ValidationContext<T>,
+) {
+    let evaluate = |statement: &AstStatement| match const_evaluator::evaluate(
+        statement,
+        context.qualifier,
+        context.index,
+    ) {
+        Ok(Some(AstStatement::Literal { kind: AstLiteral::Integer(value), .. })) => Some(value),
+        _ => None,
+    };
+
+    // the (inclusive) ranges of values handled by the case blocks
+    let mut handled = vec![];
+    for condition in case_blocks.iter().flat_map(|it| flatten_expression_list(it.condition.as_ref())) {
+        let bounds = match condition {
+            AstStatement::RangeStatement { start, end, .. } => vec![start.as_ref(), end.as_ref()],
+            _ => vec![condition],
+        };
+        for bound in &bounds {
+            validate_strict_enum_mix(
+                validator,
+                selector_type,
+                get_effective_type_info(bound, context),
+                bound.get_location(),
+            );
+        }
+        if let (Some(start), Some(end)) = (evaluate(bounds[0]), evaluate(bounds[bounds.len() - 1])) {
+            handled.push(start..=end);
+        }
+    }
+
+    let DataTypeInformation::Enum { name, elements, .. } = selector_type else { return };
+    if !else_block.is_empty() {
+        return;
+    }
+    let unhandled = elements
+        .iter()
+        .filter(|element| {
+            context
+                .index
+                .find_enum_element_value(name, element)
+                .is_some_and(|value| !handled.iter().any(|it| it.contains(&value)))
+        })
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if !unhandled.is_empty() {
+        validator.push_diagnostic(Diagnostic::unhandled_enum_elements(
+            name,
+            &unhandled,
+            selector.get_location(),
+        ));
+    }
 }
 
 /// Validates that the assigned type and type hint are compatible with the nature for this
@@ -4,6 +4,7 @@ mod assignment_validation_tests;
 mod bitaccess_validation_test;
 mod duplicates_validation_test;
 mod generic_validation_tests;
+mod hardware_validation_tests;
 mod literals_validation_tests;
 mod naming_validation_test;
 mod pou_validation_tests;
@@ -1598,3 +1598,39 @@ fn any_date_multiple_parameters() {
     let diagnostics = parse_and_validate(src);
     assert_validation_snapshot!(&diagnostics);
 }
+
+#[test]
+fn generic_instances_are_validated_against_the_declared_natures() {
+    let src = r"
+        FUNCTION_BLOCK FIFO<T : ANY_NUM>
+        VAR
+            buffer : ARRAY[0..9] OF T;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        TYPE Point : STRUCT x, y : INT; END_STRUCT END_TYPE
+
+        PROGRAM main
+        VAR
+            a : FIFO<INT>;
+            b : FIFO<LREAL>;
+            c : FIFO<STRING>;
+            d : FIFO<INT, REAL>;
+            e : Point<INT>;
+            f : Unknown<INT>;
+        END_VAR
+        END_PROGRAM
+    ";
+
+    let diagnostics = parse_and_validate(src);
+    let messages = diagnostics.iter().map(|it| it.get_message()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Invalid type nature for generic argument. STRING is no Num.",
+            "Invalid type argument count for `FIFO`. Received 2 type arguments while 1 were expected.",
+            "`Point` is not a generic type",
+            "Unknown type: Unknown",
+        ]
+    );
+}
@@ -0,0 +1,195 @@
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::test_utils::tests::parse_and_validate;
+
+#[test]
+fn valid_hardware_addresses_are_accepted() {
+    let diagnostics = parse_and_validate(
+        "
+        VAR_GLOBAL
+            a AT %IX1.7 : BOOL;
+            b AT %QB3 : BYTE;
+            c AT %QW2 : INT;
+            d AT %QD2 : DINT;
+            e AT %QX0.1 : BOOL;
+            f AT %QX0.2 : BOOL;
+            g AT %IB0 : ARRAY[0..3] OF BYTE;
+        END_VAR
+        ",
+    );
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn bit_indexes_must_be_below_8() {
+    let diagnostics = parse_and_validate(
+        "
+        VAR_GLOBAL
+            a AT %IX1.8 : BOOL;
+            b AT %MX0.7 : BOOL;
+        END_VAR
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["Invalid bit index 8, a byte only has the bits 0 to 7"]);
+}
+
+#[test]
+fn variable_sizes_must_match_their_addresses() {
+    let diagnostics = parse_and_validate(
+        "
+        VAR_GLOBAL
+            a AT %IX1.0 : INT;
+            b AT %IW1 : DINT;
+            c AT %QD1 : REAL;
+            d AT %QL1 : LREAL;
+        END_VAR
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "The size of INT does not match the size of a Bit address",
+            "The size of DINT does not match the size of a Word address",
+        ]
+    );
+}
+
+#[test]
+fn inputs_and_outputs_of_pous_must_match_their_area() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb
+        VAR_INPUT
+            i AT %QX0.0 : BOOL;
+            i2 AT %IX0.0 : BOOL;
+        END_VAR
+        VAR_OUTPUT
+            o AT %IB1 : BYTE;
+            o2 AT %QB1 : BYTE;
+        END_VAR
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "fb.i is an input of its POU but is bound to an output address",
+            "fb.o is an output of its POU but is bound to an input address",
+        ]
+    );
+}
+
+#[test]
+fn overlapping_outputs_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb
+        VAR
+            x AT %QX8.1 : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        VAR_GLOBAL
+            w AT %QW4 : WORD;
+            b AT %QB9 : BYTE;
+            i1 AT %IB9 : BYTE;
+            i2 AT %IB9 : BYTE;
+            fbs : ARRAY[0..1] OF fb;
+        END_VAR
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "The output addresses of w and fbs[0].x overlap",
+            "The output addresses of w and fbs[1].x overlap",
+            "The output addresses of w and b overlap",
+            "The output addresses of fbs[0].x and fbs[1].x overlap",
+        ]
+    );
+}
+
+#[test]
+fn template_addresses_must_be_configured() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb
+        VAR
+            x AT %I* : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+        VAR
+            fbs : ARRAY[0..1] OF fb;
+            w AT %Q* : WORD;
+        END_VAR
+        END_PROGRAM
+
+        VAR_CONFIG
+            prg.fbs[0].x AT %IX0.1 : BOOL;
+            PRG.W AT %QW1 : WORD;
+        END_VAR
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["The template address of prg.fbs[1].x is not assigned in a VAR_CONFIG block"]);
+}
+
+#[test]
+fn invalid_config_variables_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb
+        VAR
+            x AT %I* : BOOL;
+            y AT %Q* : INT;
+            z : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        VAR_GLOBAL
+            a : fb;
+            b : fb;
+            c : fb;
+        END_VAR
+
+        VAR_CONFIG
+            a.x AT %IX0.0 : BOOL;
+            a.x AT %IX0.1 : BOOL;
+            a.y AT %IW1 : INT;
+            a.z AT %QX1.0 : BOOL;
+            b.x AT %I* : BOOL;
+            b.y AT %QW1 : DINT;
+            c.x AT %IX1.9 : BOOL;
+            c.y AT %QD1 : INT;
+            d.x AT %IX2.0 : BOOL;
+        END_VAR
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Invalid VAR_CONFIG entry for a.x: the instance is configured more than once",
+            "Invalid VAR_CONFIG entry for a.y: the assigned address must be in the Output area like its template",
+            "Invalid VAR_CONFIG entry for a.z: the instance is not declared with a template address",
+            "Invalid VAR_CONFIG entry for b.x: the assigned address must not be a template",
+            "Invalid VAR_CONFIG entry for b.y: DINT does not match the declared type INT",
+            "Invalid bit index 9, a byte only has the bits 0 to 7",
+            "The size of INT does not match the size of a DWord address",
+            "Invalid VAR_CONFIG entry for d.x: the instance is not declared with a template address",
+        ]
+    );
+}
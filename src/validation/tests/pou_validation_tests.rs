@@ -1,3 +1,5 @@
+use plc_diagnostics::diagnostics::Diagnostic;
+
 use crate::{assert_validation_snapshot, test_utils::tests::parse_and_validate};
 
 #[test]
@@ -222,3 +224,49 @@ fn in_out_variable_out_of_order() {
 
     assert_validation_snapshot!(diagnostics);
 }
+
+#[test]
+fn tests_with_parameters_or_aggregate_returns_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        {test}
+        FUNCTION test_ok : BOOL
+        END_FUNCTION
+
+        {test}
+        FUNCTION test_with_input : BOOL
+        VAR_INPUT x : INT; END_VAR
+        END_FUNCTION
+
+        {test}
+        FUNCTION test_with_string : STRING
+        END_FUNCTION
+    ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_test_function("A test cannot have parameters", (102..117).into()),
+            Diagnostic::invalid_test_function("A test must return an elementary type", (214..230).into()),
+        ]
+    );
+}
+
+#[test]
+fn invalid_cycle_budgets_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        {attribute 'cycle_budget' := 'T#5ms'}
+        PROGRAM valid
+        END_PROGRAM
+
+        {attribute 'cycle_budget' := '5'}
+        PROGRAM invalid
+        END_PROGRAM
+    ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["Invalid cycle budget '5', expected a positive TIME literal (e.g. T#5ms)"]);
+}
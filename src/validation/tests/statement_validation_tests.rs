@@ -1,7 +1,8 @@
 use plc_diagnostics::diagnostics::Diagnostic;
 
 use crate::assert_validation_snapshot;
-use crate::test_utils::tests::parse_and_validate;
+use crate::test_utils::tests::{parse_and_validate, parse_and_validate_with_options};
+use crate::validation::ValidationOptions;
 
 #[test]
 fn assign_pointer_to_too_small_type_result_in_an_error() {
@@ -1348,3 +1349,448 @@ fn invalid_cast_statement_causes_error() {
 
     assert_validation_snapshot!(diagnostics);
 }
+
+#[test]
+fn dynamic_memory_builtins_are_valid_for_types_and_pointers() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+            instances : REF_TO fb;
+        END_VAR
+            value := __NEW(DINT);
+            instances := __NEW(fb, 4);
+            __DELETE(value);
+            __DELETE(instances);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn dynamic_memory_builtins_with_invalid_parameters_cause_errors() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+            x : DINT;
+            r : REAL;
+        END_VAR
+            value := __NEW(x);
+            value := __NEW(DINT, r);
+            __DELETE(x);
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert!(
+        messages.contains(&"Invalid dynamic memory usage: __NEW expects a data type as its first parameter"),
+        "{messages:?}"
+    );
+    assert!(messages.contains(&"Invalid type nature for generic argument. REAL is no Int."), "{messages:?}");
+    assert!(
+        messages.contains(&"Invalid dynamic memory usage: __DELETE expects a pointer, found DINT"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn dynamic_memory_builtins_are_reported_if_dynamic_memory_is_disabled() {
+    let diagnostics = parse_and_validate_with_options(
+        "
+        PROGRAM main
+        VAR
+            value : REF_TO DINT;
+        END_VAR
+            value := __NEW(DINT);
+            __DELETE(value);
+        END_PROGRAM
+        ",
+        ValidationOptions { allow_dynamic_memory: false, ..Default::default() },
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Invalid dynamic memory usage: dynamic memory is disabled for this build",
+            "Invalid dynamic memory usage: dynamic memory is disabled for this build",
+        ]
+    );
+}
+
+#[test]
+fn bound_references_are_valid() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION foo : DINT
+        VAR_INPUT
+            condition : BOOL;
+        END_VAR
+        VAR
+            x, y : DINT;
+            r : REFERENCE TO DINT;
+        END_VAR
+            IF __ISVALIDREF(r) THEN
+                foo := 1;
+            END_IF
+            IF condition THEN
+                r REF= x;
+            ELSE
+                r REF= y;
+            END_IF
+            r := r + 1;
+            foo := r;
+        END_FUNCTION
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn invalid_reference_bindings_cause_errors() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM main
+        VAR
+            x : DINT;
+            i : INT;
+            p : REF_TO DINT;
+            r : REFERENCE TO DINT;
+        END_VAR
+        VAR CONSTANT
+            c : DINT := 1;
+        END_VAR
+            p REF= x;
+            r REF= i;
+            r REF= c;
+            r REF= 5;
+            __ISVALIDREF(x);
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Invalid reference binding: only REFERENCE TO variables can be bound",
+            "Invalid reference binding: expected a variable of type DINT, found INT",
+            "Invalid reference binding: cannot bind a reference to a constant",
+            "Invalid reference binding: a reference can only be bound to a variable",
+            "Invalid reference binding: __ISVALIDREF expects a REFERENCE TO variable",
+        ]
+    );
+}
+
+#[test]
+fn references_used_before_they_are_bound_cause_errors() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION foo : DINT
+        VAR
+            x : DINT;
+            r : REFERENCE TO DINT;
+        END_VAR
+            foo := r;
+            r := 3;
+            r REF= x;
+            foo := r;
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+        VAR
+            r : REFERENCE TO DINT;
+        END_VAR
+        VAR_TEMP
+            t : REFERENCE TO DINT;
+        END_VAR
+            r := t;
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    // every reference is only reported once, the member `r` of fb may be bound in an earlier call
+    assert_eq!(
+        messages,
+        vec!["Reference `r` is used before it is bound", "Reference `t` is used before it is bound"]
+    );
+}
+
+#[test]
+fn jumps_to_defined_labels_are_valid() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM main
+        VAR
+            i, x : DINT;
+        END_VAR
+        start:
+            FOR i := 0 TO 10 DO
+                IF i = x THEN
+                    JMP found;
+                END_IF
+            next:
+                x := x + 1;
+                JMP next;
+            END_FOR
+            JMP start;
+        found:
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn invalid_jumps_and_labels_cause_errors() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM main
+        VAR
+            x : DINT;
+        END_VAR
+            JMP nowhere;
+            JMP inner;
+        twice:
+            WHILE x < 10 DO
+            inner:
+                x := x + 1;
+            END_WHILE
+        TWICE:
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Duplicate label `TWICE`",
+            "Could not find label `nowhere`",
+            "Cannot jump to label `inner`, jumping into a loop is not allowed",
+        ]
+    );
+}
+
+#[test]
+fn reading_and_writing_properties_is_valid() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS Motor
+        VAR
+            speed_ : INT;
+        END_VAR
+            PROPERTY Speed : INT
+                GET
+                    Speed := speed_;
+                END_GET
+                SET
+                    speed_ := Speed;
+                END_SET
+            END_PROPERTY
+            PROPERTY PROTECTED Limit : INT
+                GET
+                    Limit := 100;
+                END_GET
+            END_PROPERTY
+        END_CLASS
+
+        CLASS FastMotor EXTENDS Motor
+        VAR
+            other : Motor;
+        END_VAR
+            METHOD accelerate
+                other.Speed := other.Speed + 1;
+                IF other.Limit > 10 THEN
+                    other.Speed := 10;
+                END_IF
+            END_METHOD
+        END_CLASS
+
+        PROGRAM main
+        VAR
+            motor : Motor;
+            x : INT;
+        END_VAR
+            motor.Speed := 10;
+            x := motor.Speed;
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn missing_accessors_and_illegal_property_access_cause_errors() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK MyFb
+        VAR
+            value : INT;
+        END_VAR
+            PROPERTY State : INT
+                GET
+                    State := value;
+                END_GET
+            END_PROPERTY
+            PROPERTY Command : INT
+                SET
+                    value := Command;
+                END_SET
+            END_PROPERTY
+            PROPERTY PRIVATE Secret : INT
+                GET
+                    Secret := 42;
+                END_GET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            fb : MyFb;
+            x : INT;
+        END_VAR
+            fb.State := 1;
+            x := fb.Command;
+            x := fb.Secret;
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Property `MyFb.State` has no SET accessor",
+            "Property `MyFb.Command` has no GET accessor",
+            "Illegal access to private property `MyFb.Secret`",
+        ]
+    );
+}
+
+#[test]
+fn strict_enums_can_only_be_mixed_with_their_own_type() {
+    let diagnostics = parse_and_validate(
+        "
+        {attribute 'strict'}
+        TYPE Color : (Red, Yellow, Green); END_TYPE
+
+        PROGRAM prg
+        VAR
+            c : Color;
+            x : DINT;
+            b : BOOL;
+        END_VAR
+            b := c = Color#Red;
+            b := c = 1;
+            x := c;
+            x := Color_TO_DINT(c);
+            c := DINT_TO_Color(x);
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Strict enum Color cannot be mixed with DINT, use an explicit conversion",
+            "Strict enum Color cannot be mixed with DINT, use an explicit conversion",
+        ]
+    );
+}
+
+#[test]
+fn enums_are_not_strict_by_default() {
+    let diagnostics = parse_and_validate(
+        "
+        TYPE Color : (Red, Yellow, Green); END_TYPE
+
+        PROGRAM prg
+        VAR
+            c : Color;
+            x : DINT;
+            b : BOOL;
+        END_VAR
+            b := c = 1;
+            x := c;
+            CASE c OF
+                Red: x := 1;
+            END_CASE
+        END_PROGRAM
+        ",
+    );
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn all_enums_are_strict_with_the_strict_enums_option() {
+    let diagnostics = parse_and_validate_with_options(
+        "
+        TYPE Color : (Red, Yellow, Green); END_TYPE
+
+        PROGRAM prg
+        VAR
+            c : Color;
+            x : DINT;
+        END_VAR
+            x := c;
+        END_PROGRAM
+        ",
+        ValidationOptions { strict_enums: true, ..Default::default() },
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["Strict enum Color cannot be mixed with DINT, use an explicit conversion"]);
+}
+
+#[test]
+fn case_over_strict_enum_reports_foreign_and_unhandled_elements() {
+    let diagnostics = parse_and_validate(
+        "
+        {attribute 'strict'}
+        TYPE Color : (Red, Yellow, Green, Blue); END_TYPE
+
+        PROGRAM prg
+        VAR
+            c : Color;
+            x : DINT;
+        END_VAR
+            CASE c OF
+                Red: x := 1;
+                Yellow..Green: x := 2;
+                7: x := 3;
+            END_CASE
+
+            CASE c OF
+                Red: x := 1;
+            ELSE
+                x := 2;
+            END_CASE
+        END_PROGRAM
+        ",
+    );
+
+    let messages = diagnostics.iter().map(Diagnostic::get_message).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Strict enum Color cannot be mixed with DINT, use an explicit conversion",
+            "CASE over Color does not handle: Blue",
+        ]
+    );
+}
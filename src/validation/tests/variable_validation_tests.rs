@@ -360,3 +360,83 @@ mod overflows {
         assert_validation_snapshot!(diagnostics);
     }
 }
+
+#[test]
+fn union_members_are_validated_like_struct_members() {
+    let diagnostics = parse_and_validate(
+        "
+        TYPE Register : UNION
+            raw : DWORD;
+            low : WORD := 1;
+        END_UNION
+        END_TYPE
+
+        PROGRAM prg
+            VAR
+                r : Register;
+            END_VAR
+            r.raw := 16#FFFF;
+            r.low := 1;
+            r.high := 1;
+        END_PROGRAM
+       ",
+    );
+
+    let mut messages = diagnostics.iter().map(|it| it.get_message()).collect::<Vec<_>>();
+    messages.sort_unstable();
+    assert_eq!(
+        messages,
+        vec![
+            "Could not resolve reference to high",
+            "Invalid union: Union members cannot have initial values"
+        ]
+    );
+}
+
+#[test]
+fn only_elementary_program_members_can_be_traced() {
+    let diagnostics = parse_and_validate(
+        "
+        TYPE Mode : (Off, Auto); END_TYPE
+
+        VAR_GLOBAL
+            {attribute 'trace'}
+            g : INT;
+        END_VAR
+
+        PROGRAM prg
+            VAR
+                {attribute 'trace'}
+                on : BOOL;
+                {attribute 'trace'}
+                speed : INT(0..100);
+                {attribute 'trace'}
+                mode : Mode;
+                {attribute 'trace'}
+                name : STRING;
+                {attribute 'trace'}
+                values : ARRAY[0..2] OF INT;
+            END_VAR
+        END_PROGRAM
+
+        FUNCTION_BLOCK fb
+            VAR
+                {attribute 'trace'}
+                x : INT;
+            END_VAR
+        END_FUNCTION_BLOCK
+       ",
+    );
+
+    let mut messages = diagnostics.iter().map(|it| it.get_message()).collect::<Vec<_>>();
+    messages.sort_unstable();
+    assert_eq!(
+        messages,
+        vec![
+            "g cannot be traced: only members of a PROGRAM are traced",
+            "name cannot be traced: only BOOL, numeric and enum variables can be traced",
+            "values cannot be traced: only BOOL, numeric and enum variables can be traced",
+            "x cannot be traced: only members of a PROGRAM are traced",
+        ]
+    );
+}
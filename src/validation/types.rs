@@ -1,4 +1,6 @@
-use plc_ast::ast::{AstStatement, DataType, DataTypeDeclaration, PouType, SourceRange, UserTypeDeclaration};
+use plc_ast::ast::{
+    AstStatement, DataType, DataTypeDeclaration, GenericBinding, PouType, SourceRange, UserTypeDeclaration,
+};
 use plc_diagnostics::diagnostics::Diagnostic;
 
 use crate::{
@@ -28,7 +30,17 @@ pub fn visit_data_type<T: AnnotationMap>(
     validate_data_type(validator, data_type, location);
 
     match data_type {
-        DataType::StructType { variables, .. } => {
+        DataType::GenericInstance { generic_type, type_arguments, generics, .. } => {
+            validate_generic_instance(
+                validator,
+                generic_type,
+                type_arguments,
+                generics,
+                location,
+                context.index,
+            )
+        }
+        DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
             variables.iter().for_each(|v| visit_variable(validator, v, context))
         }
         DataType::ArrayType { referenced_type, .. } => {
@@ -48,6 +60,18 @@ fn validate_data_type(validator: &mut Validator, data_type: &DataType, location:
                 validator.push_diagnostic(Diagnostic::empty_variable_block(location.clone()));
             }
         }
+        DataType::UnionType { variables, .. } => {
+            if variables.is_empty() {
+                validator.push_diagnostic(Diagnostic::empty_variable_block(location.clone()));
+            }
+            // the members share their memory, so there is no single initial value
+            variables.iter().filter_map(|it| it.initializer.as_ref()).for_each(|it| {
+                validator.push_diagnostic(Diagnostic::invalid_union(
+                    "Union members cannot have initial values",
+                    it.get_location(),
+                ))
+            });
+        }
         DataType::EnumType { elements: AstStatement::ExpressionList { expressions, .. }, .. }
             if expressions.is_empty() =>
         {
@@ -63,6 +87,47 @@ fn validate_data_type(validator: &mut Validator, data_type: &DataType, location:
     }
 }
 
+/// validates the type arguments of a generic instance (e.g. `FIFO<INT>`) against the
+/// natures declared by its generic FUNCTION_BLOCK, CLASS or TYPE
+fn validate_generic_instance(
+    validator: &mut Validator,
+    generic_type: &str,
+    type_arguments: &[String],
+    generics: &[GenericBinding],
+    location: &SourceRange,
+    index: &Index,
+) {
+    if generics.is_empty() {
+        // the instance could not be bound to a generic declaration
+        if index.find_type(generic_type).is_some() {
+            validator.push_diagnostic(Diagnostic::not_a_generic_type(generic_type, location.clone()));
+        } else {
+            validator.push_diagnostic(Diagnostic::unknown_type(generic_type, location.clone()));
+        }
+    } else if generics.len() != type_arguments.len() {
+        validator.push_diagnostic(Diagnostic::invalid_type_argument_count(
+            generic_type,
+            generics.len(),
+            type_arguments.len(),
+            location.clone(),
+        ));
+    } else {
+        for (GenericBinding { nature, .. }, argument) in generics.iter().zip(type_arguments) {
+            match index.find_effective_type_by_name(argument) {
+                Some(data_type) if !data_type.has_nature(*nature, index) => {
+                    validator.push_diagnostic(Diagnostic::invalid_type_nature(
+                        data_type.get_name(),
+                        format!("{nature:?}").as_str(),
+                        location.clone(),
+                    ))
+                }
+                None => validator.push_diagnostic(Diagnostic::unknown_type(argument, location.clone())),
+                _ => {}
+            }
+        }
+    }
+}
+
 pub fn visit_user_type_declaration<T: AnnotationMap>(
     validator: &mut Validator,
     user_type: &UserTypeDeclaration,
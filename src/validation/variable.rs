@@ -1,11 +1,15 @@
 use plc_ast::ast::{ArgumentProperty, Pou, PouType, Variable, VariableBlock, VariableBlockType};
 use plc_diagnostics::diagnostics::Diagnostic;
 
-use crate::{index::const_expressions::ConstExpression, resolver::AnnotationMap};
+use crate::{
+    index::const_expressions::ConstExpression,
+    resolver::AnnotationMap,
+    trace::{Signal, TRACE_ATTRIBUTE},
+};
 
 use super::{
     array::{validate_array_assignment, Wrapper},
-    statement::{validate_enum_variant_assignment, visit_statement},
+    statement::{validate_enum_variant_assignment, validate_strict_enum_mix, visit_statement},
     types::{data_type_is_fb_or_class_instance, visit_data_type_declaration},
     ValidationContext, Validator, Validators,
 };
@@ -20,6 +24,7 @@ pub fn visit_variable_block<T: AnnotationMap>(
 
     for variable in &block.variables {
         visit_variable(validator, variable, context);
+        validate_trace(validator, pou, variable, context);
 
         if let Some(referenced_type) = variable.data_type_declaration.get_referenced_type() {
             if context.index.get_type_information_or_void(&referenced_type).is_vla() {
@@ -47,6 +52,34 @@ pub fn visit_variable<T: AnnotationMap>(
     visit_data_type_declaration(validator, &variable.data_type_declaration, context);
 }
 
+/// Validates that variables marked with `{attribute 'trace'}` are elementary members of a program
+fn validate_trace<T: AnnotationMap>(
+    validator: &mut Validator,
+    pou: Option<&Pou>,
+    variable: &Variable,
+    context: &ValidationContext<T>,
+) {
+    let attribute = variable.attributes.iter().find(|it| it.name.eq_ignore_ascii_case(TRACE_ATTRIBUTE));
+    match (attribute, pou) {
+        (None, _) => {}
+        (Some(_), Some(pou)) if pou.pou_type == PouType::Program => {
+            let Some(member) = context.index.find_member(&pou.name, &variable.name) else { return };
+            if Signal::new(member.get_name(), member.get_type_name(), context.index).is_none() {
+                validator.push_diagnostic(Diagnostic::invalid_trace(
+                    &variable.name,
+                    "only BOOL, numeric and enum variables can be traced",
+                    variable.location.clone(),
+                ));
+            }
+        }
+        (Some(attribute), _) => validator.push_diagnostic(Diagnostic::invalid_trace(
+            &variable.name,
+            "only members of a PROGRAM are traced",
+            attribute.location.clone(),
+        )),
+    }
+}
+
 /// Validates Variable Length Arrays as specified in the IEC61131-3, i.e. VLAs are only allowed to be defined
 /// inside the following Variable Block and POU combinations
 /// - Input, Output and InOut within a Function or Method or
@@ -136,16 +169,22 @@ fn validate_variable<T: AnnotationMap>(
             }
             _ => {
                 if let Some(rhs) = variable.initializer.as_ref() {
+                    let left_type = context
+                        .index
+                        .get_effective_type_or_void_by_name(v_entry.get_type_name())
+                        .get_type_information();
+                    let right_type =
+                        context.annotations.get_type_or_void(rhs, context.index).get_type_information();
                     validate_enum_variant_assignment(
                         validator,
-                        context
-                            .index
-                            .get_effective_type_or_void_by_name(v_entry.get_type_name())
-                            .get_type_information(),
-                        context.annotations.get_type_or_void(rhs, context.index).get_type_information(),
+                        left_type,
+                        right_type,
                         v_entry.get_qualified_name(),
                         rhs.get_location(),
-                    )
+                    );
+                    if !left_type.is_enum() {
+                        validate_strict_enum_mix(validator, left_type, right_type, rhs.get_location());
+                    }
                 }
             }
         }